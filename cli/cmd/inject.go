@@ -7,6 +7,7 @@ import (
 	"os"
 	"strconv"
 	"strings"
+	"time"
 
 	"github.com/ghodss/yaml"
 	"github.com/runconduit/conduit/controller"
@@ -47,6 +48,9 @@ with 'conduit inject'. e.g. curl http://url.to/yml | conduit inject -
 		if len(args) < 1 {
 			return fmt.Errorf("please specify a deployment file")
 		}
+		if err := validateAddressUpdateInterval(); err != nil {
+			return err
+		}
 
 		var in io.Reader
 		var err error
@@ -279,6 +283,10 @@ func injectPodTemplateSpec(t *v1.PodTemplateSpec) enhancedPodTemplateSpec {
 				Name:      "CONDUIT_PROXY_POD_NAMESPACE",
 				ValueFrom: &v1.EnvVarSource{FieldRef: &v1.ObjectFieldSelector{FieldPath: "metadata.namespace"}},
 			},
+			v1.EnvVar{
+				Name:  "CONDUIT_PROXY_ADDRESS_UPDATE_INTERVAL_SECS",
+				Value: strconv.FormatInt(int64(addressUpdateInterval/time.Second), 10),
+			},
 			v1.EnvVar{
 				Name:  "CONDUIT_PROXY_DESTINATIONS_AUTOCOMPLETE_FQDN",
 				Value: "Kubernetes",
@@ -386,4 +394,5 @@ func init() {
 	injectCmd.PersistentFlags().UintSliceVar(&ignoreOutboundPorts, "skip-outbound-ports", nil, "outbound ports that should skip the proxy")
 	injectCmd.PersistentFlags().UintVar(&proxyControlPort, "control-port", 4190, "proxy port to use for control")
 	injectCmd.PersistentFlags().UintVar(&proxyAPIPort, "api-port", 8086, "port where the Conduit controller is running")
+	injectCmd.PersistentFlags().DurationVar(&addressUpdateInterval, "address-update-interval", 10*time.Second, "how often the controller sends an update on an unchanged destination stream; must match 'conduit install'")
 }
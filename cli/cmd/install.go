@@ -6,6 +6,7 @@ import (
 	"os"
 	"regexp"
 	"text/template"
+	"time"
 
 	"github.com/runconduit/conduit/controller"
 	uuid "github.com/satori/go.uuid"
@@ -37,7 +38,7 @@ rules:
   resources: ["deployments", "replicasets"]
   verbs: ["list", "get", "watch"]
 - apiGroups: [""]
-  resources: ["pods", "endpoints", "services"]
+  resources: ["pods", "endpoints", "services", "nodes"]
   verbs: ["list", "get", "watch"]
 
 ---
@@ -179,6 +180,7 @@ spec:
         - "destination"
         - "-addr=:8089"
         - "-metrics-addr=:9999"
+        - "-address-update-interval={{.AddressUpdateInterval}}"
       - name: proxy-api
         ports:
         - name: grpc
@@ -387,16 +389,17 @@ data:
 `
 
 type installConfig struct {
-	Namespace          string
-	ControllerImage    string
-	WebImage           string
-	PrometheusImage    string
-	ControllerReplicas uint
-	WebReplicas        uint
-	PrometheusReplicas uint
-	ImagePullPolicy    string
-	UUID               string
-	CliVersion         string
+	Namespace             string
+	ControllerImage       string
+	WebImage              string
+	PrometheusImage       string
+	ControllerReplicas    uint
+	WebReplicas           uint
+	PrometheusReplicas    uint
+	ImagePullPolicy       string
+	UUID                  string
+	CliVersion            string
+	AddressUpdateInterval time.Duration
 }
 
 var (
@@ -406,6 +409,10 @@ var (
 	webReplicas        uint
 	prometheusReplicas uint
 	imagePullPolicy    string
+
+	// Shared by the controller, which sends updates this often, and by injected
+	// proxies, which expect them this often.
+	addressUpdateInterval time.Duration
 )
 
 var installCmd = &cobra.Command{
@@ -421,16 +428,17 @@ var installCmd = &cobra.Command{
 			return err
 		}
 		template.Execute(os.Stdout, installConfig{
-			Namespace:          controlPlaneNamespace,
-			ControllerImage:    fmt.Sprintf("%s/controller:%s", dockerRegistry, version),
-			WebImage:           fmt.Sprintf("%s/web:%s", dockerRegistry, version),
-			PrometheusImage:    "prom/prometheus:v1.8.1",
-			ControllerReplicas: controllerReplicas,
-			WebReplicas:        webReplicas,
-			PrometheusReplicas: prometheusReplicas,
-			ImagePullPolicy:    imagePullPolicy,
-			UUID:               uuid.NewV4().String(),
-			CliVersion:         fmt.Sprintf("conduit/cli %s", controller.Version),
+			Namespace:             controlPlaneNamespace,
+			ControllerImage:       fmt.Sprintf("%s/controller:%s", dockerRegistry, version),
+			WebImage:              fmt.Sprintf("%s/web:%s", dockerRegistry, version),
+			PrometheusImage:       "prom/prometheus:v1.8.1",
+			ControllerReplicas:    controllerReplicas,
+			WebReplicas:           webReplicas,
+			PrometheusReplicas:    prometheusReplicas,
+			ImagePullPolicy:       imagePullPolicy,
+			UUID:                  uuid.NewV4().String(),
+			CliVersion:            fmt.Sprintf("conduit/cli %s", controller.Version),
+			AddressUpdateInterval: addressUpdateInterval,
 		})
 		return nil
 	},
@@ -455,6 +463,14 @@ func validate() error {
 	if imagePullPolicy != "Always" && imagePullPolicy != "IfNotPresent" && imagePullPolicy != "Never" {
 		return fmt.Errorf("imagePullPolicy must be one of Always, IfNotPresent, or Never")
 	}
+	return validateAddressUpdateInterval()
+}
+
+// The proxy reads its address update interval as a whole number of seconds.
+func validateAddressUpdateInterval() error {
+	if addressUpdateInterval < time.Second || addressUpdateInterval%time.Second != 0 {
+		return fmt.Errorf("address-update-interval must be a positive whole number of seconds, not %s", addressUpdateInterval)
+	}
 	return nil
 }
 
@@ -466,4 +482,5 @@ func init() {
 	installCmd.PersistentFlags().UintVar(&webReplicas, "web-replicas", 1, "replicas of the web server to deploy")
 	installCmd.PersistentFlags().UintVar(&prometheusReplicas, "prometheus-replicas", 1, "replicas of prometheus to deploy")
 	installCmd.PersistentFlags().StringVar(&imagePullPolicy, "image-pull-policy", "IfNotPresent", "Docker image pull policy")
+	installCmd.PersistentFlags().DurationVar(&addressUpdateInterval, "address-update-interval", 10*time.Second, "how often the controller sends an update on an unchanged destination stream; must match 'conduit inject'")
 }
@@ -23,6 +23,11 @@ enum Item {
     Module(Module),
     Struct(Struct),
     Enum(Enum),
+    Trait(Trait),
+    TypeAlias(TypeAlias),
+    Const(Const),
+    Static(Static),
+    Function(Function),
     Impl(Impl),
 }
 
@@ -38,6 +43,9 @@ pub struct Module {
     /// Module documentation
     docs: Option<Docs>,
 
+    /// Module attributes
+    attrs: Vec<String>,
+
     /// Contents of the module
     scope: Scope,
 }
@@ -58,6 +66,53 @@ pub struct Struct {
     fields: Fields,
 }
 
+/// Defines a trait
+#[derive(Debug, Clone)]
+pub struct Trait {
+    type_def: TypeDef,
+
+    /// Supertraits
+    parents: Vec<Type>,
+
+    /// Associated types
+    assoc_tys: Vec<AssociatedType>,
+
+    /// Required and provided methods
+    fns: Vec<Function>,
+}
+
+/// Declares an associated type of a trait
+#[derive(Debug, Clone)]
+pub struct AssociatedType {
+    name: String,
+    attrs: Vec<String>,
+    bounds: Vec<Type>,
+}
+
+/// Defines a type alias
+#[derive(Debug, Clone)]
+pub struct TypeAlias {
+    type_def: TypeDef,
+
+    /// The aliased type
+    target: Type,
+}
+
+/// Defines a `const` item
+#[derive(Debug, Clone)]
+pub struct Const {
+    value: ValueDef,
+}
+
+/// Defines a `static` item
+#[derive(Debug, Clone)]
+pub struct Static {
+    value: ValueDef,
+
+    /// If the static is `static mut`
+    mutable: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct Type {
     name: String,
@@ -71,13 +126,28 @@ struct TypeDef {
     vis: Option<String>,
     docs: Option<Docs>,
     derive: Vec<String>,
+    attrs: Vec<String>,
     bounds: Vec<Field>,
 }
 
+/// A `const` or `static` definition
+#[derive(Debug, Clone)]
+struct ValueDef {
+    name: String,
+    vis: Option<String>,
+    docs: Option<Docs>,
+    attrs: Vec<String>,
+    ty: Type,
+
+    /// Initializer expression
+    value: String,
+}
+
 /// An enum variant
 #[derive(Debug, Clone)]
 pub struct Variant {
     name: String,
+    attrs: Vec<String>,
     fields: Fields,
 }
 
@@ -90,12 +160,18 @@ enum Fields {
 
 /// Defines a struct field
 #[derive(Debug, Clone)]
-struct Field {
+pub struct Field {
     /// Field name
     name: String,
 
     /// Field type
     ty: Type,
+
+    /// Field documentation
+    docs: Option<Docs>,
+
+    /// Field attributes
+    attrs: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -107,7 +183,10 @@ pub struct Impl {
     generics: Vec<String>,
 
     /// If implementing a trait
-    impl_trait: Option<String>,
+    impl_trait: Option<Type>,
+
+    /// Impl attributes
+    attrs: Vec<String>,
 
     /// Associated types
     assoc_tys: Vec<Field>,
@@ -137,6 +216,9 @@ pub struct Function {
     /// Function visibility
     vis: Option<String>,
 
+    /// Function attributes
+    attrs: Vec<String>,
+
     /// Function generics
     generics: Vec<String>,
 
@@ -152,8 +234,8 @@ pub struct Function {
     /// Where bounds
     bounds: Vec<Field>,
 
-    /// Body contents
-    body: Vec<Body>,
+    /// Body contents. Trait methods without a body are only declared.
+    body: Option<Vec<Body>>,
 }
 
 /// A block of code
@@ -261,6 +343,97 @@ impl Scope {
         self
     }
 
+    /// Push a new trait definition, returning a mutable reference to the
+    /// definition.
+    pub fn trait_def(&mut self, name: &str) -> &mut Trait {
+        self.push_trait_def(Trait::new(name));
+
+        match *self.items.last_mut().unwrap() {
+            Item::Trait(ref mut v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push a trait definition
+    pub fn push_trait_def(&mut self, item: Trait) -> &mut Self {
+        self.items.push(Item::Trait(item));
+        self
+    }
+
+    /// Push a new type alias, returning a mutable reference to the
+    /// definition.
+    pub fn type_alias<T>(&mut self, name: &str, target: T) -> &mut TypeAlias
+    where T: Into<Type>,
+    {
+        self.push_type_alias(TypeAlias::new(name, target));
+
+        match *self.items.last_mut().unwrap() {
+            Item::TypeAlias(ref mut v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push a type alias
+    pub fn push_type_alias(&mut self, alias: TypeAlias) -> &mut Self {
+        self.items.push(Item::TypeAlias(alias));
+        self
+    }
+
+    /// Push a new `const` item, returning a mutable reference to the
+    /// definition.
+    pub fn constant<T>(&mut self, name: &str, ty: T, value: &str) -> &mut Const
+    where T: Into<Type>,
+    {
+        self.push_constant(Const::new(name, ty, value));
+
+        match *self.items.last_mut().unwrap() {
+            Item::Const(ref mut v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push a `const` item
+    pub fn push_constant(&mut self, constant: Const) -> &mut Self {
+        self.items.push(Item::Const(constant));
+        self
+    }
+
+    /// Push a new `static` item, returning a mutable reference to the
+    /// definition.
+    pub fn static_item<T>(&mut self, name: &str, ty: T, value: &str) -> &mut Static
+    where T: Into<Type>,
+    {
+        self.push_static_item(Static::new(name, ty, value));
+
+        match *self.items.last_mut().unwrap() {
+            Item::Static(ref mut v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push a `static` item
+    pub fn push_static_item(&mut self, item: Static) -> &mut Self {
+        self.items.push(Item::Static(item));
+        self
+    }
+
+    /// Push a new free function, returning a mutable reference to the
+    /// definition.
+    pub fn function(&mut self, name: &str) -> &mut Function {
+        self.push_function(Function::new(name));
+
+        match *self.items.last_mut().unwrap() {
+            Item::Function(ref mut v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push a free function
+    pub fn push_function(&mut self, func: Function) -> &mut Self {
+        self.items.push(Item::Function(func));
+        self
+    }
+
     pub fn imp(&mut self, target: &str) -> &mut Impl {
         self.push_imp(Impl::new(target));
 
@@ -293,18 +466,23 @@ impl Scope {
         self.fmt_imports(fmt)?;
 
         if !self.imports.is_empty() {
-            write!(fmt, "\n")?;
+            writeln!(fmt)?;
         }
 
         for (i, item) in self.items.iter().enumerate() {
             if i != 0 {
-                write!(fmt, "\n")?;
+                writeln!(fmt)?;
             }
 
             match *item {
                 Item::Module(ref v) => v.fmt(fmt)?,
                 Item::Struct(ref v) => v.fmt(fmt)?,
                 Item::Enum(ref v) => v.fmt(fmt)?,
+                Item::Trait(ref v) => v.fmt(fmt)?,
+                Item::TypeAlias(ref v) => v.fmt(fmt)?,
+                Item::Const(ref v) => v.fmt(fmt)?,
+                Item::Static(ref v) => v.fmt(fmt)?,
+                Item::Function(ref v) => v.fmt(fmt)?,
                 Item::Impl(ref v) => v.fmt(fmt)?,
             }
         }
@@ -352,9 +530,9 @@ impl Scope {
                             write!(fmt, "{}", ty)?;
                         }
 
-                        write!(fmt, "}};\n")?;
+                        writeln!(fmt, "}};")?;
                     } else if tys.len() == 1 {
-                        write!(fmt, "{};\n", tys[0])?;
+                        writeln!(fmt, "{};", tys[0])?;
                     }
                 }
             }
@@ -373,6 +551,7 @@ impl Module {
             name: name.to_string(),
             vis: None,
             docs: None,
+            attrs: vec![],
             scope: Scope::new(),
         }
     }
@@ -382,6 +561,17 @@ impl Module {
         self
     }
 
+    pub fn doc(&mut self, docs: &str) -> &mut Self {
+        self.docs = Some(Docs::new(docs));
+        self
+    }
+
+    /// Add an outer attribute, e.g. `cfg(test)`
+    pub fn attr(&mut self, attr: &str) -> &mut Self {
+        self.attrs.push(attr.to_string());
+        self
+    }
+
     /// Push an `use` line
     pub fn import(&mut self, path: &str, ty: &str) -> &mut Self {
         self.scope.import(path, ty);
@@ -424,6 +614,72 @@ impl Module {
         self
     }
 
+    /// Push a new trait definition, returning a mutable reference to the
+    /// definition.
+    pub fn trait_def(&mut self, name: &str) -> &mut Trait {
+        self.scope.trait_def(name)
+    }
+
+    /// Push a trait definition
+    pub fn push_trait_def(&mut self, item: Trait) -> &mut Self {
+        self.scope.push_trait_def(item);
+        self
+    }
+
+    /// Push a new type alias, returning a mutable reference to the
+    /// definition.
+    pub fn type_alias<T>(&mut self, name: &str, target: T) -> &mut TypeAlias
+    where T: Into<Type>,
+    {
+        self.scope.type_alias(name, target)
+    }
+
+    /// Push a type alias
+    pub fn push_type_alias(&mut self, alias: TypeAlias) -> &mut Self {
+        self.scope.push_type_alias(alias);
+        self
+    }
+
+    /// Push a new `const` item, returning a mutable reference to the
+    /// definition.
+    pub fn constant<T>(&mut self, name: &str, ty: T, value: &str) -> &mut Const
+    where T: Into<Type>,
+    {
+        self.scope.constant(name, ty, value)
+    }
+
+    /// Push a `const` item
+    pub fn push_constant(&mut self, constant: Const) -> &mut Self {
+        self.scope.push_constant(constant);
+        self
+    }
+
+    /// Push a new `static` item, returning a mutable reference to the
+    /// definition.
+    pub fn static_item<T>(&mut self, name: &str, ty: T, value: &str) -> &mut Static
+    where T: Into<Type>,
+    {
+        self.scope.static_item(name, ty, value)
+    }
+
+    /// Push a `static` item
+    pub fn push_static_item(&mut self, item: Static) -> &mut Self {
+        self.scope.push_static_item(item);
+        self
+    }
+
+    /// Push a new free function, returning a mutable reference to the
+    /// definition.
+    pub fn function(&mut self, name: &str) -> &mut Function {
+        self.scope.function(name)
+    }
+
+    /// Push a free function
+    pub fn push_function(&mut self, func: Function) -> &mut Self {
+        self.scope.push_function(func);
+        self
+    }
+
     pub fn imp(&mut self, target: &str) -> &mut Impl {
         self.scope.imp(target)
     }
@@ -434,6 +690,12 @@ impl Module {
     }
 
     pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        if let Some(ref docs) = self.docs {
+            docs.fmt(fmt)?;
+        }
+
+        fmt_attrs(&self.attrs, fmt)?;
+
         if let Some(ref vis) = self.vis {
             write!(fmt, "{} ", vis)?;
         }
@@ -471,6 +733,18 @@ impl Struct {
         self
     }
 
+    /// Add a lifetime parameter, ordered before any type parameters
+    pub fn lifetime(&mut self, name: &str) -> &mut Self {
+        self.type_def.lifetime(name);
+        self
+    }
+
+    /// Add an outer attribute, e.g. `allow(dead_code)`
+    pub fn attr(&mut self, attr: &str) -> &mut Self {
+        self.type_def.attr(attr);
+        self
+    }
+
     pub fn bound<T>(&mut self, name: &str, ty: T) -> &mut Self
     where T: Into<Type>,
     {
@@ -491,12 +765,18 @@ impl Struct {
     pub fn field<T>(&mut self, name: &str, ty: T) -> &mut Self
     where T: Into<Type>,
     {
-        self.fields.named(name, ty);
+        self.fields.push_named(Field::new(name, ty));
+        self
+    }
+
+    /// Push a field definition
+    pub fn push_field(&mut self, field: Field) -> &mut Self {
+        self.fields.push_named(field);
         self
     }
 
     pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
-        self.type_def.fmt_head("struct", fmt)?;
+        self.type_def.fmt_head("struct", &[], fmt)?;
         self.fields.fmt(fmt)?;
 
         Ok(())
@@ -529,6 +809,18 @@ impl Enum {
         self
     }
 
+    /// Add a lifetime parameter, ordered before any type parameters
+    pub fn lifetime(&mut self, name: &str) -> &mut Self {
+        self.type_def.lifetime(name);
+        self
+    }
+
+    /// Add an outer attribute, e.g. `allow(dead_code)`
+    pub fn attr(&mut self, attr: &str) -> &mut Self {
+        self.type_def.attr(attr);
+        self
+    }
+
     pub fn bound<T>(&mut self, name: &str, ty: T) -> &mut Self
     where T: Into<Type>,
     {
@@ -557,7 +849,7 @@ impl Enum {
     }
 
     pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
-        self.type_def.fmt_head("enum", fmt)?;
+        self.type_def.fmt_head("enum", &[], fmt)?;
 
         fmt.block(|fmt| {
             for variant in &self.variants {
@@ -569,20 +861,327 @@ impl Enum {
     }
 }
 
+// ===== impl Trait =====
+
+impl Trait {
+    /// Return a trait definition with the provided name
+    pub fn new(name: &str) -> Self {
+        Trait {
+            type_def: TypeDef::new(name),
+            parents: vec![],
+            assoc_tys: vec![],
+            fns: vec![],
+        }
+    }
+
+    /// Returns a reference to the type
+    pub fn ty(&self) -> &Type {
+        &self.type_def.ty
+    }
+
+    pub fn vis(&mut self, vis: &str) -> &mut Self {
+        self.type_def.vis(vis);
+        self
+    }
+
+    pub fn generic(&mut self, name: &str) -> &mut Self {
+        self.type_def.ty.generic(name);
+        self
+    }
+
+    /// Add a lifetime parameter, ordered before any type parameters
+    pub fn lifetime(&mut self, name: &str) -> &mut Self {
+        self.type_def.lifetime(name);
+        self
+    }
+
+    /// Add an outer attribute, e.g. `allow(dead_code)`
+    pub fn attr(&mut self, attr: &str) -> &mut Self {
+        self.type_def.attr(attr);
+        self
+    }
+
+    pub fn bound<T>(&mut self, name: &str, ty: T) -> &mut Self
+    where T: Into<Type>,
+    {
+        self.type_def.bound(name, ty);
+        self
+    }
+
+    /// Add a supertrait
+    pub fn parent<T>(&mut self, ty: T) -> &mut Self
+    where T: Into<Type>,
+    {
+        self.parents.push(ty.into());
+        self
+    }
+
+    pub fn doc(&mut self, docs: &str) -> &mut Self {
+        self.type_def.doc(docs);
+        self
+    }
+
+    /// Declare a new associated type, returning a mutable reference to the
+    /// declaration.
+    pub fn associated_type(&mut self, name: &str) -> &mut AssociatedType {
+        self.assoc_tys.push(AssociatedType::new(name));
+        self.assoc_tys.last_mut().unwrap()
+    }
+
+    /// Define a new method
+    ///
+    /// Methods without body lines are only declared, leaving them to be
+    /// defined by implementors.
+    pub fn function(&mut self, name: &str) -> &mut Function {
+        self.push_function(Function::new(name));
+        self.fns.last_mut().unwrap()
+    }
+
+    /// Push a method definition
+    pub fn push_function(&mut self, func: Function) -> &mut Self {
+        self.fns.push(func);
+        self
+    }
+
+    pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        self.type_def.fmt_head("trait", &self.parents, fmt)?;
+
+        fmt.block(|fmt| {
+            for ty in &self.assoc_tys {
+                ty.fmt(fmt)?;
+            }
+
+            for (i, func) in self.fns.iter().enumerate() {
+                if i != 0 || !self.assoc_tys.is_empty() { writeln!(fmt)?; }
+
+                func.fmt_trait_item(fmt)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+// ===== impl AssociatedType =====
+
+impl AssociatedType {
+    pub fn new(name: &str) -> Self {
+        AssociatedType {
+            name: name.to_string(),
+            attrs: vec![],
+            bounds: vec![],
+        }
+    }
+
+    /// Add an outer attribute
+    pub fn attr(&mut self, attr: &str) -> &mut Self {
+        self.attrs.push(attr.to_string());
+        self
+    }
+
+    /// Add a trait bound on the associated type
+    pub fn bound<T>(&mut self, ty: T) -> &mut Self
+    where T: Into<Type>,
+    {
+        self.bounds.push(ty.into());
+        self
+    }
+
+    pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt_attrs(&self.attrs, fmt)?;
+        write!(fmt, "type {}", self.name)?;
+        fmt_bound_list(&self.bounds, fmt)?;
+        writeln!(fmt, ";")
+    }
+}
+
+// ===== impl TypeAlias =====
+
+impl TypeAlias {
+    /// Return an alias with the provided name for `target`
+    pub fn new<T>(name: &str, target: T) -> Self
+    where T: Into<Type>,
+    {
+        TypeAlias {
+            type_def: TypeDef::new(name),
+            target: target.into(),
+        }
+    }
+
+    /// Returns a reference to the type
+    pub fn ty(&self) -> &Type {
+        &self.type_def.ty
+    }
+
+    pub fn vis(&mut self, vis: &str) -> &mut Self {
+        self.type_def.vis(vis);
+        self
+    }
+
+    pub fn generic(&mut self, name: &str) -> &mut Self {
+        self.type_def.ty.generic(name);
+        self
+    }
+
+    /// Add a lifetime parameter, ordered before any type parameters
+    pub fn lifetime(&mut self, name: &str) -> &mut Self {
+        self.type_def.lifetime(name);
+        self
+    }
+
+    /// Add an outer attribute, e.g. `allow(dead_code)`
+    pub fn attr(&mut self, attr: &str) -> &mut Self {
+        self.type_def.attr(attr);
+        self
+    }
+
+    pub fn doc(&mut self, docs: &str) -> &mut Self {
+        self.type_def.doc(docs);
+        self
+    }
+
+    pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        self.type_def.fmt_head("type", &[], fmt)?;
+        write!(fmt, " = ")?;
+        self.target.fmt(fmt)?;
+        writeln!(fmt, ";")
+    }
+}
+
+// ===== impl Const =====
+
+impl Const {
+    /// Return a `const` item with the provided name, type and value
+    pub fn new<T>(name: &str, ty: T, value: &str) -> Self
+    where T: Into<Type>,
+    {
+        Const {
+            value: ValueDef::new(name, ty, value),
+        }
+    }
+
+    pub fn vis(&mut self, vis: &str) -> &mut Self {
+        self.value.vis = Some(vis.to_string());
+        self
+    }
+
+    pub fn doc(&mut self, docs: &str) -> &mut Self {
+        self.value.docs = Some(Docs::new(docs));
+        self
+    }
+
+    /// Add an outer attribute, e.g. `allow(dead_code)`
+    pub fn attr(&mut self, attr: &str) -> &mut Self {
+        self.value.attrs.push(attr.to_string());
+        self
+    }
+
+    pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        self.value.fmt("const", fmt)
+    }
+}
+
+// ===== impl Static =====
+
+impl Static {
+    /// Return a `static` item with the provided name, type and value
+    pub fn new<T>(name: &str, ty: T, value: &str) -> Self
+    where T: Into<Type>,
+    {
+        Static {
+            value: ValueDef::new(name, ty, value),
+            mutable: false,
+        }
+    }
+
+    pub fn vis(&mut self, vis: &str) -> &mut Self {
+        self.value.vis = Some(vis.to_string());
+        self
+    }
+
+    pub fn doc(&mut self, docs: &str) -> &mut Self {
+        self.value.docs = Some(Docs::new(docs));
+        self
+    }
+
+    /// Add an outer attribute, e.g. `allow(dead_code)`
+    pub fn attr(&mut self, attr: &str) -> &mut Self {
+        self.value.attrs.push(attr.to_string());
+        self
+    }
+
+    /// Make the item a `static mut`
+    pub fn mutable(&mut self) -> &mut Self {
+        self.mutable = true;
+        self
+    }
+
+    pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        let keyword = if self.mutable { "static mut" } else { "static" };
+        self.value.fmt(keyword, fmt)
+    }
+}
+
+// ===== impl ValueDef =====
+
+impl ValueDef {
+    fn new<T>(name: &str, ty: T, value: &str) -> Self
+    where T: Into<Type>,
+    {
+        ValueDef {
+            name: name.to_string(),
+            vis: None,
+            docs: None,
+            attrs: vec![],
+            ty: ty.into(),
+            value: value.to_string(),
+        }
+    }
+
+    fn fmt(&self, keyword: &str, fmt: &mut Formatter) -> fmt::Result {
+        if let Some(ref docs) = self.docs {
+            docs.fmt(fmt)?;
+        }
+
+        fmt_attrs(&self.attrs, fmt)?;
+
+        if let Some(ref vis) = self.vis {
+            write!(fmt, "{} ", vis)?;
+        }
+
+        write!(fmt, "{} {}: ", keyword, self.name)?;
+        self.ty.fmt(fmt)?;
+        writeln!(fmt, " = {};", self.value)
+    }
+}
+
 // ===== impl Variant =====
 
 impl Variant {
     pub fn new(name: &str) -> Self {
         Variant {
             name: name.to_string(),
+            attrs: vec![],
             fields: Fields::Empty,
         }
     }
 
+    /// Add an outer attribute, e.g. `serde(rename = "foo")`
+    pub fn attr(&mut self, attr: &str) -> &mut Self {
+        self.attrs.push(attr.to_string());
+        self
+    }
+
     pub fn named<T>(&mut self, name: &str, ty: T) -> &mut Self
     where T: Into<Type>,
     {
-        self.fields.named(name, ty);
+        self.fields.push_named(Field::new(name, ty));
+        self
+    }
+
+    /// Push a named field definition
+    pub fn push_named(&mut self, field: Field) -> &mut Self {
+        self.fields.push_named(field);
         self
     }
 
@@ -592,9 +1191,10 @@ impl Variant {
     }
 
     pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt_attrs(&self.attrs, fmt)?;
         write!(fmt, "{}", self.name)?;
         self.fields.fmt(fmt)?;
-        write!(fmt, ",\n")?;
+        writeln!(fmt, ",")?;
 
         Ok(())
     }
@@ -620,21 +1220,27 @@ impl Type {
         self
     }
 
-    /// Rewrite the `Type` with the provided path
+    /// Rewrite the `Type` relative to the provided path
+    ///
+    /// Absolute paths, lifetimes and an empty
+    /// `path` leave the type unchanged.
     pub fn path(&self, path: &str) -> Type {
-        // TODO: This isn't really correct
-        assert!(!self.name.contains("::"));
+        let path = path.trim_end_matches("::");
 
-        let mut name = path.to_string();
-        name.push_str("::");
-        name.push_str(&self.name);
+        if path.is_empty() || self.name.starts_with("::") || self.is_lifetime() {
+            return self.clone();
+        }
 
         Type {
-            name,
+            name: format!("{}::{}", path, self.name),
             generics: self.generics.clone(),
         }
     }
 
+    fn is_lifetime(&self) -> bool {
+        self.name.starts_with('\'')
+    }
+
     pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         write!(fmt, "{}", self.name)?;
         Type::fmt_slice(&self.generics, fmt)
@@ -693,6 +1299,7 @@ impl TypeDef {
             vis: None,
             docs: None,
             derive: vec![],
+            attrs: vec![],
             bounds: vec![],
         }
     }
@@ -704,10 +1311,7 @@ impl TypeDef {
     fn bound<T>(&mut self, name: &str, ty: T)
     where T: Into<Type>,
     {
-        self.bounds.push(Field {
-            name: name.to_string(),
-            ty: ty.into(),
-        });
+        self.bounds.push(Field::new(name, ty));
     }
 
     fn doc(&mut self, docs: &str) {
@@ -718,12 +1322,24 @@ impl TypeDef {
         self.derive.push(name.to_string());
     }
 
-    fn fmt_head(&self, keyword: &str, fmt: &mut Formatter) -> fmt::Result {
+    fn attr(&mut self, attr: &str) {
+        self.attrs.push(attr.to_string());
+    }
+
+    fn lifetime(&mut self, name: &str) {
+        let pos = self.ty.generics.iter()
+            .position(|ty| !ty.is_lifetime())
+            .unwrap_or(self.ty.generics.len());
+        self.ty.generics.insert(pos, Type::new(name));
+    }
+
+    fn fmt_head(&self, keyword: &str, parents: &[Type], fmt: &mut Formatter) -> fmt::Result {
         if let Some(ref docs) = self.docs {
             docs.fmt(fmt)?;
         }
 
         self.fmt_derive(fmt)?;
+        fmt_attrs(&self.attrs, fmt)?;
 
         if let Some(ref vis) = self.vis {
             write!(fmt, "{} ", vis)?;
@@ -732,6 +1348,8 @@ impl TypeDef {
         write!(fmt, "{} ", keyword)?;
         self.ty.fmt(fmt)?;
 
+        fmt_bound_list(parents, fmt)?;
+
         fmt_bounds(&self.bounds, fmt)?;
 
         Ok(())
@@ -746,7 +1364,7 @@ impl TypeDef {
                 write!(fmt, "{}", name)?;
             }
 
-            write!(fmt, ")]\n")?;
+            writeln!(fmt, ")]")?;
         }
 
         Ok(())
@@ -770,41 +1388,91 @@ fn fmt_generics(generics: &[String], fmt: &mut Formatter) -> fmt::Result {
 
 fn fmt_bounds(bounds: &[Field], fmt: &mut Formatter) -> fmt::Result {
     if !bounds.is_empty() {
-        write!(fmt, "\n")?;
+        writeln!(fmt)?;
 
         // Write first bound
         write!(fmt, "where {}: ", bounds[0].name)?;
         bounds[0].ty.fmt(fmt)?;
-        write!(fmt, ",\n")?;
+        writeln!(fmt, ",")?;
 
         for bound in &bounds[1..] {
             write!(fmt, "      {}: ", bound.name)?;
             bound.ty.fmt(fmt)?;
-            write!(fmt, ",\n")?;
+            writeln!(fmt, ",")?;
         }
     }
 
     Ok(())
 }
 
-// ===== impl Fields =====
+/// Formats a `+` separated list of bounds, e.g. `: Foo + Bar`.
+fn fmt_bound_list(bounds: &[Type], fmt: &mut Formatter) -> fmt::Result {
+    for (i, ty) in bounds.iter().enumerate() {
+        if i == 0 {
+            write!(fmt, ": ")?;
+        } else {
+            write!(fmt, " + ")?;
+        }
 
-impl Fields {
-    fn named<T>(&mut self, name: &str, ty: T) -> &mut Self
+        ty.fmt(fmt)?;
+    }
+
+    Ok(())
+}
+
+fn fmt_attrs(attrs: &[String], fmt: &mut Formatter) -> fmt::Result {
+    for attr in attrs {
+        writeln!(fmt, "#[{}]", attr)?;
+    }
+
+    Ok(())
+}
+
+/// Inserts a lifetime parameter after any other lifetime parameters.
+fn push_lifetime(generics: &mut Vec<String>, name: &str) {
+    let pos = generics.iter()
+        .position(|g| !g.starts_with('\''))
+        .unwrap_or(generics.len());
+    generics.insert(pos, name.to_string());
+}
+
+// ===== impl Field =====
+
+impl Field {
+    /// Return a field definition with the provided name and type
+    pub fn new<T>(name: &str, ty: T) -> Self
     where T: Into<Type>,
     {
+        Field {
+            name: name.to_string(),
+            ty: ty.into(),
+            docs: None,
+            attrs: vec![],
+        }
+    }
+
+    pub fn doc(&mut self, docs: &str) -> &mut Self {
+        self.docs = Some(Docs::new(docs));
+        self
+    }
+
+    /// Add an outer attribute, e.g. `serde(default)`
+    pub fn attr(&mut self, attr: &str) -> &mut Self {
+        self.attrs.push(attr.to_string());
+        self
+    }
+}
+
+// ===== impl Fields =====
+
+impl Fields {
+    fn push_named(&mut self, field: Field) -> &mut Self {
         match *self {
             Fields::Empty => {
-                *self = Fields::Named(vec![Field {
-                    name: name.to_string(),
-                    ty: ty.into(),
-                }]);
+                *self = Fields::Named(vec![field]);
             }
             Fields::Named(ref mut fields) => {
-                fields.push(Field {
-                    name: name.to_string(),
-                    ty: ty.into(),
-                });
+                fields.push(field);
             }
             _ => panic!("field list is named"),
         }
@@ -835,9 +1503,14 @@ impl Fields {
 
                 fmt.block(|fmt| {
                     for f in fields {
+                        if let Some(ref docs) = f.docs {
+                            docs.fmt(fmt)?;
+                        }
+
+                        fmt_attrs(&f.attrs, fmt)?;
                         write!(fmt, "{}: ", f.name)?;
                         f.ty.fmt(fmt)?;
-                        write!(fmt, ",\n")?;
+                        writeln!(fmt, ",")?;
                     }
 
                     Ok(())
@@ -873,6 +1546,7 @@ impl Impl {
             target: target.into(),
             generics: vec![],
             impl_trait: None,
+            attrs: vec![],
             assoc_tys: vec![],
             bounds: vec![],
             fns: vec![],
@@ -884,6 +1558,18 @@ impl Impl {
         self
     }
 
+    /// Add a lifetime parameter, ordered before any type parameters
+    pub fn lifetime(&mut self, name: &str) -> &mut Self {
+        push_lifetime(&mut self.generics, name);
+        self
+    }
+
+    /// Add an outer attribute, e.g. `cfg(feature = "foo")`
+    pub fn attr(&mut self, attr: &str) -> &mut Self {
+        self.attrs.push(attr.to_string());
+        self
+    }
+
     pub fn target_generic<T>(&mut self, ty: T) -> &mut Self
     where T: Into<Type>,
     {
@@ -892,17 +1578,22 @@ impl Impl {
     }
 
     pub fn impl_trait(&mut self, name: &str) -> &mut Self {
-        self.impl_trait = Some(name.to_string());
+        self.impl_trait = Some(Type::new(name));
+        self
+    }
+
+    /// Implement a trait that may take generic arguments.
+    pub fn impl_trait_type<T>(&mut self, ty: T) -> &mut Self
+    where T: Into<Type>,
+    {
+        self.impl_trait = Some(ty.into());
         self
     }
 
     pub fn associate_type<T>(&mut self, name: &str, ty: T) -> &mut Self
     where T: Into<Type>,
     {
-        self.assoc_tys.push(Field {
-            name: name.to_string(),
-            ty: ty.into(),
-        });
+        self.assoc_tys.push(Field::new(name, ty));
 
         self
     }
@@ -910,10 +1601,7 @@ impl Impl {
     pub fn bound<T>(&mut self, name: &str, ty: T) -> &mut Self
     where T: Into<Type>,
     {
-        self.bounds.push(Field {
-            name: name.to_string(),
-            ty: ty.into(),
-        });
+        self.bounds.push(Field::new(name, ty));
         self
     }
 
@@ -930,11 +1618,14 @@ impl Impl {
     }
 
     pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt_attrs(&self.attrs, fmt)?;
         write!(fmt, "impl")?;
         fmt_generics(&self.generics[..], fmt)?;
 
         if let Some(ref t) = self.impl_trait {
-            write!(fmt, " {} for", t)?;
+            write!(fmt, " ")?;
+            t.fmt(fmt)?;
+            write!(fmt, " for")?;
         }
 
         write!(fmt, " ")?;
@@ -948,12 +1639,12 @@ impl Impl {
                 for ty in &self.assoc_tys {
                     write!(fmt, "type {} = ", ty.name)?;
                     ty.ty.fmt(fmt)?;
-                    write!(fmt, ";\n")?;
+                    writeln!(fmt, ";")?;
                 }
             }
 
             for (i, func) in self.fns.iter().enumerate() {
-                if i != 0 || !self.assoc_tys.is_empty() { write!(fmt, "\n")?; }
+                if i != 0 || !self.assoc_tys.is_empty() { writeln!(fmt)?; }
 
                 func.fmt(fmt)?;
             }
@@ -987,12 +1678,13 @@ impl Function {
             name: name.to_string(),
             docs: None,
             vis: None,
+            attrs: vec![],
             generics: vec![],
             arg_self: None,
             args: vec![],
             ret: None,
             bounds: vec![],
-            body: vec![],
+            body: None,
         }
     }
 
@@ -1011,6 +1703,18 @@ impl Function {
         self
     }
 
+    /// Add a lifetime parameter, ordered before any type parameters
+    pub fn lifetime(&mut self, name: &str) -> &mut Self {
+        push_lifetime(&mut self.generics, name);
+        self
+    }
+
+    /// Add an outer attribute, e.g. `inline`
+    pub fn attr(&mut self, attr: &str) -> &mut Self {
+        self.attrs.push(attr.to_string());
+        self
+    }
+
     pub fn arg_self(&mut self) -> &mut Self {
         self.arg_self = Some("self".to_string());
         self
@@ -1029,10 +1733,7 @@ impl Function {
     pub fn arg<T>(&mut self, name: &str, ty: T) -> &mut Self
     where T: Into<Type>,
     {
-        self.args.push(Field {
-            name: name.to_string(),
-            ty: ty.into(),
-        });
+        self.args.push(Field::new(name, ty));
 
         self
     }
@@ -1047,30 +1748,41 @@ impl Function {
     pub fn bound<T>(&mut self, name: &str, ty: T) -> &mut Self
     where T: Into<Type>,
     {
-        self.bounds.push(Field {
-            name: name.to_string(),
-            ty: ty.into(),
-        });
+        self.bounds.push(Field::new(name, ty));
         self
     }
 
     pub fn line<T>(&mut self, line: T) -> &mut Self
     where T: ToString,
     {
-        self.body.push(Body::String(line.to_string()));
+        self.body.get_or_insert_with(Vec::new)
+            .push(Body::String(line.to_string()));
         self
     }
 
     pub fn block(&mut self, block: Block) -> &mut Self {
-        self.body.push(Body::Block(block));
+        self.body.get_or_insert_with(Vec::new)
+            .push(Body::Block(block));
         self
     }
 
     pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        self.fmt_item(false, fmt)
+    }
+
+    /// Formats the function as a trait item. A method without a body is
+    /// formatted as a declaration.
+    pub fn fmt_trait_item(&self, fmt: &mut Formatter) -> fmt::Result {
+        self.fmt_item(true, fmt)
+    }
+
+    fn fmt_item(&self, is_trait: bool, fmt: &mut Formatter) -> fmt::Result {
         if let Some(ref docs) = self.docs {
             docs.fmt(fmt)?;
         }
 
+        fmt_attrs(&self.attrs, fmt)?;
+
         if let Some(ref vis) = self.vis {
             write!(fmt, "{} ", vis)?;
         }
@@ -1102,13 +1814,17 @@ impl Function {
 
         fmt_bounds(&self.bounds, fmt)?;
 
-        fmt.block(|fmt| {
-            for b in &self.body {
-                b.fmt(fmt)?;
-            }
+        match self.body {
+            Some(ref body) => fmt.block(|fmt| {
+                for b in body {
+                    b.fmt(fmt)?;
+                }
 
-            Ok(())
-        })
+                Ok(())
+            }),
+            None if is_trait => writeln!(fmt, ";"),
+            None => fmt.block(|_| Ok(())),
+        }
     }
 }
 
@@ -1151,7 +1867,7 @@ impl Block {
             write!(fmt, " ")?;
         }
 
-        write!(fmt, "{{\n")?;
+        writeln!(fmt, "{{")?;
 
         fmt.indent(|fmt| {
             for b in &self.body {
@@ -1167,7 +1883,7 @@ impl Block {
             write!(fmt, "{}", after)?;
         }
 
-        write!(fmt, "\n")?;
+        writeln!(fmt)?;
         Ok(())
     }
 }
@@ -1178,7 +1894,7 @@ impl Body {
     pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         match *self {
             Body::String(ref s) => {
-                write!(fmt, "{}\n", s)
+                writeln!(fmt, "{}", s)
             }
             Body::Block(ref b) => {
                 b.fmt(fmt)
@@ -1196,7 +1912,7 @@ impl Docs {
 
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         for line in self.docs.lines() {
-            write!(fmt, "/// {}\n", line)?;
+            writeln!(fmt, "/// {}", line)?;
         }
 
         Ok(())
@@ -1221,9 +1937,9 @@ impl<'a> Formatter<'a> {
             write!(self, " ")?;
         }
 
-        write!(self, "{{\n")?;
+        writeln!(self, "{{")?;
         self.indent(f)?;
-        write!(self, "}}\n")?;
+        writeln!(self, "}}")?;
         Ok(())
     }
 
@@ -1,6 +1,6 @@
 extern crate codegen;
 
-use codegen::Scope;
+use codegen::{Field, Scope, Type};
 
 #[test]
 fn empty_scope() {
@@ -238,3 +238,315 @@ mod foo {
 
     assert_eq!(scope.to_string(), &expect[1..]);
 }
+
+#[test]
+fn trait_with_assoc_types() {
+    let mut scope = Scope::new();
+
+    {
+        let t = scope.trait_def("Foo");
+        t.vis("pub")
+            .doc("A foo")
+            .parent("Clone")
+            .parent("Send");
+
+        t.associated_type("Item")
+            .bound("Clone")
+            .bound("Default");
+
+        t.associated_type("Error");
+
+        t.function("get")
+            .arg_ref_self()
+            .ret("Self::Item");
+
+        t.function("name")
+            .arg_ref_self()
+            .ret("&'static str")
+            .line("\"foo\"");
+    }
+
+    let expect = r#"
+/// A foo
+pub trait Foo: Clone + Send {
+    type Item: Clone + Default;
+    type Error;
+
+    fn get(&self) -> Self::Item;
+
+    fn name(&self) -> &'static str {
+        "foo"
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn trait_with_generics_and_bounds() {
+    let mut scope = Scope::new();
+
+    scope.trait_def("Foo")
+        .generic("T")
+        .parent("Bar<T>")
+        .bound("T", "Baz")
+        .function("foo")
+        .arg("t", "T");
+
+    let expect = r#"
+trait Foo<T>: Bar<T>
+where T: Baz,
+{
+    fn foo(t: T);
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn type_alias() {
+    let mut scope = Scope::new();
+
+    scope.type_alias("Result", "::std::result::Result<T, Error>")
+        .vis("pub")
+        .generic("T");
+
+    let expect = r#"
+pub type Result<T> = ::std::result::Result<T, Error>;"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn const_and_static() {
+    let mut scope = Scope::new();
+
+    scope.constant("MAX", "usize", "100")
+        .vis("pub")
+        .doc("The maximum");
+
+    scope.static_item("NAME", "&'static str", "\"foo\"");
+
+    scope.static_item("COUNT", "usize", "0")
+        .mutable();
+
+    let expect = r#"
+/// The maximum
+pub const MAX: usize = 100;
+
+static NAME: &'static str = "foo";
+
+static mut COUNT: usize = 0;"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn free_function_in_mod() {
+    let mut scope = Scope::new();
+
+    scope.module("foo")
+        .function("add")
+        .vis("pub")
+        .generic("T")
+        .arg("a", "T")
+        .arg("b", "T")
+        .ret("T")
+        .bound("T", "::std::ops::Add<Output = T>")
+        .line("a + b");
+
+    let expect = r#"
+mod foo {
+    pub fn add<T>(a: T, b: T) -> T
+    where T: ::std::ops::Add<Output = T>,
+    {
+        a + b
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn struct_with_attrs() {
+    let mut scope = Scope::new();
+
+    {
+        let s = scope.structure("Foo");
+        s.derive("Debug")
+            .attr("cfg(test)")
+            .attr("serde(rename_all = \"camelCase\")")
+            .field("one", "usize");
+
+        let mut two = Field::new("two", "String");
+        two.doc("The second field")
+            .attr("serde(default)");
+        s.push_field(two);
+    }
+
+    let expect = r#"
+#[derive(Debug)]
+#[cfg(test)]
+#[serde(rename_all = "camelCase")]
+struct Foo {
+    one: usize,
+    /// The second field
+    #[serde(default)]
+    two: String,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn enum_variant_attrs() {
+    let mut scope = Scope::new();
+
+    {
+        let e = scope.enumeration("Kind");
+        e.attr("allow(dead_code)");
+        e.variant("One")
+            .attr("serde(rename = \"one\")");
+        e.variant("Two")
+            .tuple("usize");
+    }
+
+    let expect = r#"
+#[allow(dead_code)]
+enum Kind {
+    #[serde(rename = "one")]
+    One,
+    Two(usize),
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn lifetimes() {
+    let mut scope = Scope::new();
+
+    scope.structure("Foo")
+        .generic("T")
+        .lifetime("'a")
+        .lifetime("'b")
+        .bound("'b", "'a")
+        .field("one", "&'a T")
+        .field("two", "&'b T");
+
+    {
+        let imp = scope.imp("Foo");
+        imp.generic("T")
+            .lifetime("'a")
+            .target_generic("'a")
+            .target_generic("'static")
+            .target_generic("T")
+            .impl_trait_type(Type::new("AsRef").generic("T").clone())
+            .attr("inline");
+
+        imp.function("as_ref")
+            .lifetime("'c")
+            .arg_ref_self()
+            .ret("&T")
+            .line("self.one");
+    }
+
+    let expect = r#"
+struct Foo<'a, 'b, T>
+where 'b: 'a,
+{
+    one: &'a T,
+    two: &'b T,
+}
+
+#[inline]
+impl<'a, T> AsRef<T> for Foo<'a, 'static, T> {
+    fn as_ref<'c>(&self) -> &T {
+        self.one
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn const_generic_and_attrs() {
+    let mut scope = Scope::new();
+
+    scope.structure("Buf")
+        .generic("const N: usize")
+        .field("bytes", "[u8; N]");
+
+    scope.constant("LEN", "usize", "4")
+        .attr("allow(dead_code)");
+
+    scope.function("main")
+        .attr("cfg(test)")
+        .line("println!(\"hello\");");
+
+    let expect = r#"
+struct Buf<const N: usize> {
+    bytes: [u8; N],
+}
+
+#[allow(dead_code)]
+const LEN: usize = 4;
+
+#[cfg(test)]
+fn main() {
+    println!("hello");
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn trait_method_where_clause() {
+    let mut scope = Scope::new();
+
+    {
+        let t = scope.trait_def("Visit");
+        t.associated_type("Out")
+            .attr("doc(hidden)")
+            .bound("Default");
+
+        t.function("visit")
+            .generic("T")
+            .arg_ref_self()
+            .arg("t", "T")
+            .bound("T", "Into<Self::Out>");
+    }
+
+    let expect = r#"
+trait Visit {
+    #[doc(hidden)]
+    type Out: Default;
+
+    fn visit<T>(&self, t: T)
+    where T: Into<Self::Out>,
+    ;
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn type_path() {
+    let mut scope = Scope::new();
+
+    scope.structure("Foo")
+        .field("one", Type::new("Foo").path("bar"))
+        .field("two", Type::new("foo::Foo").path("bar::"))
+        .field("three", Type::new("::std::string::String").path("bar"))
+        .field("four", Type::new("Foo").generic("T").path(""));
+
+    let expect = r#"
+struct Foo {
+    one: bar::Foo,
+    two: bar::foo::Foo,
+    three: ::std::string::String,
+    four: Foo<T>,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
@@ -7,6 +7,7 @@ import (
 	"os"
 	"os/signal"
 	"syscall"
+	"time"
 
 	"github.com/prometheus/client_golang/prometheus/promhttp"
 	"github.com/runconduit/conduit/controller/destination"
@@ -17,6 +18,7 @@ func main() {
 	addr := flag.String("addr", ":8089", "address to serve on")
 	metricsAddr := flag.String("metrics-addr", ":9999", "address to serve scrapable metrics on")
 	kubeConfigPath := flag.String("kubeconfig", "", "path to kube config")
+	addressUpdateInterval := flag.Duration("address-update-interval", 10*time.Second, "how often to send an update on an unchanged stream; must not exceed the proxies' CONDUIT_PROXY_ADDRESS_UPDATE_INTERVAL_SECS")
 	flag.Parse()
 
 	log.SetLevel(log.DebugLevel) // TODO: make configurable
@@ -26,7 +28,7 @@ func main() {
 
 	done := make(chan struct{})
 
-	server, lis, err := destination.NewServer(*addr, *kubeConfigPath, done)
+	server, lis, err := destination.NewServer(*addr, *kubeConfigPath, *addressUpdateInterval, done)
 	if err != nil {
 		log.Fatal(err)
 	}
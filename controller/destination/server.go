@@ -5,6 +5,8 @@ import (
 	"net"
 	"strconv"
 	"strings"
+	"sync"
+	"time"
 
 	common "github.com/runconduit/conduit/controller/gen/common"
 	pb "github.com/runconduit/conduit/controller/gen/proxy/destination"
@@ -12,14 +14,35 @@ import (
 	"github.com/runconduit/conduit/controller/util"
 	log "github.com/sirupsen/logrus"
 	"google.golang.org/grpc"
+	"google.golang.org/grpc/codes"
+	"google.golang.org/grpc/status"
+	"k8s.io/api/core/v1"
 )
 
 type (
 	server struct {
-		endpoints *k8s.EndpointsWatcher
+		endpoints   *k8s.EndpointsWatcher
+		pods        *k8s.PodIndex
+		replicaSets *k8s.ReplicaSetStore
+		nodes       *k8s.NodeStore
+
+		// How often an empty update is sent on a stream that has had no
+		// changes, so that the proxy can tell the stream is still healthy.
+		addressUpdateInterval time.Duration
 	}
 )
 
+// Labels sent with each address, describing the pod behind it.
+const (
+	podLabel        = "pod"
+	deploymentLabel = "deployment"
+	versionLabel    = "version"
+	zoneLabel       = "zone"
+
+	// The node label that Kubernetes uses for the node's availability zone.
+	nodeZoneLabel = "failure-domain.beta.kubernetes.io/zone"
+)
+
 // The Destination service serves service discovery information to the proxy.
 // This implementation supports the "k8s" destination scheme and expects
 // destination paths to be of the form:
@@ -29,8 +52,21 @@ type (
 // omitted, "default" is used as a default.append
 //
 // Addresses for the given destination are fetched from the Kubernetes Endpoints
-// API.
-func NewServer(addr, kubeconfig string, done chan struct{}) (*grpc.Server, net.Listener, error) {
+// API. Destinations that are not of this form fail with InvalidArgument, which
+// tells the proxy to resolve them some other way.
+//
+// Each address is labeled with its pod, deployment, version and zone, as far
+// as they are known.
+//
+// Every stream is sent an update at least once every addressUpdateInterval.
+// Proxies treat a stream as stale once it has been silent for two of their own
+// CONDUIT_PROXY_ADDRESS_UPDATE_INTERVAL_SECS, so addressUpdateInterval must be
+// no longer than the proxies' interval.
+func NewServer(addr, kubeconfig string, addressUpdateInterval time.Duration, done chan struct{}) (*grpc.Server, net.Listener, error) {
+	if addressUpdateInterval <= 0 {
+		return nil, nil, fmt.Errorf("address update interval must be positive: %s", addressUpdateInterval)
+	}
+
 	clientSet, err := k8s.NewClientSet(kubeconfig)
 	if err != nil {
 		return nil, nil, err
@@ -42,8 +78,40 @@ func NewServer(addr, kubeconfig string, done chan struct{}) (*grpc.Server, net.L
 		return nil, nil, err
 	}
 
+	pods, err := k8s.NewPodIndex(clientSet, podIPKeyFunc)
+	if err != nil {
+		return nil, nil, err
+	}
+	err = pods.Run()
+	if err != nil {
+		return nil, nil, err
+	}
+
+	replicaSets, err := k8s.NewReplicaSetStore(clientSet)
+	if err != nil {
+		return nil, nil, err
+	}
+	err = replicaSets.Run()
+	if err != nil {
+		return nil, nil, err
+	}
+
+	nodes, err := k8s.NewNodeStore(clientSet)
+	if err != nil {
+		return nil, nil, err
+	}
+	err = nodes.Run()
+	if err != nil {
+		return nil, nil, err
+	}
+
 	srv := &server{
-		endpoints: endpoints,
+		endpoints:   endpoints,
+		pods:        pods,
+		replicaSets: replicaSets,
+		nodes:       nodes,
+
+		addressUpdateInterval: addressUpdateInterval,
 	}
 
 	lis, err := net.Listen("tcp", addr)
@@ -57,6 +125,9 @@ func NewServer(addr, kubeconfig string, done chan struct{}) (*grpc.Server, net.L
 	go func() {
 		<-done
 		endpoints.Stop()
+		pods.Stop()
+		replicaSets.Stop()
+		nodes.Stop()
 	}()
 
 	return s, lis, nil
@@ -65,13 +136,13 @@ func NewServer(addr, kubeconfig string, done chan struct{}) (*grpc.Server, net.L
 func (s *server) Get(dest *common.Destination, stream pb.Destination_GetServer) error {
 	log.Debugf("Get %v", dest)
 	if dest.Scheme != "k8s" {
-		err := fmt.Errorf("Unsupported scheme %v", dest.Scheme)
+		err := status.Errorf(codes.InvalidArgument, "Unsupported scheme %v", dest.Scheme)
 		log.Error(err)
 		return err
 	}
 	hostPort := strings.Split(dest.Path, ":")
 	if len(hostPort) > 2 {
-		err := fmt.Errorf("Invalid destination %s", dest.Path)
+		err := status.Errorf(codes.InvalidArgument, "Invalid destination %s", dest.Path)
 		log.Error(err)
 		return err
 	}
@@ -81,7 +152,7 @@ func (s *server) Get(dest *common.Destination, stream pb.Destination_GetServer)
 		var err error
 		port, err = strconv.Atoi(hostPort[1])
 		if err != nil {
-			err := fmt.Errorf("Invalid port %s", hostPort[1])
+			err := status.Errorf(codes.InvalidArgument, "Invalid port %s", hostPort[1])
 			log.Error(err)
 			return err
 		}
@@ -90,7 +161,7 @@ func (s *server) Get(dest *common.Destination, stream pb.Destination_GetServer)
 	domains := strings.Split(host, ".")
 
 	if len(domains) < 2 {
-		err := fmt.Errorf("not a service: %s", host)
+		err := status.Errorf(codes.InvalidArgument, "not a service: %s", host)
 		log.Error(err)
 		return err
 	}
@@ -100,26 +171,85 @@ func (s *server) Get(dest *common.Destination, stream pb.Destination_GetServer)
 
 	id := namespace + "/" + service
 
-	listener := endpointListener{stream: stream}
+	listener := &endpointListener{stream: stream, labels: s.labels}
 
 	s.endpoints.Subscribe(id, uint32(port), listener)
 
-	<-stream.Context().Done()
+	heartbeat := time.NewTicker(s.addressUpdateInterval)
+	defer heartbeat.Stop()
+
+	for {
+		select {
+		case <-heartbeat.C:
+			listener.Heartbeat()
+		case <-stream.Context().Done():
+			s.endpoints.Unsubscribe(id, uint32(port), listener)
+			return nil
+		}
+	}
+}
+
+// labels describes the pod with the given address. Addresses that do not
+// belong to a known pod have no labels.
+func (s *server) labels(addr *common.TcpAddress) map[string]string {
+	ip := util.IPToString(addr.GetIp())
+	pods, err := s.pods.GetPodsByIndex(ip)
+	if err != nil || len(pods) == 0 {
+		log.Debugf("Cannot get pod for address %s: %v", ip, err)
+		return nil
+	}
+	pod := pods[0]
 
-	s.endpoints.Unsubscribe(id, uint32(port), listener)
+	labels := map[string]string{
+		podLabel: pod.Namespace + "/" + pod.Name,
+	}
 
-	return nil
+	deployment, err := s.replicaSets.GetDeploymentForPod(pod)
+	if err != nil {
+		log.Debugf("Cannot get deployment for pod %s: %s", pod.Name, err)
+	} else {
+		labels[deploymentLabel] = deployment
+	}
+
+	if version, ok := pod.Labels[versionLabel]; ok {
+		labels[versionLabel] = version
+	}
+
+	if pod.Spec.NodeName != "" {
+		node, err := s.nodes.GetNode(pod.Spec.NodeName)
+		if err != nil {
+			log.Debugf("Cannot get node for pod %s: %s", pod.Name, err)
+		} else if zone, ok := node.Labels[nodeZoneLabel]; ok {
+			labels[zoneLabel] = zone
+		}
+	}
+
+	return labels
+}
+
+func podIPKeyFunc(obj interface{}) ([]string, error) {
+	if pod, ok := obj.(*v1.Pod); ok {
+		return []string{pod.Status.PodIP}, nil
+	}
+	return nil, fmt.Errorf("Object is not a Pod")
 }
 
 type endpointListener struct {
+	// Serializes sends, which come from both the endpoints watcher and the
+	// heartbeat.
+	mutex  sync.Mutex
 	stream pb.Destination_GetServer
+	labels func(*common.TcpAddress) map[string]string
 }
 
-func (listener endpointListener) Update(add []common.TcpAddress, remove []common.TcpAddress) {
+func (listener *endpointListener) Update(add []common.TcpAddress, remove []common.TcpAddress) {
+	listener.mutex.Lock()
+	defer listener.mutex.Unlock()
+
 	if len(add) > 0 {
 		update := &pb.Update{
 			Update: &pb.Update_Add{
-				Add: toWeightedAddrSet(add),
+				Add: toWeightedAddrSet(add, listener.labels),
 			},
 		}
 		listener.stream.Send(update)
@@ -134,12 +264,26 @@ func (listener endpointListener) Update(add []common.TcpAddress, remove []common
 	}
 }
 
-func toWeightedAddrSet(endpoints []common.TcpAddress) *pb.WeightedAddrSet {
+// Heartbeat sends an empty update.
+func (listener *endpointListener) Heartbeat() {
+	listener.mutex.Lock()
+	defer listener.mutex.Unlock()
+
+	update := &pb.Update{
+		Update: &pb.Update_Add{
+			Add: &pb.WeightedAddrSet{},
+		},
+	}
+	listener.stream.Send(update)
+}
+
+func toWeightedAddrSet(endpoints []common.TcpAddress, labels func(*common.TcpAddress) map[string]string) *pb.WeightedAddrSet {
 	addrs := make([]*pb.WeightedAddr, 0)
 	for i := range endpoints {
 		addrs = append(addrs, &pb.WeightedAddr{
 			Addr:   &endpoints[i],
 			Weight: 1,
+			Labels: labels(&endpoints[i]),
 		})
 	}
 	return &pb.WeightedAddrSet{Addrs: addrs}
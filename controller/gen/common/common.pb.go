@@ -533,6 +533,8 @@ func (m *Destination) GetPath() string {
 type TapEvent struct {
 	Source *TcpAddress `protobuf:"bytes,1,opt,name=source" json:"source,omitempty"`
 	Target *TcpAddress `protobuf:"bytes,2,opt,name=target" json:"target,omitempty"`
+	// The labels of `target`, as given by the Destination API.
+	TargetLabels map[string]string `protobuf:"bytes,4,rep,name=target_labels,json=targetLabels" json:"target_labels,omitempty" protobuf_key:"bytes,1,opt,name=key" protobuf_val:"bytes,2,opt,name=value"`
 	// Types that are valid to be assigned to Event:
 	//	*TapEvent_Http_
 	Event isTapEvent_Event `protobuf_oneof:"event"`
@@ -574,6 +576,13 @@ func (m *TapEvent) GetTarget() *TcpAddress {
 	return nil
 }
 
+func (m *TapEvent) GetTargetLabels() map[string]string {
+	if m != nil {
+		return m.TargetLabels
+	}
+	return nil
+}
+
 func (m *TapEvent) GetHttp() *TapEvent_Http {
 	if x, ok := m.GetEvent().(*TapEvent_Http_); ok {
 		return x.Http
@@ -647,7 +656,7 @@ type TapEvent_Http struct {
 func (m *TapEvent_Http) Reset()                    { *m = TapEvent_Http{} }
 func (m *TapEvent_Http) String() string            { return proto.CompactTextString(m) }
 func (*TapEvent_Http) ProtoMessage()               {}
-func (*TapEvent_Http) Descriptor() ([]byte, []int) { return fileDescriptor0, []int{6, 0} }
+func (*TapEvent_Http) Descriptor() ([]byte, []int) { return fileDescriptor0, []int{6, 1} }
 
 type isTapEvent_Http_Event interface {
 	isTapEvent_Http_Event()
@@ -798,7 +807,7 @@ type TapEvent_Http_StreamId struct {
 func (m *TapEvent_Http_StreamId) Reset()                    { *m = TapEvent_Http_StreamId{} }
 func (m *TapEvent_Http_StreamId) String() string            { return proto.CompactTextString(m) }
 func (*TapEvent_Http_StreamId) ProtoMessage()               {}
-func (*TapEvent_Http_StreamId) Descriptor() ([]byte, []int) { return fileDescriptor0, []int{6, 0, 0} }
+func (*TapEvent_Http_StreamId) Descriptor() ([]byte, []int) { return fileDescriptor0, []int{6, 1, 0} }
 
 func (m *TapEvent_Http_StreamId) GetBase() uint32 {
 	if m != nil {
@@ -825,7 +834,7 @@ type TapEvent_Http_RequestInit struct {
 func (m *TapEvent_Http_RequestInit) Reset()                    { *m = TapEvent_Http_RequestInit{} }
 func (m *TapEvent_Http_RequestInit) String() string            { return proto.CompactTextString(m) }
 func (*TapEvent_Http_RequestInit) ProtoMessage()               {}
-func (*TapEvent_Http_RequestInit) Descriptor() ([]byte, []int) { return fileDescriptor0, []int{6, 0, 1} }
+func (*TapEvent_Http_RequestInit) Descriptor() ([]byte, []int) { return fileDescriptor0, []int{6, 1, 1} }
 
 func (m *TapEvent_Http_RequestInit) GetId() *TapEvent_Http_StreamId {
 	if m != nil {
@@ -872,7 +881,7 @@ func (m *TapEvent_Http_ResponseInit) Reset()         { *m = TapEvent_Http_Respon
 func (m *TapEvent_Http_ResponseInit) String() string { return proto.CompactTextString(m) }
 func (*TapEvent_Http_ResponseInit) ProtoMessage()    {}
 func (*TapEvent_Http_ResponseInit) Descriptor() ([]byte, []int) {
-	return fileDescriptor0, []int{6, 0, 2}
+	return fileDescriptor0, []int{6, 1, 2}
 }
 
 func (m *TapEvent_Http_ResponseInit) GetId() *TapEvent_Http_StreamId {
@@ -907,7 +916,7 @@ type TapEvent_Http_ResponseEnd struct {
 func (m *TapEvent_Http_ResponseEnd) Reset()                    { *m = TapEvent_Http_ResponseEnd{} }
 func (m *TapEvent_Http_ResponseEnd) String() string            { return proto.CompactTextString(m) }
 func (*TapEvent_Http_ResponseEnd) ProtoMessage()               {}
-func (*TapEvent_Http_ResponseEnd) Descriptor() ([]byte, []int) { return fileDescriptor0, []int{6, 0, 3} }
+func (*TapEvent_Http_ResponseEnd) Descriptor() ([]byte, []int) { return fileDescriptor0, []int{6, 1, 3} }
 
 func (m *TapEvent_Http_ResponseEnd) GetId() *TapEvent_Http_StreamId {
 	if m != nil {
@@ -965,56 +974,59 @@ func init() {
 func init() { proto.RegisterFile("common/common.proto", fileDescriptor0) }
 
 var fileDescriptor0 = []byte{
-	// 808 bytes of a gzipped FileDescriptorProto
-	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0xc4, 0x54, 0xdd, 0x6e, 0xe3, 0x54,
-	0x10, 0x8e, 0x1d, 0xc7, 0x49, 0x26, 0x4d, 0x65, 0xce, 0x56, 0xab, 0x12, 0xb1, 0xb0, 0x1b, 0xb1,
-	0x68, 0xdb, 0x0b, 0x17, 0x65, 0x51, 0x24, 0x2e, 0xf3, 0x63, 0x35, 0x11, 0x90, 0x98, 0x13, 0x73,
-	0x5d, 0x39, 0xf6, 0xd9, 0xc4, 0x52, 0x63, 0x9b, 0x73, 0x8e, 0x2b, 0xf5, 0x65, 0x90, 0xe0, 0x09,
-	0x78, 0x1f, 0x1e, 0x82, 0x0b, 0x5e, 0x00, 0x9d, 0x9f, 0x38, 0x4e, 0x77, 0x69, 0x11, 0x5c, 0xec,
-	0x55, 0x66, 0x26, 0x33, 0x9f, 0xbf, 0xf9, 0x66, 0xce, 0xc0, 0xb3, 0x28, 0xdb, 0xed, 0xb2, 0xf4,
-	0x4a, 0xfd, 0xb8, 0x39, 0xcd, 0x78, 0x86, 0x4e, 0xa3, 0x2c, 0x8d, 0x8b, 0x84, 0xbb, 0x2a, 0xda,
-	0xfb, 0x7c, 0x93, 0x65, 0x9b, 0x5b, 0x72, 0x25, 0xff, 0x5d, 0x17, 0xef, 0xae, 0xe2, 0x82, 0x86,
-	0x3c, 0xd9, 0xe7, 0xf7, 0xff, 0x32, 0x00, 0x66, 0x9c, 0xe7, 0x3f, 0x10, 0xbe, 0xcd, 0x62, 0x74,
-	0x0d, 0x40, 0xc9, 0x26, 0x61, 0x9c, 0x50, 0x12, 0x9f, 0x1b, 0x2f, 0x8d, 0x37, 0xa7, 0x83, 0xd7,
-	0xee, 0x31, 0xa6, 0x7b, 0xc8, 0x77, 0x71, 0x99, 0x3c, 0xab, 0xe1, 0x4a, 0x29, 0xfa, 0x12, 0x4e,
-	0x8a, 0xb4, 0x02, 0x65, 0xbe, 0x34, 0xde, 0xb4, 0x67, 0x35, 0x7c, 0x14, 0xed, 0xa7, 0x00, 0x07,
-	0x04, 0xd4, 0x84, 0xfa, 0xb5, 0x17, 0x38, 0x35, 0xd4, 0x02, 0xcb, 0x5f, 0xae, 0x02, 0xc7, 0x10,
-	0x21, 0xff, 0xa7, 0xc0, 0x31, 0x11, 0x80, 0x3d, 0xf5, 0xbe, 0xf7, 0x02, 0xcf, 0xa9, 0xa3, 0x36,
-	0x34, 0xfc, 0x51, 0x30, 0x99, 0x39, 0x16, 0xea, 0x40, 0x73, 0xe9, 0x07, 0xf3, 0xe5, 0x62, 0xe5,
-	0x34, 0x84, 0x33, 0x59, 0x2e, 0x16, 0xde, 0x24, 0x70, 0x6c, 0x81, 0x31, 0xf3, 0x46, 0x53, 0xa7,
-	0x29, 0xd2, 0x03, 0x3c, 0x9a, 0x78, 0x4e, 0x6b, 0x6c, 0x83, 0xc5, 0xef, 0x73, 0xd2, 0xff, 0xc5,
-	0x00, 0x7b, 0x15, 0x6d, 0xc9, 0x8e, 0xa0, 0xc9, 0x07, 0x3a, 0x7e, 0xf5, 0xb0, 0x63, 0x95, 0xfb,
-	0x7f, 0xbb, 0x7d, 0x75, 0xd4, 0xad, 0x20, 0x18, 0x04, 0xbe, 0x53, 0x13, 0x04, 0x85, 0xb5, 0x72,
-	0x8c, 0x92, 0xe0, 0x0a, 0xda, 0x73, 0x7f, 0x14, 0xc7, 0x94, 0x30, 0x86, 0xce, 0xc0, 0x4a, 0xf2,
-	0xbb, 0x6f, 0x24, 0xb9, 0xe6, 0xac, 0x86, 0xa5, 0x87, 0x2e, 0x65, 0x74, 0x28, 0xbf, 0xd5, 0x19,
-	0x9c, 0x3d, 0xa4, 0x3c, 0xf7, 0xef, 0x86, 0x3a, 0x77, 0x38, 0xb6, 0xc0, 0x4c, 0xf2, 0xfe, 0xd7,
-	0x60, 0x89, 0x28, 0x3a, 0x83, 0xc6, 0xbb, 0x84, 0x32, 0x2e, 0x01, 0x6d, 0xac, 0x1c, 0x84, 0xc0,
-	0xba, 0x0d, 0x19, 0x97, 0x78, 0x36, 0x96, 0x76, 0xff, 0x3b, 0x80, 0x20, 0xca, 0xf7, 0x3c, 0x2e,
-	0x04, 0x8a, 0x2c, 0xea, 0x0c, 0x3e, 0x7d, 0xff, 0x7b, 0x3a, 0x0d, 0x9b, 0x49, 0x2e, 0xc0, 0xf2,
-	0x8c, 0x2a, 0xb0, 0x2e, 0x96, 0x76, 0xff, 0x5b, 0xe8, 0x4c, 0x09, 0xe3, 0x49, 0x2a, 0xf7, 0x0f,
-	0x3d, 0x07, 0x9b, 0x49, 0x59, 0x25, 0x62, 0x1b, 0x6b, 0x4f, 0x96, 0x86, 0x7c, 0xab, 0x34, 0xc4,
-	0xd2, 0xee, 0xff, 0xd6, 0x86, 0x56, 0x10, 0xe6, 0xde, 0x1d, 0x49, 0x39, 0x1a, 0x80, 0xcd, 0xb2,
-	0x82, 0x46, 0x44, 0x53, 0xe9, 0x3d, 0xa4, 0x72, 0xa0, 0x8c, 0x75, 0xa6, 0xa8, 0xe1, 0x21, 0xdd,
-	0x10, 0xae, 0xe5, 0x7a, 0xb4, 0x46, 0x65, 0xa2, 0xb7, 0x60, 0x6d, 0x39, 0xcf, 0xcf, 0xeb, 0xb2,
-	0xe2, 0xc5, 0x7b, 0x15, 0x9a, 0x8f, 0x7c, 0x0e, 0x42, 0x69, 0x91, 0xdc, 0xfb, 0xb3, 0x09, 0x96,
-	0x08, 0xa0, 0x05, 0x9c, 0x50, 0xf2, 0x73, 0x41, 0x18, 0xbf, 0x49, 0xd2, 0x84, 0x6b, 0xae, 0x17,
-	0x8f, 0xa2, 0xb8, 0x58, 0x55, 0xcc, 0xd3, 0x84, 0xcf, 0x6a, 0xb8, 0x43, 0x0f, 0x2e, 0xfa, 0x11,
-	0xba, 0x94, 0xb0, 0x3c, 0x4b, 0x19, 0x51, 0x80, 0xaa, 0x91, 0xcb, 0xa7, 0x00, 0x55, 0x89, 0x46,
-	0x3c, 0xa1, 0x15, 0x5f, 0x51, 0xd4, 0x90, 0x24, 0x8d, 0x75, 0xa3, 0x17, 0xff, 0x0e, 0xd1, 0x4b,
-	0x63, 0x45, 0xb1, 0x74, 0x7b, 0x43, 0x68, 0xad, 0x38, 0x25, 0xe1, 0x6e, 0x1e, 0x8b, 0x29, 0xae,
-	0x43, 0xa6, 0x46, 0xd4, 0xc5, 0xd2, 0x96, 0x13, 0x97, 0xff, 0x4b, 0xee, 0x16, 0xd6, 0x5e, 0xef,
-	0x0f, 0x03, 0x3a, 0x95, 0xce, 0xd1, 0x10, 0xcc, 0x24, 0xd6, 0x82, 0x7d, 0xf5, 0x38, 0x9b, 0xfd,
-	0xf7, 0xb0, 0x99, 0xc4, 0x62, 0xc8, 0x3b, 0x79, 0x96, 0xfe, 0x69, 0xc8, 0x87, 0xc3, 0x85, 0x75,
-	0x26, 0x72, 0xcb, 0x2d, 0x54, 0xdd, 0x3f, 0xff, 0xf0, 0xd3, 0x2f, 0xb7, 0xf3, 0x33, 0x68, 0x87,
-	0x05, 0xdf, 0x66, 0x34, 0xe1, 0xf7, 0xe7, 0x96, 0x5c, 0xd1, 0x43, 0xa0, 0xdc, 0xdd, 0xc6, 0x61,
-	0x77, 0x7b, 0xbf, 0x1b, 0x70, 0x52, 0x1d, 0xc3, 0x7f, 0x6e, 0xef, 0x1a, 0x10, 0x4b, 0xd2, 0x88,
-	0xdc, 0x1c, 0xed, 0x95, 0xa9, 0x9f, 0xa3, 0xba, 0xf3, 0xee, 0xfe, 0xce, 0xbb, 0x53, 0x7d, 0xe7,
-	0xb1, 0x23, 0x8b, 0xaa, 0xfa, 0x7e, 0x01, 0x1d, 0xb1, 0xab, 0x37, 0x8c, 0x87, 0xbc, 0x60, 0xb2,
-	0xf1, 0x2e, 0x06, 0x11, 0x5a, 0xc9, 0x48, 0xef, 0x57, 0x53, 0x0c, 0xa4, 0x1c, 0xec, 0xc7, 0x67,
-	0x3c, 0x87, 0x67, 0x7b, 0xa0, 0xea, 0x13, 0xa8, 0x3f, 0x85, 0xf4, 0x89, 0x46, 0xaa, 0xa8, 0xff,
-	0x1a, 0x4e, 0x4b, 0x90, 0xf5, 0x3d, 0x27, 0x4c, 0x4e, 0xd1, 0xc2, 0xe5, 0xeb, 0x1a, 0x8b, 0xa0,
-	0xd0, 0x68, 0x43, 0xf3, 0x68, 0xaf, 0x51, 0x43, 0x69, 0x24, 0x42, 0x4a, 0xa3, 0x71, 0x13, 0x1a,
-	0x44, 0xb4, 0x5d, 0x1a, 0x97, 0x2f, 0xa0, 0xe5, 0x0b, 0x06, 0x51, 0x76, 0x5b, 0x39, 0xee, 0x4d,
-	0xa8, 0x07, 0x13, 0xdf, 0x31, 0xd6, 0xb6, 0xa4, 0xf7, 0xf6, 0xef, 0x00, 0x00, 0x00, 0xff, 0xff,
-	0x2d, 0x99, 0x17, 0xde, 0xb7, 0x07, 0x00, 0x00,
+	// 863 bytes of a gzipped FileDescriptorProto
+	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0xc5, 0x55, 0xcd, 0x6e, 0xd3, 0x40,
+	0x10, 0xae, 0x13, 0xe7, 0x6f, 0x92, 0x54, 0xee, 0xb6, 0xaa, 0x4a, 0x44, 0xa1, 0x44, 0x14, 0xd1,
+	0x1e, 0x52, 0xd4, 0xa2, 0x08, 0xb8, 0xa0, 0x36, 0xb5, 0xda, 0x88, 0x92, 0x18, 0xc7, 0x9c, 0x2b,
+	0x27, 0xde, 0xa6, 0x16, 0x89, 0x6d, 0xec, 0x75, 0xa4, 0xbc, 0x0c, 0x12, 0x6f, 0xc0, 0x81, 0xb7,
+	0xe1, 0x21, 0x38, 0xf0, 0x02, 0xec, 0x5f, 0x1c, 0xa7, 0xbf, 0x08, 0x0e, 0x9c, 0x32, 0x33, 0x99,
+	0xf9, 0xfc, 0xcd, 0xb7, 0x33, 0xbb, 0xb0, 0x3a, 0xf0, 0xc7, 0x63, 0xdf, 0xdb, 0x13, 0x3f, 0x8d,
+	0x20, 0xf4, 0x89, 0x8f, 0x96, 0x07, 0xbe, 0xe7, 0xc4, 0x2e, 0x69, 0x88, 0x68, 0xed, 0xd1, 0xd0,
+	0xf7, 0x87, 0x23, 0xbc, 0xc7, 0xff, 0xed, 0xc7, 0x17, 0x7b, 0x4e, 0x1c, 0xda, 0xc4, 0x9d, 0xe5,
+	0xd7, 0x7f, 0x29, 0x00, 0xa7, 0x84, 0x04, 0xef, 0x31, 0xb9, 0xf4, 0x1d, 0x74, 0x02, 0x10, 0xe2,
+	0xa1, 0x1b, 0x11, 0x1c, 0x62, 0x67, 0x43, 0xd9, 0x52, 0x9e, 0x2f, 0xef, 0x6f, 0x37, 0x16, 0x31,
+	0x1b, 0xf3, 0xfc, 0x86, 0x99, 0x24, 0x9f, 0x2e, 0x99, 0xa9, 0x52, 0xf4, 0x14, 0x2a, 0xb1, 0x97,
+	0x82, 0xca, 0x50, 0xa8, 0x12, 0xcd, 0x59, 0x88, 0xd6, 0x3d, 0x80, 0x39, 0x02, 0x2a, 0x40, 0xf6,
+	0x44, 0xb7, 0xb4, 0x25, 0x54, 0x04, 0xd5, 0xe8, 0xf6, 0x2c, 0x4d, 0x61, 0x21, 0xe3, 0xa3, 0xa5,
+	0x65, 0x10, 0x40, 0xfe, 0x58, 0x3f, 0xd3, 0x2d, 0x5d, 0xcb, 0xa2, 0x12, 0xe4, 0x8c, 0x43, 0xab,
+	0x75, 0xaa, 0xa9, 0xa8, 0x0c, 0x85, 0xae, 0x61, 0xb5, 0xbb, 0x9d, 0x9e, 0x96, 0x63, 0x4e, 0xab,
+	0xdb, 0xe9, 0xe8, 0x2d, 0x4b, 0xcb, 0x33, 0x8c, 0x53, 0xfd, 0xf0, 0x58, 0x2b, 0xb0, 0x74, 0xcb,
+	0x3c, 0x6c, 0xe9, 0x5a, 0xf1, 0x28, 0x0f, 0x2a, 0x99, 0x06, 0xb8, 0xfe, 0x45, 0x81, 0x7c, 0x6f,
+	0x70, 0x89, 0xc7, 0x18, 0xb5, 0x6e, 0xe8, 0xf8, 0xc9, 0xd5, 0x8e, 0x45, 0xee, 0xbf, 0x76, 0xfb,
+	0x64, 0xa1, 0x5b, 0x46, 0xd0, 0xb2, 0x0c, 0xda, 0x2e, 0x25, 0xc8, 0xac, 0x9e, 0xa6, 0x24, 0x04,
+	0x7b, 0x50, 0x6a, 0x1b, 0x87, 0x8e, 0x13, 0xe2, 0x28, 0x42, 0x6b, 0xa0, 0xba, 0xc1, 0xe4, 0x25,
+	0x27, 0x57, 0xa0, 0xa8, 0xdc, 0x43, 0xbb, 0x3c, 0xda, 0xe4, 0xdf, 0x2a, 0xef, 0xaf, 0x5d, 0xa5,
+	0xdc, 0x36, 0x26, 0x4d, 0x99, 0xdb, 0x3c, 0x52, 0x21, 0xe3, 0x06, 0xf5, 0x17, 0xa0, 0xb2, 0x28,
+	0xc5, 0xcb, 0x5d, 0xb8, 0x61, 0x44, 0x38, 0x60, 0xde, 0x14, 0x0e, 0x42, 0xa0, 0x8e, 0x6c, 0x1a,
+	0xcc, 0xf0, 0x20, 0xb7, 0xeb, 0xef, 0x00, 0xac, 0x41, 0x30, 0xe3, 0xb1, 0xc3, 0x50, 0x78, 0x51,
+	0x79, 0xff, 0xc1, 0xf5, 0xef, 0xc9, 0x34, 0x93, 0x26, 0x31, 0xb0, 0xc0, 0x0f, 0x05, 0x58, 0xd5,
+	0xe4, 0x76, 0xfd, 0x35, 0x94, 0x8f, 0x71, 0x44, 0x5c, 0x8f, 0xcf, 0x1f, 0x5a, 0x87, 0x7c, 0xc4,
+	0x65, 0xe5, 0x88, 0x25, 0x53, 0x7a, 0xbc, 0xd4, 0x26, 0x97, 0x42, 0x43, 0x93, 0xdb, 0xf5, 0xef,
+	0x00, 0x45, 0xcb, 0x0e, 0xf4, 0x09, 0xf6, 0x08, 0xda, 0xa7, 0x85, 0x7e, 0x1c, 0x0e, 0xb0, 0xa4,
+	0x52, 0xbb, 0x4a, 0x65, 0x4e, 0xd9, 0x94, 0x99, 0xac, 0x86, 0xd8, 0xe1, 0x10, 0x13, 0x29, 0xd7,
+	0x9d, 0x35, 0x22, 0x13, 0x75, 0xa1, 0x2a, 0xac, 0xf3, 0x91, 0xdd, 0xc7, 0xa3, 0x68, 0x43, 0xdd,
+	0xca, 0xd2, 0xd2, 0xdd, 0x6b, 0xa5, 0x92, 0x18, 0x35, 0x58, 0xf6, 0x19, 0x4f, 0xd6, 0x3d, 0x12,
+	0x4e, 0xcd, 0x0a, 0x49, 0x85, 0xd0, 0x01, 0xa8, 0x97, 0x74, 0x75, 0x36, 0xb2, 0x9c, 0xc2, 0xe6,
+	0xad, 0x38, 0x6c, 0xbf, 0xd8, 0xd1, 0xb1, 0xe4, 0xda, 0x5b, 0x58, 0xb9, 0x86, 0x8b, 0x34, 0xc8,
+	0x7e, 0xc2, 0x53, 0x29, 0x1c, 0x33, 0xd9, 0x99, 0x4e, 0xec, 0x51, 0x8c, 0xa5, 0x6c, 0xc2, 0x79,
+	0x93, 0x79, 0xa5, 0xd4, 0x7e, 0x16, 0xe8, 0xa0, 0x51, 0x24, 0xd4, 0x81, 0x4a, 0x88, 0x3f, 0xc7,
+	0xf4, 0x08, 0xce, 0x5d, 0xcf, 0x25, 0x52, 0xbd, 0x9d, 0x3b, 0x69, 0xd0, 0x91, 0xe7, 0x15, 0x6d,
+	0x5a, 0x40, 0x29, 0x95, 0xc3, 0xb9, 0x8b, 0x3e, 0x40, 0x95, 0xea, 0x15, 0xf8, 0x5e, 0x84, 0x05,
+	0xa0, 0x90, 0x76, 0xf7, 0x3e, 0x40, 0x51, 0x22, 0x11, 0x2b, 0x61, 0xca, 0x17, 0x14, 0x25, 0x24,
+	0xf6, 0x1c, 0xa9, 0xd4, 0xce, 0x9f, 0x21, 0xea, 0x9e, 0x23, 0x28, 0x26, 0x6e, 0xad, 0x09, 0xc5,
+	0x1e, 0x09, 0xb1, 0x3d, 0x6e, 0x3b, 0x6c, 0xae, 0xfa, 0x76, 0x24, 0x86, 0x86, 0x8e, 0x24, 0xb3,
+	0xf9, 0x0c, 0xf2, 0xff, 0x39, 0x77, 0xd5, 0x94, 0x5e, 0xed, 0x87, 0x02, 0xe5, 0x54, 0xe7, 0xa8,
+	0x49, 0x27, 0xdf, 0x91, 0x82, 0x3d, 0xbb, 0x9b, 0xcd, 0xec, 0x7b, 0x74, 0x0d, 0x1c, 0x36, 0x76,
+	0x63, 0x7e, 0x51, 0xde, 0x36, 0x76, 0xf3, 0xab, 0xd4, 0x94, 0x99, 0xa8, 0x91, 0xec, 0x85, 0xe8,
+	0x7e, 0xfd, 0xe6, 0xcb, 0x28, 0xd9, 0x97, 0x87, 0x50, 0xb2, 0x63, 0x5a, 0x19, 0xba, 0x64, 0x4a,
+	0x47, 0x94, 0x9d, 0xfe, 0x3c, 0x90, 0x6c, 0x53, 0x6e, 0xbe, 0x4d, 0xb5, 0x6f, 0x0a, 0x54, 0xd2,
+	0xc7, 0xf0, 0xd7, 0xed, 0x9d, 0x00, 0x8a, 0x5c, 0x6f, 0x80, 0xcf, 0x17, 0xe6, 0x2a, 0x23, 0x2f,
+	0x08, 0xf1, 0xf2, 0x34, 0x66, 0x2f, 0x4f, 0xe3, 0x58, 0xbe, 0x3c, 0xa6, 0xc6, 0x8b, 0xd2, 0xfa,
+	0x3e, 0x86, 0x32, 0x1b, 0xf6, 0xf3, 0x88, 0xd8, 0x24, 0x8e, 0x78, 0xe3, 0x55, 0x13, 0x58, 0xa8,
+	0xc7, 0x23, 0xb5, 0xaf, 0x19, 0x76, 0x20, 0xc9, 0xc1, 0xfe, 0x7f, 0xc6, 0x6d, 0x58, 0x9d, 0x01,
+	0xa5, 0x57, 0x20, 0x7b, 0x1f, 0xd2, 0x8a, 0x44, 0x4a, 0xa9, 0xbf, 0x0d, 0xcb, 0x09, 0x48, 0x7f,
+	0x4a, 0x70, 0xc4, 0x4f, 0x51, 0x35, 0x93, 0xed, 0x3a, 0x62, 0x41, 0xa6, 0xd1, 0x30, 0x0c, 0x06,
+	0x33, 0x8d, 0x72, 0x42, 0x23, 0x16, 0x12, 0x1a, 0x1d, 0x15, 0x20, 0x87, 0x59, 0xdb, 0x89, 0xb1,
+	0xbb, 0x09, 0x45, 0x83, 0x31, 0x18, 0xf8, 0xa3, 0xd4, 0x73, 0x43, 0xdf, 0x54, 0xab, 0x65, 0x68,
+	0x4a, 0x3f, 0xcf, 0xe9, 0x1d, 0xfc, 0x06, 0x6e, 0x78, 0x1d, 0xd3, 0x49, 0x08, 0x00, 0x00,
 }
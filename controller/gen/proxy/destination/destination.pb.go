@@ -192,6 +192,10 @@ func (m *WeightedAddrSet) GetAddrs() []*WeightedAddr {
 type WeightedAddr struct {
 	Addr   *conduit_common.TcpAddress `protobuf:"bytes,1,opt,name=addr" json:"addr,omitempty"`
 	Weight uint32                     `protobuf:"varint,3,opt,name=weight" json:"weight,omitempty"`
+	// Describes the endpoint at `addr`, e.g. its pod, deployment, version and
+	// zone. The proxy includes these in telemetry and tap events about traffic
+	// to the endpoint.
+	Labels map[string]string `protobuf:"bytes,4,rep,name=labels" json:"labels,omitempty" protobuf_key:"bytes,1,opt,name=key" protobuf_val:"bytes,2,opt,name=value"`
 }
 
 func (m *WeightedAddr) Reset()                    { *m = WeightedAddr{} }
@@ -213,6 +217,13 @@ func (m *WeightedAddr) GetWeight() uint32 {
 	return 0
 }
 
+func (m *WeightedAddr) GetLabels() map[string]string {
+	if m != nil {
+		return m.Labels
+	}
+	return nil
+}
+
 func init() {
 	proto.RegisterType((*Update)(nil), "conduit.proxy.destination.Update")
 	proto.RegisterType((*AddrSet)(nil), "conduit.proxy.destination.AddrSet")
@@ -326,23 +337,26 @@ var _Destination_serviceDesc = grpc.ServiceDesc{
 func init() { proto.RegisterFile("proxy/destination/destination.proto", fileDescriptor0) }
 
 var fileDescriptor0 = []byte{
-	// 281 bytes of a gzipped FileDescriptorProto
-	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0x8c, 0x91, 0x4b, 0x4b, 0xc4, 0x30,
-	0x14, 0x85, 0x27, 0x56, 0xa3, 0xdc, 0x2a, 0x42, 0x04, 0xa9, 0x75, 0x53, 0xe3, 0xc2, 0xe2, 0x22,
-	0x33, 0xd4, 0xa5, 0x0f, 0x50, 0x04, 0x75, 0x27, 0xf5, 0xb9, 0xad, 0xbd, 0x41, 0xbb, 0x68, 0x53,
-	0xd2, 0x8c, 0x8f, 0x7f, 0xe1, 0x4f, 0x96, 0xa6, 0xd1, 0xa9, 0x03, 0x16, 0x57, 0x79, 0xdc, 0x73,
-	0xbe, 0x9c, 0x9b, 0x0b, 0xbb, 0xb5, 0x56, 0xef, 0x1f, 0x63, 0x94, 0x8d, 0x29, 0xaa, 0xcc, 0x14,
-	0xaa, 0xea, 0xef, 0x45, 0xad, 0x95, 0x51, 0x6c, 0x2b, 0x57, 0x15, 0x4e, 0x0b, 0x23, 0xac, 0x58,
-	0xf4, 0x04, 0xe1, 0x46, 0xae, 0xca, 0x52, 0x55, 0xe3, 0x6e, 0xe9, 0xf4, 0xfc, 0x93, 0x00, 0xbd,
-	0xab, 0x31, 0x33, 0x92, 0x9d, 0x80, 0x97, 0x21, 0x06, 0x24, 0x22, 0xb1, 0x9f, 0xec, 0x8b, 0x3f,
-	0x41, 0xe2, 0x41, 0x16, 0xcf, 0x2f, 0x46, 0xe2, 0x29, 0xa2, 0xbe, 0x91, 0xe6, 0x72, 0x94, 0xb6,
-	0x46, 0x76, 0x04, 0x54, 0xcb, 0x52, 0xbd, 0xca, 0x60, 0xc1, 0x22, 0xf8, 0x00, 0x62, 0x66, 0x75,
-	0x9e, 0xb3, 0x15, 0xa0, 0x53, 0x9b, 0x83, 0x1f, 0xc2, 0xb2, 0x2b, 0xb3, 0x09, 0x2c, 0x65, 0x88,
-	0xba, 0x09, 0x48, 0xe4, 0xc5, 0x7e, 0x12, 0xfe, 0x10, 0x5d, 0x0f, 0xb7, 0x79, 0xdd, 0x4a, 0x65,
-	0xd3, 0xa4, 0x9d, 0x90, 0x5f, 0xc3, 0xfa, 0x5c, 0x3c, 0x76, 0xfc, 0x1b, 0xb2, 0xf7, 0xcf, 0xce,
-	0xbe, 0x89, 0xf7, 0xb0, 0xda, 0xbf, 0x66, 0x02, 0x16, 0xdb, 0x82, 0xfb, 0xa7, 0xa1, 0x48, 0x56,
-	0xc7, 0x36, 0x81, 0xbe, 0x59, 0x7f, 0xe0, 0x45, 0x24, 0x5e, 0x4b, 0xdd, 0x29, 0x79, 0x04, 0xff,
-	0x7c, 0xf6, 0x34, 0xbb, 0x02, 0xef, 0x42, 0x1a, 0xb6, 0x3d, 0xcf, 0xeb, 0x69, 0xc2, 0x9d, 0x81,
-	0xe8, 0xdd, 0x10, 0xf9, 0x68, 0x42, 0x9e, 0xa8, 0x1d, 0xed, 0xc1, 0x57, 0x00, 0x00, 0x00, 0xff,
-	0xff, 0xf8, 0xcd, 0x94, 0x88, 0x31, 0x02, 0x00, 0x00,
+	// 335 bytes of a gzipped FileDescriptorProto
+	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0x8d, 0x92, 0x4f, 0x4f, 0x83, 0x30,
+	0x18, 0xc6, 0xc7, 0xd8, 0xaa, 0x7b, 0xd1, 0x68, 0xaa, 0x31, 0x88, 0x17, 0xc5, 0x83, 0xc6, 0x43,
+	0x5d, 0xb6, 0x8b, 0xff, 0x13, 0x8d, 0x46, 0x8d, 0x1e, 0x0c, 0x6a, 0xf4, 0xca, 0xd6, 0x46, 0x89,
+	0x40, 0x09, 0x94, 0x29, 0xdf, 0xc2, 0x4f, 0xe8, 0x67, 0xb1, 0x14, 0x74, 0xb8, 0x44, 0xb2, 0x53,
+	0xff, 0xbc, 0xcf, 0xf3, 0xeb, 0xf3, 0xb6, 0x85, 0xcd, 0x28, 0xe6, 0x1f, 0xd9, 0x2e, 0x65, 0x89,
+	0xf0, 0x42, 0x57, 0x78, 0x3c, 0xac, 0xce, 0x89, 0xac, 0x0a, 0x8e, 0x57, 0x87, 0x3c, 0xa4, 0xa9,
+	0x27, 0x88, 0x12, 0x93, 0x8a, 0xc0, 0x5a, 0x1a, 0xf2, 0x20, 0x90, 0xa6, 0x62, 0x28, 0xf4, 0xf6,
+	0xa7, 0x06, 0xe8, 0x31, 0xa2, 0xae, 0x60, 0xf8, 0x04, 0x74, 0x97, 0x52, 0x53, 0x5b, 0xd7, 0xb6,
+	0x8d, 0xde, 0x0e, 0xf9, 0x17, 0x44, 0x9e, 0x98, 0xf7, 0xf2, 0x2a, 0x18, 0x3d, 0xa5, 0x34, 0xbe,
+	0x67, 0xe2, 0xaa, 0xe1, 0xe4, 0x46, 0x7c, 0x04, 0x28, 0x66, 0x01, 0x1f, 0x31, 0xb3, 0xa9, 0x10,
+	0x76, 0x0d, 0x62, 0x6c, 0x2d, 0x3d, 0x67, 0xb3, 0x80, 0x52, 0x95, 0xc3, 0x3e, 0x84, 0x99, 0xb2,
+	0x8c, 0xbb, 0xd0, 0x96, 0xe4, 0x38, 0x91, 0xa1, 0x74, 0x49, 0xb4, 0x7e, 0x89, 0x65, 0x0f, 0x0f,
+	0xc3, 0x28, 0x97, 0xb2, 0x24, 0x71, 0x0a, 0xa1, 0x7d, 0x07, 0x0b, 0x13, 0xf1, 0xf0, 0xf1, 0x5f,
+	0xc8, 0xd6, 0x94, 0x9d, 0xfd, 0x10, 0xbf, 0x34, 0x98, 0xab, 0xee, 0x63, 0x02, 0xad, 0xbc, 0x52,
+	0x5e, 0x54, 0x5d, 0x26, 0xa5, 0xc3, 0x2b, 0x80, 0xde, 0x95, 0xdf, 0xd4, 0xa5, 0x63, 0xde, 0x29,
+	0x57, 0xf8, 0x06, 0x90, 0xef, 0x0e, 0x98, 0x9f, 0x98, 0x2d, 0x15, 0xac, 0x3f, 0x65, 0x30, 0x72,
+	0xab, 0x5c, 0x17, 0xa1, 0x88, 0x33, 0xa7, 0x44, 0x58, 0xfb, 0x60, 0x54, 0xb6, 0xf1, 0x22, 0xe8,
+	0x6f, 0x2c, 0x53, 0x11, 0x3b, 0x4e, 0x3e, 0xc5, 0xcb, 0xd0, 0x1e, 0xb9, 0x7e, 0x5a, 0x3c, 0x4e,
+	0xc7, 0x29, 0x16, 0x07, 0xcd, 0x3d, 0xad, 0xf7, 0x0c, 0xc6, 0xf9, 0xf8, 0x28, 0x7c, 0x0d, 0xfa,
+	0xa5, 0xbc, 0xb5, 0xb5, 0xc9, 0xbe, 0x2a, 0x1a, 0x6b, 0xa3, 0x26, 0x6a, 0xf1, 0x9b, 0xec, 0x46,
+	0x57, 0x1b, 0x20, 0xf5, 0xc7, 0xfa, 0xdf, 0x9e, 0x20, 0x0c, 0x75, 0xba, 0x02, 0x00, 0x00,
 }
@@ -73,6 +73,17 @@ type ReportRequest struct {
 	ServerTransports []*ServerTransport  `protobuf:"bytes,3,rep,name=server_transports,json=serverTransports" json:"server_transports,omitempty"`
 	ClientTransports []*ClientTransport  `protobuf:"bytes,4,rep,name=client_transports,json=clientTransports" json:"client_transports,omitempty"`
 	Requests         []*RequestScope     `protobuf:"bytes,5,rep,name=requests" json:"requests,omitempty"`
+	// The number of telemetry events the proxy dropped since its previous
+	// report because its event buffer was full. When nonzero, this report is
+	// incomplete.
+	DroppedEvents uint32 `protobuf:"varint,6,opt,name=dropped_events,json=droppedEvents" json:"dropped_events,omitempty"`
+	// Destinations that the controller hasn't updated recently. The proxy
+	// keeps routing to their last known addresses, which may be out of date.
+	StaleDestinations []string `protobuf:"bytes,7,rep,name=stale_destinations,json=staleDestinations" json:"stale_destinations,omitempty"`
+	// The number of destinations for which the inbound and outbound proxies
+	// currently cache clients.
+	InboundRoutes  uint32 `protobuf:"varint,8,opt,name=inbound_routes,json=inboundRoutes" json:"inbound_routes,omitempty"`
+	OutboundRoutes uint32 `protobuf:"varint,9,opt,name=outbound_routes,json=outboundRoutes" json:"outbound_routes,omitempty"`
 }
 
 func (m *ReportRequest) Reset()                    { *m = ReportRequest{} }
@@ -115,6 +126,34 @@ func (m *ReportRequest) GetRequests() []*RequestScope {
 	return nil
 }
 
+func (m *ReportRequest) GetDroppedEvents() uint32 {
+	if m != nil {
+		return m.DroppedEvents
+	}
+	return 0
+}
+
+func (m *ReportRequest) GetStaleDestinations() []string {
+	if m != nil {
+		return m.StaleDestinations
+	}
+	return nil
+}
+
+func (m *ReportRequest) GetInboundRoutes() uint32 {
+	if m != nil {
+		return m.InboundRoutes
+	}
+	return 0
+}
+
+func (m *ReportRequest) GetOutboundRoutes() uint32 {
+	if m != nil {
+		return m.OutboundRoutes
+	}
+	return 0
+}
+
 type Process struct {
 	Node               string `protobuf:"bytes,1,opt,name=node" json:"node,omitempty"`
 	ScheduledInstance  string `protobuf:"bytes,2,opt,name=scheduled_instance,json=scheduledInstance" json:"scheduled_instance,omitempty"`
@@ -192,6 +231,8 @@ type ClientTransport struct {
 	Connects    uint32                     `protobuf:"varint,2,opt,name=connects" json:"connects,omitempty"`
 	Disconnects []*TransportSummary        `protobuf:"bytes,3,rep,name=disconnects" json:"disconnects,omitempty"`
 	Protocol    conduit_common.Protocol    `protobuf:"varint,4,opt,name=protocol,enum=conduit.common.Protocol" json:"protocol,omitempty"`
+	// The labels of `target_addr`, as given by the Destination API.
+	TargetLabels map[string]string `protobuf:"bytes,5,rep,name=target_labels,json=targetLabels" json:"target_labels,omitempty" protobuf_key:"bytes,1,opt,name=key" protobuf_val:"bytes,2,opt,name=value"`
 }
 
 func (m *ClientTransport) Reset()                    { *m = ClientTransport{} }
@@ -227,6 +268,13 @@ func (m *ClientTransport) GetProtocol() conduit_common.Protocol {
 	return conduit_common.Protocol_HTTP
 }
 
+func (m *ClientTransport) GetTargetLabels() map[string]string {
+	if m != nil {
+		return m.TargetLabels
+	}
+	return nil
+}
+
 type TransportSummary struct {
 	DurationMs uint64 `protobuf:"varint,1,opt,name=duration_ms,json=durationMs" json:"duration_ms,omitempty"`
 	BytesSent  uint64 `protobuf:"varint,2,opt,name=bytes_sent,json=bytesSent" json:"bytes_sent,omitempty"`
@@ -289,6 +337,11 @@ type RequestCtx struct {
 	Authority  string                     `protobuf:"bytes,3,opt,name=authority" json:"authority,omitempty"`
 	Method     *conduit_common.HttpMethod `protobuf:"bytes,4,opt,name=method" json:"method,omitempty"`
 	Path       string                     `protobuf:"bytes,5,opt,name=path" json:"path,omitempty"`
+	// The labels of `target_addr`, as given by the Destination API.
+	TargetLabels map[string]string `protobuf:"bytes,6,rep,name=target_labels,json=targetLabels" json:"target_labels,omitempty" protobuf_key:"bytes,1,opt,name=key" protobuf_val:"bytes,2,opt,name=value"`
+	// Whether the request was sent outside of the proxy's zone because the
+	// endpoints in its zone were unavailable or overloaded.
+	ZoneSpillover bool `protobuf:"varint,7,opt,name=zone_spillover,json=zoneSpillover" json:"zone_spillover,omitempty"`
 }
 
 func (m *RequestCtx) Reset()                    { *m = RequestCtx{} }
@@ -331,6 +384,20 @@ func (m *RequestCtx) GetPath() string {
 	return ""
 }
 
+func (m *RequestCtx) GetTargetLabels() map[string]string {
+	if m != nil {
+		return m.TargetLabels
+	}
+	return nil
+}
+
+func (m *RequestCtx) GetZoneSpillover() bool {
+	if m != nil {
+		return m.ZoneSpillover
+	}
+	return false
+}
+
 // A latency value in tenths of a millisecond and a count of the times
 // that latency was observed.
 type Latency struct {
@@ -708,61 +775,72 @@ var _Telemetry_serviceDesc = grpc.ServiceDesc{
 func init() { proto.RegisterFile("proxy/telemetry/telemetry.proto", fileDescriptor0) }
 
 var fileDescriptor0 = []byte{
-	// 888 bytes of a gzipped FileDescriptorProto
-	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0xcc, 0x56, 0x5f, 0x6f, 0x23, 0x35,
-	0x10, 0xef, 0x36, 0x49, 0x93, 0x4c, 0x2e, 0xbd, 0xd4, 0x87, 0x60, 0xa9, 0x40, 0x2d, 0x0b, 0x1c,
-	0x01, 0x41, 0x2a, 0x02, 0x77, 0x08, 0x78, 0xe1, 0xda, 0x3b, 0xa9, 0x15, 0x77, 0x6d, 0xe5, 0xb4,
-	0x4f, 0x3c, 0xac, 0xf6, 0xbc, 0xc3, 0x25, 0x52, 0xd6, 0x5e, 0x6c, 0x2f, 0x6a, 0x1e, 0xd0, 0x7d,
-	0x16, 0x24, 0xbe, 0x0f, 0x1f, 0x03, 0x5e, 0xf8, 0x0e, 0xc8, 0xf6, 0x7a, 0xb3, 0x0d, 0x97, 0x96,
-	0x3f, 0x2f, 0x3c, 0xc5, 0x9e, 0xf9, 0xcd, 0xcf, 0x33, 0xbf, 0x19, 0x7b, 0x03, 0x7b, 0xb9, 0x14,
-	0x57, 0x8b, 0x03, 0x8d, 0x73, 0xcc, 0x50, 0xcb, 0xda, 0x6a, 0x94, 0x4b, 0xa1, 0x05, 0x79, 0x83,
-	0x09, 0x9e, 0x16, 0x33, 0x3d, 0xb2, 0xc0, 0x51, 0xe5, 0xde, 0xbd, 0xc7, 0x44, 0x96, 0x09, 0x7e,
-	0xe0, 0x7e, 0x1c, 0x3a, 0xfa, 0xa5, 0x01, 0x7d, 0x8a, 0xb9, 0x90, 0x9a, 0xe2, 0x0f, 0x05, 0x2a,
-	0x4d, 0xbe, 0x82, 0x76, 0x2e, 0x05, 0x43, 0xa5, 0xc2, 0x60, 0x3f, 0x18, 0xf6, 0xc6, 0xfb, 0xa3,
-	0x35, 0x8c, 0xa3, 0x73, 0x87, 0xa3, 0x3e, 0x80, 0x1c, 0x42, 0xcb, 0x62, 0xc2, 0xcd, 0xfd, 0x60,
-	0xb8, 0x3d, 0xfe, 0x78, 0x6d, 0xe4, 0xb5, 0x23, 0x0d, 0xcf, 0xd5, 0x82, 0xba, 0x50, 0x72, 0x09,
-	0x3b, 0x0a, 0xe5, 0x8f, 0x28, 0x63, 0x2d, 0x13, 0xae, 0x0c, 0x4e, 0x85, 0x8d, 0xfd, 0xc6, 0xb0,
-	0x37, 0x1e, 0xae, 0xe5, 0x9b, 0xd8, 0x88, 0x0b, 0x1f, 0x40, 0x07, 0xea, 0xba, 0x41, 0x19, 0x5a,
-	0x36, 0x9f, 0x21, 0xd7, 0x75, 0xda, 0xe6, 0x2d, 0xb4, 0x47, 0x36, 0xa2, 0x46, 0xcb, 0xae, 0x1b,
-	0x14, 0x79, 0x04, 0x1d, 0xe9, 0xaa, 0x50, 0x61, 0xcb, 0xb2, 0xbd, 0x7f, 0x43, 0xd1, 0x16, 0x38,
-	0x61, 0x22, 0x47, 0x5a, 0x85, 0x45, 0x11, 0xb4, 0xac, 0x00, 0xa4, 0x07, 0xed, 0x93, 0xd3, 0xc3,
-	0xb3, 0xcb, 0xd3, 0xc7, 0x83, 0x0d, 0x72, 0x07, 0x3a, 0x67, 0x97, 0x17, 0x6e, 0x17, 0x44, 0x3f,
-	0x41, 0xbb, 0x14, 0x9b, 0x10, 0x68, 0x72, 0x91, 0xa2, 0x6d, 0x4e, 0x97, 0xda, 0x35, 0xf9, 0x04,
-	0x88, 0x62, 0x53, 0x4c, 0x8b, 0x39, 0xa6, 0xf1, 0x8c, 0x2b, 0x9d, 0x70, 0x86, 0xb6, 0x09, 0x5d,
-	0xba, 0x53, 0x79, 0x4e, 0x4a, 0x07, 0x39, 0x80, 0x7b, 0x4b, 0x38, 0x4f, 0x32, 0x54, 0x79, 0xc2,
-	0x30, 0x6c, 0x58, 0xfc, 0x92, 0xe9, 0xd4, 0x7b, 0xa2, 0xdf, 0x03, 0xb8, 0xbb, 0x22, 0x31, 0x79,
-	0x08, 0x5d, 0x25, 0x0a, 0xc9, 0x30, 0x9e, 0xe5, 0xe5, 0xa4, 0xbc, 0x59, 0x95, 0x5e, 0xce, 0xd8,
-	0xc9, 0xf9, 0xa3, 0x34, 0x95, 0x66, 0x44, 0x3a, 0x0e, 0x7b, 0x92, 0x93, 0x5d, 0xe8, 0x30, 0xc1,
-	0x39, 0x32, 0xad, 0x6c, 0x86, 0x7d, 0x5a, 0xed, 0xc9, 0xb7, 0xd0, 0x4b, 0x67, 0xaa, 0x72, 0xbb,
-	0xae, 0x7f, 0xb8, 0x56, 0xd0, 0x2a, 0x99, 0x49, 0x91, 0x65, 0x89, 0x5c, 0xd0, 0x7a, 0x34, 0xf9,
-	0x1c, 0x3a, 0x76, 0xc6, 0x99, 0x98, 0x87, 0x4d, 0x3b, 0x8f, 0xe1, 0x6a, 0x7e, 0xe7, 0xa5, 0x9f,
-	0x56, 0xc8, 0xe8, 0x8f, 0x00, 0xee, 0xae, 0xb4, 0x9d, 0x7c, 0x0d, 0x3d, 0x9d, 0xc8, 0x17, 0xa8,
-	0xe3, 0x24, 0x4d, 0x65, 0x59, 0xec, 0xee, 0x2a, 0xd9, 0x05, 0xcb, 0x7d, 0xb5, 0xe0, 0xe0, 0x66,
-	0xfb, 0x7f, 0xaf, 0x97, 0xc2, 0x60, 0x95, 0x96, 0xec, 0x41, 0x2f, 0x2d, 0x64, 0xa2, 0x67, 0x82,
-	0xc7, 0x99, 0x7b, 0x06, 0x9a, 0x14, 0xbc, 0xe9, 0x99, 0x22, 0x6f, 0x03, 0x3c, 0x5f, 0x68, 0x54,
-	0xb1, 0x42, 0xae, 0x6d, 0x55, 0x4d, 0xda, 0xb5, 0x96, 0x09, 0x72, 0x1d, 0xfd, 0x1c, 0xc0, 0x9d,
-	0xfa, 0xb0, 0x93, 0x07, 0xd0, 0x60, 0xfa, 0xaa, 0x14, 0xee, 0xdd, 0xdb, 0x2e, 0xc8, 0x91, 0xbe,
-	0xa2, 0x06, 0x4f, 0x5e, 0x83, 0x16, 0x13, 0x45, 0x79, 0x42, 0x9f, 0xba, 0x0d, 0x79, 0x0c, 0x5d,
-	0x89, 0x2a, 0x17, 0x5c, 0xa1, 0x97, 0xec, 0xfe, 0x0d, 0x94, 0x0e, 0xe9, 0x2e, 0xdd, 0x32, 0x30,
-	0xfa, 0x2d, 0x00, 0x58, 0x9e, 0xf7, 0xaf, 0xa7, 0x79, 0x65, 0x34, 0x36, 0xff, 0xd1, 0x68, 0xbc,
-	0x05, 0xdd, 0xa4, 0xd0, 0x53, 0x21, 0x67, 0x7a, 0x51, 0xde, 0xbe, 0xa5, 0x81, 0x8c, 0x61, 0x2b,
-	0x43, 0x3d, 0x15, 0xa9, 0xed, 0xe6, 0x2b, 0x58, 0x8f, 0xb5, 0xce, 0x9f, 0x59, 0x04, 0x2d, 0x91,
-	0xe6, 0x71, 0xc8, 0x13, 0x3d, 0x0d, 0x5b, 0xee, 0x71, 0x30, 0xeb, 0xe8, 0x4b, 0x68, 0x3f, 0x4d,
-	0x34, 0x72, 0xb6, 0x20, 0x21, 0xb4, 0xe7, 0x6e, 0x69, 0x6b, 0xec, 0x53, 0xbf, 0x7d, 0xb5, 0xd4,
-	0xd1, 0xaf, 0x81, 0xf9, 0x3a, 0xd4, 0x14, 0x24, 0x0f, 0xeb, 0x9d, 0x7c, 0xef, 0x56, 0xd9, 0xab,
-	0x56, 0x9e, 0x01, 0xf1, 0xda, 0xc7, 0xee, 0xcc, 0x19, 0x9a, 0xfb, 0xd0, 0xb8, 0xf1, 0x03, 0x53,
-	0xe6, 0x4d, 0x77, 0x7c, 0xec, 0x53, 0x1f, 0x4a, 0x1e, 0x40, 0x13, 0x79, 0xea, 0x07, 0xe0, 0x9d,
-	0xb5, 0x14, 0x4f, 0x84, 0x72, 0xbd, 0xb7, 0xf0, 0xe8, 0x0b, 0xe8, 0xd5, 0x72, 0x23, 0x43, 0x18,
-	0x4c, 0xb5, 0xce, 0x63, 0xa5, 0x13, 0x5d, 0xa8, 0x98, 0xf9, 0x87, 0xb5, 0x4f, 0xb7, 0x8d, 0x7d,
-	0x62, 0xcd, 0x47, 0x22, 0xc5, 0xe8, 0x25, 0x74, 0x3c, 0x15, 0xf9, 0xb4, 0x2e, 0xc2, 0xde, 0x4d,
-	0x47, 0x57, 0xf5, 0x7f, 0x03, 0x6d, 0xa5, 0x25, 0x26, 0x99, 0x2f, 0x7a, 0xfd, 0xc8, 0x4e, 0x2c,
-	0xce, 0x5f, 0x71, 0x1f, 0x16, 0xbd, 0x84, 0x2d, 0x47, 0x48, 0x3e, 0x82, 0xc1, 0x0b, 0x99, 0xb3,
-	0xbf, 0x26, 0x7d, 0xbc, 0x41, 0xb7, 0x8d, 0x67, 0x99, 0xb6, 0xc1, 0x4a, 0x54, 0xa8, 0x63, 0x94,
-	0x52, 0x48, 0x87, 0xdd, 0xf4, 0x58, 0xeb, 0x79, 0x62, 0x1c, 0x16, 0xfb, 0x3a, 0xb4, 0x84, 0x9e,
-	0xa2, 0xb4, 0xa3, 0xd8, 0x39, 0xde, 0xa0, 0x6e, 0x7b, 0xd8, 0x82, 0x06, 0xf2, 0x34, 0xca, 0xa1,
-	0x7f, 0x2d, 0xb5, 0xff, 0xfa, 0x4c, 0x98, 0xf8, 0xef, 0xa5, 0xf9, 0xc6, 0x38, 0x7f, 0xc3, 0xea,
-	0x0e, 0xce, 0x64, 0xdf, 0x91, 0x01, 0x6c, 0xfb, 0x3f, 0x0a, 0xae, 0x65, 0xe3, 0x29, 0x74, 0x2f,
-	0xbc, 0x50, 0xe4, 0x3b, 0xd8, 0x72, 0x6e, 0x72, 0xff, 0xef, 0xfd, 0xd1, 0xd8, 0xfd, 0xe0, 0x56,
-	0x9c, 0x3b, 0x27, 0xda, 0x78, 0xbe, 0x65, 0x5f, 0xc8, 0xcf, 0xfe, 0x0c, 0x00, 0x00, 0xff, 0xff,
-	0x94, 0x14, 0x47, 0x5a, 0x70, 0x09, 0x00, 0x00,
+	// 1061 bytes of a gzipped FileDescriptorProto
+	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0xcd, 0x56, 0xcd, 0x6e, 0x23, 0x45,
+	0x10, 0x8e, 0xff, 0xed, 0x72, 0xec, 0x38, 0xbd, 0x08, 0x8c, 0x05, 0x4a, 0x18, 0xd8, 0xdd, 0x2c,
+	0x02, 0x47, 0x04, 0x96, 0x9f, 0x70, 0x80, 0xcd, 0x8f, 0xb4, 0x11, 0xd9, 0x24, 0x6a, 0x3b, 0x17,
+	0xf6, 0x30, 0x9a, 0xcc, 0x34, 0xeb, 0x11, 0xe3, 0xe9, 0xa1, 0xbb, 0x27, 0x8a, 0x91, 0x10, 0x8f,
+	0xc0, 0x33, 0x70, 0xe6, 0x4d, 0xb8, 0xf0, 0x1a, 0x3c, 0x06, 0xfd, 0x33, 0x6d, 0x8f, 0xbd, 0xeb,
+	0x38, 0xb0, 0x17, 0x4e, 0xd3, 0x5d, 0xf5, 0x55, 0x75, 0x55, 0xd7, 0x57, 0xd5, 0x03, 0x5b, 0x09,
+	0xa3, 0x37, 0x93, 0x5d, 0x41, 0x22, 0x32, 0x26, 0x82, 0xe5, 0x56, 0x7d, 0xa9, 0x11, 0x14, 0xbd,
+	0xe5, 0xd3, 0x38, 0x48, 0x43, 0xd1, 0xd7, 0xc0, 0xfe, 0x54, 0xdd, 0xbb, 0xe7, 0xd3, 0xf1, 0x98,
+	0xc6, 0xbb, 0xe6, 0x63, 0xd0, 0xce, 0x9f, 0x65, 0x68, 0x61, 0x92, 0x50, 0x26, 0x30, 0xf9, 0x29,
+	0x25, 0x5c, 0xa0, 0x7d, 0xa8, 0x49, 0x95, 0x4f, 0x38, 0xef, 0x16, 0xb6, 0x0b, 0x3b, 0xcd, 0xbd,
+	0xed, 0xfe, 0x12, 0x8f, 0xfd, 0x0b, 0x83, 0xc3, 0xd6, 0x00, 0x1d, 0x40, 0x45, 0x63, 0xba, 0x45,
+	0x69, 0xd9, 0xde, 0xfb, 0x68, 0xa9, 0xe5, 0xdc, 0x91, 0xca, 0xcf, 0xcd, 0x04, 0x1b, 0x53, 0x74,
+	0x09, 0x9b, 0x9c, 0xb0, 0x6b, 0xc2, 0x5c, 0xc1, 0xbc, 0x98, 0x2b, 0x1c, 0xef, 0x96, 0xb6, 0x4b,
+	0x32, 0x92, 0x9d, 0xa5, 0xfe, 0x06, 0xda, 0x62, 0x68, 0x0d, 0x70, 0x87, 0xcf, 0x0b, 0xb8, 0x72,
+	0xeb, 0x47, 0x21, 0x89, 0x45, 0xde, 0x6d, 0x79, 0x85, 0xdb, 0x43, 0x6d, 0x91, 0x73, 0xeb, 0xcf,
+	0x0b, 0x38, 0x7a, 0x02, 0x75, 0x66, 0xb2, 0xe0, 0xdd, 0x8a, 0xf6, 0x76, 0xff, 0x96, 0xa4, 0x35,
+	0x70, 0xe0, 0xd3, 0x84, 0xe0, 0xa9, 0x19, 0xba, 0x0f, 0xed, 0x80, 0xd1, 0x24, 0x21, 0x81, 0x4b,
+	0xae, 0xa5, 0x77, 0xde, 0xad, 0xca, 0xdb, 0x6b, 0xe1, 0x56, 0x26, 0x3d, 0xd6, 0x42, 0xf4, 0x31,
+	0x20, 0x2e, 0xbc, 0x88, 0xb8, 0x81, 0xb4, 0x0a, 0x63, 0x4f, 0x84, 0x34, 0xe6, 0xdd, 0x9a, 0x3c,
+	0xb3, 0x81, 0x37, 0xb5, 0xe6, 0x28, 0xa7, 0x50, 0x5e, 0xc3, 0xf8, 0x8a, 0xa6, 0x71, 0xe0, 0x32,
+	0x9a, 0x0a, 0xc2, 0xbb, 0x75, 0xe3, 0x35, 0x93, 0x62, 0x2d, 0x44, 0x0f, 0x61, 0x43, 0x2e, 0xe6,
+	0x70, 0x0d, 0x8d, 0x6b, 0x5b, 0xb1, 0x01, 0x3a, 0x0e, 0x54, 0x74, 0x99, 0x50, 0x13, 0x6a, 0x27,
+	0x67, 0x07, 0xe7, 0x97, 0x67, 0x47, 0x9d, 0x35, 0xb4, 0x0e, 0xf5, 0xf3, 0xcb, 0xa1, 0xd9, 0x15,
+	0x9c, 0x5f, 0xa0, 0x96, 0x51, 0x02, 0x21, 0x28, 0xc7, 0x34, 0x20, 0x9a, 0x42, 0x0d, 0xac, 0xd7,
+	0x3a, 0x03, 0x7f, 0x44, 0x82, 0x34, 0x92, 0xa9, 0x86, 0xb1, 0x8c, 0x39, 0xf6, 0x89, 0xa6, 0x8a,
+	0xca, 0xc0, 0x6a, 0x4e, 0x32, 0x05, 0xda, 0x85, 0x7b, 0x33, 0x78, 0xec, 0x8d, 0x09, 0x4f, 0x3c,
+	0x89, 0x2f, 0x69, 0xfc, 0xcc, 0xd3, 0x99, 0xd5, 0x38, 0x7f, 0x17, 0x60, 0x63, 0x81, 0x08, 0xe8,
+	0x73, 0x68, 0x70, 0x9a, 0x32, 0x9f, 0xb8, 0x61, 0x92, 0xf1, 0xf9, 0xed, 0x69, 0x81, 0xb2, 0x4e,
+	0x38, 0xb9, 0x78, 0x12, 0x04, 0x4c, 0x11, 0xb9, 0x6e, 0xb0, 0x27, 0x09, 0xea, 0x41, 0x5d, 0xa2,
+	0x62, 0xe2, 0xcb, 0x72, 0x14, 0xf5, 0x85, 0x4c, 0xf7, 0xe8, 0x3b, 0x68, 0x06, 0x21, 0x9f, 0xaa,
+	0x0d, 0x37, 0x1f, 0x2d, 0x2d, 0xfb, 0x34, 0x98, 0x41, 0x3a, 0x1e, 0x7b, 0x6c, 0x82, 0xf3, 0xd6,
+	0xe8, 0x33, 0xa8, 0xeb, 0x4e, 0xf4, 0x69, 0x24, 0xe9, 0xa8, 0xba, 0xa6, 0xbb, 0x18, 0xdf, 0x45,
+	0xa6, 0xc7, 0x53, 0xa4, 0xf3, 0x5b, 0x09, 0x36, 0x16, 0xc8, 0x89, 0xbe, 0x86, 0xa6, 0xf0, 0xd8,
+	0x0b, 0x22, 0x5c, 0x4f, 0xa6, 0x93, 0x25, 0xdb, 0x5b, 0x74, 0x36, 0xf4, 0x13, 0x9b, 0x2d, 0x18,
+	0xb8, 0xda, 0xfe, 0xcf, 0xf3, 0x45, 0x2e, 0xb4, 0xb2, 0xdc, 0x22, 0xef, 0x8a, 0x44, 0xb6, 0xd7,
+	0xf6, 0xef, 0xda, 0xb9, 0xfd, 0xa1, 0xb6, 0x3e, 0xd5, 0xc6, 0xc7, 0xb1, 0x44, 0xe0, 0x75, 0x91,
+	0x13, 0xf5, 0xbe, 0x81, 0xcd, 0x97, 0x20, 0xa8, 0x03, 0xa5, 0x1f, 0xc9, 0x24, 0xe3, 0xb0, 0x5a,
+	0xa2, 0x37, 0xa0, 0x72, 0xed, 0x45, 0xa9, 0x65, 0xad, 0xd9, 0xec, 0x17, 0xbf, 0x2c, 0x38, 0x18,
+	0x3a, 0x8b, 0x89, 0xa3, 0x2d, 0x79, 0x71, 0x29, 0xd3, 0x0d, 0xe9, 0x8e, 0xcd, 0x38, 0x2d, 0x63,
+	0xb0, 0xa2, 0x67, 0x1c, 0xbd, 0x0b, 0x70, 0x35, 0x91, 0xdd, 0xe5, 0x72, 0x19, 0xac, 0xf6, 0x59,
+	0xc6, 0x0d, 0x2d, 0x19, 0x48, 0x81, 0xf3, 0x7b, 0x01, 0xd6, 0xf3, 0x43, 0x03, 0x3d, 0x86, 0x92,
+	0x2f, 0x6e, 0xb2, 0xd2, 0xbe, 0xbf, 0x6a, 0xd0, 0x1c, 0x8a, 0x1b, 0xac, 0xf0, 0x2a, 0x6a, 0x5f,
+	0xb6, 0xb2, 0xc8, 0x2a, 0x6b, 0x36, 0xe8, 0x08, 0x1a, 0x92, 0x06, 0x89, 0x1c, 0x16, 0xc4, 0x16,
+	0xf5, 0xc1, 0x2d, 0x2e, 0x0d, 0xd2, 0x0c, 0xaf, 0x99, 0xa1, 0xf3, 0x47, 0x09, 0x60, 0x76, 0xde,
+	0x7f, 0xee, 0xb7, 0x05, 0xf2, 0x16, 0xff, 0x15, 0x79, 0xdf, 0x81, 0x86, 0x97, 0x8a, 0x11, 0x65,
+	0xa1, 0x98, 0x64, 0xf3, 0x61, 0x26, 0x40, 0x7b, 0x50, 0x95, 0x39, 0x8c, 0x68, 0xa0, 0xf9, 0xf6,
+	0x0a, 0xaf, 0x4f, 0x85, 0x48, 0x9e, 0x69, 0x04, 0xce, 0x90, 0x6a, 0x7c, 0x25, 0x9e, 0x18, 0x49,
+	0x9a, 0xe9, 0xf1, 0xa5, 0xd6, 0xe8, 0xfb, 0x45, 0x0e, 0x56, 0xf5, 0x9d, 0x3d, 0xbe, 0x43, 0x19,
+	0x56, 0xd1, 0x4f, 0x4d, 0xeb, 0x9f, 0x69, 0x4c, 0x5c, 0x9e, 0x84, 0x51, 0x44, 0xe5, 0x04, 0x93,
+	0x83, 0xbd, 0xb0, 0x53, 0xc7, 0x2d, 0x25, 0x1d, 0x58, 0xe1, 0xeb, 0xb3, 0xf4, 0x2b, 0xa8, 0x9d,
+	0x7a, 0x82, 0xc4, 0xfe, 0x04, 0x75, 0xa1, 0x16, 0x99, 0xa5, 0x36, 0x6d, 0x61, 0xbb, 0x7d, 0x35,
+	0x5d, 0x9c, 0xbf, 0x0a, 0xea, 0x4f, 0x21, 0xc7, 0x02, 0x59, 0xeb, 0x1c, 0x1b, 0x3f, 0x58, 0x49,
+	0x9d, 0x29, 0x1d, 0xcf, 0x01, 0x59, 0xfe, 0xb8, 0xe6, 0xcc, 0x90, 0xa8, 0xa9, 0x53, 0xba, 0xf5,
+	0x67, 0x23, 0x8b, 0x1b, 0x6f, 0x5a, 0xdb, 0x53, 0x6b, 0x2a, 0xdb, 0xa2, 0x4c, 0xe2, 0xc0, 0x92,
+	0xf8, 0xbd, 0xa5, 0x2e, 0x8e, 0x29, 0x37, 0xfc, 0xd5, 0x70, 0xe7, 0x0b, 0x68, 0xe6, 0x62, 0x43,
+	0x3b, 0xd0, 0x19, 0x49, 0x26, 0xb8, 0xf2, 0xf9, 0x11, 0x29, 0x77, 0x7d, 0xfb, 0x7c, 0xc9, 0xb7,
+	0x50, 0xc9, 0x07, 0x5a, 0x7c, 0x28, 0xa5, 0xce, 0xaf, 0x50, 0xb7, 0xae, 0xd0, 0x27, 0xf9, 0x4b,
+	0xd8, 0xba, 0xed, 0xe8, 0x69, 0xfe, 0xdf, 0x42, 0x8d, 0x0b, 0x46, 0xbc, 0xb1, 0x4d, 0x7a, 0x79,
+	0xdb, 0x0d, 0x34, 0xce, 0x0e, 0x52, 0x6b, 0x26, 0x03, 0xa8, 0x1a, 0x87, 0xe8, 0x43, 0xe8, 0xbc,
+	0x60, 0x89, 0xff, 0x72, 0xd0, 0x4f, 0xd7, 0x70, 0x5b, 0x69, 0x66, 0x61, 0x2b, 0xac, 0xbc, 0x3b,
+	0xc9, 0x5f, 0xc2, 0x18, 0x65, 0x06, 0x5b, 0xb4, 0x58, 0xad, 0x39, 0x56, 0x0a, 0x8d, 0x7d, 0x13,
+	0x2a, 0x54, 0x8c, 0x24, 0x0f, 0x55, 0x3b, 0xd5, 0x25, 0xc0, 0x6c, 0x0f, 0x2a, 0x50, 0x92, 0x77,
+	0xe7, 0x24, 0xd0, 0x9a, 0x0b, 0xed, 0x75, 0x47, 0x9d, 0xb2, 0xff, 0x81, 0xa9, 0x97, 0xdc, 0xe8,
+	0x4b, 0xfa, 0xde, 0xc1, 0x88, 0xf4, 0x2c, 0xec, 0x40, 0xdb, 0xfe, 0x34, 0x9a, 0x92, 0xed, 0x8d,
+	0xa0, 0x31, 0xb4, 0x17, 0x85, 0x9e, 0x43, 0xd5, 0xa8, 0xd1, 0x83, 0xbb, 0xfd, 0x74, 0xf6, 0x1e,
+	0xae, 0xc4, 0x99, 0x73, 0x9c, 0xb5, 0xab, 0xaa, 0x7e, 0x87, 0x3e, 0xfd, 0x07, 0xff, 0xe0, 0xf5,
+	0xc6, 0x7c, 0x0b, 0x00, 0x00,
 }
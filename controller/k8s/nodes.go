@@ -0,0 +1,71 @@
+package k8s
+
+import (
+	"fmt"
+	"time"
+
+	"k8s.io/api/core/v1"
+	"k8s.io/apimachinery/pkg/fields"
+	"k8s.io/client-go/kubernetes"
+	"k8s.io/client-go/tools/cache"
+)
+
+const nodeResource = "nodes"
+
+type NodeStore struct {
+	store     *cache.Store
+	reflector *cache.Reflector
+	stopCh    chan struct{}
+}
+
+func NewNodeStore(clientset *kubernetes.Clientset) (*NodeStore, error) {
+	store := cache.NewStore(cache.MetaNamespaceKeyFunc)
+
+	nodeListWatcher := cache.NewListWatchFromClient(
+		clientset.CoreV1().RESTClient(),
+		nodeResource,
+		v1.NamespaceAll,
+		fields.Everything(),
+	)
+
+	reflector := cache.NewReflector(
+		nodeListWatcher,
+		&v1.Node{},
+		store,
+		time.Duration(0),
+	)
+
+	stopCh := make(chan struct{})
+
+	return &NodeStore{
+		store:     &store,
+		reflector: reflector,
+		stopCh:    stopCh,
+	}, nil
+}
+
+func (p *NodeStore) Run() error {
+	go p.reflector.ListAndWatch(p.stopCh)
+	return initializeWatcher(p.reflector)
+}
+
+func (p *NodeStore) Stop() {
+	p.stopCh <- struct{}{}
+}
+
+// GetNode returns the node with the given name. Nodes are not namespaced, so
+// the name is also the node's key.
+func (p *NodeStore) GetNode(name string) (*v1.Node, error) {
+	item, exists, err := (*p.store).GetByKey(name)
+	if err != nil {
+		return nil, err
+	}
+	if !exists {
+		return nil, fmt.Errorf("No node exists for name %s", name)
+	}
+	node, ok := item.(*v1.Node)
+	if !ok {
+		return nil, fmt.Errorf("%v is not a Node", item)
+	}
+	return node, nil
+}
@@ -1,11 +1,17 @@
 extern crate futures;
 
+mod queue;
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::fmt;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
 
 use futures::{Async, AsyncSink, Poll, Sink, StartSend, Stream};
-use futures::sync::mpsc;
+use futures::task::AtomicTask;
+
+use queue::{Pop, Queue};
 
 /// Creates a lossy multi-producer single-consumer channel.
 ///
@@ -14,31 +20,40 @@ use futures::sync::mpsc;
 ///
 /// This allows producers to send events on this channel without obtaining a mutable
 /// reference to a sender.
+///
+/// Items sent while the channel is full are rejected. Use a `Builder` to choose another
+/// `DropPolicy` or to coalesce items.
 pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
-    let (tx, rx) = mpsc::unbounded();
-    let capacity = Arc::new(AtomicUsize::new(capacity));
+    Builder::new(capacity).build()
+}
 
-    let s = Sender {
-        tx,
-        capacity: capacity.clone(),
-    };
+/// Configures a lossy channel.
+pub struct Builder<T> {
+    capacity: usize,
+    policy: DropPolicy<T>,
+    coalesce: Option<fn(&T) -> Option<u64>>,
+}
+
+/// Determines which item is dropped when an item is sent on a full channel.
+pub enum DropPolicy<T> {
+    /// The item being sent is rejected.
+    DropNewest,
 
-    let r = Receiver {
-        rx,
-        capacity,
-    };
+    /// The oldest queued item is dropped to make room for the item being sent.
+    DropOldest,
 
-    (s, r)
+    /// The oldest queued item of the lowest class is dropped to make room for the item
+    /// being sent, unless the item being sent is of a lower class, in which case it is
+    /// rejected.
+    DropByClass(fn(&T) -> u8),
 }
 
 pub struct Receiver<T> {
-    rx: mpsc::UnboundedReceiver<T>,
-    capacity: Arc<AtomicUsize>,
+    shared: Arc<Shared<T>>,
 }
 
 pub struct Sender<T> {
-    tx: mpsc::UnboundedSender<T>,
-    capacity: Arc<AtomicUsize>,
+    shared: Arc<Shared<T>>,
 }
 
 /// Indicates that channel was not able to send an item. Subsequents items, however, may
@@ -49,6 +64,142 @@ pub enum SendError<T> {
     Rejected(T),
 }
 
+/// While the channel has room, items without a coalescing key are sent to `inbox`
+/// without locking `state`. Other items, and all items once the channel is full, are
+/// sent while `state` is locked, so that queued items may be replaced or dropped.
+struct Shared<T> {
+    capacity: usize,
+    policy: DropPolicy<T>,
+    coalesce: Option<fn(&T) -> Option<u64>>,
+
+    /// Items sent while the channel had room. They are moved to `state`, in the order
+    /// they were sent, whenever it is locked.
+    inbox: Queue<T>,
+
+    /// The number of items that are queued, in `inbox` or `state`.
+    depth: AtomicUsize,
+
+    /// The greatest number of items that have been queued at once.
+    high_water_mark: AtomicUsize,
+
+    /// The total number of items dropped because the channel was full.
+    rejected: AtomicUsize,
+
+    /// The total number of items that replaced a queued item with the same key.
+    coalesced: AtomicUsize,
+
+    senders: AtomicUsize,
+    has_receiver: AtomicBool,
+    rx_task: AtomicTask,
+
+    state: Mutex<State<T>>,
+}
+
+struct State<T> {
+    /// Queued items by class, each with its sequence number. There is a queue for
+    /// each class of which items are queued, so the oldest item of the lowest class
+    /// is always at the front of the first queue.
+    classes: BTreeMap<u8, VecDeque<(u64, T)>>,
+
+    /// The sequence number of the next queued item.
+    next_seq: u64,
+
+    /// The class and sequence number of queued items by their coalescing key.
+    keys: HashMap<u64, (u8, u64)>,
+}
+
+// ===== impl Builder =====
+
+impl<T> Builder<T> {
+    pub fn new(capacity: usize) -> Self {
+        Builder {
+            capacity,
+            policy: DropPolicy::DropNewest,
+            coalesce: None,
+        }
+    }
+
+    /// Sets the policy that determines which item is dropped when the channel is full.
+    pub fn drop_policy(self, policy: DropPolicy<T>) -> Self {
+        Builder { policy, ..self }
+    }
+
+    /// Coalesces items by key.
+    ///
+    /// An item for which `key` returns a key replaces the queued item with the same key,
+    /// if there is one, keeping its position in the queue.
+    pub fn coalesce(self, key: fn(&T) -> Option<u64>) -> Self {
+        Builder {
+            coalesce: Some(key),
+            ..self
+        }
+    }
+
+    pub fn build(self) -> (Sender<T>, Receiver<T>) {
+        let shared = Arc::new(Shared {
+            capacity: self.capacity,
+            policy: self.policy,
+            coalesce: self.coalesce,
+            inbox: Queue::new(),
+            depth: AtomicUsize::new(0),
+            high_water_mark: AtomicUsize::new(0),
+            rejected: AtomicUsize::new(0),
+            coalesced: AtomicUsize::new(0),
+            senders: AtomicUsize::new(1),
+            has_receiver: AtomicBool::new(true),
+            rx_task: AtomicTask::new(),
+            state: Mutex::new(State {
+                classes: BTreeMap::new(),
+                next_seq: 0,
+                keys: HashMap::new(),
+            }),
+        });
+
+        let s = Sender {
+            shared: shared.clone(),
+        };
+
+        let r = Receiver {
+            shared,
+        };
+
+        (s, r)
+    }
+}
+
+impl<T> fmt::Debug for Builder<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Builder")
+            .field("capacity", &self.capacity)
+            .field("policy", &self.policy)
+            .field("coalesce", &self.coalesce.is_some())
+            .finish()
+    }
+}
+
+// ===== impl DropPolicy =====
+
+// NB Clone cannot be derived because `T` doesn't have to implement Clone.
+impl<T> Clone for DropPolicy<T> {
+    fn clone(&self) -> Self {
+        match *self {
+            DropPolicy::DropNewest => DropPolicy::DropNewest,
+            DropPolicy::DropOldest => DropPolicy::DropOldest,
+            DropPolicy::DropByClass(class) => DropPolicy::DropByClass(class),
+        }
+    }
+}
+
+impl<T> fmt::Debug for DropPolicy<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DropPolicy::DropNewest => fmt.write_str("DropNewest"),
+            DropPolicy::DropOldest => fmt.write_str("DropOldest"),
+            DropPolicy::DropByClass(_) => fmt.write_str("DropByClass"),
+        }
+    }
+}
+
 // ===== impl Receiver =====
 
 impl<T> Stream for Receiver<T> {
@@ -56,21 +207,67 @@ impl<T> Stream for Receiver<T> {
     type Error = ();
 
     fn poll(&mut self) -> Poll<Option<T>, Self::Error> {
-        match self.rx.poll() {
-            Ok(Async::Ready(Some(v))) => {
-                self.capacity.fetch_add(1, Ordering::SeqCst);
-                Ok(Async::Ready(Some(v)))
-            }
-            res => res,
+        // Senders are counted before items are received, so that items sent before the
+        // last sender was dropped are received first.
+        let done = self.shared.senders.load(Ordering::Acquire) == 0;
+        if let Some(v) = self.shared.recv() {
+            return Ok(Async::Ready(Some(v)));
+        }
+        if done {
+            return Ok(Async::Ready(None));
+        }
+
+        // Items sent, and senders dropped, before the task was registered didn't
+        // notify it.
+        self.shared.rx_task.register();
+        let done = self.shared.senders.load(Ordering::Acquire) == 0;
+        match self.shared.recv() {
+            Some(v) => Ok(Async::Ready(Some(v))),
+            None if done => Ok(Async::Ready(None)),
+            None => Ok(Async::NotReady),
         }
     }
 }
 
-// NB: `rx` does not have a `Debug` impl.
+impl<T> Receiver<T> {
+    /// Returns the number of items that are queued.
+    pub fn depth(&self) -> usize {
+        self.shared.depth.load(Ordering::Acquire)
+    }
+
+    /// Returns the greatest number of items that have been queued at once.
+    pub fn high_water_mark(&self) -> usize {
+        self.shared.high_water_mark.load(Ordering::Acquire)
+    }
+
+    /// Returns the total number of items that have been dropped because the channel was
+    /// full.
+    pub fn rejected(&self) -> usize {
+        self.shared.rejected.load(Ordering::Acquire)
+    }
+
+    /// Returns the total number of items that have replaced a queued item with the same
+    /// key.
+    pub fn coalesced(&self) -> usize {
+        self.shared.coalesced.load(Ordering::Acquire)
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.has_receiver.store(false, Ordering::Release);
+
+        let mut state = self.shared.lock();
+        state.classes.clear();
+        state.keys.clear();
+        self.shared.depth.store(0, Ordering::Release);
+    }
+}
+
 impl<T> fmt::Debug for Receiver<T> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         fmt.debug_struct("Receiver")
-            .field("capacity", &self.capacity)
+            .field("shared", &self.shared)
             .finish()
     }
 }
@@ -79,22 +276,78 @@ impl<T> fmt::Debug for Receiver<T> {
 
 impl<T> Sender<T> {
     pub fn lossy_send(&self, v: T) -> Result<(), SendError<T>> {
-        loop {
-            let cap = self.capacity.load(Ordering::SeqCst);
-            if cap == 0 {
-                return Err(SendError::Rejected(v));
-            }
+        let shared = &*self.shared;
+
+        if !shared.has_receiver.load(Ordering::Acquire) {
+            return Err(SendError::NoReceiver(v));
+        }
+
+        let key = shared.coalesce.and_then(|key| key(&v));
+        if key.is_none() && shared.reserve() {
+            shared.inbox.push(v);
+            shared.rx_task.notify();
+            return Ok(());
+        }
 
-            let ret = self.capacity
-                .compare_and_swap(cap, cap - 1, Ordering::SeqCst);
-            if ret == cap {
-                break;
+        let mut state = shared.lock();
+        let class = shared.class(&v);
+
+        let v = match key {
+            Some(key) => match state.replace(key, class, v) {
+                Ok(()) => {
+                    shared.coalesced.fetch_add(1, Ordering::AcqRel);
+                    return Ok(());
+                }
+                Err(v) => v,
+            },
+            None => v,
+        };
+
+        if !shared.reserve() {
+            let victim = match shared.policy {
+                DropPolicy::DropNewest => None,
+                DropPolicy::DropOldest => state.oldest_class(),
+                DropPolicy::DropByClass(_) => state.lowest_class()
+                    .and_then(|lowest| if lowest <= class { Some(lowest) } else { None }),
+            };
+
+            // The item takes the place of the victim, so the depth is unchanged.
+            shared.rejected.fetch_add(1, Ordering::AcqRel);
+            match victim {
+                Some(victim) => {
+                    state.pop_class(victim, shared.coalesce);
+                }
+                None => return Err(SendError::Rejected(v)),
             }
         }
 
-        self.tx
-            .unbounded_send(v)
-            .map_err(|se| SendError::NoReceiver(se.into_inner()))
+        state.push(class, key, v);
+        drop(state);
+
+        shared.rx_task.notify();
+        Ok(())
+    }
+
+    /// Returns the number of items that are queued.
+    pub fn depth(&self) -> usize {
+        self.shared.depth.load(Ordering::Acquire)
+    }
+
+    /// Returns the greatest number of items that have been queued at once.
+    pub fn high_water_mark(&self) -> usize {
+        self.shared.high_water_mark.load(Ordering::Acquire)
+    }
+
+    /// Returns the total number of items that have been dropped because the channel was
+    /// full.
+    pub fn rejected(&self) -> usize {
+        self.shared.rejected.load(Ordering::Acquire)
+    }
+
+    /// Returns the total number of items that have replaced a queued item with the same
+    /// key.
+    pub fn coalesced(&self) -> usize {
+        self.shared.coalesced.load(Ordering::Acquire)
     }
 }
 
@@ -115,22 +368,207 @@ impl<T> Sink for Sender<T> {
 // NB Clone cannot be derived because `T` doesn't have to implement Clone.
 impl<T> Clone for Sender<T> {
     fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::AcqRel);
+
         Sender {
-            tx: self.tx.clone(),
-            capacity: self.capacity.clone(),
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        if self.shared.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            // Wake the receiver so that it observes the end of the stream.
+            self.shared.rx_task.notify();
         }
     }
 }
 
-// NB: `tx` does not have a `Debug` impl.
 impl<T> fmt::Debug for Sender<T> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         fmt.debug_struct("Sender")
+            .field("shared", &self.shared)
+            .finish()
+    }
+}
+
+// ===== impl Shared =====
+
+impl<T> Shared<T> {
+    fn class(&self, v: &T) -> u8 {
+        match self.policy {
+            DropPolicy::DropByClass(class) => class(v),
+            _ => 0,
+        }
+    }
+
+    /// Counts an item as queued if the channel has room for it. Returns whether it did.
+    fn reserve(&self) -> bool {
+        let mut depth = self.depth.load(Ordering::Acquire);
+        loop {
+            if depth >= self.capacity {
+                return false;
+            }
+
+            match self.depth.compare_exchange(depth, depth + 1, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => break,
+                Err(actual) => depth = actual,
+            }
+        }
+
+        let mut high = self.high_water_mark.load(Ordering::Acquire);
+        while high <= depth {
+            match self.high_water_mark.compare_exchange(high, depth + 1, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => break,
+                Err(actual) => high = actual,
+            }
+        }
+
+        true
+    }
+
+    /// Locks the queued items, after moving the items in the inbox to them.
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        let mut state = self.state.lock().unwrap();
+
+        loop {
+            // Only one thread pops from the inbox at a time, as `state` is locked.
+            match unsafe { self.inbox.pop() } {
+                Pop::Data(v) => {
+                    let class = self.class(&v);
+                    state.push(class, None, v);
+                }
+                Pop::Empty => break,
+                Pop::Inconsistent => thread::yield_now(),
+            }
+        }
+
+        state
+    }
+
+    /// Dequeues the oldest item.
+    fn recv(&self) -> Option<T> {
+        let v = self.lock().pop(self.coalesce);
+        if v.is_some() {
+            self.depth.fetch_sub(1, Ordering::AcqRel);
+        }
+        v
+    }
+}
+
+// NB: Items are not required to implement `Debug`.
+impl<T> fmt::Debug for Shared<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Shared")
             .field("capacity", &self.capacity)
+            .field("policy", &self.policy)
+            .field("depth", &self.depth.load(Ordering::Acquire))
+            .field("high_water_mark", &self.high_water_mark.load(Ordering::Acquire))
+            .field("rejected", &self.rejected.load(Ordering::Acquire))
+            .field("coalesced", &self.coalesced.load(Ordering::Acquire))
             .finish()
     }
 }
 
+// ===== impl State =====
+
+impl<T> State<T> {
+    fn push(&mut self, class: u8, key: Option<u64>, v: T) {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+
+        if let Some(key) = key {
+            self.keys.insert(key, (class, seq));
+        }
+
+        self.classes.entry(class).or_default().push_back((seq, v));
+    }
+
+    /// Dequeues the oldest item.
+    fn pop(&mut self, coalesce: Option<fn(&T) -> Option<u64>>) -> Option<T> {
+        match self.oldest_class() {
+            Some(class) => self.pop_class(class, coalesce),
+            None => None,
+        }
+    }
+
+    /// Dequeues the oldest item of `class`.
+    fn pop_class(&mut self, class: u8, coalesce: Option<fn(&T) -> Option<u64>>) -> Option<T> {
+        let (item, empty) = match self.classes.get_mut(&class) {
+            Some(queue) => (queue.pop_front(), queue.is_empty()),
+            None => return None,
+        };
+        if empty {
+            self.classes.remove(&class);
+        }
+
+        let (seq, v) = item?;
+
+        self.forget_key(class, seq, &v, coalesce);
+        Some(v)
+    }
+
+    /// Returns the class of the oldest queued item.
+    ///
+    /// The oldest item is at the front of one of the queues, so this takes time
+    /// proportional to the number of classes, rather than the number of items.
+    fn oldest_class(&self) -> Option<u8> {
+        self.classes.iter()
+            .filter_map(|(&class, queue)| queue.front().map(|&(seq, _)| (seq, class)))
+            .min()
+            .map(|(_, class)| class)
+    }
+
+    /// Returns the lowest class of which items are queued.
+    fn lowest_class(&self) -> Option<u8> {
+        self.classes.keys().next().cloned()
+    }
+
+    /// Replaces the queued item with the same key as `v`, in its place, or returns `v`
+    /// if there is none.
+    fn replace(&mut self, key: u64, class: u8, v: T) -> Result<(), T> {
+        let (old_class, seq) = match self.keys.get(&key) {
+            Some(&queued) => queued,
+            None => return Err(v),
+        };
+
+        let empty = {
+            let queue = self.classes.get_mut(&old_class).expect("keyed item must be queued");
+            let idx = queue.iter()
+                .position(|&(s, _)| s == seq)
+                .expect("keyed item must be queued");
+
+            if old_class == class {
+                queue[idx].1 = v;
+                return Ok(());
+            }
+
+            queue.remove(idx);
+            queue.is_empty()
+        };
+        if empty {
+            self.classes.remove(&old_class);
+        }
+
+        // The item's class changed, so it moves to the queue of its new class, in the
+        // same order with respect to other items.
+        let queue = self.classes.entry(class).or_default();
+        let idx = queue.iter().position(|&(s, _)| s > seq).unwrap_or(queue.len());
+        queue.insert(idx, (seq, v));
+        self.keys.insert(key, (class, seq));
+        Ok(())
+    }
+
+    fn forget_key(&mut self, class: u8, seq: u64, v: &T, coalesce: Option<fn(&T) -> Option<u64>>) {
+        if let Some(key) = coalesce.and_then(|key| key(v)) {
+            if self.keys.get(&key) == Some(&(class, seq)) {
+                self.keys.remove(&key);
+            }
+        }
+    }
+}
+
 // ===== impl SendError =====
 
 impl<T> SendError<T> {
@@ -140,3 +578,223 @@ impl<T> SendError<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{future, Future};
+
+    /// Receives all queued items.
+    fn drain<T>(rx: &mut Receiver<T>) -> Vec<T> {
+        let mut items = vec![];
+        future::lazy(|| {
+            while let Async::Ready(Some(v)) = rx.poll().unwrap() {
+                items.push(v);
+            }
+            Ok::<(), ()>(())
+        }).wait().unwrap();
+        items
+    }
+
+    /// Items are `(key, class)` pairs.
+    fn key(v: &(u64, u8)) -> Option<u64> {
+        Some(v.0)
+    }
+
+    fn class(v: &(u64, u8)) -> u8 {
+        v.1
+    }
+
+    #[test]
+    fn drop_newest_rejects_items_sent_when_full() {
+        let (tx, mut rx) = channel(2);
+
+        assert_eq!(tx.lossy_send(1), Ok(()));
+        assert_eq!(tx.lossy_send(2), Ok(()));
+        assert_eq!(tx.lossy_send(3), Err(SendError::Rejected(3)));
+        assert_eq!(rx.depth(), 2);
+        assert_eq!(rx.rejected(), 1);
+
+        assert_eq!(drain(&mut rx), vec![1, 2]);
+        assert_eq!(tx.depth(), 0);
+        assert_eq!(tx.high_water_mark(), 2);
+
+        assert_eq!(tx.lossy_send(4), Ok(()));
+        assert_eq!(drain(&mut rx), vec![4]);
+        assert_eq!(rx.rejected(), 1);
+    }
+
+    #[test]
+    fn drop_oldest_makes_room_for_items_sent_when_full() {
+        let (tx, mut rx) = Builder::new(2)
+            .drop_policy(DropPolicy::DropOldest)
+            .build();
+
+        for i in 1..5 {
+            assert_eq!(tx.lossy_send(i), Ok(()));
+        }
+        assert_eq!(rx.depth(), 2);
+        assert_eq!(rx.high_water_mark(), 2);
+        assert_eq!(rx.rejected(), 2);
+
+        assert_eq!(drain(&mut rx), vec![3, 4]);
+    }
+
+    #[test]
+    fn drop_by_class_drops_the_oldest_item_of_the_lowest_class() {
+        let (tx, mut rx) = Builder::new(2)
+            .drop_policy(DropPolicy::DropByClass(class))
+            .build();
+
+        assert_eq!(tx.lossy_send((1, 1)), Ok(()));
+        assert_eq!(tx.lossy_send((2, 0)), Ok(()));
+
+        // The lower class item is dropped.
+        assert_eq!(tx.lossy_send((3, 2)), Ok(()));
+
+        // An item of a lower class than all queued items is rejected.
+        assert_eq!(tx.lossy_send((4, 0)), Err(SendError::Rejected((4, 0))));
+
+        // Of items of the same class, the oldest is dropped.
+        assert_eq!(tx.lossy_send((5, 1)), Ok(()));
+
+        assert_eq!(rx.rejected(), 3);
+        assert_eq!(drain(&mut rx), vec![(3, 2), (5, 1)]);
+    }
+
+    #[test]
+    fn coalesces_items_by_key() {
+        let (tx, mut rx) = Builder::new(2).coalesce(key).build();
+
+        assert_eq!(tx.lossy_send((1, 0)), Ok(()));
+        assert_eq!(tx.lossy_send((2, 0)), Ok(()));
+
+        // Replaces the first item, in its place, though the channel is full.
+        assert_eq!(tx.lossy_send((1, 1)), Ok(()));
+        assert_eq!(rx.coalesced(), 1);
+        assert_eq!(rx.depth(), 2);
+
+        assert_eq!(tx.lossy_send((3, 0)), Err(SendError::Rejected((3, 0))));
+        assert_eq!(drain(&mut rx), vec![(1, 1), (2, 0)]);
+
+        // Received items are not replaced.
+        assert_eq!(tx.lossy_send((1, 2)), Ok(()));
+        assert_eq!(rx.coalesced(), 1);
+        assert_eq!(drain(&mut rx), vec![(1, 2)]);
+    }
+
+    #[test]
+    fn items_without_keys_are_not_coalesced() {
+        fn odd(v: &u64) -> Option<u64> {
+            if v % 2 == 1 { Some(1) } else { None }
+        }
+
+        let (tx, mut rx) = Builder::new(4).coalesce(odd).build();
+
+        for i in 0..4 {
+            assert_eq!(tx.lossy_send(i), Ok(()));
+        }
+        assert_eq!(rx.coalesced(), 1);
+        assert_eq!(drain(&mut rx), vec![0, 3, 2]);
+    }
+
+    #[test]
+    fn coalesces_items_of_other_classes() {
+        let (tx, mut rx) = Builder::new(2)
+            .drop_policy(DropPolicy::DropByClass(class))
+            .coalesce(key)
+            .build();
+
+        assert_eq!(tx.lossy_send((1, 1)), Ok(()));
+
+        // Each item is dropped to make room for the next one.
+        for k in 2..8 {
+            assert_eq!(tx.lossy_send((k, 0)), Ok(()));
+        }
+        assert_eq!(rx.depth(), 2);
+        assert_eq!(rx.rejected(), 5);
+
+        // Items that change class keep their places.
+        assert_eq!(tx.lossy_send((1, 2)), Ok(()));
+        assert_eq!(tx.lossy_send((7, 1)), Ok(()));
+        assert_eq!(rx.coalesced(), 2);
+
+        assert_eq!(drain(&mut rx), vec![(1, 2), (7, 1)]);
+    }
+
+    #[test]
+    fn counts_high_water_mark() {
+        let (tx, mut rx) = channel(8);
+
+        for i in 0..3 {
+            tx.lossy_send(i).unwrap();
+        }
+        drain(&mut rx);
+        tx.lossy_send(3).unwrap();
+
+        assert_eq!(rx.depth(), 1);
+        assert_eq!(rx.high_water_mark(), 3);
+    }
+
+    #[test]
+    fn ends_when_senders_are_dropped() {
+        let (tx, mut rx) = channel(2);
+        let tx2 = tx.clone();
+
+        tx.lossy_send(1).unwrap();
+        drop(tx);
+        tx2.lossy_send(2).unwrap();
+        drop(tx2);
+
+        assert_eq!(rx.poll(), Ok(Async::Ready(Some(1))));
+        assert_eq!(rx.poll(), Ok(Async::Ready(Some(2))));
+        assert_eq!(rx.poll(), Ok(Async::Ready(None)));
+    }
+
+    #[test]
+    fn receives_items_from_many_threads_in_order() {
+        const THREADS: usize = 4;
+        const ITEMS: usize = 10_000;
+
+        let (tx, rx) = Builder::new(64)
+            .drop_policy(DropPolicy::DropOldest)
+            .build();
+
+        let threads = (0..THREADS)
+            .map(|t| {
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    for i in 0..ITEMS {
+                        let _ = tx.lossy_send((t, i));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        drop(tx);
+
+        let mut last = [None; THREADS];
+        let mut received = 0;
+        let mut rx = rx.wait();
+        while let Some(Ok((t, i))) = rx.next() {
+            assert!(last[t] < Some(i), "{:?} received after {:?}", (t, i), last[t]);
+            last[t] = Some(i);
+            received += 1;
+        }
+
+        for t in threads {
+            t.join().unwrap();
+        }
+        let rx = rx.into_inner();
+        assert_eq!(received + rx.rejected(), THREADS * ITEMS);
+        assert_eq!(rx.depth(), 0);
+    }
+
+    #[test]
+    fn rejects_items_without_receiver() {
+        let (tx, rx) = channel(2);
+        drop(rx);
+
+        assert_eq!(tx.lossy_send(1), Err(SendError::NoReceiver(1)));
+        assert_eq!(tx.rejected(), 0);
+    }
+}
@@ -9,10 +9,11 @@ use h2;
 use http;
 use tokio_core::reactor::Handle;
 use tower_h2;
-use tower_reconnect::{self, Reconnect};
+use tower_h2::client::{pool, Pool};
 
 use control;
 use ctx;
+use ctx::transport::Labels;
 use telemetry;
 use transport;
 use ::timeout::Timeout;
@@ -21,7 +22,8 @@ const DEFAULT_TIMEOUT_MS: u64 = 300;
 
 /// Binds a `Service` from a `SocketAddr`.
 ///
-/// The returned `Service` buffers request until a connection is established.
+/// The returned `Service` buffers request until a connection is established,
+/// and pools connections to each address.
 ///
 /// # TODO
 ///
@@ -33,10 +35,11 @@ pub struct Bind<C, B> {
     executor: Handle,
     req_ids: Arc<AtomicUsize>,
     connect_timeout: Duration,
+    pool: pool::Config,
     _p: PhantomData<B>,
 }
 
-type Service<B> = Reconnect<
+type Service<B> = Pool<
     telemetry::sensor::NewHttp<
         tower_h2::client::Client<
             telemetry::sensor::Connect<transport::TimeoutConnect<transport::Connect>>,
@@ -63,6 +66,7 @@ impl<B> Bind<(), B> {
             req_ids: Default::default(),
             h2_builder,
             connect_timeout: Duration::from_millis(DEFAULT_TIMEOUT_MS),
+            pool: pool::Config::default(),
             _p: PhantomData,
         }
     }
@@ -74,6 +78,13 @@ impl<B> Bind<(), B> {
         }
     }
 
+    pub fn with_pool_config(self, pool: pool::Config) -> Self {
+        Self {
+            pool,
+            ..self
+        }
+    }
+
     pub fn with_sensors(self, sensors: telemetry::Sensors) -> Self {
         Self {
             sensors,
@@ -81,6 +92,15 @@ impl<B> Bind<(), B> {
         }
     }
 
+    /// Shares request IDs with other `Bind`s, so that no two requests get
+    /// the same ID.
+    pub fn with_req_ids(self, req_ids: Arc<AtomicUsize>) -> Self {
+        Self {
+            req_ids,
+            ..self
+        }
+    }
+
     pub fn with_ctx<C>(self, ctx: C) -> Bind<C, B> {
         Bind {
             ctx,
@@ -89,6 +109,7 @@ impl<B> Bind<(), B> {
             executor: self.executor,
             req_ids: self.req_ids,
             connect_timeout: self.connect_timeout,
+            pool: self.pool,
             _p: PhantomData,
         }
     }
@@ -103,6 +124,7 @@ impl<C: Clone, B> Clone for Bind<C, B> {
             executor: self.executor.clone(),
             req_ids: self.req_ids.clone(),
             connect_timeout: self.connect_timeout,
+            pool: self.pool,
             _p: PhantomData,
         }
     }
@@ -132,11 +154,12 @@ impl<B> Bind<Arc<ctx::Proxy>, B>
 where
     B: tower_h2::Body + 'static,
 {
-    pub fn bind_service(&self, addr: &SocketAddr) -> Service<B> {
-        trace!("bind_service {}", addr);
+    pub fn bind_service(&self, addr: &SocketAddr, labels: &Arc<Labels>) -> Service<B> {
+        trace!("bind_service {} {:?}", addr, labels);
         let client_ctx = ctx::transport::Client::new(
             &self.ctx,
             addr,
+            labels,
             control::pb::proxy::common::Protocol::Http,
         );
 
@@ -160,10 +183,11 @@ where
 
         let h2_proxy = self.sensors.http(self.req_ids.clone(), client, &client_ctx);
 
-        // Automatically perform reconnects if the connection fails.
+        // Spread requests over several connections, reconnecting if they all
+        // fail.
         //
         // TODO: Add some sort of backoff logic.
-        Reconnect::new(h2_proxy)
+        Pool::new(h2_proxy, self.pool)
     }
 }
 
@@ -174,15 +198,15 @@ where
     B: tower_h2::Body + 'static,
 {
     type Request = http::Request<B>;
-    type Response = http::Response<telemetry::sensor::http::ResponseBody<tower_h2::RecvBody>>;
-    type Error = tower_reconnect::Error<
+    type Response = http::Response<pool::ResponseBody<telemetry::sensor::http::ResponseBody<tower_h2::RecvBody>>>;
+    type Error = pool::Error<
         tower_h2::client::Error,
         tower_h2::client::ConnectError<transport::TimeoutError<io::Error>>,
     >;
     type Service = Service<B>;
     type BindError = ();
 
-    fn bind(&self, addr: &SocketAddr) -> Result<Self::Service, Self::BindError> {
-        Ok::<_, ()>(self.bind_service(addr))
+    fn bind(&self, addr: &SocketAddr, labels: &Arc<Labels>) -> Result<Self::Service, Self::BindError> {
+        Ok::<_, ()>(self.bind_service(addr, labels))
     }
 }
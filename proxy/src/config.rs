@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::net::SocketAddr;
 use std::path::PathBuf;
@@ -22,6 +22,10 @@ pub struct Config {
     /// Where to listen for connections initiated by external sources.
     pub public_listener: Listener,
 
+    /// How connections initiated by external sources are redirected to the
+    /// public listener.
+    pub inbound_interception_mode: InterceptionMode,
+
     /// Where to listen for connectoins initiated by the control planey.
     pub control_listener: Listener,
 
@@ -34,11 +38,62 @@ pub struct Config {
     /// The maximum amount of time to wait for a connection to the private peer.
     pub private_connect_timeout: Duration,
 
+    /// The maximum number of destinations for which inbound clients are
+    /// cached. If unset, the number is not limited.
+    ///
+    /// Each worker thread caches clients in its own router, so up to
+    /// `worker_threads` times this many may be cached by the proxy.
+    pub inbound_router_capacity: Option<usize>,
+
+    /// The maximum number of destinations for which outbound clients are
+    /// cached. If unset, the number is not limited.
+    ///
+    /// Each worker thread caches clients in its own router, so up to
+    /// `worker_threads` times this many may be cached by the proxy.
+    pub outbound_router_capacity: Option<usize>,
+
+    /// The amount of time an unused inbound client is cached for. If unset,
+    /// clients are cached until they are evicted to make room for others.
+    pub inbound_router_max_idle_age: Option<Duration>,
+
+    /// The amount of time an unused outbound client is cached for. If unset,
+    /// clients are cached until they are evicted to make room for others.
+    pub outbound_router_max_idle_age: Option<Duration>,
+
+    /// The maximum number of HTTP/2 connections opened to each endpoint.
+    pub max_connections_per_endpoint: usize,
+
+    /// The amount of time an extra connection to an endpoint may be unused
+    /// before it is closed.
+    pub connection_idle_timeout: Duration,
+
+    /// If set, another connection to an endpoint is opened when its
+    /// responses take longer than this on average.
+    pub connection_latency_threshold: Option<Duration>,
+
+    /// The number of requests in flight per endpoint in the proxy's zone
+    /// beyond which requests spill over to other zones.
+    pub zone_spillover_threshold: usize,
+
+    /// The number of threads that accept and serve proxied connections.
+    pub worker_threads: usize,
+
+    /// Connections to these ports are forwarded as opaque TCP streams, rather
+    /// than being served as HTTP.
+    pub opaque_ports: HashSet<u16>,
+
     /// The path to "/etc/resolv.conf"
     pub resolv_conf_path: PathBuf,
 
-    /// Where to talk to the control plane.
-    pub control_host_and_port: HostAndPort,
+    /// Where destinations are discovered and telemetry is reported.
+    pub control_plane: ControlPlane,
+
+    /// How often the control plane is expected to update each destination.
+    pub address_update_interval: Duration,
+
+    /// If set, where the last known addresses of destinations are saved, so
+    /// that they can be used before the control plane is reachable.
+    pub destination_snapshot_path: Option<PathBuf>,
 
     /// Event queue capacity.
     pub event_buffer_capacity: usize,
@@ -49,6 +104,14 @@ pub struct Config {
     /// Timeout after which to cancel telemetry reports.
     pub report_timeout: Duration,
 
+    /// If set, requests are traced, and spans are sent to the
+    /// Zipkin-compatible collector at this address.
+    pub trace_collector: Option<Addr>,
+
+    /// The fraction of requests without trace context that start a sampled
+    /// trace, between 0 and 1.
+    pub trace_sample_rate: f64,
+
     pub pod_name: Option<String>,
     pub pod_namespace: Option<String>,
     pub pod_zone: Option<String>,
@@ -59,6 +122,37 @@ pub struct Config {
     destinations_autocomplete_fqdn: Option<Environment>,
 }
 
+/// Where destinations are discovered and telemetry is reported.
+#[derive(Clone, Debug)]
+pub enum ControlPlane {
+    /// Talk to the controller at this address.
+    Controller(HostAndPort),
+
+    /// Run without a controller.
+    Standalone {
+        /// The file that destinations are read from.
+        destinations_path: PathBuf,
+
+        /// If set, telemetry reports are appended to this file. Otherwise,
+        /// they're dropped.
+        telemetry_path: Option<PathBuf>,
+    },
+}
+
+/// How connections are redirected to a listener, which determines how their
+/// original destination is found.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InterceptionMode {
+    /// Connections are redirected with NAT (the iptables `REDIRECT` target),
+    /// and their original destination is read with `SO_ORIGINAL_DST`.
+    Redirect,
+
+    /// Connections are redirected with the iptables `TPROXY` target to a
+    /// transparent listener, which keeps their original destination as the
+    /// local address.
+    Tproxy,
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum Environment {
     Kubernetes,
@@ -87,7 +181,10 @@ pub enum Error {
 #[derive(Clone, Debug)]
 pub enum ParseError {
     EnvironmentUnsupported,
+    InterceptionModeUnsupported,
     NotANumber,
+    NotPositive,
+    OutOfRange,
     HostIsNotAnIpAddress,
     NotUnicode,
     UrlError(UrlError),
@@ -139,6 +236,17 @@ pub const ENV_PUBLIC_LISTENER: &str = "CONDUIT_PROXY_PUBLIC_LISTENER";
 pub const ENV_CONTROL_LISTENER: &str = "CONDUIT_PROXY_CONTROL_LISTENER";
 const ENV_PRIVATE_CONNECT_TIMEOUT: &str = "CONDUIT_PROXY_PRIVATE_CONNECT_TIMEOUT";
 const ENV_PUBLIC_CONNECT_TIMEOUT: &str = "CONDUIT_PROXY_PUBLIC_CONNECT_TIMEOUT";
+const ENV_INBOUND_ROUTER_CAPACITY: &str = "CONDUIT_PROXY_INBOUND_ROUTER_CAPACITY";
+const ENV_OUTBOUND_ROUTER_CAPACITY: &str = "CONDUIT_PROXY_OUTBOUND_ROUTER_CAPACITY";
+const ENV_INBOUND_ROUTER_MAX_IDLE_AGE_SECS: &str = "CONDUIT_PROXY_INBOUND_ROUTER_MAX_IDLE_AGE_SECS";
+const ENV_OUTBOUND_ROUTER_MAX_IDLE_AGE_SECS: &str = "CONDUIT_PROXY_OUTBOUND_ROUTER_MAX_IDLE_AGE_SECS";
+const ENV_MAX_CONNECTIONS_PER_ENDPOINT: &str = "CONDUIT_PROXY_MAX_CONNECTIONS_PER_ENDPOINT";
+const ENV_CONNECTION_IDLE_TIMEOUT_SECS: &str = "CONDUIT_PROXY_CONNECTION_IDLE_TIMEOUT_SECS";
+const ENV_CONNECTION_LATENCY_THRESHOLD_MS: &str = "CONDUIT_PROXY_CONNECTION_LATENCY_THRESHOLD_MS";
+const ENV_ZONE_SPILLOVER_THRESHOLD: &str = "CONDUIT_PROXY_ZONE_SPILLOVER_THRESHOLD";
+const ENV_WORKER_THREADS: &str = "CONDUIT_PROXY_WORKER_THREADS";
+const ENV_OPAQUE_PORTS: &str = "CONDUIT_PROXY_OPAQUE_PORTS";
+const ENV_INBOUND_INTERCEPTION_MODE: &str = "CONDUIT_PROXY_INBOUND_INTERCEPTION_MODE";
 
 const ENV_NODE_NAME: &str = "CONDUIT_PROXY_NODE_NAME";
 const ENV_POD_NAME: &str = "CONDUIT_PROXY_POD_NAME";
@@ -148,6 +256,12 @@ const ENV_DESTINATIONS_AUTOCOMPLETE_FQDN: &str = "CONDUIT_PROXY_DESTINATIONS_AUT
 
 pub const ENV_CONTROL_URL: &str = "CONDUIT_PROXY_CONTROL_URL";
 const ENV_RESOLV_CONF: &str = "CONDUIT_RESOLV_CONF";
+const ENV_ADDRESS_UPDATE_INTERVAL_SECS: &str = "CONDUIT_PROXY_ADDRESS_UPDATE_INTERVAL_SECS";
+const ENV_DESTINATION_SNAPSHOT_PATH: &str = "CONDUIT_PROXY_DESTINATION_SNAPSHOT_PATH";
+pub const ENV_DESTINATIONS_PATH: &str = "CONDUIT_PROXY_DESTINATIONS_PATH";
+const ENV_TELEMETRY_PATH: &str = "CONDUIT_PROXY_TELEMETRY_PATH";
+pub const ENV_TRACE_COLLECTOR: &str = "CONDUIT_PROXY_TRACE_COLLECTOR";
+const ENV_TRACE_SAMPLE_RATE: &str = "CONDUIT_PROXY_TRACE_SAMPLE_RATE";
 
 // Default values for various configuration fields
 const DEFAULT_EVENT_BUFFER_CAPACITY: usize = 10_000; // FIXME
@@ -157,7 +271,13 @@ const DEFAULT_PRIVATE_LISTENER: &str = "tcp://127.0.0.1:4140";
 const DEFAULT_PUBLIC_LISTENER: &str = "tcp://0.0.0.0:4143";
 const DEFAULT_CONTROL_LISTENER: &str = "tcp://0.0.0.0:4190";
 const DEFAULT_PRIVATE_CONNECT_TIMEOUT_MS: u64 = 20;
+const DEFAULT_MAX_CONNECTIONS_PER_ENDPOINT: usize = 4;
+const DEFAULT_CONNECTION_IDLE_TIMEOUT_SECS: u64 = 10;
+const DEFAULT_ZONE_SPILLOVER_THRESHOLD: usize = 10;
+const DEFAULT_WORKER_THREADS: usize = 1;
 const DEFAULT_RESOLV_CONF: &str = "/etc/resolv.conf";
+const DEFAULT_ADDRESS_UPDATE_INTERVAL_SECS: u64 = 10;
+const DEFAULT_TRACE_SAMPLE_RATE: f64 = 1.0;
 
 // ===== impl Config =====
 
@@ -174,11 +294,35 @@ impl<'a> TryFrom<&'a Strings> for Config {
         let private_forward = parse(strings, ENV_PRIVATE_FORWARD, str::parse);
         let public_connect_timeout = parse(strings, ENV_PUBLIC_CONNECT_TIMEOUT, parse_number);
         let private_connect_timeout = parse(strings, ENV_PRIVATE_CONNECT_TIMEOUT, parse_number);
+        let inbound_router_capacity = parse(strings, ENV_INBOUND_ROUTER_CAPACITY, parse_nonzero);
+        let outbound_router_capacity =
+            parse(strings, ENV_OUTBOUND_ROUTER_CAPACITY, parse_nonzero);
+        let inbound_router_max_idle_age =
+            parse(strings, ENV_INBOUND_ROUTER_MAX_IDLE_AGE_SECS, parse_nonzero);
+        let outbound_router_max_idle_age =
+            parse(strings, ENV_OUTBOUND_ROUTER_MAX_IDLE_AGE_SECS, parse_nonzero);
+        let max_connections_per_endpoint =
+            parse(strings, ENV_MAX_CONNECTIONS_PER_ENDPOINT, parse_nonzero);
+        let connection_idle_timeout =
+            parse(strings, ENV_CONNECTION_IDLE_TIMEOUT_SECS, parse_number);
+        let connection_latency_threshold =
+            parse(strings, ENV_CONNECTION_LATENCY_THRESHOLD_MS, parse_number);
+        let zone_spillover_threshold =
+            parse(strings, ENV_ZONE_SPILLOVER_THRESHOLD, parse_nonzero);
+        let worker_threads = parse(strings, ENV_WORKER_THREADS, parse_nonzero);
+        let opaque_ports = parse(strings, ENV_OPAQUE_PORTS, parse_port_set);
+        let inbound_interception_mode =
+            parse(strings, ENV_INBOUND_INTERCEPTION_MODE, parse_interception_mode);
         let resolv_conf_path = strings.get(ENV_RESOLV_CONF);
+        let address_update_interval =
+            parse(strings, ENV_ADDRESS_UPDATE_INTERVAL_SECS, parse_nonzero);
+        let destination_snapshot_path = strings.get(ENV_DESTINATION_SNAPSHOT_PATH);
         let event_buffer_capacity = parse(strings, ENV_EVENT_BUFFER_CAPACITY, parse_number);
         let metrics_flush_interval_secs =
             parse(strings, ENV_METRICS_FLUSH_INTERVAL_SECS, parse_number);
         let report_timeout = parse(strings, ENV_REPORT_TIMEOUT_SECS, parse_number);
+        let trace_collector = parse(strings, ENV_TRACE_COLLECTOR, str::parse);
+        let trace_sample_rate = parse(strings, ENV_TRACE_SAMPLE_RATE, parse_sample_rate);
         let pod_name = strings.get(ENV_POD_NAME);
         let pod_namespace = strings.get(ENV_POD_NAMESPACE);
         let pod_zone = strings.get(ENV_POD_ZONE);
@@ -186,15 +330,27 @@ impl<'a> TryFrom<&'a Strings> for Config {
         let destinations_autocomplete_fqdn =
             parse(strings, ENV_DESTINATIONS_AUTOCOMPLETE_FQDN, parse_environment);
 
+        let destinations_path = strings.get(ENV_DESTINATIONS_PATH);
+
         // There is no default controller URL because a default would make it
         // too easy to connect to the wrong controller, which would be dangerous.
-        let control_host_and_port = match parse(strings, ENV_CONTROL_URL, parse_url) {
-            Ok(Some(x)) => Ok(x),
-            Ok(None) => {
+        // Without one, destinations must be read from a file.
+        let control_plane = match (parse(strings, ENV_CONTROL_URL, parse_url)?, destinations_path?) {
+            (Some(host_and_port), None) => Ok(ControlPlane::Controller(host_and_port)),
+            // The telemetry file is only written without a controller.
+            (None, Some(path)) => strings.get(ENV_TELEMETRY_PATH)
+                .map(|telemetry_path| ControlPlane::Standalone {
+                    destinations_path: path.into(),
+                    telemetry_path: telemetry_path.map(PathBuf::from),
+                }),
+            (Some(_), Some(_)) => {
+                error!("only one of {} and {} may be set", ENV_CONTROL_URL, ENV_DESTINATIONS_PATH);
+                Err(Error::InvalidEnvVar)
+            }
+            (None, None) => {
                 error!("{} is not set", ENV_CONTROL_URL);
                 Err(Error::InvalidEnvVar)
-            },
-            Err(e) => Err(e),
+            }
         };
 
         Ok(Config {
@@ -206,6 +362,8 @@ impl<'a> TryFrom<&'a Strings> for Config {
                 addr: public_listener_addr?
                     .unwrap_or_else(|| Addr::from_str(DEFAULT_PUBLIC_LISTENER).unwrap()),
             },
+            inbound_interception_mode: inbound_interception_mode?
+                .unwrap_or(InterceptionMode::Redirect),
             control_listener: Listener {
                 addr: control_listener_addr?
                     .unwrap_or_else(|| Addr::from_str(DEFAULT_CONTROL_LISTENER).unwrap()),
@@ -215,10 +373,29 @@ impl<'a> TryFrom<&'a Strings> for Config {
             private_connect_timeout:
                 Duration::from_millis(private_connect_timeout?
                                           .unwrap_or(DEFAULT_PRIVATE_CONNECT_TIMEOUT_MS)),
+            inbound_router_capacity: inbound_router_capacity?,
+            outbound_router_capacity: outbound_router_capacity?,
+            inbound_router_max_idle_age: inbound_router_max_idle_age?.map(Duration::from_secs),
+            outbound_router_max_idle_age: outbound_router_max_idle_age?.map(Duration::from_secs),
+            max_connections_per_endpoint: max_connections_per_endpoint?
+                .unwrap_or(DEFAULT_MAX_CONNECTIONS_PER_ENDPOINT),
+            connection_idle_timeout:
+                Duration::from_secs(connection_idle_timeout?
+                                        .unwrap_or(DEFAULT_CONNECTION_IDLE_TIMEOUT_SECS)),
+            connection_latency_threshold:
+                connection_latency_threshold?.map(Duration::from_millis),
+            zone_spillover_threshold: zone_spillover_threshold?
+                .unwrap_or(DEFAULT_ZONE_SPILLOVER_THRESHOLD),
+            worker_threads: worker_threads?.unwrap_or(DEFAULT_WORKER_THREADS),
+            opaque_ports: opaque_ports?.unwrap_or_default(),
             resolv_conf_path: resolv_conf_path?
                 .unwrap_or(DEFAULT_RESOLV_CONF.into())
                 .into(),
-            control_host_and_port: control_host_and_port?,
+            control_plane: control_plane?,
+            address_update_interval:
+                Duration::from_secs(address_update_interval?
+                                        .unwrap_or(DEFAULT_ADDRESS_UPDATE_INTERVAL_SECS)),
+            destination_snapshot_path: destination_snapshot_path?.map(PathBuf::from),
 
             event_buffer_capacity: event_buffer_capacity?.unwrap_or(DEFAULT_EVENT_BUFFER_CAPACITY),
             metrics_flush_interval:
@@ -226,6 +403,8 @@ impl<'a> TryFrom<&'a Strings> for Config {
                                         .unwrap_or(DEFAULT_METRICS_FLUSH_INTERVAL_SECS)),
             report_timeout:
                 Duration::from_secs(report_timeout?.unwrap_or(DEFAULT_REPORT_TIMEOUT_SECS)),
+            trace_collector: trace_collector?,
+            trace_sample_rate: trace_sample_rate?.unwrap_or(DEFAULT_TRACE_SAMPLE_RATE),
             pod_name: pod_name?,
             pod_namespace: pod_namespace?,
             pod_zone: pod_zone?,
@@ -324,10 +503,43 @@ fn parse_environment(s: &str) -> Result<Environment, ParseError> {
     }
 }
 
+fn parse_interception_mode(s: &str) -> Result<InterceptionMode, ParseError> {
+    match s {
+        "redirect" => Ok(InterceptionMode::Redirect),
+        "tproxy" => Ok(InterceptionMode::Tproxy),
+        _ => Err(ParseError::InterceptionModeUnsupported),
+    }
+}
+
 fn parse_number<T>(s: &str) -> Result<T, ParseError> where T: FromStr {
     s.parse().map_err(|_| ParseError::NotANumber)
 }
 
+fn parse_nonzero<T>(s: &str) -> Result<T, ParseError> where T: FromStr + Default + PartialEq {
+    let n = parse_number(s)?;
+    if n == T::default() {
+        return Err(ParseError::NotPositive);
+    }
+    Ok(n)
+}
+
+fn parse_port_set(s: &str) -> Result<HashSet<u16>, ParseError> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(parse_number)
+        .collect()
+}
+
+fn parse_sample_rate(s: &str) -> Result<f64, ParseError> {
+    let rate: f64 = parse_number(s)?;
+    if rate >= 0.0 && rate <= 1.0 {
+        Ok(rate)
+    } else {
+        Err(ParseError::OutOfRange)
+    }
+}
+
 fn parse_url(s: &str) -> Result<HostAndPort, ParseError> {
     let url = Url::parse(&s).map_err(|_| ParseError::UrlError(UrlError::SyntaxError))?;
     let host = url.host()
@@ -362,3 +574,24 @@ fn parse<T, Parse>(strings: &Strings, name: &str, parse: Parse) -> Result<Option
         None => Ok(None),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_interception_mode_accepts_known_modes() {
+        assert_eq!(parse_interception_mode("redirect").unwrap(), InterceptionMode::Redirect);
+        assert_eq!(parse_interception_mode("tproxy").unwrap(), InterceptionMode::Tproxy);
+    }
+
+    #[test]
+    fn parse_interception_mode_rejects_unknown_modes() {
+        for s in &["", "TPROXY", "Redirect", "tproxy ", "nat"] {
+            match parse_interception_mode(s) {
+                Err(ParseError::InterceptionModeUnsupported) => {}
+                res => panic!("{:?} parsed as {:?}", s, res),
+            }
+        }
+    }
+}
@@ -1,13 +1,14 @@
 use futures::*;
+use net2::TcpBuilder;
 use std;
 use std::io;
-use std::net::SocketAddr;
+use std::net::{Shutdown, SocketAddr};
 use tokio_core;
-use tokio_core::net::{TcpListener, TcpStreamNew};
+use tokio_core::net::{TcpListener, TcpStream, TcpStreamNew};
 use tokio_core::reactor::Handle;
 use tokio_io::{AsyncRead, AsyncWrite};
 
-use config::Addr;
+use config::{Addr, InterceptionMode};
 use transport;
 
 pub type PlaintextSocket = tokio_core::net::TcpStream;
@@ -15,8 +16,12 @@ pub type PlaintextSocket = tokio_core::net::TcpStream;
 pub struct BoundPort {
     inner: std::net::TcpListener,
     local_addr: SocketAddr,
+    transparent: bool,
 }
 
+/// The backlog that `std::net::TcpListener::bind` listens with.
+const LISTEN_BACKLOG: i32 = 128;
+
 /// Initiates a client connection to the given address.
 pub fn connect(addr: &SocketAddr, executor: &Handle) -> Connecting {
     Connecting(PlaintextSocket::connect(addr, executor))
@@ -40,11 +45,34 @@ pub enum Connection {
 
 impl BoundPort {
     pub fn new(addr: Addr) -> Result<Self, io::Error> {
-        let inner = std::net::TcpListener::bind(SocketAddr::from(addr))?;
+        Self::bind(SocketAddr::from(addr), false)
+    }
+
+    /// Binds a listener that accepts connections redirected to it by
+    /// TPROXY, which are addressed to their original destination rather than
+    /// to the listener.
+    pub fn new_transparent(addr: Addr) -> Result<Self, io::Error> {
+        Self::bind(SocketAddr::from(addr), true)
+    }
+
+    fn bind(addr: SocketAddr, transparent: bool) -> Result<Self, io::Error> {
+        let builder = match addr {
+            SocketAddr::V4(_) => TcpBuilder::new_v4()?,
+            SocketAddr::V6(_) => TcpBuilder::new_v6()?,
+        };
+        set_reuse(&builder)?;
+        // The listener must be transparent before it's bound, so that it may
+        // be bound to an address that isn't local.
+        if transparent {
+            transport::set_transparent(&builder, addr.is_ipv6())?;
+        }
+        builder.bind(addr)?;
+        let inner = builder.listen(LISTEN_BACKLOG)?;
         let local_addr = inner.local_addr()?;
         Ok(BoundPort {
             inner,
             local_addr,
+            transparent,
         })
     }
 
@@ -52,6 +80,25 @@ impl BoundPort {
         self.local_addr
     }
 
+    /// Binds another listener to the same address, so that several reactors
+    /// can accept connections on it.
+    ///
+    /// Each listener has its own accept queue, and the kernel spreads new
+    /// connections over them. Were the reactors to share one listener, every
+    /// one of them would be woken for each connection. Platforms without
+    /// `SO_REUSEPORT` share one anyway.
+    pub fn bind_another(&self) -> Result<Self, io::Error> {
+        if cfg!(unix) {
+            return Self::bind(self.local_addr, self.transparent);
+        }
+
+        Ok(BoundPort {
+            inner: self.inner.try_clone()?,
+            local_addr: self.local_addr,
+            transparent: self.transparent,
+        })
+    }
+
     // Listen for incoming connections and dispatch them to the handler `f`.
     //
     // This ensures that every incoming connection has the correct options set.
@@ -81,6 +128,23 @@ impl BoundPort {
     }
 }
 
+/// Lets several listeners bind the same address, as long as every one of them
+/// sets `SO_REUSEPORT`.
+#[cfg(unix)]
+fn set_reuse(builder: &TcpBuilder) -> io::Result<()> {
+    use net2::unix::UnixTcpBuilderExt;
+
+    // As `std::net::TcpListener::bind` does.
+    builder.reuse_address(true)?;
+    builder.reuse_port(true)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_reuse(_: &TcpBuilder) -> io::Result<()> {
+    Ok(())
+}
+
 // ===== impl Connecting =====
 
 impl Future for Connecting {
@@ -97,8 +161,12 @@ impl Future for Connecting {
 // ===== impl Connection =====
 
 impl Connection {
-    pub fn original_dst_addr(&self) -> Option<SocketAddr> {
-        transport::get_original_dst(self.socket())
+    pub fn original_dst_addr(&self, mode: InterceptionMode) -> Option<SocketAddr> {
+        match mode {
+            InterceptionMode::Redirect => transport::get_original_dst(self.socket()),
+            // TPROXY doesn't rewrite the destination of a connection.
+            InterceptionMode::Tproxy => self.local_addr().ok(),
+        }
     }
 
     pub fn local_addr(&self) -> Result<SocketAddr, std::io::Error> {
@@ -155,7 +223,26 @@ impl AsyncWrite for Connection {
         use self::Connection::*;
 
         match *self {
-            Plain(ref mut t) => t.shutdown(),
+            Plain(ref mut t) => {
+                try_ready!(AsyncWrite::shutdown(t));
+                // tokio-core leaves the socket open for writing, so the peer
+                // wouldn't see the end of the stream.
+                if let Err(e) = PlaintextSocket::shutdown(t, Shutdown::Write) {
+                    if e.kind() != io::ErrorKind::NotConnected {
+                        return Err(e);
+                    }
+                }
+                Ok(Async::Ready(()))
+            }
+        }
+    }
+}
+
+// Plaintext sockets have nothing to inspect, so the kernel may move their bytes.
+impl transport::Splice for Connection {
+    fn tcp_stream(&self) -> Option<&TcpStream> {
+        match *self {
+            Connection::Plain(ref socket) => Some(socket),
         }
     }
 }
@@ -0,0 +1,205 @@
+//! Reads destinations from a local file, for running without a controller.
+//!
+//! The file has a line per destination: its authority, followed by its
+//! addresses, separated by whitespace. An address may be followed by `/` and
+//! a weight; otherwise its weight is 1. Blank lines and lines starting with
+//! `#` are ignored.
+//!
+//! ```text
+//! # authority                          addresses
+//! web.default.svc.cluster.local:8080   10.1.1.1:8080 10.1.1.2:8080/2
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use bytes::Bytes;
+use futures::Future;
+use http::uri::Authority;
+use tokio_core::reactor::{Handle, Timeout as ReactorTimeout};
+
+use fully_qualified_authority::FullyQualifiedAuthority;
+
+/// The addresses of each destination, with their weights.
+///
+/// Weights are kept as the controller's `WeightedAddr`s are, though neither
+/// affects load balancing yet.
+pub type Destinations = HashMap<FullyQualifiedAuthority, HashMap<SocketAddr, u32>>;
+
+/// Watches a destinations file, reading it again whenever it's modified.
+pub struct DestinationsFile {
+    path: PathBuf,
+    /// When the file was modified, as of when it was last read.
+    modified: Option<SystemTime>,
+    /// Fires when the file should be checked for changes.
+    check: ReactorTimeout,
+}
+
+/// How often the file is checked for changes.
+const CHECK_INTERVAL_SECS: u64 = 1;
+
+/// The weight of an address that doesn't have one.
+const DEFAULT_WEIGHT: u32 = 1;
+
+// ===== impl DestinationsFile =====
+
+impl DestinationsFile {
+    pub fn new(path: PathBuf, executor: &Handle) -> Self {
+        // Read the file as soon as it's first polled.
+        let check = ReactorTimeout::new(Duration::from_secs(0), executor).expect("timer");
+        DestinationsFile {
+            path,
+            modified: None,
+            check,
+        }
+    }
+
+    /// Returns the destinations in the file, if it was modified since it was
+    /// last read.
+    ///
+    /// If the file can't be read, the error is logged and the destinations
+    /// that were read before are kept.
+    pub fn poll(&mut self) -> Option<Destinations> {
+        let mut latest = None;
+        while self.check.poll().expect("timer").is_ready() {
+            self.check.reset(Instant::now() + Duration::from_secs(CHECK_INTERVAL_SECS));
+            if let Some(destinations) = self.read_if_modified() {
+                latest = Some(destinations);
+            }
+        }
+        latest
+    }
+
+    fn read_if_modified(&mut self) -> Option<Destinations> {
+        let modified = match fs::metadata(&self.path).and_then(|m| m.modified()) {
+            Ok(modified) => modified,
+            Err(e) => {
+                warn!("failed to read destinations file {:?}: {}", self.path, e);
+                return None;
+            }
+        };
+
+        if self.modified == Some(modified) {
+            return None;
+        }
+
+        match read(&self.path) {
+            Ok(destinations) => {
+                info!("read {} destinations from {:?}", destinations.len(), self.path);
+                self.modified = Some(modified);
+                Some(destinations)
+            }
+            Err(e) => {
+                warn!("failed to read destinations file {:?}: {}", self.path, e);
+                // Don't read the file again until it's modified again.
+                self.modified = Some(modified);
+                None
+            }
+        }
+    }
+}
+
+impl fmt::Debug for DestinationsFile {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("DestinationsFile")
+            .field("path", &self.path)
+            .field("modified", &self.modified)
+            .finish()
+    }
+}
+
+fn read(path: &Path) -> io::Result<Destinations> {
+    let mut contents = String::new();
+    File::open(path)?.read_to_string(&mut contents)?;
+    parse(&contents)
+}
+
+/// Parses the contents of a destinations file.
+///
+/// Unlike a snapshot, the file is written by hand, so a line that can't be
+/// parsed fails the whole file rather than being skipped.
+fn parse(contents: &str) -> io::Result<Destinations> {
+    let invalid = |n: usize, msg: &str| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", n + 1, msg))
+    };
+
+    let mut destinations = HashMap::new();
+    for (n, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let authority = fields.next()
+            .and_then(|a| Authority::from_shared(Bytes::from(a)).ok())
+            .ok_or_else(|| invalid(n, "invalid authority"))?;
+        let authority = FullyQualifiedAuthority::new(&authority, None, None);
+
+        let mut addrs = HashMap::new();
+        for field in fields {
+            let mut parts = field.splitn(2, '/');
+            let addr = parts.next()
+                .and_then(|a| a.parse::<SocketAddr>().ok())
+                .ok_or_else(|| invalid(n, "invalid address"))?;
+            let weight = match parts.next() {
+                Some(w) => w.parse().map_err(|_| invalid(n, "invalid weight"))?,
+                None => DEFAULT_WEIGHT,
+            };
+            addrs.insert(addr, weight);
+        }
+
+        if destinations.insert(authority, addrs).is_some() {
+            return Err(invalid(n, "duplicate authority"));
+        }
+    }
+
+    Ok(destinations)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use bytes::Bytes;
+    use http::uri::Authority;
+
+    use fully_qualified_authority::FullyQualifiedAuthority;
+
+    fn fqa(s: &'static str) -> FullyQualifiedAuthority {
+        let authority = Authority::from_shared(Bytes::from_static(s.as_bytes())).unwrap();
+        FullyQualifiedAuthority::new(&authority, None, None)
+    }
+
+    #[test]
+    fn test_parse_destinations() {
+        let contents = "\
+            # a comment\n\
+            \n\
+            web.default.svc.cluster.local:8080 10.1.1.1:8080 [fe80::1]:8080/3\n\
+            empty.default.svc.cluster.local:80\n\
+        ";
+        let destinations = super::parse(contents).unwrap();
+        assert_eq!(destinations.len(), 2);
+
+        let web = &destinations[&fqa("web.default.svc.cluster.local:8080")];
+        assert_eq!(web.len(), 2);
+        assert_eq!(web[&"10.1.1.1:8080".parse::<SocketAddr>().unwrap()], 1);
+        assert_eq!(web[&"[fe80::1]:8080".parse::<SocketAddr>().unwrap()], 3);
+
+        assert!(destinations[&fqa("empty.default.svc.cluster.local:80")].is_empty());
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_lines() {
+        assert!(super::parse("web:80 10.1.1.1").is_err());
+        assert!(super::parse("web:80 10.1.1.1:80/x").is_err());
+        assert!(super::parse("web:80 10.1.1.1/2").is_err());
+        assert!(super::parse("web:80 10.1.1.1:80\nweb:80 10.1.1.2:80").is_err());
+    }
+}
@@ -1,21 +1,33 @@
+use std::cell::{Cell, RefCell};
+use std::cmp;
 use std::collections::{HashSet, VecDeque};
 use std::collections::hash_map::{Entry, HashMap};
+use std::fmt;
 use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use futures::{Async, Future, Poll, Stream};
+use futures::{task, Async, Future, Poll, Stream};
 use futures::sync::mpsc;
+use tokio_core::reactor::{Handle, Timeout as ReactorTimeout};
 use tower::Service;
 use tower_discover::{Change, Discover};
 use tower_grpc;
+use tower_grpc::protobuf::Codec as Protobuf;
+use url;
 
+use ctx::transport::Labels;
+use dns;
 use fully_qualified_authority::FullyQualifiedAuthority;
 
-use super::codec::Protobuf;
 use super::pb::common::{Destination, TcpAddress};
 use super::pb::proxy::destination::Update as PbUpdate;
 use super::pb::proxy::destination::client::Destination as DestinationSvc;
-use super::pb::proxy::destination::client::destination_methods::Get as GetRpc;
 use super::pb::proxy::destination::update::Update as PbUpdate2;
+use super::destinations_file::{self, DestinationsFile};
+use super::snapshot;
 
 pub type ClientBody = ::tower_grpc::client::codec::EncodingBody<
     Protobuf<Destination, PbUpdate>,
@@ -35,10 +47,36 @@ pub struct Watch<B> {
     bind: B,
 }
 
+/// One side of a `Watch` that was split by zone: either the endpoints in a
+/// zone, or all others.
+///
+/// Both sides must be polled by the same task.
+#[derive(Debug)]
+pub struct ZoneWatch<B> {
+    split: Rc<RefCell<ZoneSplit>>,
+    local: bool,
+    /// The number of endpoints on this side.
+    endpoints: Rc<Cell<usize>>,
+    bind: B,
+}
+
+/// The updates of a split `Watch`, sorted by zone.
+#[derive(Debug)]
+struct ZoneSplit {
+    rx: mpsc::UnboundedReceiver<Update>,
+    zone: Option<String>,
+    local: VecDeque<Update>,
+    remote: VecDeque<Update>,
+    /// The addresses in `zone`, so that removals go to the right side.
+    local_addrs: HashSet<SocketAddr>,
+}
+
 /// A background handle to eventually bind on the controller thread.
 #[derive(Debug)]
 pub struct Background {
     rx: mpsc::UnboundedReceiver<(FullyQualifiedAuthority, mpsc::UnboundedSender<Update>)>,
+    address_update_interval: Duration,
+    snapshot_path: Option<PathBuf>,
 }
 
 type DiscoveryWatch<F> = DestinationSet<
@@ -50,9 +88,16 @@ type DiscoveryWatch<F> = DestinationSet<
 
 /// A future returned from `Background::work()`, doing the work of talking to
 /// the controller destination API.
+///
+/// Destinations that the controller does not know about are resolved with
+/// DNS instead.
 #[derive(Debug)]
 pub struct DiscoveryWork<F> {
+    /// The zone of the services that the controller can resolve.
+    default_zone: Option<String>,
     destinations: HashMap<FullyQualifiedAuthority, DiscoveryWatch<F>>,
+    dns_resolver: dns::Resolver,
+    executor: Handle,
     /// A queue of authorities that need to be reconnected.
     reconnects: VecDeque<FullyQualifiedAuthority>,
     /// The Destination.Get RPC client service.
@@ -60,22 +105,125 @@ pub struct DiscoveryWork<F> {
     rpc_ready: bool,
     /// A receiver of new watch requests.
     rx: mpsc::UnboundedReceiver<(FullyQualifiedAuthority, mpsc::UnboundedSender<Update>)>,
+    /// How long the controller may go without updating a destination before
+    /// its addresses are considered stale.
+    stale_after: Duration,
+    /// Fires when destinations should be checked for staleness again.
+    stale_check: ReactorTimeout,
+    /// Addresses loaded from a snapshot, for destinations that haven't been
+    /// watched yet.
+    restored: HashMap<FullyQualifiedAuthority, Endpoints>,
+    snapshot: Option<Snapshot>,
+}
+
+/// A future returned from `Background::work_from_file()`, serving
+/// destinations from a local file instead of the controller.
+///
+/// Destinations that aren't in the file are resolved with DNS.
+#[derive(Debug)]
+pub struct FileWork {
+    destinations: HashMap<FullyQualifiedAuthority, FileSet>,
+    dns_resolver: dns::Resolver,
+    executor: Handle,
+    file: DestinationsFile,
+    /// The destinations in the file, as of when it was last read.
+    listed: destinations_file::Destinations,
+    /// A receiver of new watch requests.
+    rx: mpsc::UnboundedReceiver<(FullyQualifiedAuthority, mpsc::UnboundedSender<Update>)>,
+}
+
+/// A destination watched by `FileWork`.
+#[derive(Debug)]
+struct FileSet {
+    addrs: Endpoints,
+    /// Resolves the destination's name while it isn't in the file.
+    dns: Option<DnsQuery>,
+    /// The watches of the destination.
+    txs: Vec<mpsc::UnboundedSender<Update>>,
+}
+
+/// Writes the addresses of destinations to a snapshot file as they change.
+struct Snapshot {
+    path: PathBuf,
+    /// Whether addresses changed since the snapshot was last written.
+    dirty: bool,
+    /// Fires when the snapshot may be written again.
+    throttle: ReactorTimeout,
 }
 
 #[derive(Debug)]
 struct DestinationSet<R> {
-    addrs: HashSet<SocketAddr>,
-    needs_reconnect: bool,
-    rx: R,
-    tx: mpsc::UnboundedSender<Update>,
+    addrs: Endpoints,
+    /// Whether `addrs` changed since the last poll.
+    changed: bool,
+    /// When the controller last sent an update for this destination.
+    last_update: Instant,
+    /// Whether the destination was stale when last checked.
+    stale: bool,
+    /// Whether the next set of addresses from the controller replaces
+    /// `addrs`, rather than adding to it. This is the case after the stream
+    /// is (re)started, when `addrs` may be out of date.
+    resync: bool,
+    rx: Resolution<R>,
+    /// The watches of the destination. Each is sent every update, until it
+    /// is dropped.
+    txs: Vec<mpsc::UnboundedSender<Update>>,
 }
 
+/// Where the addresses of a `DestinationSet` come from.
 #[derive(Debug)]
+enum Resolution<R> {
+    /// Waiting for the controller to be ready so that the destination can be
+    /// (re)watched.
+    Pending,
+
+    /// A `Destination.Get` response stream.
+    Controller(R),
+
+    /// The controller does not know the destination, so its name is
+    /// periodically resolved with DNS.
+    Dns(DnsQuery),
+}
+
+/// Resolves a name with DNS, again whenever its records expire.
+struct DnsQuery {
+    host: url::Host,
+    port: u16,
+    resolving: Option<dns::SocketAddrListFuture>,
+    /// Fires when the name should be resolved again.
+    refresh: ReactorTimeout,
+}
+
+/// The addresses of a destination, with their labels.
+type Endpoints = HashMap<SocketAddr, Arc<Labels>>;
+
+#[derive(Clone, Debug)]
 enum Update {
-    Insert(SocketAddr),
+    Insert(SocketAddr, Arc<Labels>),
     Remove(SocketAddr),
 }
 
+/// How long to wait before resolving a name again after resolution failed,
+/// or when its records don't expire.
+const DNS_RETRY_SECS: u64 = 5;
+
+/// The least amount of time to wait before resolving a name again, so that
+/// records with very short TTLs aren't resolved constantly.
+const DNS_MIN_REFRESH_SECS: u64 = 1;
+
+/// The port used when a destination does not specify one.
+const DEFAULT_PORT: u16 = 80;
+
+/// The label that holds the zone of an endpoint.
+const ZONE_LABEL: &str = "zone";
+
+/// How many address update intervals may pass without an update before a
+/// destination is considered stale.
+const MISSED_UPDATES_BEFORE_STALE: u32 = 2;
+
+/// The least amount of time between writes of the snapshot.
+const SNAPSHOT_INTERVAL_SECS: u64 = 5;
+
 /// Bind a `SocketAddr` with a protocol.
 pub trait Bind {
     /// Requests handled by the discovered services
@@ -93,14 +241,25 @@ pub trait Bind {
     type Service: Service<Request = Self::Request, Response = Self::Response, Error = Self::Error>;
 
     /// Bind a socket address with a service.
-    fn bind(&self, addr: &SocketAddr) -> Result<Self::Service, Self::BindError>;
+    ///
+    /// `labels` describe the endpoint at `addr`, as given by service
+    /// discovery.
+    fn bind(&self, addr: &SocketAddr, labels: &Arc<Labels>) -> Result<Self::Service, Self::BindError>;
 }
 
 /// Creates a "channel" of `Discovery` to `Background` handles.
 ///
 /// The `Discovery` is used by a listener, the `Background` is consumed
 /// on the controller thread.
-pub fn new() -> (Discovery, Background) {
+///
+/// The controller is expected to update each destination at least once every
+/// `address_update_interval`. If `snapshot_path` is set, the addresses of
+/// destinations are saved there, and restored from there when the proxy
+/// starts.
+pub fn new(
+    address_update_interval: Duration,
+    snapshot_path: Option<PathBuf>,
+) -> (Discovery, Background) {
     let (tx, rx) = mpsc::unbounded();
     (
         Discovery {
@@ -108,6 +267,8 @@ pub fn new() -> (Discovery, Background) {
         },
         Background {
             rx,
+            address_update_interval,
+            snapshot_path,
         },
     )
 }
@@ -132,6 +293,37 @@ impl Discovery {
 
 // ==== impl Watch =====
 
+impl<B: Clone> Watch<B> {
+    /// Splits the watch into the endpoints in `zone`, and all others.
+    ///
+    /// Endpoints without a zone label are treated as if they're in `zone`,
+    /// since their locality is unknown. If `zone` is unknown, all endpoints
+    /// are.
+    pub fn split_by_zone(self, zone: Option<String>) -> (ZoneWatch<B>, ZoneWatch<B>) {
+        let split = Rc::new(RefCell::new(ZoneSplit {
+            rx: self.rx,
+            zone,
+            local: VecDeque::new(),
+            remote: VecDeque::new(),
+            local_addrs: HashSet::new(),
+        }));
+
+        let local = ZoneWatch {
+            split: split.clone(),
+            local: true,
+            endpoints: Rc::new(Cell::new(0)),
+            bind: self.bind.clone(),
+        };
+        let remote = ZoneWatch {
+            split,
+            local: false,
+            endpoints: Rc::new(Cell::new(0)),
+            bind: self.bind,
+        };
+        (local, remote)
+    }
+}
+
 impl<B> Discover for Watch<B>
 where
     B: Bind,
@@ -148,14 +340,18 @@ where
         trace!("watch: {:?}", up);
         let update = match up {
             Ok(Async::Ready(Some(update))) => update,
-            Ok(Async::Ready(None)) => unreachable!(),
+            Ok(Async::Ready(None)) => {
+                // Discovery stopped, so the endpoints won't change again.
+                trace!("watch: discovery stopped");
+                return Ok(Async::NotReady);
+            }
             Ok(Async::NotReady) => return Ok(Async::NotReady),
             Err(_) => return Err(()),
         };
 
         match update {
-            Update::Insert(addr) => {
-                let service = self.bind.bind(&addr).map_err(|_| ())?;
+            Update::Insert(addr, labels) => {
+                let service = self.bind.bind(&addr, &labels).map_err(|_| ())?;
 
                 Ok(Async::Ready(Change::Insert(addr, service)))
             }
@@ -164,16 +360,182 @@ where
     }
 }
 
+// ==== impl ZoneWatch =====
+
+impl<B> ZoneWatch<B> {
+    /// Returns a handle to the number of endpoints on this side, as of when
+    /// it was last polled.
+    pub fn endpoints(&self) -> Rc<Cell<usize>> {
+        self.endpoints.clone()
+    }
+}
+
+impl<B> Discover for ZoneWatch<B>
+where
+    B: Bind,
+{
+    type Key = SocketAddr;
+    type Request = B::Request;
+    type Response = B::Response;
+    type Error = B::Error;
+    type Service = B::Service;
+    type DiscoverError = ();
+
+    fn poll(&mut self) -> Poll<Change<Self::Key, Self::Service>, Self::DiscoverError> {
+        let update = try_ready!(self.split.borrow_mut().poll(self.local));
+        trace!("zone watch: local={} {:?}", self.local, update);
+
+        match update {
+            Update::Insert(addr, labels) => {
+                let service = self.bind.bind(&addr, &labels).map_err(|_| ())?;
+                self.endpoints.set(self.endpoints.get() + 1);
+
+                Ok(Async::Ready(Change::Insert(addr, service)))
+            }
+            Update::Remove(addr) => {
+                self.endpoints.set(self.endpoints.get().saturating_sub(1));
+
+                Ok(Async::Ready(Change::Remove(addr)))
+            }
+        }
+    }
+}
+
+// ==== impl ZoneSplit =====
+
+impl ZoneSplit {
+    /// Returns the next update for one side of the split.
+    fn poll(&mut self, local: bool) -> Poll<Update, ()> {
+        loop {
+            match self.rx.poll() {
+                Ok(Async::Ready(Some(update))) => self.sort(update),
+                Ok(Async::Ready(None)) => {
+                    // Discovery stopped, so the endpoints won't change
+                    // again.
+                    trace!("zone watch: discovery stopped");
+                    break;
+                }
+                Ok(Async::NotReady) => break,
+                Err(_) => return Err(()),
+            }
+        }
+
+        let (updates, other) = if local {
+            (&mut self.local, &self.remote)
+        } else {
+            (&mut self.remote, &self.local)
+        };
+
+        // The other side won't be woken by the receiver for updates that
+        // have already been received.
+        if !other.is_empty() {
+            task::current().notify();
+        }
+
+        match updates.pop_front() {
+            Some(update) => Ok(Async::Ready(update)),
+            None => Ok(Async::NotReady),
+        }
+    }
+
+    fn sort(&mut self, update: Update) {
+        match update {
+            Update::Insert(addr, labels) => {
+                let local = match (self.zone.as_ref(), labels.get(ZONE_LABEL)) {
+                    (Some(ours), Some(theirs)) => ours == theirs,
+                    _ => true,
+                };
+                if local {
+                    self.local_addrs.insert(addr);
+                    self.local.push_back(Update::Insert(addr, labels));
+                } else {
+                    self.remote.push_back(Update::Insert(addr, labels));
+                }
+            }
+            Update::Remove(addr) => {
+                if self.local_addrs.remove(&addr) {
+                    self.local.push_back(Update::Remove(addr));
+                } else {
+                    self.remote.push_back(Update::Remove(addr));
+                }
+            }
+        }
+    }
+}
+
 // ==== impl Background =====
 
 impl Background {
     /// Bind this handle to start talking to the controller API.
-    pub fn work<F>(self) -> DiscoveryWork<F> {
+    ///
+    /// `dns_resolver` is used for destinations that the controller does not
+    /// know about, i.e. those that aren't services in `default_zone`.
+    pub fn work<F>(
+        self,
+        dns_resolver: dns::Resolver,
+        default_zone: Option<String>,
+        executor: &Handle,
+    ) -> DiscoveryWork<F> {
+        let restored = self.snapshot_path.as_ref()
+            .map(|path| match snapshot::load(path) {
+                Ok(destinations) => {
+                    info!("restored {} destinations from {:?}", destinations.len(), path);
+                    destinations.into_iter()
+                        .map(|(auth, addrs)| (auth, unlabeled(addrs)))
+                        .collect()
+                }
+                Err(e) => {
+                    warn!("failed to read destination snapshot {:?}: {}", path, e);
+                    HashMap::new()
+                }
+            })
+            .unwrap_or_default();
+
+        let snapshot = self.snapshot_path.map(|path| {
+            let throttle = ReactorTimeout::new(Duration::from_secs(SNAPSHOT_INTERVAL_SECS), executor)
+                .expect("timer");
+            Snapshot {
+                path,
+                dirty: false,
+                throttle,
+            }
+        });
+
+        let stale_after = self.address_update_interval * MISSED_UPDATES_BEFORE_STALE;
+        let stale_check = ReactorTimeout::new(stale_after, executor).expect("timer");
+
         DiscoveryWork {
+            default_zone,
             destinations: HashMap::new(),
+            dns_resolver,
+            executor: executor.clone(),
             reconnects: VecDeque::new(),
             rpc_ready: false,
             rx: self.rx,
+            stale_after,
+            stale_check,
+            restored,
+            snapshot,
+        }
+    }
+
+    /// Bind this handle to serve destinations from the file at `path`,
+    /// without a controller.
+    ///
+    /// `dns_resolver` is used for destinations that aren't in the file.
+    pub fn work_from_file(
+        self,
+        path: PathBuf,
+        dns_resolver: dns::Resolver,
+        executor: &Handle,
+    ) -> FileWork {
+        FileWork {
+            destinations: HashMap::new(),
+            dns_resolver,
+            executor: executor.clone(),
+            file: DestinationsFile::new(path, executor),
+            listed: HashMap::new(),
+            rx: self.rx,
         }
     }
 }
@@ -200,15 +562,31 @@ where
         loop {
             trace!("poll_rpc");
             self.poll_new_watches(client);
+            remove_unwatched(&mut self.destinations, |set| &mut set.txs);
             self.poll_destinations();
 
             if self.reconnects.is_empty() || !self.rpc_ready {
                 break;
             }
         }
+
+        self.poll_stale();
+        self.poll_snapshot();
+    }
+
+    /// Returns the authorities of destinations that the controller hasn't
+    /// updated recently, and whose addresses may be out of date.
+    ///
+    /// Their last known addresses are still used.
+    pub fn stale_destinations(&self) -> Vec<String> {
+        let now = Instant::now();
+        self.destinations.iter()
+            .filter(|&(_, set)| set.is_stale(now, self.stale_after))
+            .map(|(auth, _)| auth.without_trailing_dot().to_owned())
+            .collect()
     }
 
-    fn poll_new_watches<S>(&mut self, mut client: &mut S)
+    fn poll_new_watches<S>(&mut self, client: &mut S)
     where
         S: Service<
             Request = ::http::Request<ClientBody>,
@@ -218,48 +596,70 @@ where
         >,
     {
         loop {
-            // if rpc service isn't ready, not much we can do...
             match client.poll_ready() {
                 Ok(Async::Ready(())) => {
                     self.rpc_ready = true;
                 }
                 Ok(Async::NotReady) => {
                     self.rpc_ready = false;
-                    break;
                 }
                 Err(err) => {
                     warn!("Destination.Get poll_ready error: {:?}", err);
                     self.rpc_ready = false;
-                    break;
                 }
             }
 
             // handle any pending reconnects first
-            if self.poll_reconnect(client) {
+            if self.rpc_ready && self.poll_reconnect(client) {
                 continue;
             }
 
-            let grpc = tower_grpc::Client::new(Protobuf::new(), &mut client);
-            let mut rpc = GetRpc::new(grpc);
-            // check for any new watches
+            // check for any new watches. Watches that need the controller are
+            // started by `poll_reconnect` once it is ready, so that names
+            // resolved with DNS don't have to wait for it.
             match self.rx.poll() {
                 Ok(Async::Ready(Some((auth, tx)))) => {
                     trace!("Destination.Get {:?}", auth);
                     match self.destinations.entry(auth) {
                         Entry::Occupied(mut occ) => {
-                            occ.get_mut().tx = tx;
+                            // Catch the new watch up on the addresses that are
+                            // already known.
+                            for (addr, labels) in &occ.get().addrs {
+                                let _ = tx.unbounded_send(Update::Insert(*addr, labels.clone()));
+                            }
+                            occ.get_mut().txs.push(tx);
                         }
                         Entry::Vacant(vac) => {
-                            let req = Destination {
-                                scheme: "k8s".into(),
-                                path: vac.key().without_trailing_dot().into(),
+                            let mut txs = vec![tx];
+                            let mut addrs = HashMap::new();
+                            let zone = self.default_zone.as_ref().map(String::as_str);
+                            let rx = if vac.key().is_cluster_service(zone) {
+                                // Route to the last known addresses until the
+                                // controller says otherwise.
+                                if let Some(restored) = self.restored.remove(vac.key()) {
+                                    debug!("restored {} addresses for {:?}", restored.len(), vac.key());
+                                    notify(&mut txs, vac.key(), &HashMap::new(), &restored);
+                                    addrs = restored;
+                                }
+                                self.reconnects.push_back(vac.key().clone());
+                                Resolution::Pending
+                            } else {
+                                trace!("{:?} is not a service; resolving with DNS", vac.key());
+                                let query = DnsQuery::new(
+                                    &self.dns_resolver,
+                                    vac.key(),
+                                    &self.executor,
+                                );
+                                Resolution::Dns(query)
                             };
-                            let stream = DestinationSvc::new(&mut rpc).get(req);
                             vac.insert(DestinationSet {
-                                addrs: HashSet::new(),
-                                needs_reconnect: false,
-                                rx: stream,
-                                tx,
+                                addrs,
+                                changed: false,
+                                last_update: Instant::now(),
+                                stale: false,
+                                resync: false,
+                                rx,
+                                txs,
                             });
                         }
                     }
@@ -285,8 +685,7 @@ where
         >,
     {
         debug_assert!(self.rpc_ready);
-        let grpc = tower_grpc::Client::new(Protobuf::new(), client);
-        let mut rpc = GetRpc::new(grpc);
+        let mut rpc = DestinationSvc::new(client);
 
         while let Some(auth) = self.reconnects.pop_front() {
             if let Some(set) = self.destinations.get_mut(&auth) {
@@ -295,8 +694,8 @@ where
                     scheme: "k8s".into(),
                     path: auth.without_trailing_dot().into(),
                 };
-                set.rx = DestinationSvc::new(&mut rpc).get(req);
-                set.needs_reconnect = false;
+                set.rx = Resolution::Controller(rpc.get(req));
+                set.resync = true;
                 return true;
             } else {
                 trace!("reconnect no longer needed: {:?}", auth);
@@ -307,57 +706,502 @@ where
 
     fn poll_destinations(&mut self) {
         for (auth, set) in &mut self.destinations {
-            if set.needs_reconnect {
+            let dns = match set.rx {
+                Resolution::Pending => continue,
+                Resolution::Controller(_) => None,
+                Resolution::Dns(ref mut query) => {
+                    let mut latest = None;
+                    while let Some(addrs) = query.poll(&self.dns_resolver) {
+                        latest = Some(addrs);
+                    }
+                    Some(latest)
+                }
+            };
+
+            match dns {
+                Some(Some(addrs)) => set.reset(auth, addrs),
+                Some(None) => {}
+                None => match set.poll_controller(auth) {
+                    Ok(()) => {}
+                    Err(Fallback::Reconnect) => {
+                        set.rx = Resolution::Pending;
+                        self.reconnects.push_back(FullyQualifiedAuthority::clone(auth));
+                    }
+                    Err(Fallback::Dns) => {
+                        debug!("controller does not know {:?}; resolving with DNS", auth);
+                        let mut query = DnsQuery::new(&self.dns_resolver, auth, &self.executor);
+                        while let Some(addrs) = query.poll(&self.dns_resolver) {
+                            set.reset(auth, addrs);
+                        }
+                        set.rx = Resolution::Dns(query);
+                    }
+                },
+            }
+
+            if set.changed {
+                set.changed = false;
+                if let Some(ref mut snapshot) = self.snapshot {
+                    snapshot.dirty = true;
+                }
+            }
+        }
+    }
+
+    /// Logs when destinations become stale, and when they are updated again.
+    ///
+    /// Stale destinations are also reported to the controller, but reports
+    /// can't be delivered while it is unreachable, so the transitions are
+    /// logged too.
+    fn poll_stale(&mut self) {
+        let now = Instant::now();
+
+        // Check again later, as destinations become stale without any other
+        // event waking the task.
+        if self.stale_check.poll().unwrap().is_ready() {
+            self.stale_check.reset(now + self.stale_after);
+            let _ = self.stale_check.poll();
+        }
+
+        let stale_after = self.stale_after;
+        for (auth, set) in &mut self.destinations {
+            let stale = set.is_stale(now, stale_after);
+            if stale == set.stale {
                 continue;
             }
-            let needs_reconnect = 'set: loop {
-                match set.rx.poll() {
-                    Ok(Async::Ready(Some(update))) => match update.update {
-                        Some(PbUpdate2::Add(a_set)) => for addr in a_set.addrs {
-                            if let Some(addr) = addr.addr.and_then(pb_to_sock_addr) {
-                                if set.addrs.insert(addr) {
-                                    trace!("update {:?} for {:?}", addr, auth);
-                                    let _ = set.tx.unbounded_send(Update::Insert(addr));
-                                }
+
+            set.stale = stale;
+            if stale {
+                warn!(
+                    "no update from the controller for {:?} in {:?}; using last known addresses",
+                    auth,
+                    stale_after
+                );
+            } else {
+                info!("controller updated {:?} again", auth);
+            }
+        }
+    }
+
+    /// Writes the snapshot if addresses changed, at most once every
+    /// `SNAPSHOT_INTERVAL_SECS`.
+    fn poll_snapshot(&mut self) {
+        let snapshot = match self.snapshot {
+            Some(ref mut snapshot) if snapshot.dirty => snapshot,
+            _ => return,
+        };
+
+        if snapshot.throttle.poll().unwrap().is_not_ready() {
+            return;
+        }
+
+        // Only the controller's destinations are saved; names resolved with
+        // DNS don't need the controller to be reachable. Restored
+        // destinations that haven't been watched yet are kept.
+        let destinations = self.destinations.iter()
+            .filter(|&(_, set)| !set.is_dns())
+            .map(|(auth, set)| (auth, set.addrs.keys()))
+            .chain(self.restored.iter().map(|(auth, addrs)| (auth, addrs.keys())));
+
+        trace!("writing destination snapshot to {:?}", snapshot.path);
+        if let Err(e) = snapshot::save(&snapshot.path, destinations) {
+            warn!("failed to write destination snapshot {:?}: {}", snapshot.path, e);
+        }
+
+        snapshot.dirty = false;
+        snapshot.throttle.reset(Instant::now() + Duration::from_secs(SNAPSHOT_INTERVAL_SECS));
+    }
+}
+
+// ==== impl FileWork =====
+
+impl FileWork {
+    pub fn poll(&mut self) {
+        self.poll_file();
+        self.poll_new_watches();
+        remove_unwatched(&mut self.destinations, |set| &mut set.txs);
+        self.poll_dns();
+    }
+
+    /// Updates watched destinations when the file changes.
+    fn poll_file(&mut self) {
+        let listed = match self.file.poll() {
+            Some(listed) => listed,
+            None => return,
+        };
+
+        for (auth, set) in &mut self.destinations {
+            match listed.get(auth) {
+                Some(addrs) => {
+                    set.dns = None;
+                    let addrs = unlabeled(addrs.keys().cloned());
+                    notify(&mut set.txs, auth, &set.addrs, &addrs);
+                    set.addrs = addrs;
+                }
+                None => if set.dns.is_none() {
+                    debug!("{:?} is no longer listed; resolving with DNS", auth);
+                    set.dns = Some(DnsQuery::new(&self.dns_resolver, auth, &self.executor));
+                },
+            }
+        }
+
+        self.listed = listed;
+    }
+
+    fn poll_new_watches(&mut self) {
+        loop {
+            match self.rx.poll() {
+                Ok(Async::Ready(Some((auth, tx)))) => {
+                    trace!("watch {:?}", auth);
+                    match self.destinations.entry(auth) {
+                        Entry::Occupied(mut occ) => {
+                            for (addr, labels) in &occ.get().addrs {
+                                let _ = tx.unbounded_send(Update::Insert(*addr, labels.clone()));
                             }
-                        },
-                        Some(PbUpdate2::Remove(r_set)) => for addr in r_set.addrs {
-                            if let Some(addr) = pb_to_sock_addr(addr) {
-                                if set.addrs.remove(&addr) {
-                                    trace!("remove {:?} for {:?}", addr, auth);
-                                    let _ = set.tx.unbounded_send(Update::Remove(addr));
+                            occ.get_mut().txs.push(tx);
+                        }
+                        Entry::Vacant(vac) => {
+                            let mut txs = vec![tx];
+                            let mut addrs = HashMap::new();
+                            let dns = match self.listed.get(vac.key()) {
+                                Some(listed) => {
+                                    addrs = unlabeled(listed.keys().cloned());
+                                    notify(&mut txs, vac.key(), &HashMap::new(), &addrs);
+                                    None
                                 }
-                            }
-                        },
-                        None => (),
-                    },
-                    Ok(Async::Ready(None)) => {
-                        trace!(
-                            "Destination.Get stream ended for {:?}, must reconnect",
-                            auth
-                        );
-                        break 'set true;
+                                None => {
+                                    trace!("{:?} is not listed; resolving with DNS", vac.key());
+                                    let query = DnsQuery::new(
+                                        &self.dns_resolver,
+                                        vac.key(),
+                                        &self.executor,
+                                    );
+                                    Some(query)
+                                }
+                            };
+                            vac.insert(FileSet {
+                                addrs,
+                                dns,
+                                txs,
+                            });
+                        }
                     }
-                    Ok(Async::NotReady) => break 'set false,
-                    Err(err) => {
-                        warn!("Destination.Get stream errored for {:?}: {:?}", auth, err);
-                        break 'set true;
+                }
+                Ok(Async::Ready(None)) => {
+                    trace!("Discover tx is dropped, shutdown?");
+                    return;
+                }
+                Ok(Async::NotReady) => return,
+                Err(_) => unreachable!("unbounded receiver doesn't error"),
+            }
+        }
+    }
+
+    fn poll_dns(&mut self) {
+        for (auth, set) in &mut self.destinations {
+            if let Some(ref mut query) = set.dns {
+                while let Some(addrs) = query.poll(&self.dns_resolver) {
+                    notify(&mut set.txs, auth, &set.addrs, &addrs);
+                    set.addrs = addrs;
+                }
+            }
+        }
+    }
+}
+
+// ===== impl DestinationSet =====
+
+/// Why a destination is no longer watched on the controller.
+enum Fallback {
+    /// The stream ended or failed, and should be restarted.
+    Reconnect,
+
+    /// The controller does not know the destination.
+    Dns,
+}
+
+impl<R> DestinationSet<R> {
+    /// Replaces the known addresses with `addrs`, notifying the watch of the
+    /// differences.
+    fn reset(&mut self, auth: &FullyQualifiedAuthority, addrs: Endpoints) {
+        if notify(&mut self.txs, auth, &self.addrs, &addrs) {
+            self.changed = true;
+        }
+        self.addrs = addrs;
+    }
+
+    fn is_dns(&self) -> bool {
+        match self.rx {
+            Resolution::Dns(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Whether the controller hasn't updated the destination within
+    /// `stale_after`, including while it can't be reached.
+    fn is_stale(&self, now: Instant, stale_after: Duration) -> bool {
+        !self.is_dns() && now.duration_since(self.last_update) > stale_after
+    }
+}
+
+impl<R, E> DestinationSet<R>
+where
+    R: Stream<Item = PbUpdate, Error = tower_grpc::Error<E>>,
+    E: fmt::Debug,
+{
+    /// Applies updates from the controller until its stream is not ready.
+    fn poll_controller(&mut self, auth: &FullyQualifiedAuthority) -> Result<(), Fallback> {
+        loop {
+            let poll = match self.rx {
+                Resolution::Controller(ref mut rx) => rx.poll(),
+                _ => return Ok(()),
+            };
+
+            match poll {
+                Ok(Async::Ready(Some(update))) => {
+                    self.last_update = Instant::now();
+                    self.apply(auth, update);
+                }
+                Ok(Async::Ready(None)) => {
+                    trace!(
+                        "Destination.Get stream ended for {:?}, must reconnect",
+                        auth
+                    );
+                    return Err(Fallback::Reconnect);
+                }
+                Ok(Async::NotReady) => return Ok(()),
+                Err(tower_grpc::Error::Grpc(ref status)) if is_unknown(status) => {
+                    return Err(Fallback::Dns);
+                }
+                Err(err) => {
+                    warn!("Destination.Get stream errored for {:?}: {:?}", auth, err);
+                    return Err(Fallback::Reconnect);
+                }
+            }
+        }
+    }
+}
+
+impl<R> DestinationSet<R> {
+    /// Applies an update from the controller.
+    fn apply(&mut self, auth: &FullyQualifiedAuthority, update: PbUpdate) {
+        match update.update {
+            Some(PbUpdate2::Add(a_set)) => {
+                let addrs = a_set.addrs.into_iter().filter_map(|addr| {
+                    let labels = Arc::new(addr.labels.into_iter().collect::<Labels>());
+                    addr.addr.and_then(pb_to_sock_addr).map(|addr| (addr, labels))
+                });
+
+                // The first addresses on a new stream are all of the
+                // destination's addresses. Empty updates are only sent to
+                // show that the stream is healthy, so they don't replace
+                // addresses restored from a snapshot.
+                if self.resync {
+                    let addrs: Endpoints = addrs.collect();
+                    if !addrs.is_empty() {
+                        self.resync = false;
+                        self.reset(auth, addrs);
+                    }
+                    return;
+                }
+
+                for (addr, labels) in addrs {
+                    match self.addrs.insert(addr, labels.clone()) {
+                        Some(ref old) if *old == labels => continue,
+                        Some(_) => {
+                            // Rebind the address, so that its labels are
+                            // updated.
+                            send(&mut self.txs, Update::Remove(addr));
+                        }
+                        None => {}
+                    }
+                    trace!("update {:?} for {:?}", addr, auth);
+                    send(&mut self.txs, Update::Insert(addr, labels));
+                    self.changed = true;
+                }
+            }
+            Some(PbUpdate2::Remove(r_set)) => for addr in r_set.addrs {
+                if let Some(addr) = pb_to_sock_addr(addr) {
+                    if self.addrs.remove(&addr).is_some() {
+                        trace!("remove {:?} for {:?}", addr, auth);
+                        send(&mut self.txs, Update::Remove(addr));
+                        self.changed = true;
                     }
                 }
+            },
+            None => (),
+        }
+    }
+}
+
+/// Sends `update` to each watch of a destination, dropping the watches that
+/// are gone.
+fn send(txs: &mut Vec<mpsc::UnboundedSender<Update>>, update: Update) {
+    txs.retain(|tx| tx.unbounded_send(update.clone()).is_ok());
+}
+
+/// Forgets the watches that were dropped, and the destinations that are no
+/// longer watched at all, so that their controller streams or DNS queries
+/// are dropped too.
+fn remove_unwatched<S, F>(destinations: &mut HashMap<FullyQualifiedAuthority, S>, watches: F)
+where
+    F: Fn(&mut S) -> &mut Vec<mpsc::UnboundedSender<Update>>,
+{
+    destinations.retain(|auth, set| {
+        let txs = watches(set);
+        txs.retain(|tx| !tx.is_closed());
+        if txs.is_empty() {
+            debug!("{:?} is no longer watched", auth);
+            return false;
+        }
+        true
+    });
+}
+
+/// Notifies the watches of a destination of the differences between its
+/// addresses, `old`, and `new`. Returns whether there were any.
+///
+/// Addresses whose labels changed are removed and inserted again.
+fn notify(
+    txs: &mut Vec<mpsc::UnboundedSender<Update>>,
+    auth: &FullyQualifiedAuthority,
+    old: &Endpoints,
+    new: &Endpoints,
+) -> bool {
+    let mut changed = false;
+
+    for (addr, labels) in old {
+        if new.get(addr) != Some(labels) {
+            trace!("remove {:?} for {:?}", addr, auth);
+            send(txs, Update::Remove(*addr));
+            changed = true;
+        }
+    }
+
+    for (addr, labels) in new {
+        if old.get(addr) != Some(labels) {
+            trace!("update {:?} for {:?}", addr, auth);
+            send(txs, Update::Insert(*addr, labels.clone()));
+            changed = true;
+        }
+    }
+
+    changed
+}
+
+/// Gives addresses that weren't discovered through the controller, and so
+/// have no labels, empty labels.
+fn unlabeled<I>(addrs: I) -> Endpoints
+where
+    I: IntoIterator<Item = SocketAddr>,
+{
+    let labels = Arc::new(Labels::new());
+    addrs.into_iter().map(|addr| (addr, labels.clone())).collect()
+}
+
+/// Whether the controller failed a `Destination.Get` because it does not
+/// know the destination.
+fn is_unknown(status: &tower_grpc::Status) -> bool {
+    let code = status.code();
+    code == tower_grpc::Status::NOT_FOUND.code() ||
+        code == tower_grpc::Status::INVALID_ARGUMENT.code()
+}
+
+// ===== impl DnsQuery =====
+
+impl DnsQuery {
+    /// Starts resolving the host of `auth`.
+    fn new(resolver: &dns::Resolver, auth: &FullyQualifiedAuthority, executor: &Handle) -> Self {
+        // Names that can't be parsed are resolved as-is, so that the resolver
+        // reports them as invalid.
+        let host = url::Host::parse(auth.host())
+            .unwrap_or_else(|_| url::Host::Domain(auth.host().to_owned()));
+        let port = auth.port().unwrap_or(DEFAULT_PORT);
+        let refresh = ReactorTimeout::new(Duration::from_secs(DNS_RETRY_SECS), executor)
+            .expect("timer");
+
+        DnsQuery {
+            resolving: Some(resolver.resolve_socket_addrs(&host, port)),
+            host,
+            port,
+            refresh,
+        }
+    }
+
+    /// Returns the addresses the name resolved to, each time it is resolved.
+    ///
+    /// If resolution fails, the previous addresses are kept until the name is
+    /// resolved again.
+    fn poll(&mut self, resolver: &dns::Resolver) -> Option<Endpoints> {
+        loop {
+            let poll = match self.resolving {
+                Some(ref mut resolving) => resolving.poll(),
+                None => {
+                    if self.refresh.poll().unwrap().is_not_ready() {
+                        return None;
+                    }
+                    self.resolving = Some(resolver.resolve_socket_addrs(&self.host, self.port));
+                    continue;
+                }
             };
-            if needs_reconnect {
-                set.needs_reconnect = true;
-                self.reconnects.push_back(FullyQualifiedAuthority::clone(auth));
+
+            let now = Instant::now();
+            let retry = now + Duration::from_secs(DNS_RETRY_SECS);
+            let (addrs, refresh) = match poll {
+                Ok(Async::NotReady) => return None,
+                Ok(Async::Ready(addrs)) => {
+                    let min_refresh = now + Duration::from_secs(DNS_MIN_REFRESH_SECS);
+                    let refresh = addrs.valid_until()
+                        .map(|t| cmp::max(t, min_refresh))
+                        .unwrap_or(retry);
+                    (Some(unlabeled(addrs)), refresh)
+                }
+                Err(dns::Error::NoAddressesFound) |
+                Err(dns::Error::InvalidDNSName(_)) => {
+                    debug!("{} has no addresses", self.host);
+                    (Some(HashMap::new()), retry)
+                }
+                Err(e) => {
+                    warn!("DNS resolution of {} failed: {:?}", self.host, e);
+                    (None, retry)
+                }
+            };
+
+            self.resolving = None;
+            self.refresh.reset(refresh);
+
+            if addrs.is_some() {
+                return addrs;
             }
         }
     }
 }
 
+impl fmt::Debug for DnsQuery {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("DnsQuery")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("resolving", &self.resolving.is_some())
+            .finish()
+    }
+}
+
+// ===== impl Snapshot =====
+
+impl fmt::Debug for Snapshot {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Snapshot")
+            .field("path", &self.path)
+            .field("dirty", &self.dirty)
+            .finish()
+    }
+}
+
 // ===== impl Bind =====
 
 impl<F, S, E> Bind for F
 where
-    F: Fn(&SocketAddr) -> Result<S, E>,
+    F: Fn(&SocketAddr, &Arc<Labels>) -> Result<S, E>,
     S: Service,
 {
     type Request = S::Request;
@@ -366,8 +1210,8 @@ where
     type Service = S;
     type BindError = E;
 
-    fn bind(&self, addr: &SocketAddr) -> Result<Self::Service, Self::BindError> {
-        (*self)(addr)
+    fn bind(&self, addr: &SocketAddr, labels: &Arc<Labels>) -> Result<Self::Service, Self::BindError> {
+        (*self)(addr, labels)
     }
 }
 
@@ -423,3 +1267,290 @@ fn pb_to_sock_addr(pb: TcpAddress) -> Option<SocketAddr> {
         None => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::pb::proxy::destination::{WeightedAddr, WeightedAddrSet};
+
+    use bytes::Bytes;
+    use futures::future;
+    use http::uri::Authority;
+
+    fn authority() -> FullyQualifiedAuthority {
+        let authority = Authority::from_shared(Bytes::from("web.ns.svc.cluster.local")).unwrap();
+        FullyQualifiedAuthority::new(&authority, None, None)
+    }
+
+    fn addr(s: &str) -> SocketAddr {
+        s.parse().unwrap()
+    }
+
+    /// A destination restored from a snapshot, whose stream to the
+    /// controller was just started.
+    fn restored(addrs: &[&str]) -> (DestinationSet<()>, mpsc::UnboundedReceiver<Update>) {
+        let (tx, rx) = mpsc::unbounded();
+        let set = DestinationSet {
+            addrs: unlabeled(addrs.iter().map(|a| addr(a))),
+            changed: false,
+            last_update: Instant::now(),
+            stale: false,
+            resync: true,
+            rx: Resolution::Pending,
+            txs: vec![tx],
+        };
+        (set, rx)
+    }
+
+    fn add(addrs: &[&str]) -> PbUpdate {
+        let addrs = addrs.iter()
+            .map(|a| WeightedAddr {
+                addr: Some((&addr(a)).into()),
+                ..Default::default()
+            })
+            .collect();
+        PbUpdate {
+            update: Some(PbUpdate2::Add(WeightedAddrSet { addrs })),
+        }
+    }
+
+    fn addrs<R>(set: &DestinationSet<R>) -> HashSet<SocketAddr> {
+        set.addrs.keys().cloned().collect()
+    }
+
+    fn labels(zone: Option<&str>) -> Arc<Labels> {
+        let mut labels = Labels::new();
+        if let Some(zone) = zone {
+            labels.insert(ZONE_LABEL.to_owned(), zone.to_owned());
+        }
+        Arc::new(labels)
+    }
+
+    struct Endpoint;
+
+    impl Service for Endpoint {
+        type Request = ();
+        type Response = ();
+        type Error = ();
+        type Future = ::futures::future::FutureResult<(), ()>;
+
+        fn poll_ready(&mut self) -> Poll<(), ()> {
+            Ok(Async::Ready(()))
+        }
+
+        fn call(&mut self, _: ()) -> Self::Future {
+            ::futures::future::ok(())
+        }
+    }
+
+    fn bind_endpoint(_: &SocketAddr, _: &Arc<Labels>) -> Result<Endpoint, ()> {
+        Ok(Endpoint)
+    }
+
+    type BindEndpoint = fn(&SocketAddr, &Arc<Labels>) -> Result<Endpoint, ()>;
+
+    fn split(zone: Option<&str>) -> (
+        mpsc::UnboundedSender<Update>,
+        ZoneWatch<BindEndpoint>,
+        ZoneWatch<BindEndpoint>,
+    ) {
+        let (tx, rx) = mpsc::unbounded();
+        let watch = Watch {
+            rx,
+            bind: bind_endpoint as BindEndpoint,
+        };
+        let (local, remote) = watch.split_by_zone(zone.map(String::from));
+        (tx, local, remote)
+    }
+
+    /// Polls `watch` until it isn't ready, returning the keys of the inserted
+    /// and removed endpoints, in order.
+    fn changes(watch: &mut ZoneWatch<BindEndpoint>) -> Vec<(&'static str, SocketAddr)> {
+        future::lazy(|| {
+            let mut changes = Vec::new();
+            loop {
+                match watch.poll() {
+                    Ok(Async::Ready(Change::Insert(addr, _))) => changes.push(("insert", addr)),
+                    Ok(Async::Ready(Change::Remove(addr))) => changes.push(("remove", addr)),
+                    Ok(Async::NotReady) => return Ok::<_, ()>(changes),
+                    Err(()) => panic!("watch failed"),
+                }
+            }
+        }).wait().unwrap()
+    }
+
+    #[test]
+    fn heartbeats_keep_restored_addresses() {
+        let (mut set, _rx) = restored(&["10.0.0.1:80"]);
+
+        set.apply(&authority(), add(&[]));
+        assert_eq!(addrs(&set), vec![addr("10.0.0.1:80")].into_iter().collect());
+        assert!(set.resync);
+    }
+
+    #[test]
+    fn first_addresses_replace_restored_addresses() {
+        let (mut set, rx) = restored(&["10.0.0.1:80"]);
+
+        set.apply(&authority(), add(&["10.0.0.2:80"]));
+        assert_eq!(addrs(&set), vec![addr("10.0.0.2:80")].into_iter().collect());
+
+        // Later updates add to the known addresses.
+        set.apply(&authority(), add(&["10.0.0.3:80"]));
+        assert_eq!(
+            addrs(&set),
+            vec![addr("10.0.0.2:80"), addr("10.0.0.3:80")].into_iter().collect()
+        );
+
+        drop(set);
+        let updates = rx.collect().wait().unwrap();
+        assert_eq!(updates.len(), 3, "{:?}", updates);
+        match updates[0] {
+            Update::Remove(a) => assert_eq!(a, addr("10.0.0.1:80")),
+            ref u => panic!("unexpected update: {:?}", u),
+        }
+        match updates[1] {
+            Update::Insert(a, _) => assert_eq!(a, addr("10.0.0.2:80")),
+            ref u => panic!("unexpected update: {:?}", u),
+        }
+        match updates[2] {
+            Update::Insert(a, _) => assert_eq!(a, addr("10.0.0.3:80")),
+            ref u => panic!("unexpected update: {:?}", u),
+        }
+    }
+
+    #[test]
+    fn destinations_are_stale_without_updates() {
+        let (set, _rx) = restored(&["10.0.0.1:80"]);
+        let stale_after = Duration::from_secs(20);
+
+        let now = Instant::now();
+        assert!(!set.is_stale(now, stale_after));
+        assert!(set.is_stale(now + Duration::from_secs(21), stale_after));
+    }
+
+    #[test]
+    fn split_by_zone_sorts_endpoints() {
+        let (tx, mut local, mut remote) = split(Some("a"));
+
+        tx.unbounded_send(Update::Insert(addr("10.0.0.1:80"), labels(Some("a")))).unwrap();
+        tx.unbounded_send(Update::Insert(addr("10.0.0.2:80"), labels(Some("b")))).unwrap();
+        // Endpoints without a zone may be local.
+        tx.unbounded_send(Update::Insert(addr("10.0.0.3:80"), labels(None))).unwrap();
+
+        assert_eq!(
+            changes(&mut local),
+            vec![("insert", addr("10.0.0.1:80")), ("insert", addr("10.0.0.3:80"))]
+        );
+        assert_eq!(changes(&mut remote), vec![("insert", addr("10.0.0.2:80"))]);
+        assert_eq!(local.endpoints().get(), 2);
+        assert_eq!(remote.endpoints().get(), 1);
+    }
+
+    #[test]
+    fn split_by_unknown_zone_keeps_all_endpoints_local() {
+        let (tx, mut local, mut remote) = split(None);
+
+        tx.unbounded_send(Update::Insert(addr("10.0.0.1:80"), labels(Some("a")))).unwrap();
+        tx.unbounded_send(Update::Insert(addr("10.0.0.2:80"), labels(Some("b")))).unwrap();
+
+        assert_eq!(
+            changes(&mut local),
+            vec![("insert", addr("10.0.0.1:80")), ("insert", addr("10.0.0.2:80"))]
+        );
+        assert_eq!(changes(&mut remote), vec![]);
+    }
+
+    #[test]
+    fn split_by_zone_removes_endpoints_from_their_old_zone() {
+        let (tx, mut local, mut remote) = split(Some("a"));
+
+        tx.unbounded_send(Update::Insert(addr("10.0.0.1:80"), labels(Some("a")))).unwrap();
+        assert_eq!(changes(&mut local), vec![("insert", addr("10.0.0.1:80"))]);
+        assert_eq!(local.endpoints().get(), 1);
+
+        // The endpoint moves to another zone, so it's rebound.
+        tx.unbounded_send(Update::Remove(addr("10.0.0.1:80"))).unwrap();
+        tx.unbounded_send(Update::Insert(addr("10.0.0.1:80"), labels(Some("b")))).unwrap();
+        assert_eq!(changes(&mut local), vec![("remove", addr("10.0.0.1:80"))]);
+        assert_eq!(changes(&mut remote), vec![("insert", addr("10.0.0.1:80"))]);
+        assert_eq!(local.endpoints().get(), 0);
+        assert_eq!(remote.endpoints().get(), 1);
+
+        // Now it's removed from the other zone.
+        tx.unbounded_send(Update::Remove(addr("10.0.0.1:80"))).unwrap();
+        assert_eq!(changes(&mut local), vec![]);
+        assert_eq!(changes(&mut remote), vec![("remove", addr("10.0.0.1:80"))]);
+        assert_eq!(remote.endpoints().get(), 0);
+    }
+
+    #[test]
+    fn split_by_zone_does_not_count_unknown_removals() {
+        let (tx, _local, mut remote) = split(Some("a"));
+
+        tx.unbounded_send(Update::Remove(addr("10.0.0.1:80"))).unwrap();
+        assert_eq!(changes(&mut remote), vec![("remove", addr("10.0.0.1:80"))]);
+        assert_eq!(remote.endpoints().get(), 0);
+    }
+
+    #[test]
+    fn updates_are_sent_to_every_watch() {
+        let (mut set, rx1) = restored(&[]);
+        let (tx2, rx2) = mpsc::unbounded();
+        set.txs.push(tx2);
+        let (tx3, rx3) = mpsc::unbounded();
+        set.txs.push(tx3);
+        drop(rx3);
+
+        set.apply(&authority(), add(&["10.0.0.1:80"]));
+        // The dropped watch is forgotten.
+        assert_eq!(set.txs.len(), 2);
+
+        drop(set);
+        for rx in vec![rx1, rx2] {
+            let updates = rx.collect().wait().unwrap();
+            assert_eq!(updates.len(), 1, "{:?}", updates);
+            match updates[0] {
+                Update::Insert(a, _) => assert_eq!(a, addr("10.0.0.1:80")),
+                ref u => panic!("unexpected update: {:?}", u),
+            }
+        }
+    }
+
+    #[test]
+    fn unwatched_destinations_are_removed() {
+        let stream = Rc::new(());
+        let (tx1, rx1) = mpsc::unbounded();
+        let (tx2, rx2) = mpsc::unbounded();
+        let mut destinations = HashMap::new();
+        destinations.insert(authority(), DestinationSet {
+            addrs: HashMap::new(),
+            changed: false,
+            last_update: Instant::now(),
+            stale: false,
+            resync: false,
+            rx: Resolution::Controller(stream.clone()),
+            txs: vec![tx1, tx2],
+        });
+
+        drop(rx1);
+        remove_unwatched(&mut destinations, |set| &mut set.txs);
+        assert_eq!(destinations[&authority()].txs.len(), 1);
+
+        drop(rx2);
+        remove_unwatched(&mut destinations, |set| &mut set.txs);
+        assert!(destinations.is_empty());
+        // The stream to the controller was dropped with the destination.
+        assert_eq!(Rc::strong_count(&stream), 1);
+    }
+
+    #[test]
+    fn watches_stop_when_discovery_stops() {
+        let (tx, mut local, _remote) = split(Some("a"));
+
+        tx.unbounded_send(Update::Insert(addr("10.0.0.1:80"), labels(Some("a")))).unwrap();
+        drop(tx);
+        assert_eq!(changes(&mut local), vec![("insert", addr("10.0.0.1:80"))]);
+        assert_eq!(changes(&mut local), vec![]);
+    }
+}
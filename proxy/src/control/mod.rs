@@ -1,4 +1,5 @@
 use std::marker::PhantomData;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
 use bytes::Bytes;
@@ -21,10 +22,11 @@ use fully_qualified_authority::FullyQualifiedAuthority;
 use transport::LookupAddressAndConnect;
 use timeout::Timeout;
 
-mod codec;
+mod destinations_file;
 pub mod discovery;
 mod observe;
 pub mod pb;
+mod snapshot;
 mod telemetry;
 
 use self::discovery::{Background as DiscoBg, Discovery, Watch};
@@ -33,6 +35,7 @@ pub use self::observe::Observe;
 use self::pb::proxy::telemetry::ReportRequest;
 use self::telemetry::Telemetry;
 
+#[derive(Clone)]
 pub struct Control {
     disco: Discovery,
 }
@@ -41,8 +44,11 @@ pub struct Background {
     disco: DiscoBg,
 }
 
-pub fn new() -> (Control, Background) {
-    let (tx, rx) = self::discovery::new();
+pub fn new(
+    address_update_interval: Duration,
+    snapshot_path: Option<PathBuf>,
+) -> (Control, Background) {
+    let (tx, rx) = self::discovery::new(address_update_interval, snapshot_path);
 
     let c = Control {
         disco: tx,
@@ -71,12 +77,15 @@ impl Background {
         events: S,
         host_and_port: HostAndPort,
         dns_config: dns::Config,
+        default_destination_zone: Option<String>,
         report_timeout: Duration,
         executor: &Handle,
     ) -> Box<Future<Item = (), Error = ()>>
     where
         S: Stream<Item = ReportRequest, Error = ()> + 'static,
     {
+        let dns_resolver = dns::Resolver::new(dns_config, executor);
+
         // Build up the Controller Client Stack
         let mut client = {
             let ctx = ("controller-client", format!("{}", host_and_port));
@@ -84,9 +93,8 @@ impl Background {
             let authority =
                 http::uri::Authority::from_shared(format!("{}", host_and_port).into()).unwrap();
 
-            let dns_resolver = dns::Resolver::new(dns_config, executor);
             let connect = Timeout::new(
-                LookupAddressAndConnect::new(host_and_port, dns_resolver, executor),
+                LookupAddressAndConnect::new(host_and_port, dns_resolver.clone(), executor),
                 Duration::from_secs(3),
                 executor,
             );
@@ -103,18 +111,65 @@ impl Background {
             AddOrigin::new(scheme, authority, backoff)
         };
 
-        let mut disco = self.disco.work();
+        let mut disco = self.disco.work(dns_resolver, default_destination_zone, executor);
         let mut telemetry = Telemetry::new(events, report_timeout, executor);
 
         let fut = future::poll_fn(move || {
             trace!("poll rpc services");
             disco.poll_rpc(&mut EnumService(&mut client, PhantomData));
-            telemetry.poll_rpc(&mut EnumService(&mut client, PhantomData));
+            telemetry.poll_rpc(
+                &mut EnumService(&mut client, PhantomData),
+                || disco.stale_destinations(),
+            );
 
             Ok(Async::NotReady)
         });
         Box::new(fut)
     }
+
+    /// Runs without a controller.
+    ///
+    /// Destinations are read from the file at `destinations_path`. Reports are
+    /// appended to the file at `telemetry_path` if it's set, and dropped
+    /// otherwise.
+    pub fn bind_standalone<S>(
+        self,
+        events: S,
+        destinations_path: PathBuf,
+        telemetry_path: Option<PathBuf>,
+        dns_config: dns::Config,
+        executor: &Handle,
+    ) -> Box<Future<Item = (), Error = ()>>
+    where
+        S: Stream<Item = ReportRequest, Error = ()> + 'static,
+    {
+        let dns_resolver = dns::Resolver::new(dns_config, executor);
+        let mut disco = self.disco.work_from_file(destinations_path, dns_resolver, executor);
+
+        // The telemetry file is opened, and created if needed, only once the
+        // proxy starts. If it can't be, reports are dropped.
+        let file = telemetry_path.and_then(|path| match telemetry::open_file(&path) {
+            Ok(file) => Some((file, path)),
+            Err(e) => {
+                error!("failed to open telemetry file {:?}: {}", path, e);
+                None
+            }
+        });
+
+        // The events must still be consumed, so that taps see them.
+        let reports: Box<Future<Item = (), Error = ()>> = match file {
+            Some((file, path)) => telemetry::write_to_file(events, file, path),
+            None => Box::new(events.for_each(|_| Ok(()))),
+        };
+
+        let fut = future::poll_fn(move || {
+            trace!("poll destinations file");
+            disco.poll();
+
+            Ok(Async::NotReady)
+        });
+        Box::new(fut.join(reports).map(|_| ()))
+    }
 }
 
 // ===== Backoff =====
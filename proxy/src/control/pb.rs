@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 #![cfg_attr(feature = "cargo-clippy", allow(clippy))]
 
+use std::collections::HashMap;
 use std::error::Error;
 use std::{fmt, hash};
 use std::sync::Arc;
@@ -56,6 +57,7 @@ fn pb_response_end(
     common::TapEvent {
         source: Some((&ctx.server.remote).into()),
         target: Some((&ctx.client.remote).into()),
+        target_labels: pb_labels(&ctx.client.labels),
         event: Some(tap_event::Event::Http(tap_event::Http {
             event: Some(tap_event::http::Event::ResponseEnd(end)),
         })),
@@ -138,6 +140,7 @@ impl<'a> TryFrom<&'a Event> for common::TapEvent {
                 common::TapEvent {
                     source: Some((&ctx.server.remote).into()),
                     target: Some((&ctx.client.remote).into()),
+                    target_labels: pb_labels(&ctx.client.labels),
                     event: Some(tap_event::Event::Http(tap_event::Http {
                         event: Some(tap_event::http::Event::RequestInit(init)),
                     })),
@@ -158,6 +161,7 @@ impl<'a> TryFrom<&'a Event> for common::TapEvent {
                 common::TapEvent {
                     source: Some((&ctx.request.server.remote).into()),
                     target: Some((&ctx.request.client.remote).into()),
+                    target_labels: pb_labels(&ctx.request.client.labels),
                     event: Some(tap_event::Event::Http(tap_event::Http {
                         event: Some(tap_event::http::Event::ResponseInit(init)),
                     })),
@@ -404,6 +408,12 @@ impl hash::Hash for common::Protocol {
     }
 }
 
+pub fn pb_labels(labels: &ctx::transport::Labels) -> HashMap<String, String> {
+    labels.iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect()
+}
+
 fn pb_duration(d: &::std::time::Duration) -> ::prost_types::Duration {
     let seconds = if d.as_secs() > ::std::i64::MAX as u64 {
         ::std::i64::MAX
@@ -0,0 +1,130 @@
+//! Persists the last known addresses of destinations, so that a restarted
+//! proxy can route to them before it reaches the controller.
+//!
+//! A snapshot is a text file with a line per destination: its authority,
+//! followed by its addresses, separated by spaces.
+
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+use bytes::Bytes;
+use http::uri::Authority;
+
+use fully_qualified_authority::FullyQualifiedAuthority;
+
+pub type Destinations = HashMap<FullyQualifiedAuthority, HashSet<SocketAddr>>;
+
+/// Reads the snapshot at `path`.
+///
+/// A missing file is an empty snapshot. Authorities and addresses that can't
+/// be parsed are skipped.
+pub fn load(path: &Path) -> io::Result<Destinations> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(HashMap::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut destinations = HashMap::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        let mut fields = line.split_whitespace();
+
+        let authority = fields.next()
+            .and_then(|a| Authority::from_shared(Bytes::from(a)).ok());
+        let authority = match authority {
+            Some(a) => FullyQualifiedAuthority::new(&a, None, None),
+            None => {
+                debug!("skipping snapshot line: {:?}", line);
+                continue;
+            }
+        };
+
+        let addrs = fields.filter_map(|a| a.parse().ok()).collect();
+        destinations.insert(authority, addrs);
+    }
+
+    Ok(destinations)
+}
+
+/// Replaces the snapshot at `path` with `destinations`.
+///
+/// The snapshot is written to a temporary file first, so that a snapshot is
+/// never left half-written.
+pub fn save<'a, I, A>(path: &Path, destinations: I) -> io::Result<()>
+where
+    I: IntoIterator<Item = (&'a FullyQualifiedAuthority, A)>,
+    A: IntoIterator<Item = &'a SocketAddr>,
+{
+    let tmp = {
+        let mut tmp = OsString::from(path.as_os_str());
+        tmp.push(".tmp");
+        PathBuf::from(tmp)
+    };
+
+    {
+        let mut file = BufWriter::new(File::create(&tmp)?);
+        for (authority, addrs) in destinations {
+            write!(file, "{}", authority.without_trailing_dot())?;
+            for addr in addrs {
+                write!(file, " {}", addr)?;
+            }
+            write!(file, "\n")?;
+        }
+        file.flush()?;
+    }
+
+    fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use bytes::Bytes;
+    use http::uri::Authority;
+
+    use fully_qualified_authority::FullyQualifiedAuthority;
+
+    /// Returns a path in the temporary directory that no other test uses,
+    /// even when several test processes run at once.
+    fn temp_path(name: &str) -> PathBuf {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let unique = format!("conduit-{}-{}-{}", name, now.as_secs(), now.subsec_nanos());
+        env::temp_dir().join(unique)
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let path = temp_path("snapshot-roundtrip");
+
+        let authority = Authority::from_shared(Bytes::from("name.namespace.svc.cluster.local:8080"))
+            .unwrap();
+        let authority = FullyQualifiedAuthority::new(&authority, None, None);
+        let addrs = vec!["10.1.1.1:8080".parse().unwrap(), "[fe80::1]:8080".parse().unwrap()]
+            .into_iter()
+            .collect();
+        let mut destinations = HashMap::new();
+        destinations.insert(authority, addrs);
+
+        super::save(&path, &destinations).unwrap();
+        let loaded = super::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, destinations);
+    }
+
+    #[test]
+    fn test_missing_snapshot_is_empty() {
+        let path = temp_path("snapshot-missing");
+        assert!(super::load(&path).unwrap().is_empty());
+    }
+}
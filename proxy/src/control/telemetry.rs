@@ -1,14 +1,17 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
-use futures::{Async, Future, Stream};
+use futures::{future, Async, Future, Stream};
+use prost::Message;
 use tower::Service;
 use tower_grpc;
+use tower_grpc::protobuf::Codec as Protobuf;
 use tokio_core::reactor::Handle;
 
-use super::codec::Protobuf;
 use super::pb::proxy::telemetry::{ReportRequest, ReportResponse};
 use super::pb::proxy::telemetry::client::Telemetry as TelemetrySvc;
-use super::pb::proxy::telemetry::client::telemetry_methods::Report as ReportRpc;
 use ::timeout::{Timeout, TimeoutFuture};
 
 pub type ClientBody = tower_grpc::client::codec::EncodingBody<
@@ -16,11 +19,9 @@ pub type ClientBody = tower_grpc::client::codec::EncodingBody<
     tower_grpc::client::codec::Unary<ReportRequest>,
 >;
 
-type TelemetryStream<F> = tower_grpc::client::BodyFuture<
-    tower_grpc::client::Unary<
-        tower_grpc::client::ResponseFuture<Protobuf<ReportRequest, ReportResponse>, TimeoutFuture<F>>,
-        Protobuf<ReportRequest, ReportResponse>,
-    >,
+type TelemetryStream<F> = tower_grpc::client::Unary<
+    tower_grpc::client::ResponseFuture<Protobuf<ReportRequest, ReportResponse>, TimeoutFuture<F>>,
+    Protobuf<ReportRequest, ReportResponse>,
 >;
 
 #[derive(Debug)]
@@ -47,8 +48,13 @@ where
         }
     }
 
-    pub fn poll_rpc<S>(&mut self, client: &mut S)
+    /// Sends reports to the controller as they become available.
+    ///
+    /// `stale_destinations` is called for each report that is sent, to mark
+    /// destinations whose addresses may be out of date.
+    pub fn poll_rpc<S, D>(&mut self, client: &mut S, stale_destinations: D)
     where
+        D: Fn() -> Vec<String>,
         S: Service<
             Request = ::http::Request<ClientBody>,
             Response = F::Item,
@@ -57,8 +63,7 @@ where
         >,
     {
         let client = Timeout::new(client, self.report_timeout, &self.handle);
-        let grpc = tower_grpc::Client::new(Protobuf::new(), client);
-        let mut rpc = ReportRpc::new(grpc);
+        let mut rpc = TelemetrySvc::new(client);
 
         //let _ctxt = ::logging::context("Telemetry.Report".into());
 
@@ -113,13 +118,15 @@ where
                             report.client_transports.len(),
                         );
                     } else {
+                        let mut report = report;
+                        report.stale_destinations = stale_destinations();
                         trace!(
                             "report sent; requests={} accepts={} connects={}",
                             report.requests.len(),
                             report.server_transports.len(),
                             report.client_transports.len(),
                         );
-                        let rep = TelemetrySvc::new(&mut rpc).report(report);
+                        let rep = rpc.report(report);
                         self.in_flight = Some((Instant::now(), rep));
                     }
                 }
@@ -127,3 +134,30 @@ where
         }
     }
 }
+
+/// Opens the file at `path` to append reports to, creating it if needed.
+pub fn open_file(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Appends reports to `file`, opened from `path`, for running without a
+/// controller.
+///
+/// Each report is written as a length-delimited protobuf message.
+pub fn write_to_file<T>(reports: T, mut file: File, path: PathBuf) -> Box<Future<Item = (), Error = ()>>
+where
+    T: Stream<Item = ReportRequest> + 'static,
+    T::Error: ::std::fmt::Debug,
+{
+    let fut = reports
+        .map_err(|e| warn!("report stream error: {:?}", e))
+        .for_each(move |report| {
+            let mut buf = Vec::with_capacity(report.encoded_len() + 10);
+            report.encode_length_delimited(&mut buf).expect("vec has capacity");
+            if let Err(e) = file.write_all(&buf) {
+                warn!("failed to write report to {:?}: {}", path, e);
+            }
+            future::ok(())
+        });
+    Box::new(fut)
+}
@@ -1,5 +1,6 @@
 use http;
 use std::sync::Arc;
+use std::time::SystemTime;
 
 use ctx;
 
@@ -12,13 +13,29 @@ pub struct Request {
     pub uri: http::Uri,
     pub method: http::Method,
 
+    /// When the request was opened, by the wall clock.
+    pub start: SystemTime,
+
     /// Identifies the proxy server that received the request.
     pub server: Arc<ctx::transport::Server>,
 
     /// Identifies the proxy client that dispatched the request.
     pub client: Arc<ctx::transport::Client>,
+
+    /// Whether the request was sent outside of the proxy's zone because
+    /// the endpoints in its zone were unavailable or overloaded.
+    pub zone_spillover: bool,
+
+    /// Describes the proxy's span for the request, if requests are traced.
+    pub trace: Option<ctx::trace::TraceContext>,
 }
 
+/// Marks a request that was sent outside of the proxy's zone.
+///
+/// Set as an extension on the request by the balancer.
+#[derive(Clone, Copy, Debug)]
+pub struct ZoneSpillover;
+
 /// Describes a stream's response headers.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Response {
@@ -40,13 +57,17 @@ impl Request {
         server: &Arc<ctx::transport::Server>,
         client: &Arc<ctx::transport::Client>,
         id: usize,
+        trace: Option<ctx::trace::TraceContext>,
     ) -> Arc<Self> {
         let r = Self {
             id,
             uri: request.uri().clone(),
             method: request.method().clone(),
+            start: SystemTime::now(),
             server: Arc::clone(server),
             client: Arc::clone(client),
+            zone_spillover: request.extensions().get::<ZoneSpillover>().is_some(),
+            trace,
         };
 
         Arc::new(r)
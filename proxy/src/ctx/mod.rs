@@ -11,6 +11,7 @@ use config;
 use control::pb::proxy::telemetry as proto;
 use std::sync::Arc;
 pub mod http;
+pub mod trace;
 pub mod transport;
 
 /// Describes a single running proxy instance.
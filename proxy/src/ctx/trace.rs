@@ -0,0 +1,437 @@
+//! Describes the distributed trace that a request is part of.
+//!
+//! Trace context is read from, and written to, both the W3C Trace Context
+//! `traceparent` header and the B3 headers used by Zipkin, so that the proxy
+//! joins traces started by applications using either.
+
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, ATOMIC_USIZE_INIT, Ordering};
+use std::time::SystemTime;
+
+use http::header::{HeaderMap, HeaderValue};
+
+const TRACEPARENT: &str = "traceparent";
+const B3: &str = "b3";
+const B3_TRACE_ID: &str = "x-b3-traceid";
+const B3_SPAN_ID: &str = "x-b3-spanid";
+const B3_PARENT_SPAN_ID: &str = "x-b3-parentspanid";
+const B3_SAMPLED: &str = "x-b3-sampled";
+const B3_FLAGS: &str = "x-b3-flags";
+
+/// Identifies a trace.
+///
+/// B3 allows 64-bit trace IDs, which are kept as a 128-bit ID with no high
+/// bits so that they're propagated unchanged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TraceId {
+    high: u64,
+    low: u64,
+}
+
+/// Identifies a span within a trace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpanId(u64);
+
+/// Describes the span that the proxy records for a request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TraceContext {
+    pub trace_id: TraceId,
+    pub span_id: SpanId,
+
+    /// The span of the request's sender, if the request was already traced.
+    pub parent_id: Option<SpanId>,
+
+    /// Whether the span should be reported.
+    pub sampled: bool,
+}
+
+/// Decides whether the traces that the proxy starts are sampled.
+#[derive(Clone, Copy, Debug)]
+pub struct Sampler {
+    /// The fraction of traces that are sampled, between 0 and 1.
+    rate: f64,
+}
+
+/// Trace context read from a request's headers.
+#[derive(Debug, PartialEq)]
+struct Parent {
+    ids: Option<(TraceId, SpanId)>,
+    sampled: Option<bool>,
+}
+
+// ===== impl TraceId =====
+
+impl TraceId {
+    fn parse(s: &str) -> Option<Self> {
+        let id = match s.len() {
+            16 => TraceId {
+                high: 0,
+                low: parse_hex(s)?,
+            },
+            32 => TraceId {
+                high: parse_hex(&s[..16])?,
+                low: parse_hex(&s[16..])?,
+            },
+            _ => return None,
+        };
+
+        if id.high == 0 && id.low == 0 {
+            return None;
+        }
+        Some(id)
+    }
+
+    /// Formats the ID with 32 hex digits, as W3C Trace Context requires.
+    fn to_wide_hex(&self) -> String {
+        format!("{:016x}{:016x}", self.high, self.low)
+    }
+}
+
+impl fmt::Display for TraceId {
+    /// Formats the ID with 16 hex digits if it's a 64-bit ID, or 32
+    /// otherwise.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.high == 0 {
+            write!(f, "{:016x}", self.low)
+        } else {
+            write!(f, "{:016x}{:016x}", self.high, self.low)
+        }
+    }
+}
+
+// ===== impl SpanId =====
+
+impl SpanId {
+    fn parse(s: &str) -> Option<Self> {
+        if s.len() != 16 {
+            return None;
+        }
+        match parse_hex(s)? {
+            0 => None,
+            id => Some(SpanId(id)),
+        }
+    }
+
+    fn random() -> Self {
+        SpanId(random_nonzero())
+    }
+}
+
+impl fmt::Display for SpanId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+// ===== impl Sampler =====
+
+impl Sampler {
+    pub fn new(rate: f64) -> Self {
+        Sampler { rate }
+    }
+
+    fn sample(&self) -> bool {
+        if self.rate >= 1.0 {
+            return true;
+        }
+        (random_nonzero() as f64 / ::std::u64::MAX as f64) < self.rate
+    }
+}
+
+// ===== impl TraceContext =====
+
+impl TraceContext {
+    /// Returns the context of the proxy's span for a request with `headers`.
+    ///
+    /// If the request carries trace context, the span is a child of the
+    /// sender's span; otherwise, the span starts a new trace. Unless the
+    /// sender decided whether the trace is sampled, `sampler` decides.
+    pub fn for_request(headers: &HeaderMap, sampler: Sampler) -> Self {
+        let parent = Parent::from_headers(headers);
+        let sampled = parent.sampled.unwrap_or_else(|| sampler.sample());
+
+        match parent.ids {
+            Some((trace_id, parent_id)) => TraceContext {
+                trace_id,
+                span_id: SpanId::random(),
+                parent_id: Some(parent_id),
+                sampled,
+            },
+            None => TraceContext {
+                trace_id: TraceId {
+                    high: random_nonzero(),
+                    low: random_nonzero(),
+                },
+                span_id: SpanId::random(),
+                parent_id: None,
+                sampled,
+            },
+        }
+    }
+
+    /// Replaces the trace context in `headers` with this context, so that
+    /// the receiver's spans are children of the proxy's span.
+    ///
+    /// Both B3 and W3C Trace Context headers are written, regardless of which
+    /// the request carried.
+    pub fn inject(&self, headers: &mut HeaderMap) {
+        // The single B3 header would take precedence over the ones below.
+        headers.remove(B3);
+
+        headers.insert(B3_TRACE_ID, hex_value(self.trace_id.to_string()));
+        headers.insert(B3_SPAN_ID, hex_value(self.span_id.to_string()));
+        match self.parent_id {
+            Some(parent_id) => {
+                headers.insert(B3_PARENT_SPAN_ID, hex_value(parent_id.to_string()));
+            }
+            None => {
+                headers.remove(B3_PARENT_SPAN_ID);
+            }
+        }
+        let sampled = if self.sampled { "1" } else { "0" };
+        headers.insert(B3_SAMPLED, HeaderValue::from_static(sampled));
+
+        let flags = if self.sampled { "01" } else { "00" };
+        let traceparent = format!(
+            "00-{}-{}-{}",
+            self.trace_id.to_wide_hex(),
+            self.span_id,
+            flags
+        );
+        headers.insert(TRACEPARENT, hex_value(traceparent));
+    }
+}
+
+// ===== impl Parent =====
+
+impl Parent {
+    /// Reads trace context from `headers`, preferring `traceparent`, then the
+    /// single `b3` header, then the multiple B3 headers.
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+        header(TRACEPARENT)
+            .and_then(Self::from_traceparent)
+            .or_else(|| header(B3).and_then(Self::from_b3))
+            .unwrap_or_else(|| {
+                let ids = header(B3_TRACE_ID)
+                    .and_then(TraceId::parse)
+                    .and_then(|t| header(B3_SPAN_ID).and_then(SpanId::parse).map(|s| (t, s)));
+                // A debug flag implies that the trace is sampled.
+                let sampled = match header(B3_FLAGS) {
+                    Some("1") => Some(true),
+                    _ => header(B3_SAMPLED).and_then(parse_b3_sampled),
+                };
+                Parent { ids, sampled }
+            })
+    }
+
+    /// Parses a `traceparent` header, of the form
+    /// `{version}-{trace-id}-{parent-id}-{flags}`.
+    fn from_traceparent(s: &str) -> Option<Self> {
+        let mut fields = s.trim().split('-');
+        let version = fields.next()?;
+        if version.len() != 2 || version == "ff" {
+            return None;
+        }
+
+        let trace_id = fields.next()?;
+        if trace_id.len() != 32 {
+            return None;
+        }
+        let trace_id = TraceId::parse(trace_id)?;
+        let span_id = SpanId::parse(fields.next()?)?;
+        let flags = fields.next()?;
+        if flags.len() != 2 {
+            return None;
+        }
+        let flags = parse_hex(flags)?;
+
+        Some(Parent {
+            ids: Some((trace_id, span_id)),
+            sampled: Some(flags & 1 == 1),
+        })
+    }
+
+    /// Parses a single `b3` header, of the form
+    /// `{trace-id}-{span-id}[-{sampled}[-{parent-span-id}]]`, or only
+    /// `{sampled}`.
+    fn from_b3(s: &str) -> Option<Self> {
+        let mut fields = s.trim().split('-');
+        let first = fields.next()?;
+
+        let trace_id = match TraceId::parse(first) {
+            Some(trace_id) => trace_id,
+            None => {
+                return parse_b3_sampled(first).map(|sampled| Parent {
+                    ids: None,
+                    sampled: Some(sampled),
+                });
+            }
+        };
+        let span_id = fields.next().and_then(SpanId::parse)?;
+        let sampled = match fields.next() {
+            Some(s) => Some(parse_b3_sampled(s)?),
+            None => None,
+        };
+
+        Some(Parent {
+            ids: Some((trace_id, span_id)),
+            sampled,
+        })
+    }
+}
+
+fn parse_b3_sampled(s: &str) -> Option<bool> {
+    match s {
+        "1" | "true" | "d" => Some(true),
+        "0" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parses up to 16 hex digits.
+///
+/// Unlike `u64::from_str_radix`, this doesn't accept a leading `+`.
+fn parse_hex(s: &str) -> Option<u64> {
+    if s.is_empty() || s.len() > 16 || !s.bytes().all(|b| (b as char).is_digit(16)) {
+        return None;
+    }
+    u64::from_str_radix(s, 16).ok()
+}
+
+fn hex_value(s: String) -> HeaderValue {
+    HeaderValue::from_str(&s).expect("hex digits are a valid header value")
+}
+
+/// Returns a random, non-zero ID.
+///
+/// IDs need only be unique, so they're hashed with a randomly keyed hasher
+/// rather than taken from a random number generator.
+fn random_nonzero() -> u64 {
+    static NEXT: AtomicUsize = ATOMIC_USIZE_INIT;
+
+    loop {
+        let mut hasher = RandomState::new().build_hasher();
+        NEXT.fetch_add(1, Ordering::Relaxed).hash(&mut hasher);
+        SystemTime::now().hash(&mut hasher);
+        match hasher.finish() {
+            0 => continue,
+            id => return id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::header::{HeaderMap, HeaderValue};
+
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for &(name, value) in pairs {
+            headers.insert(name, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    #[test]
+    fn test_traceparent() {
+        let parent = Parent::from_headers(&headers(&[(
+            TRACEPARENT,
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+        )]));
+        let (trace_id, span_id) = parent.ids.unwrap();
+        assert_eq!(trace_id.to_string(), "0af7651916cd43dd8448eb211c80319c");
+        assert_eq!(span_id.to_string(), "b7ad6b7169203331");
+        assert_eq!(parent.sampled, Some(true));
+
+        let invalid = Parent::from_headers(&headers(&[(
+            TRACEPARENT,
+            "00-00000000000000000000000000000000-b7ad6b7169203331-01",
+        )]));
+        assert_eq!(invalid.ids, None);
+    }
+
+    #[test]
+    fn test_b3() {
+        let single = Parent::from_headers(&headers(&[(
+            B3,
+            "80f198ee56343ba864fe8b2a57d3eff7-e457b5a2e4d86bd1-0-05e3ac9a4f6e3b90",
+        )]));
+        let (trace_id, span_id) = single.ids.unwrap();
+        assert_eq!(trace_id.to_string(), "80f198ee56343ba864fe8b2a57d3eff7");
+        assert_eq!(span_id.to_string(), "e457b5a2e4d86bd1");
+        assert_eq!(single.sampled, Some(false));
+
+        let deny = Parent::from_headers(&headers(&[(B3, "0")]));
+        assert_eq!(deny, Parent { ids: None, sampled: Some(false) });
+
+        let multi = Parent::from_headers(&headers(&[
+            (B3_TRACE_ID, "463ac35c9f6413ad"),
+            (B3_SPAN_ID, "a2fb4a1d1a96d312"),
+            (B3_FLAGS, "1"),
+        ]));
+        let (trace_id, span_id) = multi.ids.unwrap();
+        assert_eq!(trace_id.to_string(), "463ac35c9f6413ad");
+        assert_eq!(span_id.to_string(), "a2fb4a1d1a96d312");
+        assert_eq!(multi.sampled, Some(true));
+    }
+
+    #[test]
+    fn test_inject_child_context() {
+        let mut headers = headers(&[
+            (B3, "463ac35c9f6413ad-a2fb4a1d1a96d312"),
+        ]);
+
+        let ctx = TraceContext::for_request(&headers, Sampler::new(1.0));
+        assert_eq!(ctx.trace_id.to_string(), "463ac35c9f6413ad");
+        assert_eq!(ctx.parent_id.unwrap().to_string(), "a2fb4a1d1a96d312");
+        assert!(ctx.sampled);
+
+        ctx.inject(&mut headers);
+        assert!(headers.get(B3).is_none());
+        assert_eq!(headers[B3_TRACE_ID], "463ac35c9f6413ad");
+        assert_eq!(headers[B3_SPAN_ID], ctx.span_id.to_string().as_str());
+        assert_eq!(headers[B3_PARENT_SPAN_ID], "a2fb4a1d1a96d312");
+        assert_eq!(headers[B3_SAMPLED], "1");
+
+        // The receiver sees the proxy's span as its parent.
+        let child = TraceContext::for_request(&headers, Sampler::new(1.0));
+        assert_eq!(child.trace_id, ctx.trace_id);
+        assert_eq!(child.parent_id, Some(ctx.span_id));
+    }
+
+    #[test]
+    fn test_new_trace_without_context() {
+        let a = TraceContext::for_request(&HeaderMap::new(), Sampler::new(1.0));
+        let b = TraceContext::for_request(&HeaderMap::new(), Sampler::new(1.0));
+        assert_eq!(a.parent_id, None);
+        assert!(a.sampled);
+        assert_ne!(a.trace_id, b.trace_id);
+        assert_ne!(a.span_id, b.span_id);
+    }
+
+    #[test]
+    fn test_sample_rate() {
+        let never = Sampler::new(0.0);
+        assert!((0..100).all(|_| !TraceContext::for_request(&HeaderMap::new(), never).sampled));
+
+        // The sender's decision is kept.
+        let sampled = headers(&[(B3, "1")]);
+        assert!(TraceContext::for_request(&sampled, never).sampled);
+
+        let always = Sampler::new(1.0);
+        let unsampled = headers(&[(B3, "0")]);
+        assert!(!TraceContext::for_request(&unsampled, always).sampled);
+
+        let half = Sampler::new(0.5);
+        let n = (0..1000)
+            .filter(|_| TraceContext::for_request(&HeaderMap::new(), half).sampled)
+            .count();
+        assert!(n > 350 && n < 650, "sampled {} of 1000", n);
+    }
+}
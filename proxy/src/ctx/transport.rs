@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
 
@@ -26,9 +27,16 @@ pub struct Server {
 pub struct Client {
     pub proxy: Arc<ctx::Proxy>,
     pub remote: SocketAddr,
+    /// Describes the process at `remote`, as given by service discovery.
+    ///
+    /// Empty if unknown.
+    pub labels: Arc<Labels>,
     pub protocol: Protocol,
 }
 
+/// Metadata about an endpoint, such as its pod, deployment, version and zone.
+pub type Labels = BTreeMap<String, String>;
+
 impl Ctx {
     pub fn proxy(&self) -> &Arc<ctx::Proxy> {
         match *self {
@@ -62,11 +70,13 @@ impl Client {
     pub fn new(
         proxy: &Arc<ctx::Proxy>,
         remote: &SocketAddr,
+        labels: &Arc<Labels>,
         protocol: Protocol,
     ) -> Arc<Client> {
         let c = Client {
             proxy: Arc::clone(proxy),
             remote: *remote,
+            labels: Arc::clone(labels),
             protocol: protocol,
         };
 
@@ -1,81 +1,675 @@
-use abstract_ns;
-use abstract_ns::HostResolve;
-use domain;
+use domain::bits::{DNameBuf, Message, ParseError, ParsedDName};
+use domain::iana::Rtype;
+use domain::rdata::{A, Aaaa, Soa, Srv};
+use domain::resolv;
+use futures::future::{self, Loop};
 use futures::prelude::*;
-use ns_dns_tokio;
-use std::net::IpAddr;
+use std::cell::RefCell;
+use std::cmp;
+use std::collections::HashMap;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
 use std::path::Path;
+use std::rc::Rc;
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 use tokio_core::reactor::Handle;
 use url;
 
 #[derive(Clone, Debug)]
-pub struct Config(domain::resolv::ResolvConf);
+pub struct Config(resolv::ResolvConf);
 
+/// Resolves names, caching the results for as long as their TTLs allow.
+///
+/// Names that don't end with a dot are expanded with the search list in the
+/// configuration, as described in resolv.conf(5).
+#[derive(Clone)]
+pub struct Resolver {
+    resolver: resolv::Resolver,
+    search: Rc<Vec<DNameBuf>>,
+    ndots: usize,
+    cache: Rc<RefCell<Cache>>,
+}
+
+/// The results of a lookup.
 #[derive(Clone, Debug)]
-pub struct Resolver(ns_dns_tokio::DnsResolver);
+pub struct Resolved<T> {
+    items: Vec<T>,
+    valid_until: Option<Instant>,
+}
 
-pub enum IpAddrFuture {
-    DNS(ns_dns_tokio::HostFuture),
-    Fixed(IpAddr),
-    InvalidDNSName(String),
+/// The addresses a name resolved to.
+pub type IpList = Resolved<IpAddr>;
+
+/// The targets of a service, ordered by priority.
+pub type SrvList = Resolved<SrvTarget>;
+
+/// The socket addresses of a service.
+pub type SocketAddrList = Resolved<SocketAddr>;
+
+/// A host serving a service, as found in an SRV record.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SrvTarget {
+    pub host: String,
+    pub port: u16,
+    pub priority: u16,
+    pub weight: u16,
 }
 
+pub type IpListFuture = Box<Future<Item = IpList, Error = Error>>;
+pub type SrvListFuture = Box<Future<Item = SrvList, Error = Error>>;
+pub type SocketAddrListFuture = Box<Future<Item = SocketAddrList, Error = Error>>;
+
+#[derive(Debug)]
 pub enum Error {
     InvalidDNSName(String),
     NoAddressesFound,
-    ResolutionFailed(<ns_dns_tokio::HostFuture as Future>::Error),
+    InvalidResponse(ParseError),
+    ResolutionFailed(resolv::error::Error),
+}
+
+/// Answers are kept until they expire. Expired answers are removed whenever
+/// a new one is added.
+#[derive(Debug, Default)]
+struct Cache {
+    hosts: HashMap<String, Cached<IpAddr>>,
+    services: HashMap<String, Cached<SrvTarget>>,
 }
 
+/// A cached answer. Answers without records are cached too, so that names
+/// that don't exist aren't looked up over and over.
+#[derive(Debug)]
+struct Cached<T> {
+    records: Vec<T>,
+    expires: Instant,
+}
+
+/// The records of one type in a response, and how long they may be cached.
+#[derive(Debug)]
+struct Answer<T> {
+    records: Vec<T>,
+    ttl: Duration,
+}
+
+/// How long an answer without records is cached if the response doesn't
+/// say.
+const DEFAULT_NEGATIVE_TTL_SECS: u64 = 5;
+
 impl Config {
     /// Note that this ignores any errors reading or parsing the resolve.conf
     /// file, just like the `domain` crate does.
     pub fn from_file(resolve_conf_path: &Path) -> Self {
-        let mut resolv_conf = domain::resolv::ResolvConf::new();
+        let mut resolv_conf = resolv::ResolvConf::new();
         let _ = resolv_conf.parse_file(resolve_conf_path);
         resolv_conf.finalize();
         Config(resolv_conf)
     }
 }
 
+// ===== impl Resolver =====
+
 impl Resolver {
     pub fn new(config: Config, executor: &Handle) -> Self {
-        Resolver(ns_dns_tokio::DnsResolver::new_from_resolver(
-            domain::resolv::Resolver::from_conf(executor, config.0),
-        ))
+        let search = Rc::new(config.0.search.clone());
+        let ndots = config.0.ndots;
+        Resolver {
+            resolver: resolv::Resolver::from_conf(executor, config.0),
+            search,
+            ndots,
+            cache: Default::default(),
+        }
     }
 
-    pub fn resolve_host(&self, host: &url::Host) -> IpAddrFuture {
+    /// Resolves `host` to all of its IPv4 and IPv6 addresses.
+    pub fn resolve_host(&self, host: &url::Host) -> IpListFuture {
         match *host {
-            url::Host::Domain(ref name) => {
-                trace!("resolve {}", name);
-                match abstract_ns::Name::from_str(name) {
-                    Ok(name) => IpAddrFuture::DNS(self.0.resolve_host(&name)),
-                    Err(_) => IpAddrFuture::InvalidDNSName(name.clone()),
+            url::Host::Domain(ref name) => self.resolve_name(name),
+            url::Host::Ipv4(ref addr) => Box::new(future::ok(Resolved::fixed(IpAddr::V4(*addr)))),
+            url::Host::Ipv6(ref addr) => Box::new(future::ok(Resolved::fixed(IpAddr::V6(*addr)))),
+        }
+    }
+
+    /// Resolves the SRV records of `name`, e.g. "_http._tcp.example.com".
+    pub fn resolve_srv(&self, name: &str) -> SrvListFuture {
+        if let Some(cached) = self.cache.borrow().services.get(name) {
+            if cached.expires > Instant::now() {
+                return Box::new(future::result(cached.resolved()));
+            }
+        }
+
+        trace!("resolve SRV {}", name);
+        let names = match search_names(name, &self.search, self.ndots) {
+            Ok(names) => names,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        let resolver = self.clone();
+        let cache = self.cache.clone();
+        let name = name.to_owned();
+        let f = search(names, move |dname| resolver.query(dname, Rtype::Srv, parse_srv))
+            .and_then(move |mut answer| {
+                sort_by_preference(&mut answer.records);
+                let cached = Cached::new(answer);
+                let resolved = cached.resolved();
+                cache.borrow_mut().insert_service(name, cached);
+                resolved
+            });
+        Box::new(f)
+    }
+
+    /// Resolves `host` to socket addresses.
+    ///
+    /// SRV names, which start with an underscore, are resolved to the
+    /// addresses and ports of their targets. Other hosts are resolved to
+    /// their addresses, with `default_port`.
+    pub fn resolve_socket_addrs(&self, host: &url::Host, default_port: u16)
+        -> SocketAddrListFuture
+    {
+        let name = match *host {
+            url::Host::Domain(ref name) if name.starts_with('_') => name.clone(),
+            _ => {
+                let f = self.resolve_host(host).map(move |ips| ips.map(|ip| {
+                    SocketAddr::new(ip, default_port)
+                }));
+                return Box::new(f);
+            }
+        };
+
+        let resolver = self.clone();
+        let f = self.resolve_srv(&name).and_then(move |targets| {
+            let valid_until = targets.valid_until;
+            let hosts = targets.items.into_iter().map(move |target| {
+                let port = target.port;
+                resolver.resolve_name(&target.host)
+                    .map(move |ips| ips.map(|ip| SocketAddr::new(ip, port)))
+                    .then(|result| -> Result<_, Error> {
+                        // A target that can't be resolved is skipped.
+                        Ok(result.ok())
+                    })
+            });
+
+            future::join_all(hosts).and_then(move |lists| {
+                let mut addrs = Resolved {
+                    items: Vec::new(),
+                    valid_until,
+                };
+                for list in lists.into_iter().filter_map(|l| l) {
+                    addrs.extend(list);
+                }
+
+                if addrs.items.is_empty() {
+                    Err(Error::NoAddressesFound)
+                } else {
+                    Ok(addrs)
                 }
+            })
+        });
+        Box::new(f)
+    }
+
+    fn resolve_name(&self, name: &str) -> IpListFuture {
+        if let Some(cached) = self.cache.borrow().hosts.get(name) {
+            if cached.expires > Instant::now() {
+                return Box::new(future::result(cached.resolved()));
+            }
+        }
+
+        trace!("resolve {}", name);
+        let names = match search_names(name, &self.search, self.ndots) {
+            Ok(names) => names,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        let resolver = self.clone();
+        let cache = self.cache.clone();
+        let name = name.to_owned();
+        let f = search(names, move |dname| resolver.query_ips(dname))
+            .and_then(move |answer| {
+                let cached = Cached::new(answer);
+                let resolved = cached.resolved();
+                cache.borrow_mut().insert_host(name, cached);
+                resolved
+            });
+        Box::new(f)
+    }
+
+    /// Looks up both the IPv4 and IPv6 addresses of `name`.
+    fn query_ips(&self, name: DNameBuf) -> Box<Future<Item = Answer<IpAddr>, Error = Error>> {
+        // Either family may be missing, so only fail if both lookups do.
+        let a = self.query(name.clone(), Rtype::A, parse_a)
+            .then(|r| -> Result<_, Error> { Ok(r) });
+        let aaaa = self.query(name, Rtype::Aaaa, parse_aaaa)
+            .then(|r| -> Result<_, Error> { Ok(r) });
+
+        let f = a.join(aaaa).and_then(|answers| {
+            match answers {
+                (Ok(a), Ok(aaaa)) => Ok(a.merge(aaaa)),
+                (Ok(answer), Err(_)) | (Err(_), Ok(answer)) => Ok(answer),
+                (Err(e), Err(_)) => Err(e),
             }
-            url::Host::Ipv4(ref addr) => IpAddrFuture::Fixed(IpAddr::V4(*addr)),
-            url::Host::Ipv6(ref addr) => IpAddrFuture::Fixed(IpAddr::V6(*addr)),
+        });
+        Box::new(f)
+    }
+
+    fn query<T, P>(&self, name: DNameBuf, rtype: Rtype, parse: P)
+        -> Box<Future<Item = Answer<T>, Error = Error>>
+    where
+        T: 'static,
+        P: FnOnce(&Message) -> Result<Answer<T>, ParseError> + 'static,
+    {
+        let f = self.resolver.query((name, rtype))
+            .map_err(Error::ResolutionFailed)
+            .and_then(move |msg| parse(&*msg).map_err(Error::InvalidResponse));
+        Box::new(f)
+    }
+}
+
+impl fmt::Debug for Resolver {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Resolver")
+            .field("cache", &self.cache)
+            .finish()
+    }
+}
+
+// ===== impl Resolved =====
+
+impl<T> Resolved<T> {
+    /// A result that never expires, such as an IP address literal.
+    fn fixed(item: T) -> Self {
+        Resolved {
+            items: vec![item],
+            valid_until: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// When the result should be resolved again, if ever.
+    pub fn valid_until(&self) -> Option<Instant> {
+        self.valid_until
+    }
+
+    fn map<U, F: FnMut(T) -> U>(self, f: F) -> Resolved<U> {
+        Resolved {
+            items: self.items.into_iter().map(f).collect(),
+            valid_until: self.valid_until,
+        }
+    }
+
+    /// Adds the items of `other`, expiring when either would.
+    fn extend(&mut self, other: Resolved<T>) {
+        self.items.extend(other.items);
+        self.valid_until = match (self.valid_until, other.valid_until) {
+            (Some(a), Some(b)) => Some(cmp::min(a, b)),
+            (a, b) => a.or(b),
+        };
+    }
+}
+
+impl<T> IntoIterator for Resolved<T> {
+    type Item = T;
+    type IntoIter = ::std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+// ===== impl Cache =====
+
+impl Cache {
+    fn insert_host(&mut self, name: String, cached: Cached<IpAddr>) {
+        self.purge_expired(Instant::now());
+        self.hosts.insert(name, cached);
+    }
+
+    fn insert_service(&mut self, name: String, cached: Cached<SrvTarget>) {
+        self.purge_expired(Instant::now());
+        self.services.insert(name, cached);
+    }
+
+    fn purge_expired(&mut self, now: Instant) {
+        self.hosts.retain(|_, cached| cached.expires > now);
+        self.services.retain(|_, cached| cached.expires > now);
+    }
+}
+
+// ===== impl Cached =====
+
+impl<T: Clone> Cached<T> {
+    fn new(answer: Answer<T>) -> Self {
+        Cached {
+            records: answer.records,
+            expires: Instant::now() + answer.ttl,
         }
     }
+
+    fn resolved(&self) -> Result<Resolved<T>, Error> {
+        if self.records.is_empty() {
+            return Err(Error::NoAddressesFound);
+        }
+
+        Ok(Resolved {
+            items: self.records.clone(),
+            valid_until: Some(self.expires),
+        })
+    }
 }
 
-impl Future for IpAddrFuture {
-    // TODO: Return the IpList so the user can try all of them.
-    type Item = IpAddr;
-    type Error = Error;
+// ===== impl Answer =====
 
-    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        match *self {
-            IpAddrFuture::DNS(ref mut inner) => match inner.poll() {
-                Ok(Async::NotReady) => Ok(Async::NotReady),
-                Ok(Async::Ready(ips)) => ips.pick_one()
-                    .map(Async::Ready)
-                    .ok_or(Error::NoAddressesFound),
-                Err(e) => Err(Error::ResolutionFailed(e)),
-            },
-            IpAddrFuture::Fixed(addr) => Ok(Async::Ready(addr)),
-            IpAddrFuture::InvalidDNSName(ref name) => Err(Error::InvalidDNSName(name.clone())),
+impl<T> Answer<T> {
+    fn new(msg: &Message) -> Self {
+        Answer {
+            records: Vec::new(),
+            ttl: negative_ttl(msg),
         }
     }
+
+    fn push(&mut self, record: T, ttl: u32) {
+        let ttl = Duration::from_secs(u64::from(ttl));
+        // The first record replaces the negative TTL.
+        self.ttl = if self.records.is_empty() { ttl } else { cmp::min(self.ttl, ttl) };
+        self.records.push(record);
+    }
+
+    fn merge(mut self, other: Answer<T>) -> Self {
+        self.ttl = match (self.records.is_empty(), other.records.is_empty()) {
+            (true, false) => other.ttl,
+            (false, true) => self.ttl,
+            _ => cmp::min(self.ttl, other.ttl),
+        };
+        self.records.extend(other.records);
+        self
+    }
+}
+
+/// Returns the fully-qualified names to look up for `name`, in order.
+///
+/// A name that ends with a dot is only looked up as is. Otherwise, names
+/// with at least `ndots` dots are looked up as is before trying each domain
+/// in `search`, and other names are looked up as is last.
+fn search_names(name: &str, search: &[DNameBuf], ndots: usize) -> Result<Vec<DNameBuf>, Error> {
+    let absolute = absolute_name(name)?;
+    if name.ends_with('.') {
+        return Ok(vec![absolute]);
+    }
+
+    let mut names = Vec::with_capacity(search.len() + 1);
+    for domain in search {
+        let domain = format!("{}", domain);
+        let domain = domain.trim_matches('.');
+        if !domain.is_empty() {
+            names.push(absolute_name(&format!("{}.{}", name, domain))?);
+        }
+    }
+
+    if name.matches('.').count() >= ndots {
+        names.insert(0, absolute);
+    } else {
+        names.push(absolute);
+    }
+
+    Ok(names)
+}
+
+/// Looks up each of `names` in turn, until one has records.
+///
+/// If none do, the answer or error for the last name is returned.
+fn search<T, F>(names: Vec<DNameBuf>, lookup: F) -> Box<Future<Item = Answer<T>, Error = Error>>
+where
+    T: 'static,
+    F: Fn(DNameBuf) -> Box<Future<Item = Answer<T>, Error = Error>> + 'static,
+{
+    let f = future::loop_fn(names.into_iter(), move |mut names| {
+        let name = names.next().expect("there is always a name to look up");
+        lookup(name).then(move |result| -> Result<_, Error> {
+            let found = result.as_ref().map(|a| !a.records.is_empty()).unwrap_or(false);
+            if found || names.as_slice().is_empty() {
+                Ok(Loop::Break(result))
+            } else {
+                Ok(Loop::Continue(names))
+            }
+        })
+    });
+    Box::new(f.and_then(|result| result))
+}
+
+/// Orders SRV targets by the lowest priority and then the highest weight.
+fn sort_by_preference(targets: &mut Vec<SrvTarget>) {
+    targets.sort_by_key(|t| (t.priority, cmp::Reverse(t.weight)));
+}
+
+/// Returns a fully-qualified name for `name`.
+fn absolute_name(name: &str) -> Result<DNameBuf, Error> {
+    let absolute = if name.ends_with('.') {
+        name.to_owned()
+    } else {
+        format!("{}.", name)
+    };
+
+    DNameBuf::from_str(&absolute).map_err(|_| Error::InvalidDNSName(name.to_owned()))
+}
+
+/// How long to cache a response without records, per RFC 2308: the lesser of
+/// the TTL and the minimum field of the SOA record in the authority section.
+fn negative_ttl(msg: &Message) -> Duration {
+    let mut ttl = None;
+
+    if let Ok(authority) = msg.authority() {
+        for record in authority.limit_to::<Soa<ParsedDName>>() {
+            if let Ok(record) = record {
+                ttl = Some(cmp::min(record.ttl(), record.data().minimum()));
+                break;
+            }
+        }
+    }
+
+    ttl.map(|ttl| Duration::from_secs(u64::from(ttl)))
+        .unwrap_or_else(|| Duration::from_secs(DEFAULT_NEGATIVE_TTL_SECS))
+}
+
+fn parse_a(msg: &Message) -> Result<Answer<IpAddr>, ParseError> {
+    let mut answer = Answer::new(msg);
+    for record in msg.answer()?.limit_to::<A>() {
+        let record = record?;
+        answer.push(IpAddr::V4(record.data().addr()), record.ttl());
+    }
+    Ok(answer)
+}
+
+fn parse_aaaa(msg: &Message) -> Result<Answer<IpAddr>, ParseError> {
+    let mut answer = Answer::new(msg);
+    for record in msg.answer()?.limit_to::<Aaaa>() {
+        let record = record?;
+        answer.push(IpAddr::V6(record.data().addr()), record.ttl());
+    }
+    Ok(answer)
+}
+
+fn parse_srv(msg: &Message) -> Result<Answer<SrvTarget>, ParseError> {
+    let mut answer = Answer::new(msg);
+    for record in msg.answer()?.limit_to::<Srv<ParsedDName>>() {
+        let record = record?;
+        let target = SrvTarget {
+            host: format!("{}", record.data().target()).trim_right_matches('.').to_owned(),
+            port: record.data().port(),
+            priority: record.data().priority(),
+            weight: record.data().weight(),
+        };
+        answer.push(target, record.ttl());
+    }
+    Ok(answer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(records: Vec<u32>, ttl: u64) -> Answer<u32> {
+        Answer {
+            records,
+            ttl: Duration::from_secs(ttl),
+        }
+    }
+
+    fn answer_ip(ttl: u64) -> Answer<IpAddr> {
+        Answer {
+            records: vec![IpAddr::from([10, 0, 0, 1])],
+            ttl: Duration::from_secs(ttl),
+        }
+    }
+
+    /// Returns a response to an A query for example.com with no answers and,
+    /// if given, an SOA record with the given TTL and minimum.
+    fn negative_response(soa: Option<(u32, u32)>) -> Vec<u8> {
+        let name = b"\x07example\x03com\x00";
+
+        let mut msg = vec![
+            0, 0,                         // ID
+            0x81, 0x83,                   // Response, NXDOMAIN
+            0, 1,                         // Questions
+            0, 0,                         // Answers
+            0, soa.is_some() as u8,       // Authority records
+            0, 0,                         // Additional records
+        ];
+        msg.extend_from_slice(name);
+        msg.extend_from_slice(&[0, 1, 0, 1]); // A, IN
+
+        if let Some((ttl, minimum)) = soa {
+            msg.extend_from_slice(name);
+            msg.extend_from_slice(&[0, 6, 0, 1]); // SOA, IN
+            msg.extend_from_slice(&be32(ttl));
+            msg.extend_from_slice(&[0, 22]);
+            // The primary name server and mailbox are the root.
+            msg.extend_from_slice(&[0, 0]);
+            // Serial, refresh, retry and expire.
+            msg.extend_from_slice(&[0; 16]);
+            msg.extend_from_slice(&be32(minimum));
+        }
+
+        msg
+    }
+
+    fn be32(n: u32) -> [u8; 4] {
+        [(n >> 24) as u8, (n >> 16) as u8, (n >> 8) as u8, n as u8]
+    }
+
+    fn srv(host: &str, priority: u16, weight: u16) -> SrvTarget {
+        SrvTarget {
+            host: host.to_owned(),
+            port: 8080,
+            priority,
+            weight,
+        }
+    }
+
+    fn names(names: &[DNameBuf]) -> Vec<String> {
+        names.iter().map(|n| format!("{}", n).trim_right_matches('.').to_owned()).collect()
+    }
+
+    #[test]
+    fn merge_keeps_the_lowest_ttl() {
+        let merged = answer(vec![1], 30).merge(answer(vec![2, 3], 10));
+        assert_eq!(merged.records, vec![1, 2, 3]);
+        assert_eq!(merged.ttl, Duration::from_secs(10));
+    }
+
+    #[test]
+    fn merge_ignores_the_ttl_of_empty_answers() {
+        // The TTL of an empty answer is the negative TTL, which shouldn't
+        // limit how long the records of the other are cached.
+        let merged = answer(vec![1], 30).merge(answer(vec![], 5));
+        assert_eq!(merged.records, vec![1]);
+        assert_eq!(merged.ttl, Duration::from_secs(30));
+
+        let merged = answer(vec![], 5).merge(answer(vec![2], 30));
+        assert_eq!(merged.records, vec![2]);
+        assert_eq!(merged.ttl, Duration::from_secs(30));
+
+        let merged = answer(vec![], 5).merge(answer(vec![], 10));
+        assert!(merged.records.is_empty());
+        assert_eq!(merged.ttl, Duration::from_secs(5));
+    }
+
+    #[test]
+    fn negative_ttl_is_the_lesser_of_the_soa_ttl_and_minimum() {
+        let msg = negative_response(Some((300, 60)));
+        let msg = Message::from_bytes(&msg).unwrap();
+        assert_eq!(negative_ttl(msg), Duration::from_secs(60));
+
+        let msg = negative_response(Some((30, 60)));
+        let msg = Message::from_bytes(&msg).unwrap();
+        assert_eq!(negative_ttl(msg), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn negative_ttl_defaults_without_soa() {
+        let msg = negative_response(None);
+        let msg = Message::from_bytes(&msg).unwrap();
+        assert_eq!(negative_ttl(msg), Duration::from_secs(DEFAULT_NEGATIVE_TTL_SECS));
+    }
+
+    #[test]
+    fn srv_targets_prefer_low_priority_then_high_weight() {
+        let mut targets = vec![
+            srv("c", 20, 100),
+            srv("b", 10, 5),
+            srv("a", 10, 50),
+            srv("d", 20, 0),
+        ];
+        sort_by_preference(&mut targets);
+
+        let hosts: Vec<&str> = targets.iter().map(|t| t.host.as_str()).collect();
+        assert_eq!(hosts, vec!["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn search_list_expands_relative_names() {
+        let search = vec![
+            DNameBuf::from_str("ns.svc.cluster.local.").unwrap(),
+            DNameBuf::from_str("svc.cluster.local.").unwrap(),
+        ];
+
+        let expanded = search_names("web", &search, 2).unwrap();
+        assert_eq!(names(&expanded), vec![
+            "web.ns.svc.cluster.local",
+            "web.svc.cluster.local",
+            "web",
+        ]);
+
+        // Names with enough dots are tried as is first.
+        let expanded = search_names("web.example.com", &search, 2).unwrap();
+        assert_eq!(names(&expanded), vec![
+            "web.example.com",
+            "web.example.com.ns.svc.cluster.local",
+            "web.example.com.svc.cluster.local",
+        ]);
+
+        // Fully-qualified names are never expanded.
+        let expanded = search_names("web.", &search, 2).unwrap();
+        assert_eq!(names(&expanded), vec!["web"]);
+    }
+
+    #[test]
+    fn cache_removes_expired_answers() {
+        let mut cache = Cache::default();
+        let now = Instant::now();
+        cache.hosts.insert("old".to_owned(), Cached {
+            records: vec![],
+            expires: now,
+        });
+        cache.services.insert("_http._tcp.old".to_owned(), Cached {
+            records: vec![],
+            expires: now,
+        });
+        cache.insert_host("new".to_owned(), Cached::new(answer_ip(60)));
+
+        assert!(cache.hosts.contains_key("new"));
+        assert!(!cache.hosts.contains_key("old"));
+        assert!(cache.services.is_empty());
+    }
 }
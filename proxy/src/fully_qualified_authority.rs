@@ -107,10 +107,76 @@ impl FullyQualifiedAuthority {
     pub fn without_trailing_dot(&self) -> &str {
         self.0.as_str()
     }
+
+    pub fn host(&self) -> &str {
+        self.0.host()
+    }
+
+    pub fn port(&self) -> Option<u16> {
+        self.0.port()
+    }
+
+    /// Whether the name is that of a Kubernetes service in `zone`, i.e.
+    /// "$name.$namespace.svc.$zone".
+    ///
+    /// Only these names can be resolved by the controller. Without a zone,
+    /// service names can't be told apart from others, so every name other
+    /// than an IP address is assumed to be one.
+    pub fn is_cluster_service(&self, zone: Option<&str>) -> bool {
+        let host = self.0.host();
+        if IpAddr::from_str(host).is_ok() {
+            return false;
+        }
+
+        let zone = match zone {
+            Some(zone) => zone,
+            None => return true,
+        };
+
+        let mut parts = host.splitn(4, '.');
+        let name = parts.next();
+        let namespace = parts.next();
+        let svc = parts.next();
+        let rest = parts.next();
+        match (name, namespace, svc, rest) {
+            (Some(_), Some(_), Some(svc), Some(rest)) => {
+                svc.eq_ignore_ascii_case("svc") && rest.eq_ignore_ascii_case(zone)
+            },
+            _ => false,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    #[test]
+    fn test_is_cluster_service() {
+        fn f(input: &str, zone: Option<&str>) -> bool {
+            use bytes::Bytes;
+            use http::uri::Authority;
+
+            let input = Authority::from_shared(Bytes::from(input.as_bytes())).unwrap();
+            super::FullyQualifiedAuthority::new(&input, Some("namespace"), zone)
+                .is_cluster_service(zone)
+        }
+
+        assert!(f("name", Some("cluster.local")));
+        assert!(f("name.namespace", Some("cluster.local")));
+        assert!(f("name.namespace.svc:8080", Some("cluster.local")));
+        assert!(f("name.namespace.svc.cluster.local", Some("cluster.local")));
+        assert!(f("name.namespace.SVC.Cluster.Local", Some("cluster.local")));
+
+        assert!(!f("name.", Some("cluster.local")));
+        assert!(!f("name.namespace.svc.other.zone", Some("cluster.local")));
+        assert!(!f("name.namespace.svc.cluster", Some("cluster.local")));
+        assert!(!f("api.example.com", Some("cluster.local")));
+        assert!(!f("10.1.2.3:80", Some("cluster.local")));
+
+        assert!(f("name", None));
+        assert!(f("api.example.com", None));
+        assert!(!f("10.1.2.3:80", None));
+    }
+
     #[test]
     fn test_normalized_authority() {
         fn f(input: &str, default_namespace: Option<&str>,
@@ -6,7 +6,7 @@ use http;
 use tokio_core::reactor::Handle;
 use tower_buffer::{self, Buffer};
 use tower_h2;
-use tower_reconnect::{self, Reconnect};
+use tower_h2::client::{pool, Pool};
 use tower_router::Recognize;
 
 use bind;
@@ -53,16 +53,16 @@ where
     B: tower_h2::Body + 'static,
 {
     type Request = http::Request<B>;
-    type Response = http::Response<telemetry::sensor::http::ResponseBody<tower_h2::RecvBody>>;
+    type Response = http::Response<pool::ResponseBody<telemetry::sensor::http::ResponseBody<tower_h2::RecvBody>>>;
     type Error = tower_buffer::Error<
-        tower_reconnect::Error<
+        pool::Error<
             tower_h2::client::Error,
             tower_h2::client::ConnectError<transport::TimeoutError<io::Error>>,
         >,
     >;
     type Key = SocketAddr;
     type RouteError = ();
-    type Service = Buffer<Reconnect<telemetry::sensor::NewHttp<Client<B>, B, tower_h2::RecvBody>>>;
+    type Service = Buffer<Pool<telemetry::sensor::NewHttp<Client<B>, B, tower_h2::RecvBody>>>;
 
     fn recognize(&self, req: &Self::Request) -> Option<Self::Key> {
         let key = req.extensions()
@@ -95,14 +95,18 @@ where
     ///
     /// Buffering is currently unbounded and does not apply timeouts. This must be
     /// changed.
-    fn bind_service(&mut self, addr: &SocketAddr) -> Result<Self::Service, Self::RouteError> {
+    fn bind_service(&self, addr: &SocketAddr) -> Result<Self::Service, Self::RouteError> {
         debug!("building inbound client to {}", addr);
 
+        // The local process isn't discovered, so there is nothing to label it
+        // with.
+        let labels = Arc::new(ctx::transport::Labels::new());
+
         // Wrap with buffering. This currently is an unbounded buffer, which
         // is not ideal.
         //
         // TODO: Don't use unbounded buffering.
-        Buffer::new(self.bind.bind_service(addr), self.bind.executor()).map_err(|_| {})
+        Buffer::new(self.bind.bind_service(addr, &labels), self.bind.executor()).map_err(|_| {})
     }
 }
 
@@ -2,7 +2,6 @@
 #![cfg_attr(feature = "cargo-clippy", allow(new_without_default_derive))]
 #![deny(warnings)]
 
-extern crate abstract_ns;
 extern crate bytes;
 extern crate chrono;
 extern crate domain;
@@ -17,7 +16,7 @@ extern crate ipnet;
 extern crate libc;
 #[macro_use]
 extern crate log;
-extern crate ns_dns_tokio;
+extern crate net2;
 extern crate ordermap;
 extern crate prost;
 #[macro_use]
@@ -42,9 +41,11 @@ extern crate url;
 
 use futures::*;
 
+use std::collections::HashSet;
 use std::io;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
 use std::thread;
 use std::time::Instant;
 
@@ -67,10 +68,12 @@ mod inbound;
 mod logging;
 mod map_err;
 mod outbound;
+mod tcp;
 mod telemetry;
 mod transport;
 pub mod timeout;
 mod tower_fn; // TODO: move to tower-fn
+mod zone;
 
 use bind::Bind;
 use connection::BoundPort;
@@ -105,8 +108,12 @@ impl Main {
 
         let control_listener = BoundPort::new(config.control_listener.addr)
             .expect("controller listener bind");
-        let inbound_listener = BoundPort::new(config.public_listener.addr)
-            .expect("public listener bind");
+        let inbound_listener = match config.inbound_interception_mode {
+            config::InterceptionMode::Redirect => BoundPort::new(config.public_listener.addr),
+            config::InterceptionMode::Tproxy => {
+                BoundPort::new_transparent(config.public_listener.addr)
+            }
+        }.expect("public listener bind");
         let outbound_listener = BoundPort::new(config.private_listener.addr)
             .expect("private listener bind");
         Main {
@@ -147,9 +154,16 @@ impl Main {
             outbound_listener,
         } = self;
 
-        let control_host_and_port = config.control_host_and_port.clone();
+        let control_plane = config.control_plane.clone();
 
-        info!("using controller at {:?}", control_host_and_port);
+        match control_plane {
+            config::ControlPlane::Controller(ref host_and_port) => {
+                info!("using controller at {:?}", host_and_port)
+            }
+            config::ControlPlane::Standalone { ref destinations_path, .. } => {
+                info!("running without a controller; destinations from {:?}", destinations_path)
+            }
+        }
         info!("routing on {:?}", outbound_listener.local_addr());
         info!(
             "proxying on {:?} to {:?}",
@@ -161,67 +175,72 @@ impl Main {
             &process_ctx,
             config.event_buffer_capacity,
             config.metrics_flush_interval,
+            config.trace_collector.map(SocketAddr::from),
+            config.trace_sample_rate,
         );
 
-        let (control, control_bg) = control::new();
-
-        let mut core = Core::new().expect("executor");
-        let executor = core.handle();
+        let (control, control_bg) = control::new(
+            config.address_update_interval,
+            config.destination_snapshot_path.clone(),
+        );
 
         let dns_config = dns::Config::from_file(&config.resolv_conf_path);
 
-        let bind = Bind::new(executor.clone()).with_sensors(sensors.clone());
-
-        // Setup the public listener. This will listen on a publicly accessible
-        // address and listen for inbound connections that should be forwarded
-        // to the managed application (private destination).
-        let inbound = {
-            let ctx = ctx::Proxy::inbound(&process_ctx);
+        let pool_config = {
+            let c = tower_h2::client::pool::Config::new()
+                .max_connections(config.max_connections_per_endpoint)
+                .idle_timeout(config.connection_idle_timeout);
+            match config.connection_latency_threshold {
+                Some(t) => c.max_latency(t),
+                None => c,
+            }
+        };
 
-            let bind = bind.clone()
-                .with_connect_timeout(config.private_connect_timeout)
-                .with_ctx(ctx.clone());
+        let worker = Worker {
+            config: config.clone(),
+            process_ctx: process_ctx.clone(),
+            sensors,
+            control,
+            pool_config,
+            req_ids: Default::default(),
+            opaque_ports: Arc::new(config.opaque_ports.clone()),
+            routes: telemetry.route_counts(),
+        };
 
-            let default_addr = config.private_forward.map(|a| a.into());
+        // Every worker thread accepts connections on its own listeners, bound
+        // to the same addresses, and serves them on its own reactor. This
+        // thread is the first worker.
+        let mut worker_shutdown = Vec::with_capacity(config.worker_threads - 1);
+        for i in 1..config.worker_threads {
+            let worker = worker.clone();
+            let inbound_listener = inbound_listener.bind_another()
+                .expect("public listener bind");
+            let outbound_listener = outbound_listener.bind_another()
+                .expect("private listener bind");
+            let (tx, shutdown_signal) = futures::sync::oneshot::channel::<()>();
+            worker_shutdown.push(tx);
 
-            let fut = serve(
-                inbound_listener,
-                h2::server::Builder::default(),
-                Inbound::new(default_addr, bind),
-                ctx,
-                sensors.clone(),
-                &executor,
-            );
-            ::logging::context_future("inbound", fut)
-        };
+            thread::Builder::new()
+                .name(format!("worker-{}", i))
+                .spawn(move || {
+                    let mut core = Core::new().expect("initialize worker core");
+                    let executor = core.handle();
 
-        // Setup the private listener. This will listen on a locally accessible
-        // address and listen for outbound requests that should be routed
-        // to a remote service (public destination).
-        let outbound = {
-            let ctx = ctx::Proxy::outbound(&process_ctx);
+                    let fut = worker
+                        .serve(inbound_listener, outbound_listener, &executor)
+                        .map_err(|err| error!("worker error: {:?}", err));
+                    executor.spawn(fut);
 
-            let bind = config
-                .public_connect_timeout
-                .map_or_else(|| bind.clone(), |t| bind.clone().with_connect_timeout(t))
-                .with_ctx(ctx.clone());
+                    let shutdown = shutdown_signal.then(|_| Ok::<(), ()>(()));
+                    core.run(shutdown).expect("worker");
+                })
+                .expect("initialize worker thread");
+        }
 
-            let outgoing = Outbound::new(
-                bind,
-                control,
-                config.default_destination_namespace().cloned(),
-                config.default_destination_zone().cloned());
+        let mut core = Core::new().expect("executor");
+        let executor = core.handle();
 
-            let fut = serve(
-                outbound_listener,
-                h2::server::Builder::default(),
-                outgoing,
-                ctx,
-                sensors,
-                &executor,
-            );
-            ::logging::context_future("outbound", fut)
-        };
+        let fut = worker.serve(inbound_listener, outbound_listener, &executor);
 
         trace!("running");
 
@@ -248,13 +267,26 @@ impl Main {
                         .make_control(&taps, &executor)
                         .expect("bad news in telemetry town");
 
-                    let client = control_bg.bind(
-                        telemetry,
-                        control_host_and_port,
-                        dns_config,
-                        config.report_timeout,
-                        &executor
-                    );
+                    let client = match control_plane {
+                        config::ControlPlane::Controller(host_and_port) => control_bg.bind(
+                            telemetry,
+                            host_and_port,
+                            dns_config,
+                            config.default_destination_zone().cloned(),
+                            config.report_timeout,
+                            &executor
+                        ),
+                        config::ControlPlane::Standalone {
+                            destinations_path,
+                            telemetry_path,
+                        } => control_bg.bind_standalone(
+                            telemetry,
+                            destinations_path,
+                            telemetry_path,
+                            dns_config,
+                            &executor
+                        ),
+                    };
 
                     let fut = client.join(server.map_err(|_| {})).map(|_| {});
                     executor.spawn(::logging::context_future("controller-client", fut));
@@ -265,20 +297,137 @@ impl Main {
                 .expect("initialize controller api thread");
         }
 
-        let fut = inbound
-            .join(outbound)
-            .map(|_| ())
-            .map_err(|err| error!("main error: {:?}", err));
+        let fut = fut.map_err(|err| error!("main error: {:?}", err));
 
         core.handle().spawn(fut);
         core.run(shutdown_signal).expect("executor");
     }
 }
 
+/// The parts of the proxy that each worker thread serves with.
+#[derive(Clone)]
+struct Worker {
+    config: config::Config,
+    process_ctx: Arc<ctx::Process>,
+    sensors: telemetry::Sensors,
+    control: control::Control,
+    pool_config: tower_h2::client::pool::Config,
+    /// Shared by all workers, so that request IDs are unique in the process.
+    req_ids: Arc<AtomicUsize>,
+    opaque_ports: Arc<HashSet<u16>>,
+    /// Shared by all workers, so that reports count the routes of all workers.
+    routes: telemetry::RouteCounts,
+}
+
+impl Worker {
+    /// Serves the inbound and outbound proxies on `executor`.
+    fn serve(
+        &self,
+        inbound_listener: BoundPort,
+        outbound_listener: BoundPort,
+        executor: &Handle,
+    ) -> Box<Future<Item = (), Error = io::Error> + 'static> {
+        let bind = Bind::new(executor.clone())
+            .with_sensors(self.sensors.clone())
+            .with_pool_config(self.pool_config)
+            .with_req_ids(self.req_ids.clone());
+
+        // Setup the public listener. This will listen on a publicly accessible
+        // address and listen for inbound connections that should be forwarded
+        // to the managed application (private destination).
+        let inbound = {
+            let ctx = ctx::Proxy::inbound(&self.process_ctx);
+
+            let bind = bind.clone()
+                .with_connect_timeout(self.config.private_connect_timeout)
+                .with_ctx(ctx.clone());
+
+            let default_addr = self.config.private_forward.map(|a| a.into());
+
+            let router = Router::with_limits(
+                Inbound::new(default_addr, bind),
+                self.config.inbound_router_capacity,
+                self.config.inbound_router_max_idle_age,
+                self.routes.inbound.clone(),
+            );
+
+            let tcp = tcp::Forward::new(
+                &self.opaque_ports,
+                Some(self.config.private_connect_timeout),
+                &ctx,
+                &self.sensors,
+                executor,
+            );
+
+            let fut = serve(
+                inbound_listener,
+                self.config.inbound_interception_mode,
+                h2::server::Builder::default(),
+                router,
+                tcp,
+                ctx,
+                self.sensors.clone(),
+                executor,
+            );
+            ::logging::context_future("inbound", fut)
+        };
+
+        // Setup the private listener. This will listen on a locally accessible
+        // address and listen for outbound requests that should be routed
+        // to a remote service (public destination).
+        let outbound = {
+            let ctx = ctx::Proxy::outbound(&self.process_ctx);
+
+            let bind = self.config
+                .public_connect_timeout
+                .map_or_else(|| bind.clone(), |t| bind.clone().with_connect_timeout(t))
+                .with_ctx(ctx.clone());
+
+            let outgoing = Outbound::new(
+                bind,
+                self.control.clone(),
+                self.config.default_destination_namespace().cloned(),
+                self.config.default_destination_zone().cloned())
+                .with_zone(self.config.pod_zone.clone(), self.config.zone_spillover_threshold);
+
+            let router = Router::with_limits(
+                outgoing,
+                self.config.outbound_router_capacity,
+                self.config.outbound_router_max_idle_age,
+                self.routes.outbound.clone(),
+            );
+
+            let tcp = tcp::Forward::new(
+                &self.opaque_ports,
+                self.config.public_connect_timeout,
+                &ctx,
+                &self.sensors,
+                executor,
+            );
+
+            let fut = serve(
+                outbound_listener,
+                config::InterceptionMode::Redirect,
+                h2::server::Builder::default(),
+                router,
+                tcp,
+                ctx,
+                self.sensors.clone(),
+                executor,
+            );
+            ::logging::context_future("outbound", fut)
+        };
+
+        Box::new(inbound.join(outbound).map(|_| ()))
+    }
+}
+
 fn serve<R, B, E, F>(
     bound_port: BoundPort,
+    interception_mode: config::InterceptionMode,
     h2_builder: h2::server::Builder,
-    recognize: R,
+    router: Router<R>,
+    tcp: tcp::Forward,
     proxy_ctx: Arc<ctx::Proxy>,
     sensors: telemetry::Sensors,
     executor: &Handle,
@@ -289,13 +438,12 @@ where
     F: ::std::fmt::Debug + 'static,
     R: Recognize<
         Request = http::Request<RecvBody>,
-        Response = http::Response<telemetry::sensor::http::ResponseBody<B>>,
+        Response = http::Response<tower_h2::client::pool::ResponseBody<telemetry::sensor::http::ResponseBody<B>>>,
         Error = E,
         RouteError = F,
     >
         + 'static,
 {
-    let router = Router::new(recognize);
     let stack = NewServiceFn::new(move || {
         // Clone the router handle
         let router = router.clone();
@@ -312,13 +460,18 @@ where
     );
     bound_port.listen_and_fold(
         executor,
-        (server, proxy_ctx, sensors, executor.clone()),
-        move |(server, proxy_ctx, sensors, executor), (connection, remote_addr)| {
+        (server, tcp, proxy_ctx, sensors, executor.clone()),
+        move |(server, tcp, proxy_ctx, sensors, executor), (connection, remote_addr)| {
             let opened_at = Instant::now();
-            let orig_dst = connection.original_dst_addr();
+            let orig_dst = connection.original_dst_addr(interception_mode);
             let local_addr = connection.local_addr().unwrap_or(listen_addr);
-            // TODO: detect protocol.
-            let protocol = control::pb::common::Protocol::Http;
+            // TODO: detect protocol, rather than configuring it by port.
+            let tcp_dst = tcp.destination(orig_dst);
+            let protocol = if tcp_dst.is_some() {
+                control::pb::common::Protocol::Tcp
+            } else {
+                control::pb::common::Protocol::Http
+            };
             let srv_ctx =
                 ctx::transport::Server::new(
                     &proxy_ctx,
@@ -330,6 +483,12 @@ where
 
             let io = sensors.accept(connection, opened_at, &srv_ctx);
 
+            if let Some(addr) = tcp_dst {
+                let fwd = tcp.serve(io, addr);
+                executor.spawn(::logging::context_future(("serve", local_addr), fwd));
+                return future::ok((server, tcp, proxy_ctx, sensors, executor));
+            }
+
             // TODO session context
             let set_ctx = move |request: &mut http::Request<()>| {
                 request.extensions_mut().insert(Arc::clone(&srv_ctx));
@@ -338,7 +497,7 @@ where
             let s = server.serve_modified(io, set_ctx).map_err(|_| ());
             executor.spawn(::logging::context_future(("serve", local_addr), s));
 
-            future::ok((server, proxy_ctx, sensors, executor))
+            future::ok((server, tcp, proxy_ctx, sensors, executor))
         },
     )
 }
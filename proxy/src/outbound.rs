@@ -5,7 +5,7 @@ use http;
 use tower_balance::{self, Balance};
 use tower_buffer::{self, Buffer};
 use tower_h2;
-use tower_reconnect;
+use tower_h2::client::pool;
 use tower_router::Recognize;
 
 use bind::Bind;
@@ -14,12 +14,13 @@ use ctx;
 use fully_qualified_authority::FullyQualifiedAuthority;
 use telemetry;
 use transport;
+use zone::ZoneAware;
 
-type Discovery<B> = control::discovery::Watch<Bind<Arc<ctx::Proxy>, B>>;
+type Discovery<B> = control::discovery::ZoneWatch<Bind<Arc<ctx::Proxy>, B>>;
 
 type Error = tower_buffer::Error<
     tower_balance::Error<
-        tower_reconnect::Error<
+        pool::Error<
             tower_h2::client::Error,
             tower_h2::client::ConnectError<transport::TimeoutError<io::Error>>,
         >,
@@ -32,6 +33,9 @@ pub struct Outbound<B> {
     discovery: control::Control,
     default_namespace: Option<String>,
     default_zone: Option<String>,
+    /// The zone that the proxy runs in, if known.
+    zone: Option<String>,
+    zone_spillover_threshold: usize,
 }
 
 // ===== impl Outbound =====
@@ -45,6 +49,18 @@ impl<B> Outbound<B> {
             discovery,
             default_namespace,
             default_zone,
+            zone: None,
+            zone_spillover_threshold: 1,
+        }
+    }
+
+    /// Prefers endpoints in `zone`, until there are `spillover_threshold`
+    /// requests in flight per endpoint in it.
+    pub fn with_zone(self, zone: Option<String>, spillover_threshold: usize) -> Self {
+        Self {
+            zone,
+            zone_spillover_threshold: spillover_threshold,
+            ..self
         }
     }
 }
@@ -54,11 +70,11 @@ where
     B: tower_h2::Body + 'static,
 {
     type Request = http::Request<B>;
-    type Response = http::Response<telemetry::sensor::http::ResponseBody<tower_h2::RecvBody>>;
+    type Response = http::Response<pool::ResponseBody<telemetry::sensor::http::ResponseBody<tower_h2::RecvBody>>>;
     type Error = Error;
     type Key = FullyQualifiedAuthority;
     type RouteError = ();
-    type Service = Buffer<Balance<Discovery<B>>>;
+    type Service = Buffer<ZoneAware<Balance<Discovery<B>>>>;
 
     fn recognize(&self, req: &Self::Request) -> Option<Self::Key> {
         req.uri().authority_part().map(|authority|
@@ -72,21 +88,28 @@ where
     /// Builds a dynamic, load balancing service.
     ///
     /// Resolves the authority in service discovery and initializes a service that buffers
-    /// and load balances requests across.
+    /// and load balances requests across. Endpoints in the proxy's zone are preferred.
     ///
     /// # TODO
     ///
     /// Buffering is currently unbounded and does not apply timeouts. This must be
     /// changed.
     fn bind_service(
-        &mut self,
+        &self,
         authority: &FullyQualifiedAuthority,
     ) -> Result<Self::Service, Self::RouteError> {
         debug!("building outbound client to {:?}", authority);
 
         let resolve = self.discovery.resolve(authority, self.bind.clone());
+        let (local, remote) = resolve.split_by_zone(self.zone.clone());
+        let local_endpoints = local.endpoints();
 
-        let balance = Balance::new(resolve);
+        let balance = ZoneAware::new(
+            Balance::new(local),
+            Balance::new(remote),
+            local_endpoints,
+            self.zone_spillover_threshold,
+        );
 
         // Wrap with buffering. This currently is an unbounded buffer,
         // which is not ideal.
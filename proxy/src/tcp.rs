@@ -0,0 +1,97 @@
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::Future;
+use tokio_connect::Connect;
+use tokio_core::reactor::Handle;
+
+use connection::Connection;
+use control::pb::common::Protocol;
+use ctx;
+use telemetry::{self, sensor};
+use transport;
+
+/// Forwards TCP connections to their original destinations without inspecting them.
+#[derive(Clone)]
+pub struct Forward {
+    /// Connections to these destination ports are forwarded.
+    ports: Arc<HashSet<u16>>,
+    connect_timeout: Option<Duration>,
+    proxy_ctx: Arc<ctx::Proxy>,
+    sensors: telemetry::Sensors,
+    executor: Handle,
+}
+
+// ===== impl Forward =====
+
+impl Forward {
+    pub fn new(
+        ports: &Arc<HashSet<u16>>,
+        connect_timeout: Option<Duration>,
+        proxy_ctx: &Arc<ctx::Proxy>,
+        sensors: &telemetry::Sensors,
+        executor: &Handle,
+    ) -> Self {
+        Forward {
+            ports: Arc::clone(ports),
+            connect_timeout,
+            proxy_ctx: Arc::clone(proxy_ctx),
+            sensors: sensors.clone(),
+            executor: executor.clone(),
+        }
+    }
+
+    /// Returns the address that a connection should be forwarded to, if it shouldn't
+    /// be served as HTTP.
+    pub fn destination(&self, orig_dst: Option<SocketAddr>) -> Option<SocketAddr> {
+        orig_dst.and_then(|addr| {
+            if self.ports.contains(&addr.port()) {
+                Some(addr)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Connects to `addr` and forwards `server` to it until both are closed.
+    pub fn serve(
+        &self,
+        server: sensor::Transport<Connection>,
+        addr: SocketAddr,
+    ) -> Box<Future<Item = (), Error = ()>> {
+        let client_ctx = ctx::transport::Client::new(
+            &self.proxy_ctx,
+            &addr,
+            &Default::default(),
+            Protocol::Tcp,
+        );
+        let connect = transport::Connect::new(addr, &self.executor);
+
+        let connecting: Box<Future<Item = sensor::Transport<Connection>, Error = ()>> =
+            match self.connect_timeout {
+                Some(timeout) => {
+                    let connect =
+                        transport::TimeoutConnect::new(connect, timeout, &self.executor);
+                    let c = self.sensors.connect(connect, &client_ctx).connect()
+                        .map_err(move |e| debug!("tcp connect to {} failed: {:?}", addr, e));
+                    Box::new(c)
+                }
+                None => {
+                    let c = self.sensors.connect(connect, &client_ctx).connect()
+                        .map_err(move |e| debug!("tcp connect to {} failed: {:?}", addr, e));
+                    Box::new(c)
+                }
+            };
+
+        let fwd = connecting.and_then(move |client| {
+            transport::duplex(server, client)
+                .map(move |(sent, received)| {
+                    trace!("tcp to {} closed; sent={}B received={}B", addr, sent, received)
+                })
+                .map_err(move |e| debug!("tcp to {} failed: {}", addr, e))
+        });
+        Box::new(fwd)
+    }
+}
@@ -1,14 +1,17 @@
 use std::{fmt, io};
+use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use futures::{Async, Future, Poll, Stream};
-use futures_mpsc_lossy::Receiver;
+use futures_mpsc_lossy::{self, Receiver, Sender};
 use tokio_core::reactor::{Handle, Timeout};
 
 use super::event::Event;
 use super::metrics::Metrics;
+use super::RouteCounts;
 use super::tap::Taps;
+use super::zipkin::{self, Span};
 use control::pb::telemetry::ReportRequest;
 use ctx;
 
@@ -22,7 +25,12 @@ pub struct MakeControl {
     /// controller.
     flush_interval: Duration,
 
+    /// If set, spans are sent to the collector at this address.
+    trace_collector: Option<SocketAddr>,
+
     process_ctx: Arc<ctx::Process>,
+
+    routes: RouteCounts,
 }
 
 /// Handles the receipt of events.
@@ -46,6 +54,9 @@ pub struct Control {
     /// Holds the current state of tap observations, as configured by an external source.
     taps: Option<Arc<Mutex<Taps>>>,
 
+    /// Sends spans to be reported to the trace collector.
+    spans: Option<Sender<Span>>,
+
     /// Limits the amount of time metrics may be buffered before being flushed to the
     /// controller.
     flush_interval: Duration,
@@ -53,6 +64,12 @@ pub struct Control {
     /// Ensures liveliness of telemetry by waking the stream to produce reports when
     /// needed.  This timeout is reset as reports are returned.
     flush_timeout: Timeout,
+
+    /// The total number of events that had been dropped as of the last report.
+    reported_dropped_events: usize,
+
+    /// Counts the routes cached by the proxy's routers.
+    routes: RouteCounts,
 }
 
 // ===== impl MakeControl =====
@@ -64,38 +81,63 @@ impl MakeControl {
     /// - `rx`: the `Receiver` side of the channel on which events are sent.
     /// - `flush_interval`: the maximum amount of time between sending reports to the
     ///   controller.
+    /// - `trace_collector`: the address to which spans are sent, if any.
     pub(super) fn new(
         rx: Receiver<Event>,
         flush_interval: Duration,
+        trace_collector: Option<SocketAddr>,
         process_ctx: &Arc<ctx::Process>,
     ) -> Self {
         Self {
             rx,
             flush_interval,
+            trace_collector,
             process_ctx: Arc::clone(process_ctx),
+            routes: RouteCounts::default(),
         }
     }
 
+    /// Returns the counts of routes that are included in reports.
+    ///
+    /// The proxy's routers must be built with these counts.
+    pub fn route_counts(&self) -> RouteCounts {
+        self.routes.clone()
+    }
+
     /// Bind a `Control` with a reactor core.
     ///
     /// # Arguments
-    /// - `handle`: a `Handle` on an event loop that will track the timeout.
+    /// - `handle`: a `Handle` on an event loop that will track the timeout, and on
+    ///   which spans are reported.
     /// - `taps`: shares a `Taps` instance.
     ///
     /// # Returns
-    /// - `Ok(())` if the timeout was successfully created.
-    /// - `Err(io::Error)` if the timeout could not be created.
+    /// - `Ok(())` if the timeouts were successfully created.
+    /// - `Err(io::Error)` if the timeouts could not be created.
     pub fn make_control(self, taps: &Arc<Mutex<Taps>>, handle: &Handle) -> io::Result<Control> {
         trace!("telemetry control flush_interval={:?}", self.flush_interval);
 
         let flush_timeout = Timeout::new(self.flush_interval, handle)?;
 
+        let spans = match self.trace_collector {
+            Some(collector) => {
+                info!("sending spans to {}", collector);
+                let (tx, rx) = futures_mpsc_lossy::channel(zipkin::SPAN_BUFFER_CAPACITY);
+                handle.spawn(zipkin::Reporter::new(rx, collector, handle)?);
+                Some(tx)
+            }
+            None => None,
+        };
+
         Ok(Control {
             metrics: Some(Metrics::new(self.process_ctx)),
             rx: Some(self.rx),
             taps: Some(taps.clone()),
+            spans,
             flush_interval: self.flush_interval,
             flush_timeout,
+            reported_dropped_events: 0,
+            routes: self.routes,
         })
     }
 }
@@ -119,22 +161,50 @@ impl Control {
     /// - we have aggregated `flush_bytes` bytes of data,
     /// - we haven't sent a report in `flush_interval` seconds.
     fn flush_report(&mut self) -> Option<ReportRequest> {
-        let metrics = if self.flush_timeout_expired() {
-            trace!("flush timeout expired");
-            self.metrics.as_mut()
-        } else {
-            None
-        };
+        if !self.flush_timeout_expired() || self.metrics.is_none() {
+            return None;
+        }
+        trace!("flush timeout expired");
 
-        metrics.map(Self::generate_report)
+        let dropped_events = self.take_dropped_events();
+        let routes = &self.routes;
+        self.metrics
+            .as_mut()
+            .map(|m| Self::generate_report(m, dropped_events, routes))
     }
 
-    fn generate_report(m: &mut Metrics) -> ReportRequest {
+    fn generate_report(m: &mut Metrics, dropped_events: u32, routes: &RouteCounts)
+        -> ReportRequest
+    {
         let mut r = m.generate_report();
         r.proxy = 0; // 0 = Inbound, 1 = Outbound
+        r.dropped_events = dropped_events;
+        r.inbound_routes = routes.inbound.get() as u32;
+        r.outbound_routes = routes.outbound.get() as u32;
         r
     }
 
+    /// Returns the number of events that have been dropped since the last report.
+    fn take_dropped_events(&mut self) -> u32 {
+        let dropped = match self.rx {
+            Some(ref rx) => rx.rejected(),
+            None => return 0,
+        };
+
+        let since_last_report = dropped.wrapping_sub(self.reported_dropped_events);
+        self.reported_dropped_events = dropped;
+
+        if since_last_report > 0 {
+            warn!(
+                "dropped {} telemetry events; event buffer high-water mark is {}",
+                since_last_report,
+                self.rx.as_ref().map(Receiver::high_water_mark).unwrap_or(0)
+            );
+        }
+
+        since_last_report as u32
+    }
+
     /// Reset the flush timeout.
     fn reset_timeout(&mut self) {
         trace!("flushing in {:?}", self.flush_interval);
@@ -175,6 +245,14 @@ impl Stream for Control {
                         }
                     }
 
+                    if let Some(spans) = self.spans.as_ref() {
+                        if let Some(span) = Span::from_event(&ev) {
+                            if spans.lossy_send(span).is_err() {
+                                debug!("dropped span");
+                            }
+                        }
+                    }
+
                     // XXX Only inbound events are currently aggregated.
                     if ev.proxy().is_inbound() {
                         if let Some(metrics) = self.metrics.as_mut() {
@@ -186,9 +264,10 @@ impl Stream for Control {
                 }
                 Async::Ready(None) => {
                     warn!("events finished");
+                    let routes = &self.routes;
                     let report = self.metrics
                         .take()
-                        .map(|mut m| Self::generate_report(&mut m));
+                        .map(|mut m| Self::generate_report(&mut m, 0, routes));
                     if report.is_none() {
                         return Ok(Async::Ready(None));
                     }
@@ -228,11 +307,14 @@ impl fmt::Debug for Control {
             .field("metrics", &self.metrics)
             .field("rx", &self.rx)
             .field("taps", &self.taps)
+            .field("spans", &self.spans)
             .field("flush_interval", &self.flush_interval)
             .field(
                 "flush_timeout",
                 &format!("Timeout({:?})", &self.flush_interval),
             )
+            .field("reported_dropped_events", &self.reported_dropped_events)
+            .field("routes", &self.routes)
             .finish()
     }
 }
@@ -26,9 +26,11 @@ pub struct TransportClose {
 
     pub duration: Duration,
 
-    // TODO
-    //pub rx_bytes: usize,
-    //pub tx_bytes: usize,
+    /// The number of bytes read from the transport.
+    pub rx_bytes: u64,
+
+    /// The number of bytes written to the transport.
+    pub tx_bytes: u64,
 }
 
 #[derive(Clone, Debug)]
@@ -81,6 +83,21 @@ impl Event {
         }
     }
 
+    /// Ranks events by how much dropping them would skew reported metrics.
+    ///
+    /// Response opens only carry latencies, whereas opens are counted and ends carry the
+    /// outcome and totals of a stream or transport.
+    pub fn class(&self) -> u8 {
+        match *self {
+            Event::StreamResponseOpen(_, _) => 0,
+            Event::TransportOpen(_) | Event::StreamRequestOpen(_) => 1,
+            Event::TransportClose(_, _) |
+            Event::StreamRequestFail(_, _) |
+            Event::StreamResponseFail(_, _) |
+            Event::StreamResponseEnd(_, _) => 2,
+        }
+    }
+
     pub fn proxy(&self) -> &Arc<ctx::Proxy> {
         match *self {
             Event::TransportOpen(ref ctx) | Event::TransportClose(ref ctx, _) => ctx.proxy(),
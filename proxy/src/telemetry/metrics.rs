@@ -7,6 +7,7 @@ use http;
 use ordermap::OrderMap;
 
 use control::pb::common::{HttpMethod, TcpAddress, Protocol};
+use control::pb::pb_labels;
 use control::pb::proxy::telemetry::{
     eos_ctx,
     ClientTransport,
@@ -23,12 +24,13 @@ use control::pb::proxy::telemetry::{
     TransportSummary,
 };
 use ctx;
+use ctx::transport::Labels;
 use telemetry::event::Event;
 
 #[derive(Debug)]
 pub struct Metrics {
     sources: OrderMap<net::IpAddr, TransportStats>,
-    destinations: OrderMap<net::SocketAddr, TransportStats>,
+    destinations: OrderMap<(net::SocketAddr, Arc<Labels>), TransportStats>,
     requests: OrderMap<RequestKey, RequestStats>,
     process_ctx: Arc<ctx::Process>,
 }
@@ -37,6 +39,8 @@ pub struct Metrics {
 struct RequestKey {
     source: net::IpAddr,
     destination: net::SocketAddr,
+    destination_labels: Arc<Labels>,
+    zone_spillover: bool,
     uri: http::Uri,
     method: http::Method,
 }
@@ -91,6 +95,8 @@ impl RequestKey {
         Self {
             source: ctx.server.remote.ip(),
             destination: ctx.client.remote,
+            destination_labels: Arc::clone(&ctx.client.labels),
+            zone_spillover: ctx.zone_spillover,
             uri: ctx.uri.clone(),
             method: ctx.method.clone(),
         }
@@ -117,7 +123,7 @@ impl Metrics {
                     .disconnects
                     .push(TransportSummary {
                         duration_ms: dur_to_ms(close.duration),
-                        bytes_sent: 0,
+                        bytes_sent: close.tx_bytes,
                     });
             }
 
@@ -206,7 +212,7 @@ impl Metrics {
                     })
             }
             ctx::transport::Ctx::Client(ref c) => self.destinations
-                .entry(c.remote)
+                .entry((c.remote, Arc::clone(&c.labels)))
                 .or_insert_with(|| TransportStats {
                     protocol: c.protocol,
                     ..TransportStats::default()
@@ -227,7 +233,7 @@ impl Metrics {
             })
         }
 
-        for (addr, stats) in self.destinations.drain(..) {
+        for ((addr, labels), stats) in self.destinations.drain(..) {
             client_transports.push(ClientTransport {
                 target_addr: Some(TcpAddress {
                     ip: Some(addr.ip().into()),
@@ -236,6 +242,7 @@ impl Metrics {
                 connects: stats.connects,
                 disconnects: stats.disconnects,
                 protocol: stats.protocol as i32,
+                target_labels: pb_labels(&labels),
             });
         }
 
@@ -295,6 +302,8 @@ impl Metrics {
                         ip: Some(req.destination.ip().into()),
                         port: u32::from(req.destination.port()),
                     }),
+                    target_labels: pb_labels(&req.destination_labels),
+                    zone_spillover: req.zone_spillover,
                 }),
                 count: stats.count,
                 responses,
@@ -308,6 +317,10 @@ impl Metrics {
             server_transports,
             client_transports,
             requests,
+            dropped_events: 0,
+            stale_destinations: Vec::new(),
+            inbound_routes: 0,
+            outbound_routes: 0,
         }
     }
 }
@@ -1,22 +1,32 @@
 //! Sensors and reports telemetry from the proxy.
 
+use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
 
-use futures_mpsc_lossy;
-
 use ctx;
+use ctx::trace::Sampler;
+use tower_router::RouteCount;
 
 mod control;
 mod event;
 mod metrics;
 pub mod sensor;
 pub mod tap;
+mod zipkin;
 
 pub use self::control::{Control, MakeControl};
 pub use self::event::Event;
 pub use self::sensor::Sensors;
 
+/// Counts the routes cached by the inbound and outbound routers of all
+/// workers.
+#[derive(Clone, Debug, Default)]
+pub struct RouteCounts {
+    pub inbound: RouteCount,
+    pub outbound: RouteCount,
+}
+
 /// Creates proxy-specific runtime telemetry.
 ///
 /// [`Sensors`] hide the details of how telemetry is recorded, but expose proxy utilties
@@ -29,6 +39,10 @@ pub use self::sensor::Sensors;
 /// - `capacity`: the number of events to aggregate.
 /// - `flush_interval`: the length of time after which a metrics report should be sent,
 ///   regardless of how many events have been aggregated.
+/// - `trace_collector`: if set, requests are traced, and spans are sent to the
+///   Zipkin-compatible collector at this address.
+/// - `trace_sample_rate`: the fraction of requests without trace context that
+///   start a sampled trace.
 ///
 /// [`Sensors`]: struct.Sensors.html
 /// [`Control`]: struct.Control.html
@@ -36,9 +50,12 @@ pub fn new(
     process: &Arc<ctx::Process>,
     capacity: usize,
     flush_interval: Duration,
+    trace_collector: Option<SocketAddr>,
+    trace_sample_rate: f64,
 ) -> (Sensors, MakeControl) {
-    let (tx, rx) = futures_mpsc_lossy::channel(capacity);
-    let s = Sensors::new(tx);
-    let c = MakeControl::new(rx, flush_interval, process);
+    let (tx, rx) = sensor::channel(capacity);
+    let sampler = trace_collector.map(|_| Sampler::new(trace_sample_rate));
+    let s = Sensors::new(tx, sampler);
+    let c = MakeControl::new(rx, flush_interval, trace_collector, process);
     (s, c)
 }
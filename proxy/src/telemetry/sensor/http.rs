@@ -10,6 +10,7 @@ use tower::{NewService, Service};
 use tower_h2::{client, Body};
 
 use ctx;
+use ctx::trace::{Sampler, TraceContext};
 use telemetry::event::{self, Event};
 
 const GRPC_STATUS: &str = "grpc-status";
@@ -19,6 +20,7 @@ pub struct NewHttp<N, A, B> {
     new_service: N,
     handle: super::Handle,
     client_ctx: Arc<ctx::transport::Client>,
+    tracing: Option<Sampler>,
     _p: PhantomData<(A, B)>,
 }
 
@@ -27,6 +29,7 @@ pub struct Init<F, A, B> {
     future: F,
     handle: super::Handle,
     client_ctx: Arc<ctx::transport::Client>,
+    tracing: Option<Sampler>,
     _p: PhantomData<(A, B)>,
 }
 
@@ -37,6 +40,7 @@ pub struct Http<S, A, B> {
     service: S,
     handle: super::Handle,
     client_ctx: Arc<ctx::transport::Client>,
+    tracing: Option<Sampler>,
     _p: PhantomData<(A, B)>,
 }
 
@@ -85,12 +89,14 @@ where
         new_service: N,
         handle: &super::Handle,
         client_ctx: &Arc<ctx::transport::Client>,
+        tracing: Option<Sampler>,
     ) -> Self {
         Self {
             next_id,
             new_service,
             handle: handle.clone(),
             client_ctx: Arc::clone(client_ctx),
+            tracing,
             _p: PhantomData,
         }
     }
@@ -116,6 +122,7 @@ where
             future: self.new_service.new_service(),
             handle: self.handle.clone(),
             client_ctx: Arc::clone(&self.client_ctx),
+            tracing: self.tracing,
             _p: PhantomData,
         }
     }
@@ -141,6 +148,7 @@ where
             handle: self.handle.clone(),
             next_id: self.next_id.clone(),
             client_ctx: self.client_ctx.clone(),
+            tracing: self.tracing,
             _p: PhantomData,
         }))
     }
@@ -169,7 +177,18 @@ where
             None => None,
             Some(ctx) => {
                 let id = self.next_id.fetch_add(1, Ordering::SeqCst);
-                let ctx = ctx::http::Request::new(&req, &ctx, &self.client_ctx, id);
+
+                // The proxy's span is the parent of the receiver's spans.
+                let trace = match self.tracing {
+                    Some(sampler) => {
+                        let trace = TraceContext::for_request(req.headers(), sampler);
+                        trace.inject(req.headers_mut());
+                        Some(trace)
+                    }
+                    None => None,
+                };
+
+                let ctx = ctx::http::Request::new(&req, &ctx, &self.client_ctx, id, trace);
 
                 self.handle
                     .send(|| Event::StreamRequestOpen(Arc::clone(&ctx)));
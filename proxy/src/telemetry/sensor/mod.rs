@@ -2,7 +2,7 @@ use std::sync::Arc;
 use std::sync::atomic::AtomicUsize;
 use std::time::Instant;
 
-use futures_mpsc_lossy::Sender;
+use futures_mpsc_lossy::{self, DropPolicy, Receiver, Sender};
 use http::{Request, Response};
 use tokio_connect;
 use tokio_io::{AsyncRead, AsyncWrite};
@@ -10,6 +10,7 @@ use tower::NewService;
 use tower_h2::{client, Body};
 
 use ctx;
+use ctx::trace::Sampler;
 use telemetry::event;
 
 pub mod http;
@@ -24,7 +25,23 @@ struct Handle(Option<Sender<event::Event>>);
 
 /// Supports the creation of telemetry scopes.
 #[derive(Clone, Debug)]
-pub struct Sensors(Handle);
+pub struct Sensors {
+    handle: Handle,
+
+    /// Samples the traces of HTTP requests, if they're traced.
+    tracing: Option<Sampler>,
+}
+
+/// Creates the channel on which sensors send events.
+///
+/// When the channel is full, events of the lowest `Event::class` are dropped first, so
+/// that the events that end streams and transports are kept in favor of those that open
+/// them.
+pub(super) fn channel(capacity: usize) -> (Sender<event::Event>, Receiver<event::Event>) {
+    futures_mpsc_lossy::Builder::new(capacity)
+        .drop_policy(DropPolicy::DropByClass(event::Event::class))
+        .build()
+}
 
 impl Handle {
     fn send<F>(&mut self, mk: F)
@@ -46,12 +63,18 @@ impl Handle {
 }
 
 impl Sensors {
-    pub(super) fn new(h: Sender<event::Event>) -> Self {
-        Sensors(Handle(Some(h)))
+    pub(super) fn new(h: Sender<event::Event>, tracing: Option<Sampler>) -> Self {
+        Sensors {
+            handle: Handle(Some(h)),
+            tracing,
+        }
     }
 
     pub fn null() -> Sensors {
-        Sensors(Handle(None))
+        Sensors {
+            handle: Handle(None),
+            tracing: None,
+        }
     }
 
     pub fn accept<T>(
@@ -65,14 +88,14 @@ impl Sensors {
     {
         debug!("server connection open");
         let ctx = Arc::new(ctx::transport::Ctx::Server(Arc::clone(ctx)));
-        Transport::open(io, opened_at, &self.0, ctx)
+        Transport::open(io, opened_at, &self.handle, ctx)
     }
 
     pub fn connect<C>(&self, connect: C, ctx: &Arc<ctx::transport::Client>) -> Connect<C>
     where
         C: tokio_connect::Connect,
     {
-        Connect::new(connect, &self.0, ctx)
+        Connect::new(connect, &self.handle, ctx)
     }
 
     pub fn http<N, A, B>(
@@ -87,6 +110,6 @@ impl Sensors {
         N: NewService<Request = Request<A>, Response = Response<B>, Error = client::Error>
             + 'static,
     {
-        NewHttp::new(next_id, new_service, &self.0, client_ctx)
+        NewHttp::new(next_id, new_service, &self.handle, client_ctx, self.tracing)
     }
 }
@@ -3,10 +3,12 @@ use std::io;
 use std::sync::Arc;
 use std::time::Instant;
 use tokio_connect;
+use tokio_core::net::TcpStream;
 use tokio_io::{AsyncRead, AsyncWrite};
 
 use ctx;
 use telemetry::event;
+use transport::Splice;
 
 /// Wraps a transport with telemetry.
 #[derive(Debug)]
@@ -18,9 +20,8 @@ struct Inner {
     ctx: Arc<ctx::transport::Ctx>,
     opened_at: Instant,
 
-    // TODO
-    //rx_bytes: usize,
-    //tx_bytes: usize,
+    rx_bytes: u64,
+    tx_bytes: u64,
 }
 
 /// Builds client transports with telemetry.
@@ -59,10 +60,24 @@ impl<T: AsyncRead + AsyncWrite> Transport<T> {
                 ctx,
                 handle,
                 opened_at,
+                rx_bytes: 0,
+                tx_bytes: 0,
             }),
         )
     }
 
+    fn record_read(&mut self, n: usize) {
+        if let Some(ref mut inner) = self.1 {
+            inner.rx_bytes += n as u64;
+        }
+    }
+
+    fn record_write(&mut self, n: usize) {
+        if let Some(ref mut inner) = self.1 {
+            inner.tx_bytes += n as u64;
+        }
+    }
+
     /// Wraps an operation on the underlying transport with error telemetry.
     ///
     /// If the transport operation results in a non-recoverable error, a transport close
@@ -79,6 +94,8 @@ impl<T: AsyncRead + AsyncWrite> Transport<T> {
                         mut handle,
                         ctx,
                         opened_at,
+                        rx_bytes,
+                        tx_bytes,
                     }) = self.1.take()
                     {
                         handle.send(move || {
@@ -86,6 +103,8 @@ impl<T: AsyncRead + AsyncWrite> Transport<T> {
                             let ev = event::TransportClose {
                                 duration,
                                 clean: false,
+                                rx_bytes,
+                                tx_bytes,
                             };
                             event::Event::TransportClose(ctx, ev)
                         });
@@ -104,6 +123,8 @@ impl<T> Drop for Transport<T> {
             mut handle,
             ctx,
             opened_at,
+            rx_bytes,
+            tx_bytes,
         }) = self.1.take()
         {
             handle.send(move || {
@@ -111,6 +132,8 @@ impl<T> Drop for Transport<T> {
                 let ev = event::TransportClose {
                     clean: true,
                     duration,
+                    rx_bytes,
+                    tx_bytes,
                 };
                 event::Event::TransportClose(ctx, ev)
             });
@@ -120,7 +143,9 @@ impl<T> Drop for Transport<T> {
 
 impl<T: AsyncRead + AsyncWrite> io::Read for Transport<T> {
     fn read(&mut self, mut buf: &mut [u8]) -> io::Result<usize> {
-        self.sense_err(move |io| io.read(buf))
+        let n = self.sense_err(move |io| io.read(buf))?;
+        self.record_read(n);
+        Ok(n)
     }
 }
 
@@ -130,7 +155,9 @@ impl<T: AsyncRead + AsyncWrite> io::Write for Transport<T> {
     }
 
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.sense_err(move |io| io.write(buf))
+        let n = self.sense_err(move |io| io.write(buf))?;
+        self.record_write(n);
+        Ok(n)
     }
 }
 
@@ -146,6 +173,23 @@ impl<T: AsyncRead + AsyncWrite> AsyncWrite for Transport<T> {
     }
 }
 
+/// Bytes that are spliced bypass `read` and `write`, so they're counted here instead.
+impl<T: Splice> Splice for Transport<T> {
+    fn tcp_stream(&self) -> Option<&TcpStream> {
+        self.0.tcp_stream()
+    }
+
+    fn record_spliced_read(&mut self, n: usize) {
+        self.0.record_spliced_read(n);
+        self.record_read(n);
+    }
+
+    fn record_spliced_write(&mut self, n: usize) {
+        self.0.record_spliced_write(n);
+        self.record_write(n);
+    }
+}
+
 // === impl Connect ===
 
 impl<C: tokio_connect::Connect> Connect<C> {
@@ -0,0 +1,376 @@
+//! Reports spans to a Zipkin-compatible collector.
+//!
+//! A span is recorded for each traced request that ends. Spans are batched and
+//! sent to the collector as Zipkin v2 JSON, over HTTP/1.1, from the telemetry
+//! thread rather than from the proxies.
+
+use std::fmt::{self, Write};
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use futures::{Async, Future, Poll, Stream};
+use futures_mpsc_lossy::Receiver;
+use h2;
+use http;
+use tokio_core::net::TcpStream;
+use tokio_core::reactor::{Handle, Timeout as ReactorTimeout};
+use tokio_io;
+
+use ctx;
+use ctx::trace::TraceContext;
+use telemetry::event::Event;
+
+/// The number of spans that may be buffered for the reporter.
+pub const SPAN_BUFFER_CAPACITY: usize = 10_000;
+
+/// The most spans sent in one request to the collector.
+const MAX_BATCH_SPANS: usize = 100;
+
+/// How long spans may be buffered before they're sent.
+const FLUSH_INTERVAL_SECS: u64 = 1;
+
+/// How long the collector has to accept a batch of spans.
+const POST_TIMEOUT_SECS: u64 = 10;
+
+const SPANS_PATH: &str = "/api/v2/spans";
+
+const SERVICE_NAME: &str = "conduit-proxy";
+
+/// Describes a request that the proxy traced.
+#[derive(Debug)]
+pub struct Span {
+    request: Arc<ctx::http::Request>,
+    trace: TraceContext,
+    start: SystemTime,
+    duration: Duration,
+    status: Option<http::StatusCode>,
+    grpc_status: Option<u32>,
+    error: Option<h2::Reason>,
+}
+
+/// Sends spans to the collector in batches.
+pub struct Reporter {
+    rx: Option<Receiver<Span>>,
+    collector: SocketAddr,
+    handle: Handle,
+
+    /// Spans that haven't been sent yet.
+    pending: Vec<Span>,
+
+    /// Fires when pending spans should be sent, even if there are fewer than
+    /// a full batch.
+    flush_timeout: ReactorTimeout,
+
+    /// Sends a batch to the collector. Only one batch is sent at a time.
+    post: Option<Box<Future<Item = (), Error = io::Error>>>,
+}
+
+// ===== impl Span =====
+
+impl Span {
+    /// Returns the span of the request that `ev` ends, if the request was
+    /// sampled.
+    ///
+    /// The span started when the request was opened, and lasted as long as
+    /// the sensor that emitted `ev` measured.
+    pub fn from_event(ev: &Event) -> Option<Self> {
+        let (request, since_request_open, status, grpc_status, error) = match *ev {
+            Event::StreamRequestFail(ref req, ref fail) => {
+                (req, fail.since_request_open, None, None, Some(fail.error))
+            }
+            Event::StreamResponseEnd(ref rsp, ref end) => (
+                &rsp.request,
+                end.since_request_open,
+                Some(rsp.status),
+                end.grpc_status,
+                None,
+            ),
+            Event::StreamResponseFail(ref rsp, ref fail) => (
+                &rsp.request,
+                fail.since_request_open,
+                Some(rsp.status),
+                None,
+                Some(fail.error),
+            ),
+            _ => return None,
+        };
+
+        let trace = match request.trace {
+            Some(trace) if trace.sampled => trace,
+            _ => return None,
+        };
+
+        Some(Span {
+            request: Arc::clone(request),
+            trace,
+            start: request.start,
+            duration: since_request_open,
+            status,
+            grpc_status,
+            error,
+        })
+    }
+
+    /// Writes the span as a Zipkin v2 JSON object.
+    fn write_json(&self, out: &mut String) -> fmt::Result {
+        let req = &self.request;
+        let direction = if req.server.proxy.is_inbound() {
+            "inbound"
+        } else {
+            "outbound"
+        };
+
+        write!(out, "{{\"traceId\":\"{}\"", self.trace.trace_id)?;
+        write!(out, ",\"id\":\"{}\"", self.trace.span_id)?;
+        if let Some(parent_id) = self.trace.parent_id {
+            write!(out, ",\"parentId\":\"{}\"", parent_id)?;
+        }
+        write!(out, ",\"name\":")?;
+        write_json_str(out, &req.method.as_str().to_lowercase())?;
+        write!(
+            out,
+            ",\"timestamp\":{},\"duration\":{}",
+            micros(self.start.duration_since(UNIX_EPOCH).unwrap_or_default()),
+            // Zipkin drops durations of zero.
+            micros(self.duration).max(1)
+        )?;
+
+        write!(out, ",\"localEndpoint\":")?;
+        write_endpoint(out, Some(SERVICE_NAME), &req.server.local)?;
+        write!(out, ",\"remoteEndpoint\":")?;
+        write_endpoint(out, None, &req.client.remote)?;
+
+        write!(out, ",\"tags\":{{\"conduit.direction\":\"{}\"", direction)?;
+        write!(out, ",\"conduit.source\":\"{}\"", req.server.remote)?;
+        write!(out, ",\"conduit.destination\":\"{}\"", req.client.remote)?;
+        write!(out, ",\"http.method\":")?;
+        write_json_str(out, req.method.as_str())?;
+        write!(out, ",\"http.path\":")?;
+        write_json_str(out, req.uri.path())?;
+        if let Some(status) = self.status {
+            write!(out, ",\"http.status_code\":\"{}\"", status.as_u16())?;
+        }
+        if let Some(grpc_status) = self.grpc_status {
+            write!(out, ",\"grpc.status_code\":\"{}\"", grpc_status)?;
+        }
+        if let Some(error) = self.error {
+            write!(out, ",\"error\":")?;
+            write_json_str(out, &format!("{:?}", error))?;
+        }
+        write!(out, "}}}}")
+    }
+}
+
+fn write_endpoint(out: &mut String, service_name: Option<&str>, addr: &SocketAddr) -> fmt::Result {
+    write!(out, "{{")?;
+    if let Some(name) = service_name {
+        write!(out, "\"serviceName\":\"{}\",", name)?;
+    }
+    match *addr {
+        SocketAddr::V4(ref a) => write!(out, "\"ipv4\":\"{}\"", a.ip())?,
+        SocketAddr::V6(ref a) => write!(out, "\"ipv6\":\"{}\"", a.ip())?,
+    }
+    write!(out, ",\"port\":{}}}", addr.port())
+}
+
+fn write_json_str(out: &mut String, s: &str) -> fmt::Result {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    Ok(())
+}
+
+fn micros(d: Duration) -> u64 {
+    d.as_secs() * 1_000_000 + u64::from(d.subsec_nanos() / 1_000)
+}
+
+// ===== impl Reporter =====
+
+impl Reporter {
+    pub fn new(rx: Receiver<Span>, collector: SocketAddr, handle: &Handle) -> io::Result<Self> {
+        let flush_timeout =
+            ReactorTimeout::new(Duration::from_secs(FLUSH_INTERVAL_SECS), handle)?;
+        Ok(Reporter {
+            rx: Some(rx),
+            collector,
+            handle: handle.clone(),
+            pending: Vec::new(),
+            flush_timeout,
+            post: None,
+        })
+    }
+
+    /// Buffers all spans that have been received.
+    fn recv(&mut self) {
+        if let Some(mut rx) = self.rx.take() {
+            // Leave spans in the channel, to be dropped there, while too many
+            // are pending.
+            while self.pending.len() < SPAN_BUFFER_CAPACITY {
+                match rx.poll().expect("recv spans") {
+                    Async::Ready(Some(span)) => self.pending.push(span),
+                    Async::Ready(None) => {
+                        debug!("span reporter finished");
+                        return;
+                    }
+                    Async::NotReady => break,
+                }
+            }
+            self.rx = Some(rx);
+        }
+    }
+
+    /// Returns true if the flush timeout has expired, and resets it.
+    fn flush_timeout_expired(&mut self) -> bool {
+        let mut expired = false;
+        while self.flush_timeout.poll().expect("timer").is_ready() {
+            self.flush_timeout
+                .reset(Instant::now() + Duration::from_secs(FLUSH_INTERVAL_SECS));
+            expired = true;
+        }
+        expired
+    }
+
+    /// Starts sending the oldest pending spans to the collector.
+    fn send_batch(&mut self) {
+        let n = self.pending.len().min(MAX_BATCH_SPANS);
+        let mut body = String::from("[");
+        for (i, span) in self.pending.drain(..n).enumerate() {
+            if i > 0 {
+                body.push(',');
+            }
+            span.write_json(&mut body).expect("write to string");
+        }
+        body.push(']');
+
+        trace!("sending {} spans to {}", n, self.collector);
+        self.post = Some(post(self.collector, body, &self.handle));
+    }
+}
+
+impl Future for Reporter {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        loop {
+            self.recv();
+
+            if let Some(mut post) = self.post.take() {
+                match post.poll() {
+                    Ok(Async::NotReady) => {
+                        self.post = Some(post);
+                        return Ok(Async::NotReady);
+                    }
+                    Ok(Async::Ready(())) => trace!("sent spans to {}", self.collector),
+                    Err(e) => warn!("failed to send spans to {}: {}", self.collector, e),
+                }
+            }
+
+            let expired = self.flush_timeout_expired();
+            if self.pending.len() >= MAX_BATCH_SPANS || (expired && !self.pending.is_empty()) {
+                self.send_batch();
+                continue;
+            }
+
+            if self.rx.is_none() && self.pending.is_empty() {
+                return Ok(Async::Ready(()));
+            }
+
+            return Ok(Async::NotReady);
+        }
+    }
+}
+
+// NOTE: `flush_timeout` does not impl `Debug`.
+impl fmt::Debug for Reporter {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Reporter")
+            .field("rx", &self.rx)
+            .field("collector", &self.collector)
+            .field("pending", &self.pending.len())
+            .field("post", &self.post.is_some())
+            .finish()
+    }
+}
+
+/// POSTs `body` to the collector's spans endpoint.
+fn post(
+    collector: SocketAddr,
+    body: String,
+    handle: &Handle,
+) -> Box<Future<Item = (), Error = io::Error>> {
+    let request = format!(
+        "POST {} HTTP/1.1\r\n\
+         Host: {}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\
+         \r\n\
+         {}",
+        SPANS_PATH,
+        collector,
+        body.len(),
+        body
+    );
+
+    let post = TcpStream::connect(&collector, handle)
+        .and_then(move |sock| tokio_io::io::write_all(sock, request.into_bytes()))
+        .and_then(|(sock, _)| tokio_io::io::read_to_end(sock, Vec::new()))
+        .and_then(|(_, rsp)| {
+            if is_success(&rsp) {
+                Ok(())
+            } else {
+                let status = rsp.split(|b| *b == b'\r').next().unwrap_or(&[]);
+                let msg = format!("collector responded {:?}", String::from_utf8_lossy(status));
+                Err(io::Error::new(io::ErrorKind::Other, msg))
+            }
+        });
+
+    let timeout = match ReactorTimeout::new(Duration::from_secs(POST_TIMEOUT_SECS), handle) {
+        Ok(timeout) => timeout,
+        Err(e) => return Box::new(::futures::future::err(e)),
+    };
+    let timeout = timeout.and_then(|_| {
+        Err(io::Error::new(io::ErrorKind::TimedOut, "collector timed out"))
+    });
+
+    Box::new(post.select(timeout).map(|(v, _)| v).map_err(|(e, _)| e))
+}
+
+/// Returns true if `rsp` has a 2xx status line.
+fn is_success(rsp: &[u8]) -> bool {
+    let mut status_line = rsp.splitn(3, |b| *b == b' ');
+    match (status_line.next(), status_line.next()) {
+        (Some(version), Some(status)) => {
+            version.starts_with(b"HTTP/1.") && status.len() == 3 && status[0] == b'2'
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_is_success() {
+        assert!(super::is_success(b"HTTP/1.1 202 Accepted\r\n\r\n"));
+        assert!(super::is_success(b"HTTP/1.0 200 OK\r\n\r\n"));
+        assert!(!super::is_success(b"HTTP/1.1 500 Internal Server Error\r\n\r\n"));
+        assert!(!super::is_success(b""));
+    }
+
+    #[test]
+    fn test_write_json_str() {
+        let mut out = String::new();
+        super::write_json_str(&mut out, "/a\"b\\c\n").unwrap();
+        assert_eq!(out, r#""/a\"b\\c\u000a""#);
+    }
+}
@@ -1,4 +1,5 @@
-use futures::Future;
+use futures::{future, Future};
+use futures::future::{Either, Loop};
 use tokio_connect;
 use tokio_core::reactor::Handle;
 use url;
@@ -69,6 +70,8 @@ impl tokio_connect::Connect for LookupAddressAndConnect {
     type Error = io::Error;
     type Future = Box<Future<Item = connection::Connection, Error = io::Error>>;
 
+    /// Connects to each address the host resolves to in turn, until a
+    /// connection is established.
     fn connect(&self) -> Self::Future {
         let port = self.host_and_port.port;
         let handle = self.handle.clone();
@@ -78,12 +81,45 @@ impl tokio_connect::Connect for LookupAddressAndConnect {
             .map_err(|_| {
                 io::Error::new(io::ErrorKind::NotFound, "DNS resolution failed")
             })
-            .and_then(move |ip_addr: IpAddr| {
-                info!("DNS resolved {} to {}", host, ip_addr);
-                let addr = SocketAddr::from((ip_addr, port));
-                trace!("connect {}", addr);
-                connection::connect(&addr, &handle)
+            .and_then(move |ips: dns::IpList| {
+                info!("DNS resolved {} to {} addresses", host, ips.len());
+                let addrs = ips.into_iter()
+                    .map(|ip: IpAddr| SocketAddr::from((ip, port)))
+                    .collect::<Vec<_>>()
+                    .into_iter();
+                connect_any(addrs, handle)
             });
         Box::new(c)
     }
 }
+
+/// Connects to each of `addrs` in order, returning the first connection
+/// that's established, or the last error.
+fn connect_any<I>(addrs: I, handle: Handle)
+    -> Box<Future<Item = connection::Connection, Error = io::Error>>
+where
+    I: Iterator<Item = SocketAddr> + 'static,
+{
+    let c = future::loop_fn((addrs, None), move |(mut addrs, last_err)| {
+        let addr = match addrs.next() {
+            Some(addr) => addr,
+            None => {
+                let err = last_err.unwrap_or_else(|| {
+                    io::Error::new(io::ErrorKind::NotFound, "no addresses to connect to")
+                });
+                return Either::A(future::err(err));
+            }
+        };
+
+        trace!("connect {}", addr);
+        let connect = connection::connect(&addr, &handle).then(move |result| match result {
+            Ok(conn) => Ok(Loop::Break(conn)),
+            Err(e) => {
+                debug!("connect {} failed: {}", addr, e);
+                Ok(Loop::Continue((addrs, Some(e))))
+            }
+        });
+        Either::B(connect)
+    });
+    Box::new(c)
+}
@@ -1,5 +1,9 @@
 mod connect;
 mod so_original_dst;
+mod splice;
+mod transparent;
 
 pub use self::connect::{Connect, LookupAddressAndConnect, TimeoutConnect, TimeoutError};
 pub use self::so_original_dst::get_original_dst;
+pub use self::splice::{duplex, Duplex, Splice};
+pub use self::transparent::set_transparent;
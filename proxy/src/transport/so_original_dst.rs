@@ -15,8 +15,23 @@ pub fn get_original_dst(sock: &TcpStream) -> Option<SocketAddr> {
 
     debug!("get_original_dst {:?}", sock);
 
-    let res = unsafe { linux::so_original_dst(sock.as_raw_fd()) };
-    res.ok()
+    let fd = sock.as_raw_fd();
+    let is_ipv6 = sock.local_addr().map(|a| a.is_ipv6()).unwrap_or(false);
+
+    let res = linux::read_original_dst(is_ipv6, |family| unsafe {
+        match family {
+            linux::Family::V4 => linux::so_original_dst(fd),
+            linux::Family::V6 => linux::ip6t_so_original_dst(fd),
+        }
+    });
+
+    match res {
+        Ok(addr) => Some(addr),
+        Err(e) => {
+            error!("failed to read SO_ORIGINAL_DST: {:?}", e);
+            None
+        }
+    }
 }
 
 #[cfg(target_os = "linux")]
@@ -26,21 +41,58 @@ mod linux {
     use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
     use std::os::unix::io::RawFd;
 
+    /// From `linux/netfilter_ipv6/ip6_tables.h`; not exported by `libc`.
+    const IP6T_SO_ORIGINAL_DST: libc::c_int = 80;
+
+    /// Which socket option an original destination is read from.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum Family {
+        /// `SO_ORIGINAL_DST`.
+        V4,
+        /// `IP6T_SO_ORIGINAL_DST`.
+        V6,
+    }
+
+    /// Reads the original destination of a socket with `read`.
+    ///
+    /// A dual-stack listener accepts IPv4 connections on an IPv6 socket, so
+    /// the IPv4 option is read if the IPv6 one fails.
+    pub fn read_original_dst<F>(is_ipv6: bool, read: F) -> io::Result<SocketAddr>
+    where
+        F: Fn(Family) -> io::Result<SocketAddr>,
+    {
+        if is_ipv6 {
+            read(Family::V6).or_else(|_| read(Family::V4))
+        } else {
+            read(Family::V4)
+        }
+    }
+
     pub unsafe fn so_original_dst(fd: RawFd) -> io::Result<SocketAddr> {
+        original_dst(fd, libc::SOL_IP, libc::SO_ORIGINAL_DST)
+    }
+
+    pub unsafe fn ip6t_so_original_dst(fd: RawFd) -> io::Result<SocketAddr> {
+        original_dst(fd, libc::SOL_IPV6, IP6T_SO_ORIGINAL_DST)
+    }
+
+    unsafe fn original_dst(
+        fd: RawFd,
+        level: libc::c_int,
+        optname: libc::c_int,
+    ) -> io::Result<SocketAddr> {
         let mut sockaddr: libc::sockaddr_storage = mem::zeroed();
         let mut socklen: libc::socklen_t = mem::size_of::<libc::sockaddr_storage>() as u32;
 
         let ret = libc::getsockopt(
             fd,
-            libc::SOL_IP,
-            libc::SO_ORIGINAL_DST,
+            level,
+            optname,
             &mut sockaddr as *mut _ as *mut _,
             &mut socklen as *mut _ as *mut _,
         );
         if ret != 0 {
-            let e = io::Error::last_os_error();
-            error!("failed to read SO_ORIGINAL_DST: {:?}", e);
-            return Err(e);
+            return Err(io::Error::last_os_error());
         }
 
         mk_addr(&sockaddr, socklen)
@@ -109,4 +161,100 @@ mod linux {
     fn ntoh32(i: u32) -> u32 {
         <u32>::from_be(i)
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::cell::RefCell;
+
+        fn addr(s: &str) -> SocketAddr {
+            s.parse().unwrap()
+        }
+
+        /// Reads with each family in turn, recording which were read.
+        fn read(is_ipv6: bool, v4: io::Result<SocketAddr>, v6: io::Result<SocketAddr>)
+            -> (io::Result<SocketAddr>, Vec<Family>)
+        {
+            let results = RefCell::new((Some(v4), Some(v6)));
+            let families = RefCell::new(Vec::new());
+            let res = read_original_dst(is_ipv6, |family| {
+                families.borrow_mut().push(family);
+                let mut results = results.borrow_mut();
+                match family {
+                    Family::V4 => results.0.take().unwrap(),
+                    Family::V6 => results.1.take().unwrap(),
+                }
+            });
+            (res, families.into_inner())
+        }
+
+        fn unavailable() -> io::Result<SocketAddr> {
+            Err(io::Error::from_raw_os_error(libc::ENOENT))
+        }
+
+        #[test]
+        fn ipv4_sockets_read_the_ipv4_option() {
+            let (res, families) = read(false, Ok(addr("10.0.0.1:80")), Ok(addr("[fd00::1]:80")));
+            assert_eq!(res.unwrap(), addr("10.0.0.1:80"));
+            assert_eq!(families, vec![Family::V4]);
+        }
+
+        #[test]
+        fn ipv6_sockets_read_the_ipv6_option() {
+            let (res, families) = read(true, Ok(addr("10.0.0.1:80")), Ok(addr("[fd00::1]:80")));
+            assert_eq!(res.unwrap(), addr("[fd00::1]:80"));
+            assert_eq!(families, vec![Family::V6]);
+        }
+
+        #[test]
+        fn ipv6_sockets_fall_back_to_the_ipv4_option() {
+            let (res, families) = read(true, Ok(addr("10.0.0.1:80")), unavailable());
+            assert_eq!(res.unwrap(), addr("10.0.0.1:80"));
+            assert_eq!(families, vec![Family::V6, Family::V4]);
+
+            let (res, _) = read(true, unavailable(), unavailable());
+            assert!(res.is_err());
+        }
+
+        #[test]
+        fn mk_addr_reads_ipv4() {
+            let mut storage: libc::sockaddr_storage = unsafe { mem::zeroed() };
+            let len = mem::size_of::<libc::sockaddr_in>() as libc::socklen_t;
+            unsafe {
+                let sa = &mut storage as *mut _ as *mut libc::sockaddr_in;
+                (*sa).sin_family = libc::AF_INET as libc::sa_family_t;
+                (*sa).sin_port = 8080u16.to_be();
+                (*sa).sin_addr.s_addr = u32::from(Ipv4Addr::new(10, 1, 2, 3)).to_be();
+            }
+
+            assert_eq!(mk_addr(&storage, len).unwrap(), addr("10.1.2.3:8080"));
+        }
+
+        #[test]
+        fn mk_addr_reads_ipv6() {
+            let ip = Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0x1234, 0x5678);
+            let mut storage: libc::sockaddr_storage = unsafe { mem::zeroed() };
+            let len = mem::size_of::<libc::sockaddr_in6>() as libc::socklen_t;
+            unsafe {
+                let sa = &mut storage as *mut _ as *mut libc::sockaddr_in6;
+                (*sa).sin6_family = libc::AF_INET6 as libc::sa_family_t;
+                (*sa).sin6_port = 8080u16.to_be();
+                (*sa).sin6_flowinfo = 7;
+                (*sa).sin6_addr.s6_addr = ip.octets();
+                (*sa).sin6_scope_id = 2;
+            }
+
+            let expected = SocketAddr::V6(SocketAddrV6::new(ip, 8080, 7, 2));
+            assert_eq!(mk_addr(&storage, len).unwrap(), expected);
+        }
+
+        #[test]
+        fn mk_addr_rejects_other_families() {
+            let mut storage: libc::sockaddr_storage = unsafe { mem::zeroed() };
+            storage.ss_family = libc::AF_UNIX as libc::sa_family_t;
+            let len = mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+
+            assert!(mk_addr(&storage, len).is_err());
+        }
+    }
 }
@@ -0,0 +1,482 @@
+//! Forwards bytes between two transports without inspecting them.
+//!
+//! On Linux, when both transports are plain TCP sockets, bytes are moved from one
+//! socket to the other with `splice(2)` through a pipe, so that they're never copied
+//! into userspace. Elsewhere, or when either transport can't be spliced, bytes are
+//! copied through a buffer.
+
+use futures::{Async, Future, Poll};
+use std::io;
+use tokio_core::net::TcpStream;
+use tokio_io::{AsyncRead, AsyncWrite};
+
+/// The size of the buffer that bytes are copied through when they can't be spliced.
+const BUFFER_SIZE: usize = 16 * 1024;
+
+/// A transport whose bytes the kernel may move directly with `splice(2)`.
+pub trait Splice: AsyncRead + AsyncWrite {
+    /// Returns the socket underlying this transport, if its bytes may be spliced.
+    fn tcp_stream(&self) -> Option<&TcpStream>;
+
+    /// Records that `n` bytes were spliced out of the socket, bypassing `read`.
+    fn record_spliced_read(&mut self, _n: usize) {}
+
+    /// Records that `n` bytes were spliced into the socket, bypassing `write`.
+    fn record_spliced_write(&mut self, _n: usize) {}
+}
+
+/// Forwards bytes in both directions between two transports, until each has been
+/// read to its end.
+///
+/// Resolves to the number of bytes forwarded from `a` to `b` and from `b` to `a`.
+pub struct Duplex<A, B> {
+    a: A,
+    b: B,
+    a_to_b: Half,
+    b_to_a: Half,
+}
+
+/// Forwards bytes in one direction.
+struct Half {
+    /// Set when bytes may be spliced from the reader to the writer.
+    pipe: Option<sys::Pipe>,
+
+    /// The number of bytes in `pipe` that have yet to be written.
+    piped: usize,
+
+    /// Holds bytes that couldn't be spliced, from `pos` to `cap`.
+    ///
+    /// Any bytes here precede those in `pipe`.
+    buf: Box<[u8]>,
+    pos: usize,
+    cap: usize,
+
+    /// The number of bytes that have been forwarded.
+    amt: u64,
+
+    read_done: bool,
+    done: bool,
+}
+
+/// Forwards bytes between `a` and `b`, splicing them when both allow it.
+pub fn duplex<A: Splice, B: Splice>(a: A, b: B) -> Duplex<A, B> {
+    let splice = a.tcp_stream().is_some() && b.tcp_stream().is_some();
+    Duplex {
+        a_to_b: Half::new(splice),
+        b_to_a: Half::new(splice),
+        a,
+        b,
+    }
+}
+
+// ===== impl Duplex =====
+
+impl<A: Splice, B: Splice> Future for Duplex<A, B> {
+    type Item = (u64, u64);
+    type Error = io::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let a_to_b = self.a_to_b.poll(&mut self.a, &mut self.b)?;
+        let b_to_a = self.b_to_a.poll(&mut self.b, &mut self.a)?;
+
+        match (a_to_b, b_to_a) {
+            (Async::Ready(a_to_b), Async::Ready(b_to_a)) => Ok(Async::Ready((a_to_b, b_to_a))),
+            _ => Ok(Async::NotReady),
+        }
+    }
+}
+
+// ===== impl Half =====
+
+impl Half {
+    fn new(splice: bool) -> Self {
+        let pipe = if splice {
+            match sys::Pipe::new() {
+                Ok(pipe) => Some(pipe),
+                Err(e) => {
+                    debug!("copying instead of splicing: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        Half {
+            pipe,
+            piped: 0,
+            buf: vec![0; BUFFER_SIZE].into_boxed_slice(),
+            pos: 0,
+            cap: 0,
+            amt: 0,
+            read_done: false,
+            done: false,
+        }
+    }
+
+    fn poll<R: Splice, W: Splice>(&mut self, reader: &mut R, writer: &mut W) -> Poll<u64, io::Error> {
+        if self.done {
+            return Ok(Async::Ready(self.amt));
+        }
+
+        loop {
+            if self.pos < self.cap {
+                let n = try_ready!(poll_io(writer.write(&self.buf[self.pos..self.cap])));
+                if n == 0 {
+                    return Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "write zero bytes into transport",
+                    ));
+                }
+                self.pos += n;
+                self.amt += n as u64;
+                continue;
+            }
+
+            if self.piped > 0 {
+                let pipe = self.pipe.as_ref().expect("bytes are only piped through a pipe");
+                let spliced = {
+                    let socket = writer.tcp_stream().expect("bytes are only piped to sockets");
+                    pipe.splice_into(socket, self.piped)
+                };
+                match spliced {
+                    Ok(n) => {
+                        writer.record_spliced_write(n);
+                        self.piped -= n;
+                        self.amt += n as u64;
+                    }
+                    Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
+                        // The socket can't be spliced into until it's writable, and only
+                        // `write` waits for that. So, the pipe's bytes are moved into the
+                        // buffer, and are written from there.
+                        let n = pipe.read(&mut self.buf)?;
+                        self.piped -= n;
+                        self.pos = 0;
+                        self.cap = n;
+                    }
+                    Err(e) => return Err(e),
+                }
+                continue;
+            }
+
+            if self.read_done {
+                try_ready!(writer.shutdown());
+                self.done = true;
+                return Ok(Async::Ready(self.amt));
+            }
+
+            if let Some(ref pipe) = self.pipe {
+                let spliced = {
+                    let socket = reader.tcp_stream().expect("bytes are only piped from sockets");
+                    pipe.splice_from(socket, sys::PIPE_CAPACITY)
+                };
+                match spliced {
+                    Ok(0) => {
+                        self.read_done = true;
+                        continue;
+                    }
+                    Ok(n) => {
+                        reader.record_spliced_read(n);
+                        self.piped = n;
+                        continue;
+                    }
+                    // The socket can't be spliced from until it's readable, and only
+                    // `read` waits for that.
+                    Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                    Err(e) => return Err(e),
+                }
+            }
+
+            let n = try_ready!(poll_io(reader.read(&mut self.buf)));
+            if n == 0 {
+                self.read_done = true;
+            } else {
+                self.pos = 0;
+                self.cap = n;
+            }
+        }
+    }
+}
+
+fn poll_io<T>(res: io::Result<T>) -> Poll<T, io::Error> {
+    match res {
+        Ok(t) => Ok(Async::Ready(t)),
+        Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => Ok(Async::NotReady),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use libc;
+    use std::io;
+    use std::os::unix::io::{AsRawFd, RawFd};
+    use std::ptr;
+    use tokio_core::net::TcpStream;
+
+    /// The most bytes that are spliced into a pipe at once, which is the default size
+    /// of a pipe's buffer.
+    pub const PIPE_CAPACITY: usize = 64 * 1024;
+
+    /// A pipe through which bytes are spliced from one socket to another.
+    pub struct Pipe {
+        read: RawFd,
+        write: RawFd,
+    }
+
+    impl Pipe {
+        pub fn new() -> io::Result<Self> {
+            let mut fds = [0; 2];
+            let ret = unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_NONBLOCK | libc::O_CLOEXEC) };
+            if ret == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Pipe {
+                read: fds[0],
+                write: fds[1],
+            })
+        }
+
+        /// Moves up to `len` bytes from `socket` into the pipe.
+        pub fn splice_from(&self, socket: &TcpStream, len: usize) -> io::Result<usize> {
+            splice(socket.as_raw_fd(), self.write, len)
+        }
+
+        /// Moves up to `len` bytes from the pipe into `socket`.
+        pub fn splice_into(&self, socket: &TcpStream, len: usize) -> io::Result<usize> {
+            splice(self.read, socket.as_raw_fd(), len)
+        }
+
+        /// Reads bytes out of the pipe into `buf`.
+        pub fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
+            let ret = unsafe {
+                libc::read(self.read, buf.as_mut_ptr() as *mut libc::c_void, buf.len())
+            };
+            if ret == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(ret as usize)
+        }
+    }
+
+    impl Drop for Pipe {
+        fn drop(&mut self) {
+            unsafe {
+                libc::close(self.read);
+                libc::close(self.write);
+            }
+        }
+    }
+
+    fn splice(from: RawFd, to: RawFd, len: usize) -> io::Result<usize> {
+        let ret = unsafe {
+            libc::splice(
+                from,
+                ptr::null_mut(),
+                to,
+                ptr::null_mut(),
+                len,
+                libc::SPLICE_F_MOVE | libc::SPLICE_F_NONBLOCK,
+            )
+        };
+        if ret == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(ret as usize)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use std::io;
+    use tokio_core::net::TcpStream;
+
+    pub const PIPE_CAPACITY: usize = 0;
+
+    /// `splice(2)` is only available on Linux, so there are never pipes elsewhere.
+    pub enum Pipe {}
+
+    impl Pipe {
+        pub fn new() -> io::Result<Self> {
+            Err(io::Error::new(
+                io::ErrorKind::Other,
+                "splice(2) is only available on Linux",
+            ))
+        }
+
+        pub fn splice_from(&self, _: &TcpStream, _: usize) -> io::Result<usize> {
+            match *self {}
+        }
+
+        pub fn splice_into(&self, _: &TcpStream, _: usize) -> io::Result<usize> {
+            match *self {}
+        }
+
+        pub fn read(&self, _: &mut [u8]) -> io::Result<usize> {
+            match *self {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::Stream;
+    use std::cell::Cell;
+    use std::io::{Read, Write};
+    use std::net::{Shutdown, SocketAddr};
+    use std::rc::Rc;
+    use tokio_core::net::TcpListener;
+    use tokio_core::reactor::Core;
+    use tokio_io::io as async_io;
+
+    /// A socket that counts the bytes read from and written to it, as the sensors do.
+    struct Counted {
+        socket: TcpStream,
+        splice: bool,
+        read: Rc<Cell<usize>>,
+        written: Rc<Cell<usize>>,
+        spliced: Rc<Cell<usize>>,
+    }
+
+    impl Counted {
+        fn new(socket: TcpStream, splice: bool) -> Self {
+            Counted {
+                socket,
+                splice,
+                read: Rc::new(Cell::new(0)),
+                written: Rc::new(Cell::new(0)),
+                spliced: Rc::new(Cell::new(0)),
+            }
+        }
+    }
+
+    impl Read for Counted {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.socket.read(buf)?;
+            self.read.set(self.read.get() + n);
+            Ok(n)
+        }
+    }
+
+    impl Write for Counted {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let n = self.socket.write(buf)?;
+            self.written.set(self.written.get() + n);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.socket.flush()
+        }
+    }
+
+    impl AsyncRead for Counted {}
+
+    impl AsyncWrite for Counted {
+        fn shutdown(&mut self) -> Poll<(), io::Error> {
+            self.socket.shutdown(Shutdown::Write)?;
+            Ok(Async::Ready(()))
+        }
+    }
+
+    impl Splice for Counted {
+        fn tcp_stream(&self) -> Option<&TcpStream> {
+            if self.splice {
+                Some(&self.socket)
+            } else {
+                None
+            }
+        }
+
+        fn record_spliced_read(&mut self, n: usize) {
+            self.read.set(self.read.get() + n);
+            self.spliced.set(self.spliced.get() + n);
+        }
+
+        fn record_spliced_write(&mut self, n: usize) {
+            self.written.set(self.written.get() + n);
+            self.spliced.set(self.spliced.get() + n);
+        }
+    }
+
+    /// Returns both ends of a TCP connection over loopback.
+    fn socket_pair(core: &mut Core) -> (TcpStream, TcpStream) {
+        let handle = core.handle();
+        let addr = "127.0.0.1:0".parse::<SocketAddr>().unwrap();
+        let listener = TcpListener::bind(&addr, &handle).unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let accept = listener
+            .incoming()
+            .into_future()
+            .map(|(socket, _)| socket.expect("accepted").0)
+            .map_err(|(e, _)| e);
+        let connect = TcpStream::connect(&addr, &handle);
+        core.run(connect.join(accept)).unwrap()
+    }
+
+    /// Forwards a request and a response, each larger than a pipe, between a
+    /// client and a server, and returns the number of bytes that were spliced.
+    fn forwards(splice: bool) -> usize {
+        let mut core = Core::new().unwrap();
+        let (client, accepted) = socket_pair(&mut core);
+        let (connected, server) = socket_pair(&mut core);
+
+        let accepted = Counted::new(accepted, splice);
+        let connected = Counted::new(connected, splice);
+        let (accepted_read, accepted_written, accepted_spliced) = (
+            Rc::clone(&accepted.read),
+            Rc::clone(&accepted.written),
+            Rc::clone(&accepted.spliced),
+        );
+        let (connected_read, connected_written, connected_spliced) = (
+            Rc::clone(&connected.read),
+            Rc::clone(&connected.written),
+            Rc::clone(&connected.spliced),
+        );
+
+        let request = (0..200_000).map(|i| i as u8).collect::<Vec<_>>();
+        let response = (0..300_000).map(|i| (i / 7) as u8).collect::<Vec<_>>();
+
+        let client = async_io::write_all(client, request.clone())
+            .and_then(|(client, _)| client.shutdown(Shutdown::Write).map(|_| client))
+            .and_then(|client| async_io::read_to_end(client, Vec::new()));
+        let server = async_io::read_to_end(server, Vec::new())
+            .and_then(|(server, req)| {
+                async_io::write_all(server, response.clone()).map(move |(server, _)| (server, req))
+            })
+            .and_then(|(server, req)| server.shutdown(Shutdown::Write).map(|_| req));
+
+        let ((_, rsp), req, (a_to_b, b_to_a)) = core
+            .run(client.join3(server, duplex(accepted, connected)))
+            .unwrap();
+
+        assert!(req == request);
+        assert!(rsp == response);
+        assert_eq!(a_to_b, request.len() as u64);
+        assert_eq!(b_to_a, response.len() as u64);
+
+        assert_eq!(accepted_read.get(), request.len());
+        assert_eq!(accepted_written.get(), response.len());
+        assert_eq!(connected_read.get(), response.len());
+        assert_eq!(connected_written.get(), request.len());
+
+        accepted_spliced.get() + connected_spliced.get()
+    }
+
+    #[test]
+    fn copies_transports_that_cant_be_spliced() {
+        assert_eq!(forwards(false), 0);
+    }
+
+    #[test]
+    fn splices_sockets() {
+        let spliced = forwards(true);
+        if cfg!(target_os = "linux") {
+            assert!(spliced > 0, "nothing was spliced");
+        } else {
+            assert_eq!(spliced, 0);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use net2::TcpBuilder;
+use std::io;
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_transparent(_: &TcpBuilder, _: bool) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Other, "TPROXY is only supported on Linux"))
+}
+
+/// Allows the listener that `builder` will bind to accept connections
+/// addressed to other hosts, as TPROXY redirects them without rewriting their
+/// destination. This must be set before the listener is bound.
+///
+/// This requires the `CAP_NET_ADMIN` capability.
+#[cfg(target_os = "linux")]
+pub fn set_transparent(builder: &TcpBuilder, is_ipv6: bool) -> io::Result<()> {
+    use self::linux;
+    use std::os::unix::io::AsRawFd;
+
+    unsafe { linux::set_transparent(builder.as_raw_fd(), is_ipv6) }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use libc;
+    use std::{io, mem};
+    use std::os::unix::io::RawFd;
+
+    /// From `linux/in.h` and `linux/in6.h`; not exported by `libc`.
+    const IP_TRANSPARENT: libc::c_int = 19;
+    const IPV6_TRANSPARENT: libc::c_int = 75;
+
+    pub unsafe fn set_transparent(fd: RawFd, is_ipv6: bool) -> io::Result<()> {
+        let (level, optname) = if is_ipv6 {
+            (libc::SOL_IPV6, IPV6_TRANSPARENT)
+        } else {
+            (libc::SOL_IP, IP_TRANSPARENT)
+        };
+
+        let enable: libc::c_int = 1;
+        let ret = libc::setsockopt(
+            fd,
+            level,
+            optname,
+            &enable as *const _ as *const _,
+            mem::size_of::<libc::c_int>() as libc::socklen_t,
+        );
+        if ret != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+}
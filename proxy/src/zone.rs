@@ -0,0 +1,247 @@
+//! Prefers endpoints in the proxy's zone.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use futures::{Async, Future, Poll};
+use http;
+use tower::Service;
+
+use ctx;
+
+/// Sends requests to the endpoints in the proxy's zone, spilling over to
+/// endpoints in other zones while the local ones are unavailable or
+/// overloaded.
+///
+/// The local endpoints are overloaded when there are at least
+/// `spillover_threshold` requests in flight per local endpoint.
+#[derive(Debug)]
+pub struct ZoneAware<S> {
+    local: S,
+    remote: S,
+    /// The number of endpoints in `local`.
+    local_endpoints: Rc<Cell<usize>>,
+    /// The number of requests in flight to `local`.
+    local_in_flight: Rc<Cell<usize>>,
+    spillover_threshold: usize,
+    /// Whether the next request is sent to `remote`, as decided by
+    /// `poll_ready`.
+    spillover: bool,
+}
+
+/// Counts a request as in flight until its response is received.
+#[derive(Debug)]
+pub struct ResponseFuture<F> {
+    inner: F,
+    in_flight: Option<Rc<Cell<usize>>>,
+}
+
+// ===== impl ZoneAware =====
+
+impl<S> ZoneAware<S> {
+    pub fn new(
+        local: S,
+        remote: S,
+        local_endpoints: Rc<Cell<usize>>,
+        spillover_threshold: usize,
+    ) -> Self {
+        ZoneAware {
+            local,
+            remote,
+            local_endpoints,
+            local_in_flight: Rc::new(Cell::new(0)),
+            spillover_threshold,
+            spillover: false,
+        }
+    }
+
+    fn is_overloaded(&self) -> bool {
+        let capacity = self.local_endpoints.get().saturating_mul(self.spillover_threshold);
+        self.local_in_flight.get() >= capacity
+    }
+}
+
+impl<S, B> Service for ZoneAware<S>
+where
+    S: Service<Request = http::Request<B>>,
+{
+    type Request = S::Request;
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self) -> Poll<(), Self::Error> {
+        // Both services are polled, so that both learn about new endpoints.
+        let local = self.local.poll_ready()?.is_ready();
+        let remote = self.remote.poll_ready()?.is_ready();
+
+        self.spillover = match (local, remote) {
+            (true, true) => self.is_overloaded(),
+            (true, false) => false,
+            (false, true) => true,
+            (false, false) => return Ok(Async::NotReady),
+        };
+
+        Ok(Async::Ready(()))
+    }
+
+    fn call(&mut self, mut req: Self::Request) -> Self::Future {
+        if self.spillover {
+            trace!("spilling over to another zone");
+            req.extensions_mut().insert(ctx::http::ZoneSpillover);
+            return ResponseFuture {
+                inner: self.remote.call(req),
+                in_flight: None,
+            };
+        }
+
+        self.local_in_flight.set(self.local_in_flight.get() + 1);
+        ResponseFuture {
+            inner: self.local.call(req),
+            in_flight: Some(self.local_in_flight.clone()),
+        }
+    }
+}
+
+// ===== impl ResponseFuture =====
+
+impl<F: Future> Future for ResponseFuture<F> {
+    type Item = F::Item;
+    type Error = F::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        self.inner.poll()
+    }
+}
+
+impl<F> Drop for ResponseFuture<F> {
+    fn drop(&mut self) {
+        if let Some(ref in_flight) = self.in_flight {
+            in_flight.set(in_flight.get() - 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::future::{self, FutureResult};
+
+    /// A service that records the requests it is called with.
+    struct Mock {
+        ready: Rc<Cell<bool>>,
+        calls: Rc<Cell<usize>>,
+        spillovers: Rc<Cell<usize>>,
+    }
+
+    impl Mock {
+        fn new() -> Self {
+            Mock {
+                ready: Rc::new(Cell::new(true)),
+                calls: Rc::new(Cell::new(0)),
+                spillovers: Rc::new(Cell::new(0)),
+            }
+        }
+
+        fn handle(&self) -> Self {
+            Mock {
+                ready: self.ready.clone(),
+                calls: self.calls.clone(),
+                spillovers: self.spillovers.clone(),
+            }
+        }
+    }
+
+    impl Service for Mock {
+        type Request = http::Request<()>;
+        type Response = ();
+        type Error = ();
+        type Future = FutureResult<(), ()>;
+
+        fn poll_ready(&mut self) -> Poll<(), ()> {
+            if self.ready.get() {
+                Ok(Async::Ready(()))
+            } else {
+                Ok(Async::NotReady)
+            }
+        }
+
+        fn call(&mut self, req: Self::Request) -> Self::Future {
+            self.calls.set(self.calls.get() + 1);
+            if req.extensions().get::<ctx::http::ZoneSpillover>().is_some() {
+                self.spillovers.set(self.spillovers.get() + 1);
+            }
+            future::ok(())
+        }
+    }
+
+    fn zone_aware(local_endpoints: usize, spillover_threshold: usize) -> (ZoneAware<Mock>, Mock, Mock) {
+        let local = Mock::new();
+        let remote = Mock::new();
+        let zone_aware = ZoneAware::new(
+            local.handle(),
+            remote.handle(),
+            Rc::new(Cell::new(local_endpoints)),
+            spillover_threshold,
+        );
+        (zone_aware, local, remote)
+    }
+
+    fn send(zone_aware: &mut ZoneAware<Mock>) -> ResponseFuture<FutureResult<(), ()>> {
+        assert!(zone_aware.poll_ready().unwrap().is_ready());
+        zone_aware.call(http::Request::new(()))
+    }
+
+    #[test]
+    fn sends_requests_to_local_endpoints_until_overloaded() {
+        let (mut zone_aware, local, remote) = zone_aware(2, 2);
+
+        // Two endpoints with two requests each.
+        let in_flight = (0..4).map(|_| send(&mut zone_aware)).collect::<Vec<_>>();
+        assert_eq!(local.calls.get(), 4);
+        assert_eq!(zone_aware.local_in_flight.get(), 4);
+
+        let _spilled = send(&mut zone_aware);
+        assert_eq!(remote.calls.get(), 1);
+        assert_eq!(remote.spillovers.get(), 1);
+        assert_eq!(local.spillovers.get(), 0);
+
+        // Spilled over requests don't count toward the local load.
+        assert_eq!(zone_aware.local_in_flight.get(), 4);
+
+        drop(in_flight);
+        assert_eq!(zone_aware.local_in_flight.get(), 0);
+
+        let _local = send(&mut zone_aware);
+        assert_eq!(local.calls.get(), 5);
+    }
+
+    #[test]
+    fn spills_over_without_local_endpoints() {
+        let (mut zone_aware, local, remote) = zone_aware(0, 10);
+
+        let _rsp = send(&mut zone_aware);
+        assert_eq!(local.calls.get(), 0);
+        assert_eq!(remote.spillovers.get(), 1);
+    }
+
+    #[test]
+    fn uses_whichever_side_is_ready() {
+        let (mut zone_aware, local, remote) = zone_aware(1, 1);
+
+        // The local endpoint is overloaded, but it's all there is.
+        let _first = send(&mut zone_aware);
+        remote.ready.set(false);
+        let _second = send(&mut zone_aware);
+        assert_eq!(local.calls.get(), 2);
+
+        local.ready.set(false);
+        remote.ready.set(true);
+        let _third = send(&mut zone_aware);
+        assert_eq!(remote.spillovers.get(), 1);
+
+        remote.ready.set(false);
+        assert!(zone_aware.poll_ready().unwrap().is_not_ready());
+    }
+}
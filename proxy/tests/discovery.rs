@@ -19,6 +19,48 @@ fn outbound_asks_controller_api() {
     assert_eq!(client.get("/bye"), "bye");
 }
 
+#[test]
+fn outbound_reports_destination_labels() {
+    let _ = env_logger::init();
+
+    let srv = server::new().route("/hey", "hello").run();
+    let mut ctrl = controller::new()
+        .destination_labeled("test.conduit.local", srv.addr, &[
+            ("pod", "default/test-1"),
+            ("deployment", "default/test"),
+            ("zone", "us-east-1a"),
+        ]);
+    let reports = ctrl.reports();
+    let proxy = proxy::new()
+        .controller(ctrl.run())
+        .outbound(srv)
+        .metrics_flush_interval(Duration::from_millis(500))
+        .run();
+    let client = client::new(proxy.outbound, "test.conduit.local");
+
+    assert_eq!(client.get("/hey"), "hello");
+
+    let report = reports.wait()
+        .map(|r| r.unwrap())
+        .find(|r| !r.requests.is_empty())
+        .unwrap();
+    // proxy outbound
+    assert_eq!(report.proxy, 1);
+
+    let expected: ::std::collections::HashMap<String, String> = vec![
+        ("pod".to_owned(), "default/test-1".to_owned()),
+        ("deployment".to_owned(), "default/test".to_owned()),
+        ("zone".to_owned(), "us-east-1a".to_owned()),
+    ].into_iter().collect();
+
+    assert_eq!(report.requests.len(), 1);
+    let ctx = report.requests[0].ctx.as_ref().unwrap();
+    assert_eq!(ctx.target_labels, expected);
+
+    assert_eq!(report.client_transports.len(), 1);
+    assert_eq!(report.client_transports[0].target_labels, expected);
+}
+
 #[test]
 fn outbound_reconnects_if_controller_stream_ends() {
     let _ = env_logger::init();
@@ -34,6 +76,68 @@ fn outbound_reconnects_if_controller_stream_ends() {
     assert_eq!(client.get("/recon"), "nect");
 }
 
+#[test]
+fn outbound_resolves_non_service_destinations_without_controller() {
+    let _ = env_logger::init();
+
+    let srv = server::new().route("/", "hello").run();
+    let authority = srv.addr.to_string();
+    let ctrl = controller::new().run();
+    let proxy = proxy::new().controller(ctrl).outbound(srv).run();
+    let client = client::new(proxy.outbound, authority);
+
+    assert_eq!(client.get("/"), "hello");
+}
+
+#[test]
+fn outbound_reads_destinations_file_without_controller() {
+    use std::fs::File;
+    use std::io::Write;
+
+    let _ = env_logger::init();
+
+    let srv = server::new().route("/", "hello").run();
+    let path = ::std::env::temp_dir().join("conduit-test-destinations");
+    {
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "disco.test.svc.cluster.local {}", srv.addr).unwrap();
+    }
+    let proxy = proxy::new().destinations_file(&path).outbound(srv).run();
+    let client = client::new(proxy.outbound, "disco.test.svc.cluster.local");
+
+    assert_eq!(client.get("/"), "hello");
+}
+
+#[test]
+fn outbound_routes_to_restored_destinations_until_controller_updates_them() {
+    use std::fs::{self, File};
+    use std::io::Write;
+
+    let _ = env_logger::init();
+
+    let srv = server::new().route("/", "hello").run();
+    let path = ::std::env::temp_dir().join(format!("conduit-test-snapshot-{}", srv.addr.port()));
+    {
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "disco.test.svc.cluster.local {}", srv.addr).unwrap();
+    }
+
+    // The controller only sends a heartbeat, as if it had lost track of the
+    // destination's endpoints, which must not clear the restored addresses.
+    let ctrl = controller::new()
+        .destination_heartbeat("disco.test.svc.cluster.local")
+        .run();
+    let proxy = proxy::new()
+        .controller(ctrl)
+        .destination_snapshot(&path)
+        .outbound(srv)
+        .run();
+    let client = client::new(proxy.outbound, "disco.test.svc.cluster.local");
+
+    assert_eq!(client.get("/"), "hello");
+    let _ = fs::remove_file(&path);
+}
+
 #[test]
 #[ignore]
 fn outbound_times_out() {
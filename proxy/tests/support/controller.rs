@@ -2,6 +2,7 @@
 
 use support::*;
 
+use std::collections::HashMap;
 use std::net::IpAddr;
 use std::sync::{Arc, Mutex};
 
@@ -36,7 +37,27 @@ impl Controller {
 
     pub fn destination(mut self, dest: &str, addr: SocketAddr) -> Self {
         self.destinations
-            .push((dest.into(), Some(destination_update(addr))));
+            .push((dest.into(), Some(destination_update(addr, HashMap::new()))));
+        self
+    }
+
+    /// Like `destination`, but describes `addr` with `labels`.
+    pub fn destination_labeled(mut self, dest: &str, addr: SocketAddr, labels: &[(&str, &str)]) -> Self {
+        let labels = labels.iter()
+            .map(|&(k, v)| (k.to_owned(), v.to_owned()))
+            .collect();
+        self.destinations
+            .push((dest.into(), Some(destination_update(addr, labels))));
+        self
+    }
+
+    /// Sends an update without any addresses, as the controller does to
+    /// show that a stream is healthy.
+    pub fn destination_heartbeat(mut self, dest: &str) -> Self {
+        let update = pb::destination::Update {
+            update: Some(pb::destination::update::Update::Add(Default::default())),
+        };
+        self.destinations.push((dest.into(), Some(update)));
         self
     }
 
@@ -249,7 +270,7 @@ fn run(controller: Controller) -> Listening {
     }
 }
 
-fn destination_update(addr: SocketAddr) -> pb::destination::Update {
+fn destination_update(addr: SocketAddr, labels: HashMap<String, String>) -> pb::destination::Update {
     pb::destination::Update {
         update: Some(pb::destination::update::Update::Add(
             pb::destination::WeightedAddrSet {
@@ -260,6 +281,7 @@ fn destination_update(addr: SocketAddr) -> pb::destination::Update {
                             port: u32::from(addr.port()),
                         }),
                         weight: 0,
+                        labels,
                     },
                 ],
             },
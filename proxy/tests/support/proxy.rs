@@ -9,10 +9,14 @@ pub fn new() -> Proxy {
 #[derive(Debug)]
 pub struct Proxy {
     controller: Option<controller::Listening>,
+    destinations_file: Option<::std::path::PathBuf>,
+    destination_snapshot: Option<::std::path::PathBuf>,
     inbound: Option<server::Listening>,
     outbound: Option<server::Listening>,
 
     metrics_flush_interval: Option<Duration>,
+    trace_collector: Option<SocketAddr>,
+    worker_threads: Option<usize>,
 }
 
 #[derive(Debug)]
@@ -28,10 +32,14 @@ impl Proxy {
     pub fn new() -> Self {
         Proxy {
             controller: None,
+            destinations_file: None,
+            destination_snapshot: None,
             inbound: None,
             outbound: None,
 
             metrics_flush_interval: None,
+            trace_collector: None,
+            worker_threads: None,
         }
     }
 
@@ -40,6 +48,18 @@ impl Proxy {
         self
     }
 
+    /// Run without a controller, reading destinations from `path`.
+    pub fn destinations_file<P: Into<::std::path::PathBuf>>(mut self, path: P) -> Self {
+        self.destinations_file = Some(path.into());
+        self
+    }
+
+    /// Restore destinations from, and save them to, the snapshot at `path`.
+    pub fn destination_snapshot<P: Into<::std::path::PathBuf>>(mut self, path: P) -> Self {
+        self.destination_snapshot = Some(path.into());
+        self
+    }
+
     pub fn inbound(mut self, s: server::Listening) -> Self {
         self.inbound = Some(s);
         self
@@ -55,6 +75,16 @@ impl Proxy {
         self
     }
 
+    pub fn trace_collector(mut self, addr: SocketAddr) -> Self {
+        self.trace_collector = Some(addr);
+        self
+    }
+
+    pub fn worker_threads(mut self, n: usize) -> Self {
+        self.worker_threads = Some(n);
+        self
+    }
+
     pub fn run(self) -> Listening {
         run(self)
     }
@@ -63,18 +93,29 @@ impl Proxy {
 fn run(proxy: Proxy) -> Listening {
     use self::conduit_proxy::config;
 
-    let controller = proxy.controller.expect("proxy controller missing");
+    let controller = proxy.controller;
     let inbound = proxy.inbound;
     let outbound = proxy.outbound;
 
     let mut env = config::TestEnv::new();
-    env.put(config::ENV_CONTROL_URL, format!("tcp://{}", controller.addr));
+    match (&controller, proxy.destinations_file) {
+        (&Some(ref controller), None) => {
+            env.put(config::ENV_CONTROL_URL, format!("tcp://{}", controller.addr));
+        }
+        (&None, Some(path)) => {
+            env.put(config::ENV_DESTINATIONS_PATH, path.to_str().unwrap().to_owned());
+        }
+        _ => panic!("proxy needs either a controller or a destinations file"),
+    }
     env.put(config::ENV_PRIVATE_LISTENER, "tcp://127.0.0.1:0".to_owned());
     if let Some(ref inbound) = inbound {
         env.put(config::ENV_PRIVATE_FORWARD, format!("tcp://{}", inbound.addr));
     }
     env.put(config::ENV_PUBLIC_LISTENER, "tcp://127.0.0.1:0".to_owned());
     env.put(config::ENV_CONTROL_LISTENER, "tcp://127.0.0.1:0".to_owned());
+    if let Some(addr) = proxy.trace_collector {
+        env.put(config::ENV_TRACE_COLLECTOR, format!("tcp://{}", addr));
+    }
 
     let mut config = config::Config::try_from(&env).unwrap();
 
@@ -86,6 +127,14 @@ fn run(proxy: Proxy) -> Listening {
         config.metrics_flush_interval = dur;
     }
 
+    if let Some(n) = proxy.worker_threads {
+        config.worker_threads = n;
+    }
+
+    if let Some(path) = proxy.destination_snapshot {
+        config.destination_snapshot_path = Some(path);
+    }
+
     let main = conduit_proxy::Main::new(config);
 
     let control_addr = main.control_addr();
@@ -0,0 +1,78 @@
+#[macro_use]
+extern crate log;
+
+mod support;
+use self::support::*;
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::mpsc;
+use std::thread;
+
+#[test]
+fn inbound_reports_spans_to_collector() {
+    let _ = env_logger::init();
+
+    let (collector, spans) = collector();
+    let srv = server::new().route("/hey", "hello").run();
+    let ctrl = controller::new().run();
+    let proxy = proxy::new()
+        .controller(ctrl)
+        .inbound(srv)
+        .trace_collector(collector)
+        .run();
+    let client = client::new(proxy.inbound, "test.conduit.local");
+
+    info!("client.get(/hey)");
+    assert_eq!(client.get("/hey"), "hello");
+
+    let spans = spans.recv_timeout(Duration::from_secs(5)).expect("spans");
+    assert!(spans.starts_with("[{\"traceId\":"), "{}", spans);
+    assert!(spans.contains("\"name\":\"get\""), "{}", spans);
+    assert!(spans.contains("\"conduit.direction\":\"inbound\""), "{}", spans);
+    assert!(spans.contains("\"http.path\":\"/hey\""), "{}", spans);
+    assert!(spans.contains("\"http.status_code\":\"200\""), "{}", spans);
+}
+
+/// Runs a Zipkin collector, which sends the body of each request it receives
+/// on the returned channel.
+fn collector() -> (SocketAddr, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (tx, rx) = mpsc::channel();
+
+    thread::Builder::new()
+        .name("support collector".into())
+        .spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = BufReader::new(stream.unwrap());
+
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    stream.read_line(&mut line).unwrap();
+                    let line = line.trim().to_lowercase();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if line.starts_with("content-length:") {
+                        content_length = line["content-length:".len()..].trim().parse().unwrap();
+                    }
+                }
+
+                let mut body = vec![0; content_length];
+                stream.read_exact(&mut body).unwrap();
+                stream
+                    .get_mut()
+                    .write_all(b"HTTP/1.1 202 Accepted\r\nContent-Length: 0\r\n\r\n")
+                    .unwrap();
+
+                if tx.send(String::from_utf8(body).unwrap()).is_err() {
+                    return;
+                }
+            }
+        })
+        .unwrap();
+
+    (addr, rx)
+}
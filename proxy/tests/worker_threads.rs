@@ -0,0 +1,125 @@
+#[macro_use]
+extern crate log;
+
+mod support;
+use self::support::*;
+
+use std::thread;
+use std::time::Instant;
+
+#[test]
+fn inbound_serves_on_several_worker_threads() {
+    let _ = env_logger::init();
+
+    let srv = server::new().route("/", "hello").run();
+    let ctrl = controller::new().run();
+    let proxy = proxy::new()
+        .controller(ctrl)
+        .inbound(srv)
+        .worker_threads(4)
+        .run();
+
+    // Each client has its own connection, so they're spread over the workers.
+    let clients = (0..8)
+        .map(|_| {
+            let addr = proxy.inbound;
+            thread::spawn(move || {
+                let client = client::new(addr, "test.conduit.local");
+                assert_eq!(client.get("/"), "hello");
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for c in clients {
+        c.join().unwrap();
+    }
+}
+
+#[test]
+fn outbound_resolves_destinations_on_several_worker_threads() {
+    let _ = env_logger::init();
+
+    let srv = server::new().route("/", "hello").run();
+    let ctrl = controller::new()
+        .destination("test.conduit.local", srv.addr)
+        .run();
+    let proxy = proxy::new()
+        .controller(ctrl)
+        .outbound(srv)
+        .worker_threads(2)
+        .run();
+
+    // Each worker resolves the destination, and each must see its addresses.
+    let clients = (0..8)
+        .map(|_| {
+            let addr = proxy.outbound;
+            thread::spawn(move || {
+                let client = client::new(addr, "test.conduit.local");
+                assert_eq!(client.get("/"), "hello");
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for c in clients {
+        c.join().unwrap();
+    }
+}
+
+/// Checks that inbound throughput scales with the number of worker threads.
+///
+/// The test server runs on a single thread, so it limits how far throughput
+/// can scale; four workers are only expected to beat one by half. This needs
+/// at least four idle cores, so it's only run on demand, with
+/// `cargo test --release --test worker_threads -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn bench_inbound_throughput_scales_with_worker_threads() {
+    let _ = env_logger::init();
+
+    const CLIENTS: usize = 16;
+    const REQUESTS_PER_CLIENT: usize = 2_000;
+    const MIN_SPEEDUP: f64 = 1.5;
+
+    let throughput = |workers: usize| -> f64 {
+        let srv = server::new().route("/", "hello").run();
+        let ctrl = controller::new().run();
+        let proxy = proxy::new()
+            .controller(ctrl)
+            .inbound(srv)
+            .worker_threads(workers)
+            .run();
+
+        let start = Instant::now();
+        let clients = (0..CLIENTS)
+            .map(|_| {
+                let addr = proxy.inbound;
+                thread::spawn(move || {
+                    let client = client::new(addr, "test.conduit.local");
+                    for _ in 0..REQUESTS_PER_CLIENT {
+                        assert_eq!(client.get("/"), "hello");
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for c in clients {
+            c.join().unwrap();
+        }
+        let elapsed = start.elapsed();
+
+        let secs = elapsed.as_secs() as f64 + f64::from(elapsed.subsec_nanos()) / 1e9;
+        let throughput = (CLIENTS * REQUESTS_PER_CLIENT) as f64 / secs;
+        info!("done with {} worker threads", workers);
+        println!("{} worker threads: {:.0} requests/s", workers, throughput);
+        throughput
+    };
+
+    let one = throughput(1);
+    let four = throughput(4);
+    assert!(
+        four >= one * MIN_SPEEDUP,
+        "4 worker threads served {:.0} requests/s, less than {} times the {:.0} requests/s of 1",
+        four,
+        MIN_SPEEDUP,
+        one
+    );
+}
@@ -1,270 +1,167 @@
+use codegen;
 use prost_build;
 
-#[allow(unused)]
-use std::ascii::AsciiExt;
-use std::fmt::{self, Write};
+use std::fmt;
+
+use super::{super_import, unqualified};
 
 /// Generates service code
 pub struct ServiceGenerator;
 
-struct State {
-    mod_levels: usize,
-}
-
-// ===== impl ServiceGenerator =====
-
 impl ServiceGenerator {
-    pub fn generate(&self, service: &prost_build::Service, buf: &mut String) -> fmt::Result {
-        let mut state = State {
-            mod_levels: 0,
-        };
-        state.generate(service, buf)
-    }
-}
-
-// ===== impl State =====
-
-impl State {
-    pub fn generate(&mut self, service: &prost_build::Service, buf: &mut String) -> fmt::Result {
-        // Generate code in a client module
-        write!(buf, "pub mod client {{\n")?;
-        self.mod_levels = 1;
-
-        self.generate_svc(&service, buf)?;
-
-        write!(buf, "\n    pub mod {}_methods {{", service.name.to_ascii_lowercase())?;
-        self.mod_levels = 2;
-
-        self.generate_rpcs(&service.methods, buf)?;
-        buf.push_str("   }\n"); // end `mod name_methods {`
-
-        buf.push_str("}"); // end `mod client {`
-
-        Ok(())
-    }
-
-    /// Generates a generic struct to represent the gRPC service.
-    fn generate_svc(&self, service: &prost_build::Service, buf: &mut String) -> fmt::Result {
-        // Generic names used to identify concrete service implementations
-        // contained by the service client struct.
-        let mut rpc_generics = vec![];
-        let mut all_generics = vec![];
-        let mut struct_fields = vec![];
-        let mut new_args = vec![];
-        let mut new_fields = vec![];
-        let mut where_bounds = vec![];
+    fn define(&self, service: &prost_build::Service, codec: &str) -> codegen::Scope {
+        // The client wraps a single HTTP/2 service, which carries the requests
+        // of all of the RPCs.
+        let mut client_struct = codegen::Struct::new(&service.name);
+        client_struct.vis("pub")
+            .derive("Debug")
+            .derive("Clone")
+            .doc(&format!("Client for the `{}.{}` service", service.package, service.proto_name))
+            .generic("T")
+            .field("inner", "T")
+            ;
+
+        let mut client_impl = codegen::Impl::new(&service.name);
+        client_impl.generic("T")
+            .target_generic("T")
+            ;
+
+        let mut new_block = codegen::Block::new(&service.name);
+        new_block.line("inner,");
+
+        client_impl.function("new")
+            .vis("pub")
+            .arg("inner", "T")
+            .ret("Self")
+            .block(new_block)
+            ;
+
+        client_impl.function("poll_ready")
+            .docs("Poll whether this client is ready to send another request")
+            .vis("pub")
+            .generic("R")
+            .arg_mut_self()
+            .ret("futures::Poll<(), grpc::Error<T::Error>>")
+            .bound("T", "tower::Service<Request = http::Request<R>>")
+            .line("tower::Service::poll_ready(&mut self.inner)")
+            .line("    .map_err(grpc::Error::Inner)")
+            ;
 
         for method in &service.methods {
-            let rpc_name = format!("{}Rpc", method.proto_name);
-
-            let arg = self.input_name(method);
-            let returns = self.svc_returns(method);
-
-            rpc_generics.push(rpc_name.clone());
-            all_generics.push(rpc_name.clone());
-
-            struct_fields.push(format!("{}: {},", method.name, rpc_name));
-
-            where_bounds.push(format!("{}: ::tower::Service<\
-                Request=::tower_grpc::Request<{}>,\
-                Response=::tower_grpc::Response<{}>,\
-            >,", rpc_name, arg, returns));
-
-            if method.server_streaming {
-                all_generics.push(returns.clone());
-                where_bounds.push(format!("{}: ::futures::Stream<Item={}, Error=::tower_grpc::Error<::h2::Error>>,", returns, self.output_name(method)));
-            }
-
-            new_args.push(format!("{}: {}", method.name, rpc_name));
-            new_fields.push(format!("{},", method.name));
-
+            client_impl.push_function(method_fn(service, method, codec));
         }
 
-        write!(buf, r##"
-    #[derive(Debug)]
-    pub struct {name}<{rpc_generics}> {{
-        {struct_fields}
-    }}
-
-    impl<{all_generics}> {name}<{rpc_generics}>
-    where
-        {where_bounds}
-    {{
-        pub fn new({new_args}) -> Self {{
-            {name} {{
-                {new_fields}
-            }}
-        }}
-"##,
-            name=service.name,
-            all_generics=all_generics.join(", "),
-            rpc_generics=rpc_generics.join(", "),
-            where_bounds=where_bounds.join("\n"),
-            struct_fields=struct_fields.join("\n        "),
-            new_args=new_args.join(", "),
-            new_fields=new_fields.join("\n        "),
-        )?;
-
-        self.generate_svc_methods(&service, buf)?;
-
-        buf.push_str("    }\n"); // close `impl {name} {`
-
-        Ok(())
-    }
+        let mut scope = codegen::Scope::new();
+        {
+        let client = scope.module("client")
+            .vis("pub")
+            .import("::tower_grpc::codegen::client", "*");
 
-    /// Generates the inherent methods on the generic service struct.
-    fn generate_svc_methods(&self, service: &prost_build::Service, buf: &mut String) -> fmt::Result {
         for method in &service.methods {
-            let path = format!("/{}.{}/{}", service.package, service.proto_name, method.proto_name);
-            let svc_returns = self.svc_returns(method);
-            let rpc_name = format!("{}Rpc", method.proto_name);
-
-            let (returns, map_fut) = if method.server_streaming {
-                let returns = format!(
-                    "::tower_grpc::client::Streaming<{rpc_name}::Future, {returns}>",
-                    rpc_name=rpc_name,
-                    returns=svc_returns,
-                );
-                (returns, "::tower_grpc::client::Streaming::map_future(fut)")
-            } else {
-                let returns = format!(
-                    "::tower_grpc::client::BodyFuture<{rpc_name}::Future>",
-                    rpc_name=rpc_name,
-                );
-                (returns, "::tower_grpc::client::BodyFuture::new(fut)")
-            };
-
-            write!(buf, r##"
-        pub fn {method}(&mut self, req: {arg}) -> {returns} {{
-            let req = ::tower_grpc::Request::new("{path}", req);
-            let fut = self.{method}.call(req);
-            {fut}
-        }}
-"##,
-            method=method.name,
-            arg=self.input_name(method),
-            returns=returns,
-            path=path,
-            fut=map_fut,
-            )?;
-        }
-
-        Ok(())
-    }
+            let (input_path, input_type) = super_import(&method.input_type);
+            let (output_path, output_type) = super_import(&method.output_type);
 
-    /// Generates the individual RPCs as `tower::Service`.
-    fn generate_rpcs(&self, methods: &[prost_build::Method], buf: &mut String) -> fmt::Result {
-        for method in methods {
-            let input = self.input_name(method);
-            let output = self.output_name(method);
-            let call_stream = if method.client_streaming {
-                unimplemented!("generate client streaming");
-            } else {
-                "::tower_grpc::client::codec::Unary"
-            };
-
-            let where_bounds = format!(r##"
-            C: ::tower_grpc::client::Codec<Encode={input}, Decode={output}>,
-            S: ::tower::Service<
-                Request=::tower_grpc::Request<
-                    {call_stream}<{input}>
-                >,
-                Response=::tower_grpc::Response<
-                    ::tower_grpc::client::codec::DecodingBody<C>
-                >,
-                Error=::tower_grpc::Error<E>
-            >,"##,
-                call_stream=call_stream,
-                input=input,
-                output=output,
-            );
-
-            let returns = self.rpc_returns(method);
-            let fut = if method.server_streaming {
-                "S::Future"
-            } else {
-                "::tower_grpc::client::Unary<S::Future, C>"
-            };
-            let call = if method.server_streaming {
-                "fut"
-            } else {
-                "::tower_grpc::client::Unary::map_future(fut)"
-            };
-            write!(buf, r##"
-        #[derive(Debug)]
-        pub struct {name}<S> {{
-            service: S,
-        }}
-
-        impl<S, C, E> {name}<S>
-        where{where_bounds}
-        {{
-            pub fn new(service: S) -> Self {{
-                {name} {{
-                    service,
-                }}
-            }}
-        }}
-
-        impl<S, C, E> ::tower::Service for {name}<S>
-        where{where_bounds}
-        {{
-            type Request = ::tower_grpc::Request<{input}>;
-            type Response = ::tower_grpc::Response<{returns}>;
-            type Error = S::Error;
-            type Future = {fut};
-
-            fn poll_ready(&mut self) -> ::futures::Poll<(), Self::Error> {{
-                ::tower::Service::poll_ready(&mut self.service)
-            }}
-
-            fn call(&mut self, req: Self::Request) -> Self::Future {{
-                let fut = ::tower::Service::call(&mut self.service, {req});
-                {call}
-            }}
-        }}
-"##,
-                name=method.proto_name,
-                where_bounds=where_bounds,
-                input=input,
-                returns=returns,
-                fut=fut,
-                req=if method.client_streaming { "req" } else { "req.into_unary()" },
-                call=call,
-            )?;
+            // Import the request and response types
+            client.import(&input_path, input_type);
+            client.import(&output_path, output_type);
         }
 
-        Ok(())
-    }
+        client.push_structure(client_struct)
+            .push_imp(client_impl)
+            ;
+        }
 
-    fn supers(&self) -> String {
-        (0..self.mod_levels)
-            .map(|_| "super::")
-            .collect::<Vec<_>>()
-            .concat()
+        scope
     }
 
-    fn input_name(&self, method: &prost_build::Method) -> String {
-        format!("{}{}", self.supers(), method.input_type)
-    }
+    pub fn generate(&self, service: &prost_build::Service, codec: &str, buf: &mut String) -> fmt::Result {
+        let scope = self.define(service, codec);
+        let mut fmt = codegen::Formatter::new(buf);
 
-    fn output_name(&self, method: &prost_build::Method) -> String {
-        format!("{}{}", self.supers(), method.output_type)
+        scope.fmt(&mut fmt)
     }
+}
 
-    fn svc_returns(&self, method: &prost_build::Method) -> String {
-        if method.server_streaming {
-            format!("{}Returns", method.proto_name)
-        } else {
-            self.output_name(method)
-        }
+/// Defines the client method that calls `method`, using `codec` for its
+/// messages.
+///
+/// Requests are sent as a single message, or as a stream of messages for
+/// client streaming RPCs. Responses are a future of a single message, or a
+/// stream of messages for server streaming RPCs.
+fn method_fn(service: &prost_build::Service, method: &prost_build::Method, codec_path: &str) -> codegen::Function {
+    let input_type = unqualified(&method.input_type);
+    let output_type = unqualified(&method.output_type);
+    let path = format!("/{}.{}/{}", service.package, service.proto_name, method.proto_name);
+
+    let mut codec = codegen::Type::new(codec_path);
+    codec.generic(input_type).generic(output_type);
+
+    let mut response_future = codegen::Type::new("grpc::ResponseFuture");
+    response_future.generic(&codec).generic("T::Future");
+
+    let mut func = codegen::Function::new(&method.name);
+    func.vis("pub")
+        .generic("R")
+        .arg_mut_self()
+        ;
+
+    let request_stream = if method.client_streaming {
+        func.generic("S").arg("request", "S");
+        codegen::Type::new("S")
+    } else {
+        func.arg("request", input_type);
+
+        let mut ty = codegen::Type::new("grpc::codec::Unary");
+        ty.generic(input_type);
+        ty
+    };
+
+    let ret = if method.server_streaming {
+        let mut body = codegen::Type::new("grpc::codec::DecodingBody");
+        body.generic(&codec);
+
+        let mut ty = codegen::Type::new("grpc::Streaming");
+        ty.generic(&response_future).generic(body);
+        ty
+    } else {
+        let mut ty = codegen::Type::new("grpc::Unary");
+        ty.generic(&response_future).generic(&codec);
+        ty
+    };
+    func.ret(ret);
+
+    let mut encoding_body = codegen::Type::new("grpc::codec::EncodingBody");
+    encoding_body.generic(&codec).generic(request_stream);
+
+    let mut into_body = codegen::Type::new("From");
+    into_body.generic(encoding_body);
+
+    func.bound("T", "tower::Service<Request = http::Request<R>, \
+                     Response = http::Response<tower_h2::RecvBody>>")
+        .bound("R", into_body)
+        ;
+
+    if method.client_streaming {
+        func.bound("S", format!("futures::Stream<Item = {}>", input_type));
     }
 
-    fn rpc_returns(&self, method: &prost_build::Method) -> String {
-        if method.server_streaming {
-            "::tower_grpc::client::codec::DecodingBody<C>".into()
-        } else {
-            self.output_name(method)
-        }
-    }
+    let call = if method.client_streaming {
+        "let response = tower::Service::call(&mut client, request);"
+    } else {
+        "let response = tower::Service::call(&mut client, request.into_unary());"
+    };
+
+    let map_future = if method.server_streaming {
+        "grpc::Streaming::map_future(response)"
+    } else {
+        "grpc::Unary::map_future(response)"
+    };
+
+    func.line(format!("let request = grpc::Request::new(\"{}\", request);", path))
+        .line(format!("let mut client = grpc::Client::new({}::new(), grpc::IntoBody::new(&mut self.inner));", codec_path))
+        .line(call)
+        .line(map_future)
+        ;
+
+    func
 }
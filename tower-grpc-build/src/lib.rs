@@ -4,21 +4,26 @@ extern crate prost_build;
 mod client;
 mod server;
 
+use std::env;
 use std::io;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::fmt::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 use std::rc::Rc;
 
 /// Code generation configuration
 pub struct Config {
     prost: prost_build::Config,
     inner: Rc<RefCell<Inner>>,
+    file_descriptor_set_path: Option<PathBuf>,
 }
 
 struct Inner {
     build_client: bool,
     build_server: bool,
+    codecs: HashMap<String, String>,
 }
 
 struct ServiceGenerator {
@@ -38,6 +43,9 @@ impl Config {
 
             // Disable server code gen by default
             build_server: false,
+
+            // Services use protobuf unless configured otherwise
+            codecs: HashMap::new(),
         }));
 
         // Set the service generator
@@ -50,6 +58,7 @@ impl Config {
         Config {
             prost,
             inner,
+            file_descriptor_set_path: None,
         }
     }
 
@@ -65,28 +74,109 @@ impl Config {
         self
     }
 
+    /// Use `codec` to encode and decode the messages of `service`.
+    ///
+    /// `service` is the fully qualified protobuf name of the service, such
+    /// as `helloworld.Greeter`. `codec` is the path to a type generic over
+    /// the encoded and decoded message types, with a `new()` constructor,
+    /// that implements `tower_grpc::client::Codec` and
+    /// `tower_grpc::server::Codec`, such as `::tower_grpc::json::Codec`.
+    ///
+    /// Services without a configured codec use `tower_grpc::protobuf::Codec`.
+    pub fn codec<S, C>(&mut self, service: S, codec: C) -> &mut Self
+    where S: Into<String>,
+          C: Into<String>,
+    {
+        self.inner.borrow_mut().codecs.insert(service.into(), codec.into());
+        self
+    }
+
+    /// Also write the encoded `FileDescriptorSet` of the protos to `path`.
+    ///
+    /// The set includes all imported files, and may be used to serve
+    /// `tower_grpc::reflection`. `protoc` is taken from the `PROTOC`
+    /// environment variable, or otherwise found on the `PATH`.
+    pub fn file_descriptor_set_path<P>(&mut self, path: P) -> &mut Self
+    where P: Into<PathBuf>,
+    {
+        self.file_descriptor_set_path = Some(path.into());
+        self
+    }
+
     /// Generate code
     pub fn build<P>(&self, protos: &[P], includes: &[P]) -> io::Result<()>
     where P: AsRef<Path>,
     {
-        self.prost.compile_protos(protos, includes)
+        self.prost.compile_protos(protos, includes)?;
+
+        if let Some(ref path) = self.file_descriptor_set_path {
+            write_file_descriptor_set(path, protos, includes)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_file_descriptor_set<P>(path: &Path, protos: &[P], includes: &[P]) -> io::Result<()>
+where P: AsRef<Path>,
+{
+    let protoc = env::var_os("PROTOC").unwrap_or_else(|| "protoc".into());
+
+    let mut cmd = Command::new(protoc);
+    cmd.arg("--include_imports")
+        .arg("--include_source_info")
+        .arg("-o").arg(path);
+
+    for include in includes {
+        cmd.arg("-I").arg(include.as_ref());
+    }
+
+    for proto in protos {
+        cmd.arg(proto.as_ref());
+    }
+
+    let output = cmd.output()?;
+
+    if !output.status.success() {
+        let msg = format!("protoc failed: {}", String::from_utf8_lossy(&output.stderr));
+        return Err(io::Error::new(io::ErrorKind::Other, msg));
     }
+
+    Ok(())
+}
+
+/// Splits a message type into the path to import it from the generated
+/// module, and its name.
+fn super_import(ty: &str) -> (String, &str) {
+    let mut v: Vec<&str> = ty.split("::").collect();
+    v.insert(0, "super");
+    let last = v.pop().unwrap_or(ty);
+    (v.join("::"), last)
+}
+
+fn unqualified(ty: &str) -> &str {
+    ty.rsplit("::").next().unwrap_or(ty)
 }
 
 impl prost_build::ServiceGenerator for ServiceGenerator {
     fn generate(&self, service: prost_build::Service, buf: &mut String) {
         let inner = self.inner.borrow();
 
+        let name = format!("{}.{}", service.package, service.proto_name);
+        let codec = inner.codecs.get(&name)
+            .map(|codec| &codec[..])
+            .unwrap_or("grpc::Protobuf");
+
         if inner.build_client {
             // Add an extra new line to separate messages
             write!(buf, "\n").unwrap();
 
-            self.client.generate(&service, buf).unwrap();
+            self.client.generate(&service, codec, buf).unwrap();
         }
 
         if inner.build_server {
             write!(buf, "\n\n").unwrap();
-            self.server.generate(&service, buf).unwrap();
+            self.server.generate(&service, codec, buf).unwrap();
         }
     }
 }
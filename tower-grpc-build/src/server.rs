@@ -5,22 +5,13 @@ use prost_build;
 use std::ascii::AsciiExt;
 use std::fmt;
 
-fn super_import(ty: &str) -> (String, &str) {
-    let mut v: Vec<&str> = ty.split("::").collect();
-    v.insert(0, "super");
-    let last = v.pop().unwrap_or(ty);
-    (v.join("::"), last)
-}
-
-fn unqualified(ty: &str) -> &str {
-    ty.rsplit("::").next().unwrap_or(ty)
-}
+use super::{super_import, unqualified};
 
 /// Generates service code
 pub struct ServiceGenerator;
 
 impl ServiceGenerator {
-    fn define(&self, service: &prost_build::Service) -> codegen::Scope {
+    fn define(&self, service: &prost_build::Service, codec: &str) -> codegen::Scope {
         // Name of the support module. This is the module that will contain all
         // the extra types for the service to avoid potential name conflicts
         // with other services.
@@ -84,6 +75,15 @@ impl ServiceGenerator {
         let mut new_service_not_implemented_ty = codegen::Type::new("NewService");
 
         let mut new_block = codegen::Block::new(&format!("inner: {}", service.name));
+        new_block.after(",");
+
+        // Applies the configured message size limits to each method service.
+        let mut new_service_fn = codegen::Function::new("new_service");
+        new_service_fn
+            .arg_ref_self()
+            .ret("Self::Future")
+            .line("let mut inner = self.inner.clone();")
+            ;
 
         // Create a response future as an enumeration of the service methods
         // response futures.
@@ -150,16 +150,16 @@ impl ServiceGenerator {
             // Push a generic representing the method service
             service_struct.generic(&method.proto_name);
 
-            let service_bound = service_bound_for(method);
+            let service_bound = service_bound_for(method, codec);
 
             // Bound a bound requiring that the service is of the appropriate
             // type.
             service_struct.bound(&method.proto_name, &service_bound);
 
             // Push a field to hold the service
-            service_struct.field(&method.name, field_type_for(&method));
+            service_struct.field(&method.name, field_type_for(&method, codec));
 
-            let ty = unimplemented_type_for(method);
+            let ty = unimplemented_type_for(method, codec);
             service_not_implemented_ty.generic(&ty);
             new_service_not_implemented_ty.generic(&ty);
 
@@ -209,7 +209,12 @@ impl ServiceGenerator {
                 .bound(&method.proto_name, &service_bound)
                 ;
 
-            new_block.line(&format!("{}: {},", method.name, new_unimplemented_for(&method)));
+            new_block.line(&format!("{}: {},", method.name, new_unimplemented_for(&method, codec)));
+
+            new_service_fn
+                .line(&format!("inner.{}.set_max_decoding_message_size(self.max_decoding_message_size);", method.name))
+                .line(&format!("inner.{}.set_max_encoding_message_size(self.max_encoding_message_size);", method.name))
+                ;
 
             // ===== ResponseFuture =====
 
@@ -349,16 +354,19 @@ impl ServiceGenerator {
 
         new_service
             .field("inner", service_struct.ty())
+            .field("max_decoding_message_size", "Option<usize>")
+            .field("max_encoding_message_size", "Option<usize>")
             ;
 
 
         // Generate all the builder functions
         for (i, m1) in service.methods.iter().enumerate() {
-            let service_bound = service_bound_for(m1);
+            let service_bound = service_bound_for(m1, codec);
 
             let mut ret = codegen::Type::new("NewService");
 
             let mut build = codegen::Block::new(&format!("inner: {}", &service.name));
+            build.after(",");
 
             // Push all generics onto the return type
             for (j, m2) in service.methods.iter().enumerate() {
@@ -378,26 +386,57 @@ impl ServiceGenerator {
                 .arg_self()
                 .arg("service", "T")
                 .ret(ret)
-                .line(new_service_line_for(&m1))
+                .line(new_service_line_for(&m1, codec))
                 .line("")
                 .block({
                     let mut b = codegen::Block::new("NewService");
                     b.block(build);
+                    b.line("max_decoding_message_size: self.max_decoding_message_size,");
+                    b.line("max_encoding_message_size: self.max_encoding_message_size,");
                     b
                 })
                 ;
         }
 
+        // Builder functions for the message size limits
+        new_service_builder_impl.function("max_decoding_message_size")
+            .vis("pub")
+            .arg_self()
+            .arg("limit", "usize")
+            .ret("Self")
+            .line("NewService { max_decoding_message_size: Some(limit), ..self }")
+            ;
+
+        new_service_builder_impl.function("max_encoding_message_size")
+            .vis("pub")
+            .arg_self()
+            .arg("limit", "usize")
+            .ret("Self")
+            .line("NewService { max_encoding_message_size: Some(limit), ..self }")
+            ;
+
+        // Wraps the service so that `interceptor` observes every call
+        new_service_builder_impl.function("intercept")
+            .vis("pub")
+            .generic("I")
+            .bound("I", "grpc::Interceptor + Clone")
+            .arg_self()
+            .arg("interceptor", "I")
+            .ret("grpc::Intercept<Self, I>")
+            .line("grpc::Intercept::new(self, interceptor)")
+            ;
+
         let mut http_response_ty = codegen::Type::new("http::Response");
         http_response_ty.generic(response_body.ty());
 
+        new_service_fn
+            .line("futures::ok(inner)")
+            ;
+
         new_service_trait_impl
             .associate_type("Response", &http_response_ty)
             .associate_type("Service", service_struct.ty())
-            .function("new_service")
-            .arg_ref_self()
-            .ret("Self::Future")
-            .line("futures::ok(self.inner.clone())")
+            .push_function(new_service_fn)
             ;
 
         let mut new_service_not_implemented_impl = codegen::Impl::new(new_service_not_implemented_ty);
@@ -406,13 +445,16 @@ impl ServiceGenerator {
             .vis("pub(super)")
             .ret("Self")
             .block(codegen::Block::new("NewService")
-                   .block(new_block).clone())
+                   .block(new_block)
+                   .line("max_decoding_message_size: None,")
+                   .line("max_encoding_message_size: None,")
+                   .clone())
             ;
 
         // ===== ResponseFuture =====
 
         let mut ty = codegen::Type::new("Result");
-        ty.generic(response_fut_kind(service));
+        ty.generic(response_fut_kind(service, codec));
         ty.generic("grpc::Status");
 
         response_fut
@@ -445,7 +487,7 @@ impl ServiceGenerator {
         // ===== ResponseBody =====
 
         let mut ty = codegen::Type::new("Result");
-        ty.generic(response_body_kind(service));
+        ty.generic(response_body_kind(service, codec));
         ty.generic("grpc::Status");
 
         response_body.field("kind", ty);
@@ -535,38 +577,58 @@ impl ServiceGenerator {
 
     }
 
-    pub fn generate(&self, service: &prost_build::Service, buf: &mut String) -> fmt::Result {
-        let scope = self.define(service);
+    pub fn generate(&self, service: &prost_build::Service, codec: &str, buf: &mut String) -> fmt::Result {
+        let scope = self.define(service, codec);
         let mut fmt = codegen::Formatter::new(buf);
 
         scope.fmt(&mut fmt)
     }
 }
 
-fn field_type_for(method: &prost_build::Method) -> codegen::Type {
-    let ty = match (method.client_streaming, method.server_streaming) {
+/// The codec type that encodes the responses and decodes the requests of
+/// `method`.
+fn codec_for(method: &prost_build::Method, codec: &str) -> String {
+    format!("{}<{}, {}>", codec,
+            unqualified(&method.output_type), unqualified(&method.input_type))
+}
+
+/// The stream of requests to `method`, decoded by `codec`.
+fn decode_for(method: &prost_build::Method, codec: &str) -> String {
+    format!("grpc::codec::Decode<<{} as grpc::codec::Codec>::Decoder>",
+            codec_for(method, codec))
+}
+
+/// The service that handles `method` once it has been wrapped for its kind
+/// of RPC.
+fn method_service_for(method: &prost_build::Method, codec: &str) -> String {
+    match (method.client_streaming, method.server_streaming) {
         (false, false) => {
-            format!("grpc::Grpc<grpc::Unary<{}, grpc::Decode<{}>>>",
-                    method.proto_name, method.input_type)
+            format!("grpc::Unary<{}, {}>",
+                    method.proto_name, decode_for(method, codec))
         }
         (false, true) => {
-            format!("grpc::Grpc<grpc::ServerStreaming<{}, grpc::Decode<{}>>>",
-                    method.proto_name, method.input_type)
+            format!("grpc::ServerStreaming<{}, {}>",
+                    method.proto_name, decode_for(method, codec))
         }
         (true, false) => {
-            format!("grpc::Grpc<grpc::ClientStreaming<{}>>",
+            format!("grpc::ClientStreaming<{}>",
                     method.proto_name)
         }
         (true, true) => {
-            format!("grpc::Grpc<{}>",
+            format!("grpc::Streaming<{}>",
                     method.proto_name)
         }
-    };
+    }
+}
+
+fn field_type_for(method: &prost_build::Method, codec: &str) -> codegen::Type {
+    let ty = format!("grpc::codec::Grpc<{}, {}>",
+                     method_service_for(method, codec), codec_for(method, codec));
 
     codegen::Type::from(ty)
 }
 
-fn service_bound_for(method: &prost_build::Method) -> codegen::Type {
+fn service_bound_for(method: &prost_build::Method, codec: &str) -> codegen::Type {
 
     let input_type = unqualified(&method.input_type);
     let output_type = unqualified(&method.output_type);
@@ -581,36 +643,30 @@ fn service_bound_for(method: &prost_build::Method) -> codegen::Type {
                     input_type, output_type)
         }
         (true, false) => {
-            format!("grpc::ClientStreamingService<Request = {input}, RequestStream = grpc::Decode<{input}>, Response = {output}>",
-                    input = input_type, output = output_type)
+            format!("grpc::ClientStreamingService<Request = {input}, RequestStream = {decode}, Response = {output}>",
+                    input = input_type, decode = decode_for(method, codec), output = output_type)
         }
         (true, true) => {
-            format!("grpc::GrpcService<Request = {input}, RequestStream = grpc::Decode<{input}>, Response = {output}>",
-                    input = input_type, output = output_type)
+            format!("grpc::GrpcService<Request = {input}, RequestStream = {decode}, Response = {output}>",
+                    input = input_type, decode = decode_for(method, codec), output = output_type)
         }
     };
 
     codegen::Type::from(ty)
 }
 
-fn new_service_line_for(method: &prost_build::Method) -> &'static str {
-    match (method.client_streaming, method.server_streaming) {
-        (false, false) => {
-            "let service = grpc::Grpc::new(grpc::Unary::new(service));"
-        }
-        (false, true) => {
-            "let service = grpc::Grpc::new(grpc::ServerStreaming::new(service));"
-        }
-        (true, false) => {
-            "let service = grpc::Grpc::new(grpc::ClientStreaming::new(service));"
-        }
-        (true, true) => {
-            "let service = grpc::Grpc::new(service);"
-        }
-    }
+fn new_service_line_for(method: &prost_build::Method, codec: &str) -> String {
+    let service = match (method.client_streaming, method.server_streaming) {
+        (false, false) => "grpc::Unary::new(service)",
+        (false, true) => "grpc::ServerStreaming::new(service)",
+        (true, false) => "grpc::ClientStreaming::new(service)",
+        (true, true) => "grpc::Streaming::new(service)",
+    };
+
+    format!("let service = grpc::codec::Grpc::new({}, {}::new());", service, codec)
 }
 
-fn unimplemented_type_for(method: &prost_build::Method) -> codegen::Type {
+fn unimplemented_type_for(method: &prost_build::Method, codec: &str) -> codegen::Type {
     let ty = match (method.client_streaming, method.server_streaming) {
         (false, false) => {
             format!("grpc::NotImplemented<{}, {}>",
@@ -621,38 +677,32 @@ fn unimplemented_type_for(method: &prost_build::Method) -> codegen::Type {
                     unqualified(&method.input_type), unqualified(&method.output_type))
         }
         (true, false) => {
-            format!("grpc::NotImplemented<grpc::Decode<{}>, {}>",
-                    unqualified(&method.input_type), unqualified(&method.output_type))
+            format!("grpc::NotImplemented<{}, {}>",
+                    decode_for(method, codec), unqualified(&method.output_type))
         }
         (true, true) => {
-            format!("grpc::NotImplemented<grpc::Decode<{}>, grpc::unary::Once<{}>>",
-                    unqualified(&method.input_type), unqualified(&method.output_type))
+            format!("grpc::NotImplemented<{}, grpc::unary::Once<{}>>",
+                    decode_for(method, codec), unqualified(&method.output_type))
         }
     };
 
     codegen::Type::from(ty)
 }
 
-fn new_unimplemented_for(method: &prost_build::Method) -> &'static str {
-    match (method.client_streaming, method.server_streaming) {
-        (false, false) => {
-            "grpc::Grpc::new(grpc::Unary::new(grpc::NotImplemented::new()))"
-        }
-        (false, true) => {
-            "grpc::Grpc::new(grpc::ServerStreaming::new(grpc::NotImplemented::new()))"
-        }
-        (true, false) => {
-            "grpc::Grpc::new(grpc::ClientStreaming::new(grpc::NotImplemented::new()))"
-        }
-        (true, true) => {
-            "grpc::Grpc::new(grpc::NotImplemented::new())"
-        }
-    }
+fn new_unimplemented_for(method: &prost_build::Method, codec: &str) -> String {
+    let service = match (method.client_streaming, method.server_streaming) {
+        (false, false) => "grpc::Unary::new(grpc::NotImplemented::new())",
+        (false, true) => "grpc::ServerStreaming::new(grpc::NotImplemented::new())",
+        (true, false) => "grpc::ClientStreaming::new(grpc::NotImplemented::new())",
+        (true, true) => "grpc::Streaming::new(grpc::NotImplemented::new())",
+    };
+
+    format!("grpc::codec::Grpc::new({}, {}::new())", service, codec)
 }
 
 // ===== Here be the crazy types =====
 
-fn response_fut_kind(service: &prost_build::Service) -> String {
+fn response_fut_kind(service: &prost_build::Service, codec: &str) -> String {
     use std::fmt::Write;
 
     // Handle theempty case...
@@ -663,31 +713,15 @@ fn response_fut_kind(service: &prost_build::Service) -> String {
     let mut ret = "Kind<\n".to_string();
 
     for method in &service.methods {
-        match (method.client_streaming, method.server_streaming) {
-            (false, false) => {
-                write!(&mut ret, "    <grpc::Grpc<grpc::Unary<{}, grpc::Decode<{}>>> as tower::Service>::Future,\n",
-                                 method.proto_name, method.input_type).unwrap();
-            }
-            (false, true) => {
-                write!(&mut ret, "    <grpc::Grpc<grpc::ServerStreaming<{}, grpc::Decode<{}>>> as tower::Service>::Future,\n",
-                                 method.proto_name, method.input_type).unwrap();
-            }
-            (true, false) => {
-                write!(&mut ret, "    <grpc::Grpc<grpc::ClientStreaming<{}>> as tower::Service>::Future,\n",
-                                 method.proto_name).unwrap();
-            }
-            (true, true) => {
-                write!(&mut ret, "    <grpc::Grpc<{}> as tower::Service>::Future,\n",
-                                 method.proto_name).unwrap();
-            }
-        }
+        write!(&mut ret, "    <grpc::codec::Grpc<{}, {}> as tower::Service>::Future,\n",
+                         method_service_for(method, codec), codec_for(method, codec)).unwrap();
     }
 
     ret.push_str(">");
     ret
 }
 
-fn response_body_kind(service: &prost_build::Service) -> String {
+fn response_body_kind(service: &prost_build::Service, codec: &str) -> String {
     use std::fmt::Write;
 
     // Handle theempty case...
@@ -698,24 +732,8 @@ fn response_body_kind(service: &prost_build::Service) -> String {
     let mut ret = "Kind<\n".to_string();
 
     for method in &service.methods {
-        match (method.client_streaming, method.server_streaming) {
-            (false, false) => {
-                write!(&mut ret, "    grpc::Encode<<grpc::Unary<{}, grpc::Decode<{}>> as grpc::GrpcService>::ResponseStream>,\n",
-                                 method.proto_name, method.input_type).unwrap();
-            }
-            (false, true) => {
-                write!(&mut ret, "    grpc::Encode<<grpc::ServerStreaming<{}, grpc::Decode<{}>> as grpc::GrpcService>::ResponseStream>,\n",
-                                 method.proto_name, method.input_type).unwrap();
-            }
-            (true, false) => {
-                write!(&mut ret, "    grpc::Encode<<grpc::ClientStreaming<{}> as grpc::GrpcService>::ResponseStream>,\n",
-                                 method.proto_name).unwrap();
-            }
-            (true, true) => {
-                write!(&mut ret, "    grpc::Encode<<{} as grpc::GrpcService>::ResponseStream>,\n",
-                                 method.proto_name).unwrap();
-            }
-        }
+        write!(&mut ret, "    grpc::codec::Encode<<{} as grpc::GrpcService>::ResponseStream, <{} as grpc::codec::Codec>::Encoder>,\n",
+                         method_service_for(method, codec), codec_for(method, codec)).unwrap();
     }
 
     ret.push_str(">");
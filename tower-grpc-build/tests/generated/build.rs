@@ -0,0 +1,19 @@
+extern crate tower_grpc_build;
+
+fn main() {
+    // Compiled by `src/lib.rs` and compared against `tests/snapshots`.
+    tower_grpc_build::Config::new()
+        .enable_client(true)
+        .enable_server(false)
+        .build(&["protos/echo.proto"], &["protos"])
+        .unwrap();
+
+    // Only inspected by `tests/codecs.rs`, as the messages do not
+    // implement the serde traits the JSON codec requires.
+    tower_grpc_build::Config::new()
+        .enable_client(true)
+        .enable_server(true)
+        .codec("json_echo.Echo", "::tower_grpc::json::Codec")
+        .build(&["protos/json_echo.proto"], &["protos"])
+        .unwrap();
+}
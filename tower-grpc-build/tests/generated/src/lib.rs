@@ -0,0 +1,13 @@
+//! Code generated by `tower-grpc-build` from `protos/echo.proto`.
+//!
+//! This crate only exists to check that the generated code compiles.
+
+extern crate bytes;
+extern crate prost;
+#[macro_use]
+extern crate prost_derive;
+extern crate tower_grpc;
+
+pub mod echo {
+    include!(concat!(env!("OUT_DIR"), "/echo.rs"));
+}
@@ -0,0 +1,10 @@
+const JSON_ECHO: &'static str = include_str!(concat!(env!("OUT_DIR"), "/json_echo.rs"));
+
+#[test]
+fn configured_codec() {
+    // Clients encode requests and decode responses, servers the reverse.
+    assert!(JSON_ECHO.contains("::tower_grpc::json::Codec<EchoRequest, EchoResponse>"));
+    assert!(JSON_ECHO.contains("::tower_grpc::json::Codec<EchoResponse, EchoRequest>"));
+    assert!(JSON_ECHO.contains("::tower_grpc::json::Codec::new()"));
+    assert!(!JSON_ECHO.contains("grpc::Protobuf"));
+}
@@ -0,0 +1,8 @@
+const ECHO: &'static str = include_str!(concat!(env!("OUT_DIR"), "/echo.rs"));
+
+#[test]
+fn echo_client() {
+    let client = &ECHO[ECHO.find("pub mod client").expect("client module")..];
+
+    assert_eq!(client, include_str!("snapshots/echo_client.rs"));
+}
@@ -0,0 +1,68 @@
+pub mod client {
+    use ::tower_grpc::codegen::client::*;
+    use super::{EchoRequest, EchoResponse};
+
+    /// Client for the `echo.Echo` service
+    #[derive(Debug, Clone)]
+    pub struct Echo<T> {
+        inner: T,
+    }
+
+    impl<T> Echo<T> {
+        pub fn new(inner: T) -> Self {
+            Echo {
+                inner,
+            }
+        }
+
+        /// Poll whether this client is ready to send another request
+        pub fn poll_ready<R>(&mut self) -> futures::Poll<(), grpc::Error<T::Error>>
+        where T: tower::Service<Request = http::Request<R>>,
+        {
+            tower::Service::poll_ready(&mut self.inner)
+                .map_err(grpc::Error::Inner)
+        }
+
+        pub fn unary_echo<R>(&mut self, request: EchoRequest) -> grpc::Unary<grpc::ResponseFuture<grpc::Protobuf<EchoRequest, EchoResponse>, T::Future>, grpc::Protobuf<EchoRequest, EchoResponse>>
+        where T: tower::Service<Request = http::Request<R>, Response = http::Response<tower_h2::RecvBody>>,
+              R: From<grpc::codec::EncodingBody<grpc::Protobuf<EchoRequest, EchoResponse>, grpc::codec::Unary<EchoRequest>>>,
+        {
+            let request = grpc::Request::new("/echo.Echo/UnaryEcho", request);
+            let mut client = grpc::Client::new(grpc::Protobuf::new(), grpc::IntoBody::new(&mut self.inner));
+            let response = tower::Service::call(&mut client, request.into_unary());
+            grpc::Unary::map_future(response)
+        }
+
+        pub fn server_streaming_echo<R>(&mut self, request: EchoRequest) -> grpc::Streaming<grpc::ResponseFuture<grpc::Protobuf<EchoRequest, EchoResponse>, T::Future>, grpc::codec::DecodingBody<grpc::Protobuf<EchoRequest, EchoResponse>>>
+        where T: tower::Service<Request = http::Request<R>, Response = http::Response<tower_h2::RecvBody>>,
+              R: From<grpc::codec::EncodingBody<grpc::Protobuf<EchoRequest, EchoResponse>, grpc::codec::Unary<EchoRequest>>>,
+        {
+            let request = grpc::Request::new("/echo.Echo/ServerStreamingEcho", request);
+            let mut client = grpc::Client::new(grpc::Protobuf::new(), grpc::IntoBody::new(&mut self.inner));
+            let response = tower::Service::call(&mut client, request.into_unary());
+            grpc::Streaming::map_future(response)
+        }
+
+        pub fn client_streaming_echo<R, S>(&mut self, request: S) -> grpc::Unary<grpc::ResponseFuture<grpc::Protobuf<EchoRequest, EchoResponse>, T::Future>, grpc::Protobuf<EchoRequest, EchoResponse>>
+        where T: tower::Service<Request = http::Request<R>, Response = http::Response<tower_h2::RecvBody>>,
+              R: From<grpc::codec::EncodingBody<grpc::Protobuf<EchoRequest, EchoResponse>, S>>,
+              S: futures::Stream<Item = EchoRequest>,
+        {
+            let request = grpc::Request::new("/echo.Echo/ClientStreamingEcho", request);
+            let mut client = grpc::Client::new(grpc::Protobuf::new(), grpc::IntoBody::new(&mut self.inner));
+            let response = tower::Service::call(&mut client, request);
+            grpc::Unary::map_future(response)
+        }
+
+        pub fn bidirectional_streaming_echo<R, S>(&mut self, request: S) -> grpc::Streaming<grpc::ResponseFuture<grpc::Protobuf<EchoRequest, EchoResponse>, T::Future>, grpc::codec::DecodingBody<grpc::Protobuf<EchoRequest, EchoResponse>>>
+        where T: tower::Service<Request = http::Request<R>, Response = http::Response<tower_h2::RecvBody>>,
+              R: From<grpc::codec::EncodingBody<grpc::Protobuf<EchoRequest, EchoResponse>, S>>,
+              S: futures::Stream<Item = EchoRequest>,
+        {
+            let request = grpc::Request::new("/echo.Echo/BidirectionalStreamingEcho", request);
+            let mut client = grpc::Client::new(grpc::Protobuf::new(), grpc::IntoBody::new(&mut self.inner));
+            let response = tower::Service::call(&mut client, request);
+            grpc::Streaming::map_future(response)
+        }
+    }
+}
@@ -1,10 +1,16 @@
 extern crate tower_grpc_build;
 
+use std::env;
+use std::path::PathBuf;
+
 fn main() {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+
     // Build helloworld
     tower_grpc_build::Config::new()
         .enable_server(true)
         .enable_client(false)
+        .file_descriptor_set_path(out_dir.join("helloworld.bin"))
         .build(&["proto/helloworld/helloworld.proto"], &["proto/helloworld"])
         .unwrap();
 
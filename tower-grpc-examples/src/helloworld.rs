@@ -23,6 +23,9 @@ use tokio_core::reactor::Core;
 use tower::Service;
 use tower_h2::Server;
 use tower_grpc::{Request, Response};
+use tower_grpc::health::{self, Health};
+use tower_grpc::reflection::{self, ServerReflection};
+use tower_grpc::server::Router;
 
 #[derive(Clone, Debug)]
 struct Greet;
@@ -54,10 +57,22 @@ pub fn main() {
     let mut core = Core::new().unwrap();
     let reactor = core.handle();
 
-    let new_service = server::Greeter::new_service()
+    let greeter = server::Greeter::new_service()
         .say_hello(Greet)
         ;
 
+    let reflection = ServerReflection::builder()
+        .register_file_descriptor_set(include_bytes!(concat!(env!("OUT_DIR"), "/helloworld.bin")))
+        .expect("decode helloworld descriptors")
+        .build();
+
+    // Serve health checks and reflection alongside the greeter.
+    let new_service = Router::builder()
+        .add_service("helloworld.Greeter", greeter)
+        .add_service(health::NAME, Health::new())
+        .add_service(reflection::NAME, reflection)
+        .build();
+
     let h2 = Server::new(new_service, Default::default(), reactor.clone());
 
     let addr = "[::1]:50051".parse().unwrap();
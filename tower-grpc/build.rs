@@ -0,0 +1,17 @@
+#[cfg(feature = "protobuf")]
+extern crate prost_build;
+
+#[cfg(feature = "protobuf")]
+fn main() {
+    let protos = &[
+        "proto/grpc/health/v1/health.proto",
+        "proto/grpc/reflection/v1alpha/reflection.proto",
+    ];
+
+    prost_build::compile_protos(protos, &["proto"]).unwrap();
+}
+
+// The health and reflection services are only available with protobuf
+// support.
+#[cfg(not(feature = "protobuf"))]
+fn main() {}
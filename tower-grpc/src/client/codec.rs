@@ -1,4 +1,5 @@
 use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
 
 use bytes::{Buf, BufMut, Bytes, BytesMut, BigEndian};
 use futures::{Async, Stream, Poll};
@@ -56,8 +57,17 @@ pub struct EncodingBody<E, S> {
     buf: BytesMut,
     encoder: E,
     stream: S,
+    max_message_size: Option<usize>,
+    local_status: LocalStatus,
 }
 
+/// The status of a call that failed before its request was sent.
+///
+/// The request body sets this when it refuses to send a message, so that the
+/// response fails with the status rather than with the reset of the stream.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LocalStatus(Arc<Mutex<Option<Status>>>);
+
 #[must_use = "futures do nothing unless polled"]
 #[derive(Debug)]
 pub struct DecodingBody<D> {
@@ -65,6 +75,7 @@ pub struct DecodingBody<D> {
     decoder: D,
     state: DecodingState,
     stream: RecvBody,
+    max_message_size: Option<usize>,
 }
 
 #[derive(Debug)]
@@ -168,13 +179,19 @@ impl Buf for BytesList {
 }
 
 impl<E, S> EncodingBody<E, S> {
-    pub(crate) fn new(encoder: E, stream: S) -> Self {
+    pub(crate) fn new(encoder: E, stream: S, max_message_size: Option<usize>) -> Self {
         EncodingBody {
             buf: BytesMut::new(),
             encoder,
             stream,
+            max_message_size,
+            local_status: LocalStatus::default(),
         }
     }
+
+    pub(crate) fn local_status(&self) -> LocalStatus {
+        self.local_status.clone()
+    }
 }
 
 impl<E, S> tower_h2::Body for EncodingBody<E, S>
@@ -200,6 +217,14 @@ where
             // now that we know length, we can write the header
             let len = self.buf.len() - 5;
             assert!(len <= ::std::u32::MAX as usize);
+
+            if exceeds_limit(len, self.max_message_size) {
+                debug!("encoded message of {} bytes exceeds limit", len);
+                self.buf.clear();
+                self.local_status.set(Status::RESOURCE_EXHAUSTED);
+                return Err(Status::RESOURCE_EXHAUSTED.into());
+            }
+
             {
                 let mut cursor = ::std::io::Cursor::new(&mut self.buf[..5]);
                 cursor.put_u8(0); // byte must be 0, reserve doesn't auto-zero
@@ -213,6 +238,20 @@ where
     }
 }
 
+impl LocalStatus {
+    fn set(&self, status: Status) {
+        *self.0.lock().expect("local status lock") = Some(status);
+    }
+
+    pub(crate) fn get(&self) -> Option<Status> {
+        self.0.lock().expect("local status lock").clone()
+    }
+}
+
+pub(crate) fn exceeds_limit(len: usize, limit: Option<usize>) -> bool {
+    limit.map(|max| len > max).unwrap_or(false)
+}
+
 fn grpc_status(trailers: &HeaderMap) -> Result<(), Status> {
     match check_grpc_status(&trailers) {
         Some(status) => if status.code() == ::Code::OK {
@@ -232,7 +271,7 @@ impl<D> DecodingBody<D>
 where
     D: Codec,
 {
-    pub(crate) fn new(decoder: D, stream: RecvBody) -> Self {
+    pub(crate) fn new(decoder: D, stream: RecvBody, max_message_size: Option<usize>) -> Self {
         DecodingBody {
             bufs: BytesList {
                 bufs: VecDeque::new(),
@@ -240,6 +279,7 @@ where
             decoder,
             state: DecodingState::ReadHeader,
             stream,
+            max_message_size,
         }
     }
 
@@ -262,6 +302,12 @@ where
             };
             let len = self.bufs.get_u32::<BigEndian>() as usize;
 
+            // Reject oversized messages before buffering any of their bytes.
+            if exceeds_limit(len, self.max_message_size) {
+                debug!("message of {} bytes exceeds limit", len);
+                return Err(Status::RESOURCE_EXHAUSTED);
+            }
+
             self.state = DecodingState::ReadBody {
                 compression: is_compressed,
                 len,
@@ -381,3 +427,91 @@ mod inner {
     pub struct Void(Void_);
     enum Void_ {}
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::stream;
+
+    const LIMIT: usize = 16;
+
+    /// Passes message bytes through unchanged.
+    #[derive(Clone)]
+    struct Raw;
+
+    impl Codec for Raw {
+        const CONTENT_TYPE: &'static str = "application/grpc+raw";
+
+        type Encode = Vec<u8>;
+        type Decode = Vec<u8>;
+        type EncodeError = ();
+        type DecodeError = ();
+
+        fn encode(&mut self, item: Vec<u8>, buf: &mut EncodeBuf) -> Result<(), ()> {
+            buf.reserve(item.len());
+            buf.put_slice(&item);
+            Ok(())
+        }
+
+        fn decode(&mut self, buf: &mut DecodeBuf) -> Result<Vec<u8>, ()> {
+            let mut item = vec![0; buf.remaining()];
+            buf.copy_to_slice(&mut item);
+            Ok(item)
+        }
+    }
+
+    fn frame(len: usize) -> Bytes {
+        let mut buf = BytesMut::with_capacity(5 + len);
+        buf.put_u8(0);
+        buf.put_u32::<BigEndian>(len as u32);
+        buf.put_slice(&vec![0; len]);
+        buf.freeze()
+    }
+
+    fn encode(len: usize) -> (Poll<Option<Bytes>, h2::Error>, Option<Status>) {
+        let items = stream::iter_ok::<_, ()>(vec![vec![0; len]]);
+        let mut body = EncodingBody::new(Raw, items, Some(LIMIT));
+        let res = body.poll_data();
+        (res, body.local_status().get())
+    }
+
+    fn decode(len: usize) -> Result<Vec<u8>, Status> {
+        let body = RecvBody::from_stream(stream::iter_ok::<_, h2::Error>(vec![frame(len)]));
+        match DecodingBody::new(Raw, body, Some(LIMIT)).poll() {
+            Ok(Async::Ready(Some(item))) => Ok(item),
+            Err(::Error::Grpc(status)) => Err(status),
+            res => panic!("unexpected poll result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn encode_within_limit() {
+        for &len in &[LIMIT - 1, LIMIT] {
+            match encode(len) {
+                (Ok(Async::Ready(Some(data))), None) => assert_eq!(data, frame(len)),
+                res => panic!("unexpected poll_data result: {:?}", res),
+            }
+        }
+    }
+
+    #[test]
+    fn encode_over_limit_is_resource_exhausted() {
+        let (res, status) = encode(LIMIT + 1);
+        assert!(res.is_err());
+        let status = status.expect("local status");
+        assert_eq!(status.code(), Status::RESOURCE_EXHAUSTED.code());
+    }
+
+    #[test]
+    fn decode_within_limit() {
+        for &len in &[LIMIT - 1, LIMIT] {
+            assert_eq!(decode(len).unwrap().len(), len);
+        }
+    }
+
+    #[test]
+    fn decode_over_limit_is_resource_exhausted() {
+        let status = decode(LIMIT + 1).unwrap_err();
+        assert_eq!(status.code(), Status::RESOURCE_EXHAUSTED.code());
+    }
+}
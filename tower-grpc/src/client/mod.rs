@@ -1,14 +1,19 @@
 pub mod codec;
 
+use bytes::Bytes;
 use futures::{Async, Future, Poll, Stream};
+use h2;
 use http;
 use http::header::{HeaderMap, HeaderValue};
 use tower::Service;
-use tower_h2::RecvBody;
+use tower_h2::{Body, RecvBody};
+
+use std::fmt;
+use std::marker::PhantomData;
 
 pub use self::codec::Codec;
 
-use self::codec::{DecodingBody, EncodingBody};
+use self::codec::{DecodingBody, EncodingBody, LocalStatus};
 use ::Status;
 
 /// A gRPC client wrapping a `Service` over `h2`.
@@ -16,12 +21,20 @@ use ::Status;
 pub struct Client<C, S> {
     codec: C,
     service: S,
+
+    /// Largest message accepted from the server, if limited.
+    max_decoding_message_size: Option<usize>,
+
+    /// Largest message sent to the server, if limited.
+    max_encoding_message_size: Option<usize>,
 }
 
 #[must_use = "futures do nothing unless polled"]
 #[derive(Debug)]
 pub struct ResponseFuture<D, F> {
     decoder: Option<D>,
+    max_decoding_message_size: Option<usize>,
+    local_status: LocalStatus,
     future: F,
 }
 
@@ -41,6 +54,22 @@ pub struct Unary<F, D> where D: Codec {
     message: Option<D::Decode>,
 }
 
+/// Converts the bodies of requests into the body type of a service.
+///
+/// This allows a single HTTP/2 service to carry requests for RPCs whose
+/// messages differ, by accepting a body type (such as `BoxBody`) that each of
+/// their `EncodingBody` types converts into.
+#[derive(Debug)]
+pub struct IntoBody<S, B> {
+    inner: S,
+    _p: PhantomData<fn(B)>,
+}
+
+/// A type-erased request body.
+pub struct BoxBody {
+    inner: Box<Body<Data = Bytes> + Send>,
+}
+
 /// A stream of a future Response's body items.
 #[must_use = "streams do nothing unless polled"]
 #[derive(Debug)]
@@ -57,8 +86,28 @@ impl<C, S> Client<C, S> {
         Client {
             codec,
             service,
+            max_decoding_message_size: None,
+            max_encoding_message_size: None,
         }
     }
+
+    /// Limit the size of messages received from the server.
+    ///
+    /// A message whose length prefix exceeds `limit` fails the response
+    /// stream with `RESOURCE_EXHAUSTED` before any of it is buffered.
+    pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+        self.max_decoding_message_size = Some(limit);
+        self
+    }
+
+    /// Limit the size of messages sent to the server.
+    ///
+    /// A request message that encodes to more than `limit` bytes fails the
+    /// call with `RESOURCE_EXHAUSTED` instead of being sent.
+    pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+        self.max_encoding_message_size = Some(limit);
+        self
+    }
 }
 
 impl<C, S, R> Service for Client<C, S>
@@ -87,17 +136,89 @@ where
         let content_type = HeaderValue::from_static(C::CONTENT_TYPE);
         head.headers.insert(http::header::CONTENT_TYPE, content_type);
 
-        let encoded = EncodingBody::new(self.codec.clone(), body);
+        let encoded = EncodingBody::new(
+            self.codec.clone(),
+            body,
+            self.max_encoding_message_size);
+        let local_status = encoded.local_status();
         let req = http::Request::from_parts(head, encoded);
         let fut = self.service.call(req);
 
         ResponseFuture {
             decoder: Some(self.codec.clone()),
+            max_decoding_message_size: self.max_decoding_message_size,
+            local_status,
             future: fut,
         }
     }
 }
 
+// ====== impl IntoBody =====
+
+impl<S, B> IntoBody<S, B> {
+    pub fn new(inner: S) -> Self {
+        IntoBody {
+            inner,
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<S, B, T> Service for IntoBody<S, B>
+where
+    S: Service<Request=http::Request<T>>,
+    T: From<B>,
+{
+    type Request = http::Request<B>;
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self) -> Poll<(), Self::Error> {
+        self.inner.poll_ready()
+    }
+
+    fn call(&mut self, req: Self::Request) -> Self::Future {
+        let (head, body) = req.into_parts();
+        self.inner.call(http::Request::from_parts(head, body.into()))
+    }
+}
+
+// ====== impl BoxBody =====
+
+impl<E, S> From<EncodingBody<E, S>> for BoxBody
+where
+    EncodingBody<E, S>: Body<Data=Bytes> + Send + 'static,
+{
+    fn from(body: EncodingBody<E, S>) -> Self {
+        BoxBody {
+            inner: Box::new(body),
+        }
+    }
+}
+
+impl Body for BoxBody {
+    type Data = Bytes;
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn poll_data(&mut self) -> Poll<Option<Self::Data>, h2::Error> {
+        self.inner.poll_data()
+    }
+
+    fn poll_trailers(&mut self) -> Poll<Option<HeaderMap>, h2::Error> {
+        self.inner.poll_trailers()
+    }
+}
+
+impl fmt::Debug for BoxBody {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("BoxBody").finish()
+    }
+}
+
 // ====== impl ResponseFuture =====
 
 impl<D, F> Future for ResponseFuture<D, F>
@@ -109,14 +230,33 @@ where
     type Error = ::Error<F::Error>;
 
     fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        let res = try_ready!(self.future.poll().map_err(::Error::Inner));
+        // A request that failed before it was sent fails here, rather than
+        // with the reset of its stream.
+        if let Some(status) = self.local_status.get() {
+            return Err(::Error::Grpc(status));
+        }
+
+        let res = match self.future.poll() {
+            Ok(Async::Ready(res)) => res,
+            Ok(Async::NotReady) => return Ok(Async::NotReady),
+            Err(e) => {
+                let err = match self.local_status.get() {
+                    Some(status) => ::Error::Grpc(status),
+                    None => ::Error::Inner(e),
+                };
+                return Err(err);
+            }
+        };
         let (head, body) = res.into_parts();
 
         if let Some(status) = check_grpc_status(&head.headers) {
             return Err(::Error::Grpc(status));
         }
 
-        let decoded = DecodingBody::new(self.decoder.take().unwrap(), body);
+        let decoded = DecodingBody::new(
+            self.decoder.take().unwrap(),
+            body,
+            self.max_decoding_message_size);
         let res = http::Response::from_parts(head, decoded);
         let grpc = ::Response::from_http(res);
         Ok(Async::Ready(grpc))
@@ -0,0 +1,426 @@
+//! An implementation of the `grpc.health.v1.Health` service.
+//!
+//! Load balancers and tooling use this service to query whether a server,
+//! or an individual service on it, is able to handle requests. It is served
+//! alongside other services with `server::Router`.
+
+use {Request, Response, Status};
+use protobuf::server::{Grpc, Decode, Encode, ResponseFuture as GrpcFuture};
+use server::{ServerStreaming, Unary};
+use server::unary::Once;
+
+use bytes::Bytes;
+use futures::{Async, Future, Stream, Poll};
+use futures::future::{self, FutureResult};
+use futures::sync::mpsc;
+use h2;
+use http::{self, HeaderMap};
+use tower::{Service, NewService};
+use tower_h2::{Body, RecvBody};
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// Messages of the `grpc.health.v1` package.
+pub mod pb {
+    include!(concat!(env!("OUT_DIR"), "/grpc.health.v1.rs"));
+}
+
+pub use self::pb::health_check_response::ServingStatus;
+
+use self::pb::{HealthCheckRequest, HealthCheckResponse};
+
+/// The fully qualified name of the `Health` service.
+pub const NAME: &'static str = "grpc.health.v1.Health";
+
+/// The path prefix of all `Health` methods.
+pub const PREFIX: &'static str = "/grpc.health.v1.Health/";
+
+const CHECK: &'static str = "/grpc.health.v1.Health/Check";
+
+const WATCH: &'static str = "/grpc.health.v1.Health/Watch";
+
+/// Serves `grpc.health.v1.Health`.
+///
+/// Clones share the same set of statuses, so a clone may be kept to update
+/// them while another is being served. The server as a whole is reported
+/// under the empty service name, which is `SERVING` initially.
+#[derive(Clone)]
+pub struct Health {
+    check: Grpc<Unary<Check, Decode<HealthCheckRequest>>>,
+    watch: Grpc<ServerStreaming<Watch, Decode<HealthCheckRequest>>>,
+    statuses: Statuses,
+}
+
+pub struct ResponseFuture {
+    kind: Kind<
+        GrpcFuture<Unary<Check, Decode<HealthCheckRequest>>>,
+        GrpcFuture<ServerStreaming<Watch, Decode<HealthCheckRequest>>>,
+    >,
+}
+
+pub struct ResponseBody {
+    kind: Kind<Encode<Once<HealthCheckResponse>>, Encode<Updates>>,
+}
+
+enum Kind<C, W> {
+    Check(C),
+    Watch(W),
+    Err(Status),
+}
+
+type Statuses = Arc<Mutex<State>>;
+
+#[derive(Debug, Default)]
+struct State {
+    statuses: HashMap<String, ServingStatus>,
+
+    /// Senders of the status updates of each watched service
+    watchers: HashMap<String, Vec<mpsc::UnboundedSender<ServingStatus>>>,
+}
+
+/// Handles the `Check` method.
+#[derive(Debug, Clone)]
+struct Check {
+    statuses: Statuses,
+}
+
+/// Handles the `Watch` method.
+#[derive(Debug, Clone)]
+struct Watch {
+    statuses: Statuses,
+}
+
+/// The status updates of a watched service.
+#[derive(Debug)]
+struct Updates {
+    rx: mpsc::UnboundedReceiver<ServingStatus>,
+}
+
+// ===== impl Health =====
+
+impl Health {
+    pub fn new() -> Self {
+        let mut state = State::default();
+        state.statuses.insert(String::new(), ServingStatus::Serving);
+
+        let statuses = Arc::new(Mutex::new(state));
+        let check = Grpc::new(Unary::new(Check { statuses: statuses.clone() }));
+        let watch = Grpc::new(ServerStreaming::new(Watch { statuses: statuses.clone() }));
+
+        Health {
+            check,
+            watch,
+            statuses,
+        }
+    }
+
+    /// Set the status reported for `service`.
+    ///
+    /// `service` is the fully qualified service name, e.g.
+    /// `helloworld.Greeter`, or the empty string for the whole server.
+    pub fn set_serving_status(&self, service: &str, status: ServingStatus) {
+        let mut state = self.statuses.lock().expect("health statuses lock");
+        if state.statuses.insert(service.to_string(), status) != Some(status) {
+            state.notify(service, status);
+        }
+    }
+
+    /// Stop reporting a status for `service`.
+    ///
+    /// Subsequent checks for `service` fail with `NOT_FOUND`, and watches
+    /// of it report `SERVICE_UNKNOWN`.
+    pub fn clear_serving_status(&self, service: &str) {
+        let mut state = self.statuses.lock().expect("health statuses lock");
+        if state.statuses.remove(service).is_some() {
+            state.notify(service, ServingStatus::ServiceUnknown);
+        }
+    }
+}
+
+impl Service for Health {
+    type Request = http::Request<RecvBody>;
+    type Response = http::Response<ResponseBody>;
+    type Error = h2::Error;
+    type Future = ResponseFuture;
+
+    fn poll_ready(&mut self) -> Poll<(), Self::Error> {
+        Ok(().into())
+    }
+
+    fn call(&mut self, request: Self::Request) -> Self::Future {
+        let kind = match request.uri().path() {
+            CHECK => Kind::Check(self.check.call(request)),
+            WATCH => Kind::Watch(self.watch.call(request)),
+            _ => Kind::Err(Status::UNIMPLEMENTED),
+        };
+
+        ResponseFuture { kind }
+    }
+}
+
+impl NewService for Health {
+    type Request = http::Request<RecvBody>;
+    type Response = http::Response<ResponseBody>;
+    type Error = h2::Error;
+    type Service = Health;
+    type InitError = h2::Error;
+    type Future = FutureResult<Self::Service, Self::InitError>;
+
+    fn new_service(&self) -> Self::Future {
+        future::ok(self.clone())
+    }
+}
+
+impl fmt::Debug for Health {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Health")
+            .field("statuses", &self.statuses)
+            .finish()
+    }
+}
+
+// ===== impl Check =====
+
+impl Service for Check {
+    type Request = Request<HealthCheckRequest>;
+    type Response = Response<HealthCheckResponse>;
+    type Error = ::Error;
+    type Future = FutureResult<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self) -> Poll<(), Self::Error> {
+        Ok(().into())
+    }
+
+    fn call(&mut self, request: Self::Request) -> Self::Future {
+        let state = self.statuses.lock().expect("health statuses lock");
+
+        match state.statuses.get(&request.get_ref().service) {
+            Some(status) => {
+                let response = HealthCheckResponse {
+                    status: *status as i32,
+                };
+                future::ok(Response::new(response))
+            }
+            None => future::err(::Error::Grpc(Status::NOT_FOUND)),
+        }
+    }
+}
+
+// ===== impl Watch =====
+
+impl Service for Watch {
+    type Request = Request<HealthCheckRequest>;
+    type Response = Response<Updates>;
+    type Error = ::Error;
+    type Future = FutureResult<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self) -> Poll<(), Self::Error> {
+        Ok(().into())
+    }
+
+    fn call(&mut self, request: Self::Request) -> Self::Future {
+        let mut state = self.statuses.lock().expect("health statuses lock");
+        let service = &request.get_ref().service;
+
+        // The current status is sent first, followed by every change.
+        let (tx, rx) = mpsc::unbounded();
+        let status = state.statuses.get(service)
+            .cloned()
+            .unwrap_or(ServingStatus::ServiceUnknown);
+        let _ = tx.unbounded_send(status);
+
+        state.watchers.entry(service.clone())
+            .or_insert_with(Vec::new)
+            .push(tx);
+
+        future::ok(Response::new(Updates { rx }))
+    }
+}
+
+impl Stream for Updates {
+    type Item = HealthCheckResponse;
+    type Error = ::Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        let status = match self.rx.poll() {
+            Ok(Async::Ready(status)) => status,
+            Ok(Async::NotReady) => return Ok(Async::NotReady),
+            Err(()) => unreachable!("unbounded receiver doesn't error"),
+        };
+
+        Ok(status.map(|status| HealthCheckResponse { status: status as i32 }).into())
+    }
+}
+
+// ===== impl State =====
+
+impl State {
+    /// Sends `status` to all watchers of `service`, forgetting those that
+    /// have gone away.
+    fn notify(&mut self, service: &str, status: ServingStatus) {
+        if let Some(watchers) = self.watchers.get_mut(service) {
+            watchers.retain(|tx| tx.unbounded_send(status).is_ok());
+        }
+
+        let unwatched = self.watchers.get(service).map_or(false, Vec::is_empty);
+        if unwatched {
+            self.watchers.remove(service);
+        }
+    }
+}
+
+// ===== impl ResponseFuture =====
+
+impl Future for ResponseFuture {
+    type Item = http::Response<ResponseBody>;
+    type Error = h2::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let (head, kind) = match self.kind {
+            Kind::Check(ref mut fut) => {
+                let (head, body) = try_ready!(fut.poll()).into_parts();
+                (head, Kind::Check(body))
+            }
+            Kind::Watch(ref mut fut) => {
+                let (head, body) = try_ready!(fut.poll()).into_parts();
+                (head, Kind::Watch(body))
+            }
+            Kind::Err(ref status) => {
+                let body = ResponseBody { kind: Kind::Err(status.clone()) };
+                return Ok(Response::new(body).into_http().into());
+            }
+        };
+
+        let body = ResponseBody { kind };
+        Ok(http::Response::from_parts(head, body).into())
+    }
+}
+
+impl fmt::Debug for ResponseFuture {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "ResponseFuture")
+    }
+}
+
+// ===== impl ResponseBody =====
+
+impl Body for ResponseBody {
+    type Data = Bytes;
+
+    fn is_end_stream(&self) -> bool {
+        match self.kind {
+            Kind::Check(ref body) => body.is_end_stream(),
+            Kind::Watch(ref body) => body.is_end_stream(),
+            Kind::Err(_) => false,
+        }
+    }
+
+    fn poll_data(&mut self) -> Poll<Option<Self::Data>, h2::Error> {
+        match self.kind {
+            Kind::Check(ref mut body) => body.poll_data(),
+            Kind::Watch(ref mut body) => body.poll_data(),
+            Kind::Err(_) => Ok(None.into()),
+        }
+    }
+
+    fn poll_trailers(&mut self) -> Poll<Option<HeaderMap>, h2::Error> {
+        match self.kind {
+            Kind::Check(ref mut body) => body.poll_trailers(),
+            Kind::Watch(ref mut body) => body.poll_trailers(),
+            Kind::Err(ref status) => {
+                let mut map = HeaderMap::new();
+                map.insert("grpc-status", status.to_header_value());
+                Ok(Some(map).into())
+            }
+        }
+    }
+}
+
+impl fmt::Debug for ResponseBody {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "ResponseBody")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(path: &str, service: &str) -> Request<HealthCheckRequest> {
+        Request::new(path, HealthCheckRequest { service: service.to_string() })
+    }
+
+    fn check(health: &Health, service: &str) -> Result<i32, ::Code> {
+        let mut check = Check { statuses: health.statuses.clone() };
+        match check.call(request(CHECK, service)).wait() {
+            Ok(response) => Ok(response.into_http().into_parts().1.status),
+            Err(::Error::Grpc(status)) => Err(status.code()),
+            Err(e) => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    fn watch(health: &Health, service: &str) -> Updates {
+        let mut watch = Watch { statuses: health.statuses.clone() };
+        let response = watch.call(request(WATCH, service)).wait().unwrap();
+        response.into_http().into_parts().1
+    }
+
+    #[test]
+    fn check_reports_status_transitions() {
+        let health = Health::new();
+        assert_eq!(check(&health, ""), Ok(ServingStatus::Serving as i32));
+        assert_eq!(check(&health, "test.Svc"), Err(Status::NOT_FOUND.code()));
+
+        health.set_serving_status("test.Svc", ServingStatus::NotServing);
+        assert_eq!(check(&health, "test.Svc"), Ok(ServingStatus::NotServing as i32));
+
+        health.set_serving_status("test.Svc", ServingStatus::Serving);
+        assert_eq!(check(&health, "test.Svc"), Ok(ServingStatus::Serving as i32));
+
+        health.clear_serving_status("test.Svc");
+        assert_eq!(check(&health, "test.Svc"), Err(Status::NOT_FOUND.code()));
+        assert_eq!(check(&health, ""), Ok(ServingStatus::Serving as i32));
+    }
+
+    #[test]
+    fn watch_reports_current_status_then_changes() {
+        let health = Health::new();
+        let updates = watch(&health, "test.Svc");
+
+        health.set_serving_status("test.Svc", ServingStatus::Serving);
+        health.set_serving_status("test.Svc", ServingStatus::Serving);
+        health.set_serving_status("other.Svc", ServingStatus::NotServing);
+        health.set_serving_status("test.Svc", ServingStatus::NotServing);
+        health.clear_serving_status("test.Svc");
+
+        // Dropping the last handle to the statuses ends the stream.
+        drop(health);
+
+        let statuses = updates.wait()
+            .map(|r| r.unwrap().status)
+            .collect::<Vec<_>>();
+        assert_eq!(statuses, vec![
+            ServingStatus::ServiceUnknown as i32,
+            ServingStatus::Serving as i32,
+            ServingStatus::NotServing as i32,
+            ServingStatus::ServiceUnknown as i32,
+        ]);
+    }
+
+    #[test]
+    fn watches_are_forgotten_when_dropped() {
+        let health = Health::new();
+        drop(watch(&health, "test.Svc"));
+        let updates = watch(&health, "test.Svc");
+        assert_eq!(health.statuses.lock().unwrap().watchers["test.Svc"].len(), 2);
+
+        health.set_serving_status("test.Svc", ServingStatus::Serving);
+        assert_eq!(health.statuses.lock().unwrap().watchers["test.Svc"].len(), 1);
+
+        drop(updates);
+        health.set_serving_status("test.Svc", ServingStatus::NotServing);
+        assert!(health.statuses.lock().unwrap().watchers.get("test.Svc").is_none());
+    }
+}
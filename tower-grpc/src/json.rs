@@ -0,0 +1,138 @@
+use client::codec::{EncodeBuf, DecodeBuf};
+
+use bytes::{Buf, BufMut};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json;
+
+use ::std::marker::PhantomData;
+
+/// JSON codec
+///
+/// Messages are encoded with `serde_json`, and sent with the
+/// `application/grpc+json` content type.
+#[derive(Debug)]
+pub struct Codec<T, U>(PhantomData<(T, U)>);
+
+#[derive(Debug)]
+pub struct Encoder<T>(PhantomData<T>);
+
+#[derive(Debug)]
+pub struct Decoder<T>(PhantomData<T>);
+
+// ===== impl Codec =====
+
+impl<T, U> Codec<T, U>
+where T: Serialize,
+      U: DeserializeOwned,
+{
+    /// Create a new JSON codec
+    pub fn new() -> Self {
+        Codec(PhantomData)
+    }
+}
+
+impl<T, U> ::server::Codec for Codec<T, U>
+where T: Serialize,
+      U: DeserializeOwned,
+{
+    /// JSON gRPC content type
+    const CONTENT_TYPE: &'static str = "application/grpc+json";
+
+    type Encode = T;
+    type Encoder = Encoder<T>;
+    type Decode = U;
+    type Decoder = Decoder<U>;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        Encoder(PhantomData)
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        Decoder(PhantomData)
+    }
+}
+
+impl<T, U> ::client::Codec for Codec<T, U>
+where T: Serialize,
+      U: DeserializeOwned,
+{
+    /// JSON gRPC content type
+    const CONTENT_TYPE: &'static str = "application/grpc+json";
+
+    type Encode = T;
+    type Decode = U;
+    type EncodeError = serde_json::Error;
+    type DecodeError = serde_json::Error;
+
+    fn encode(&mut self, item: T, buf: &mut EncodeBuf) -> Result<(), serde_json::Error> {
+        encode(&item, buf)
+    }
+
+    fn decode(&mut self, buf: &mut DecodeBuf) -> Result<U, serde_json::Error> {
+        serde_json::from_reader(buf.reader())
+    }
+}
+
+impl<T, U> Clone for Codec<T, U> {
+    fn clone(&self) -> Self {
+        Codec(PhantomData)
+    }
+}
+
+// ===== impl Encoder =====
+
+impl<T> ::server::Encoder for Encoder<T>
+where T: Serialize,
+{
+    type Item = T;
+
+    fn encode(&mut self, item: T, buf: &mut EncodeBuf) -> Result<(), ::Error> {
+        encode(&item, buf)
+            .map_err(|e| {
+                debug!("JSON encode error: {}", e);
+                ::Error::Grpc(::Status::INTERNAL)
+            })
+    }
+}
+
+impl<T> Clone for Encoder<T> {
+    fn clone(&self) -> Self {
+        Encoder(PhantomData)
+    }
+}
+
+// ===== impl Decoder =====
+
+impl<T> ::server::Decoder for Decoder<T>
+where T: DeserializeOwned,
+{
+    type Item = T;
+
+    fn decode(&mut self, buf: &mut DecodeBuf) -> Result<T, ::Error> {
+        serde_json::from_reader(buf.reader())
+            .map_err(|e| {
+                debug!("JSON decode error: {}", e);
+                ::Error::Grpc(::Status::INVALID_ARGUMENT)
+            })
+    }
+}
+
+impl<T> Clone for Decoder<T> {
+    fn clone(&self) -> Self {
+        Decoder(PhantomData)
+    }
+}
+
+fn encode<T: Serialize>(item: &T, buf: &mut EncodeBuf) -> Result<(), serde_json::Error> {
+    // `EncodeBuf` does not grow on its own, so serialize first to learn the
+    // length to reserve.
+    let bytes = serde_json::to_vec(item)?;
+
+    if buf.remaining_mut() < bytes.len() {
+        buf.reserve(bytes.len());
+    }
+
+    buf.put_slice(&bytes);
+    Ok(())
+}
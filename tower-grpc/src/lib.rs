@@ -9,9 +9,27 @@ extern crate h2;
 #[macro_use] extern crate log;
 extern crate tower;
 extern crate tower_h2;
+extern crate tower_router;
 
 #[cfg(feature = "protobuf")]
 extern crate prost;
+#[cfg(feature = "protobuf")]
+#[macro_use]
+extern crate prost_derive;
+#[cfg(feature = "protobuf")]
+extern crate prost_types;
+
+#[cfg(feature = "json")]
+extern crate serde;
+#[cfg(feature = "json")]
+extern crate serde_json;
+
+#[cfg(feature = "web")]
+extern crate base64;
+#[cfg(feature = "web")]
+extern crate hyper;
+#[cfg(feature = "web")]
+extern crate tokio_io;
 
 pub mod client;
 pub mod server;
@@ -19,6 +37,18 @@ pub mod server;
 #[cfg(feature = "protobuf")]
 pub mod protobuf;
 
+#[cfg(feature = "protobuf")]
+pub mod health;
+
+#[cfg(feature = "protobuf")]
+pub mod reflection;
+
+#[cfg(feature = "json")]
+pub mod json;
+
+#[cfg(feature = "web")]
+pub mod web;
+
 mod error;
 mod request;
 mod response;
@@ -32,6 +62,43 @@ pub use self::response::Response;
 
 /// Type re-exports used by generated code
 pub mod codegen {
+    /// Type re-exports used by generated client code
+    pub mod client {
+        /// Re-export types from this crate
+        pub mod grpc {
+            pub use ::{Request, Response, Error, Status};
+            pub use ::client::{
+                codec,
+                Client,
+                IntoBody,
+                ResponseFuture,
+                Streaming,
+                Unary,
+            };
+            pub use ::protobuf::Codec as Protobuf;
+        }
+
+        /// Re-export types from the `future` crate.
+        pub mod futures {
+            pub use ::futures::{Future, Poll, Stream};
+        }
+
+        /// Re-exported types from the `http` crate.
+        pub mod http {
+            pub use ::http::{Request, Response};
+        }
+
+        /// Re-export types from the `tower_h2` crate
+        pub mod tower_h2 {
+            pub use ::tower_h2::RecvBody;
+        }
+
+        /// Re-exported types from the `tower` crate.
+        pub mod tower {
+            pub use ::tower::Service;
+        }
+    }
+
     /// Type re-exports used by generated server code
     pub mod server {
         /// Re-export types from this crate
@@ -42,7 +109,10 @@ pub mod codegen {
                 Unary,
                 ClientStreaming,
                 ServerStreaming,
+                Streaming,
                 NotImplemented,
+                Intercept,
+                Interceptor,
             };
             pub use ::protobuf::server::{
                 Grpc,
@@ -53,6 +123,12 @@ pub mod codegen {
                 Encode,
                 Decode,
             };
+            pub use ::protobuf::Codec as Protobuf;
+
+            /// Types generic over the codec, used with any configured codec
+            pub mod codec {
+                pub use ::server::{Codec, Decode, Encode, Grpc};
+            }
         }
 
         /// Re-export types from the `bytes` crate.
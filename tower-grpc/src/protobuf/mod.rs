@@ -1,7 +1,7 @@
 use client::codec::{EncodeBuf, DecodeBuf};
 
 use bytes::{BufMut};
-use prost::Message;
+use prost::{DecodeError, EncodeError, Message};
 
 use ::std::marker::PhantomData;
 
@@ -216,10 +216,7 @@ pub mod server {
     }
 
     /// A protobuf encoded gRPC request stream
-    #[derive(Debug)]
-    pub struct Decode<T> {
-        inner: ::server::Decode<::protobuf::Decoder<T>>,
-    }
+    pub type Decode<T> = ::server::Decode<::protobuf::Decoder<T>>;
 
     /// A protobuf encoded gRPC response body
     pub struct Encode<T>
@@ -241,6 +238,24 @@ pub mod server {
         }
     }
 
+    impl<T> Grpc<T>
+    where T: GrpcService,
+    {
+        /// Limit the size of request messages.
+        ///
+        /// Requests exceeding the limit fail with `RESOURCE_EXHAUSTED`.
+        pub fn set_max_decoding_message_size(&mut self, limit: Option<usize>) {
+            self.inner.set_max_decoding_message_size(limit);
+        }
+
+        /// Limit the size of response messages.
+        ///
+        /// Responses exceeding the limit end with `RESOURCE_EXHAUSTED`.
+        pub fn set_max_encoding_message_size(&mut self, limit: Option<usize>) {
+            self.inner.set_max_encoding_message_size(limit);
+        }
+    }
+
     impl<T, U> Service for Grpc<T>
     where T: GrpcService<Request = U, RequestStream = Decode<U>>,
           T::Request: ::prost::Message + Default,
@@ -310,19 +325,6 @@ pub mod server {
         }
     }
 
-    // ===== impl Decode =====
-
-    impl<T> Stream for Decode<T>
-    where T: ::prost::Message + Default,
-    {
-        type Item = T;
-        type Error = ::Error;
-
-        fn poll(&mut self) -> Poll<Option<T>, ::Error> {
-            self.inner.poll()
-        }
-    }
-
     // ===== impl Wrap =====
 
     #[derive(Debug, Clone)]
@@ -333,7 +335,7 @@ pub mod server {
           T::Request: ::prost::Message + Default,
           T::Response: ::prost::Message,
     {
-        type Request = Request<::server::Decode<::protobuf::Decoder<T::Request>>>;
+        type Request = Request<Decode<T::Request>>;
         type Response = Response<T::ResponseStream>;
         type Error = ::Error;
         type Future = T::Future;
@@ -343,7 +345,6 @@ pub mod server {
         }
 
         fn call(&mut self, request: Self::Request) -> Self::Future {
-            let request = request.map(|inner| Decode { inner });
             self.0.call(request)
         }
     }
@@ -382,6 +383,33 @@ where T: Message,
     }
 }
 
+impl<T, U> ::client::Codec for Codec<T, U>
+where T: Message,
+      U: Message + Default,
+{
+    /// Protocol buffer gRPC content type
+    const CONTENT_TYPE: &'static str = "application/grpc+proto";
+
+    type Encode = T;
+    type Decode = U;
+    type EncodeError = EncodeError;
+    type DecodeError = DecodeError;
+
+    fn encode(&mut self, item: T, buf: &mut EncodeBuf) -> Result<(), EncodeError> {
+        let len = item.encoded_len();
+
+        if buf.remaining_mut() < len {
+            buf.reserve(len);
+        }
+
+        item.encode(buf)
+    }
+
+    fn decode(&mut self, buf: &mut DecodeBuf) -> Result<U, DecodeError> {
+        Message::decode(buf)
+    }
+}
+
 impl<T, U> Clone for Codec<T, U> {
     fn clone(&self) -> Self {
         Codec(PhantomData)
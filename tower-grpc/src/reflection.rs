@@ -0,0 +1,536 @@
+//! An implementation of the `grpc.reflection.v1alpha.ServerReflection`
+//! service.
+//!
+//! Reflection lets tools such as `grpcurl` discover the services a server
+//! exposes and the message types they use. The service answers from encoded
+//! `FileDescriptorSet`s, such as the one written by
+//! `tower_grpc_build::Config::file_descriptor_set_path`. It is served
+//! alongside other services with `server::Router`.
+
+use {Request, Response, Status};
+use protobuf::server::{Grpc, Decode, Encode, ResponseFuture as GrpcFuture};
+
+use bytes::Bytes;
+use futures::{Future, Stream, Poll};
+use futures::future::{self, FutureResult};
+use h2;
+use http::{self, HeaderMap};
+use prost::Message;
+use prost_types::{DescriptorProto, FileDescriptorProto, FileDescriptorSet};
+use tower::{Service, NewService};
+use tower_h2::{Body, RecvBody};
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::Cursor;
+use std::sync::Arc;
+
+/// Messages of the `grpc.reflection.v1alpha` package.
+pub mod pb {
+    include!(concat!(env!("OUT_DIR"), "/grpc.reflection.v1alpha.rs"));
+}
+
+use self::pb::{ServerReflectionRequest, ServerReflectionResponse};
+use self::pb::server_reflection_request::MessageRequest;
+use self::pb::server_reflection_response::MessageResponse;
+
+/// The fully qualified name of the `ServerReflection` service.
+pub const NAME: &'static str = "grpc.reflection.v1alpha.ServerReflection";
+
+/// The path prefix of all `ServerReflection` methods.
+pub const PREFIX: &'static str = "/grpc.reflection.v1alpha.ServerReflection/";
+
+const SERVER_REFLECTION_INFO: &'static str =
+    "/grpc.reflection.v1alpha.ServerReflection/ServerReflectionInfo";
+
+/// Serves `grpc.reflection.v1alpha.ServerReflection`.
+#[derive(Clone)]
+pub struct ServerReflection {
+    info: Grpc<Info>,
+    index: Arc<Index>,
+}
+
+/// Builds a `ServerReflection` service from file descriptors.
+#[derive(Debug, Default)]
+pub struct Builder {
+    files: Vec<FileDescriptorProto>,
+}
+
+/// An encoded `FileDescriptorSet` could not be decoded.
+#[derive(Debug)]
+pub struct DecodeError(());
+
+pub struct ResponseFuture {
+    kind: Result<GrpcFuture<Info>, Status>,
+}
+
+pub struct ResponseBody {
+    kind: Result<Encode<InfoStream>, Status>,
+}
+
+/// Handles the `ServerReflectionInfo` method.
+#[derive(Debug, Clone)]
+struct Info {
+    index: Arc<Index>,
+}
+
+/// Answers each reflection request on a stream.
+#[derive(Debug)]
+struct InfoStream {
+    requests: Decode<ServerReflectionRequest>,
+    index: Arc<Index>,
+}
+
+/// Lookup tables over the registered file descriptors.
+#[derive(Debug, Default)]
+struct Index {
+    /// Encoded `FileDescriptorProto`s by file name
+    files: HashMap<String, Vec<u8>>,
+
+    /// Direct dependencies of each file
+    dependencies: HashMap<String, Vec<String>>,
+
+    /// The file declaring each fully qualified symbol
+    symbols: HashMap<String, String>,
+
+    /// Fully qualified names of all services
+    services: Vec<String>,
+}
+
+// ===== impl ServerReflection =====
+
+impl ServerReflection {
+    /// Returns a `Builder` to register file descriptors with.
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+}
+
+impl Service for ServerReflection {
+    type Request = http::Request<RecvBody>;
+    type Response = http::Response<ResponseBody>;
+    type Error = h2::Error;
+    type Future = ResponseFuture;
+
+    fn poll_ready(&mut self) -> Poll<(), Self::Error> {
+        Ok(().into())
+    }
+
+    fn call(&mut self, request: Self::Request) -> Self::Future {
+        let kind = match request.uri().path() {
+            SERVER_REFLECTION_INFO => Ok(self.info.call(request)),
+            _ => Err(Status::UNIMPLEMENTED),
+        };
+
+        ResponseFuture { kind }
+    }
+}
+
+impl NewService for ServerReflection {
+    type Request = http::Request<RecvBody>;
+    type Response = http::Response<ResponseBody>;
+    type Error = h2::Error;
+    type Service = ServerReflection;
+    type InitError = h2::Error;
+    type Future = FutureResult<Self::Service, Self::InitError>;
+
+    fn new_service(&self) -> Self::Future {
+        future::ok(self.clone())
+    }
+}
+
+impl fmt::Debug for ServerReflection {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("ServerReflection")
+            .field("services", &self.index.services)
+            .finish()
+    }
+}
+
+// ===== impl Builder =====
+
+impl Builder {
+    /// Register all files of an encoded `FileDescriptorSet`.
+    ///
+    /// The set should be produced with `--include_imports` so that the
+    /// dependencies of each file can be served as well.
+    pub fn register_file_descriptor_set(&mut self, encoded: &[u8])
+        -> Result<&mut Self, DecodeError>
+    {
+        let set = FileDescriptorSet::decode(Cursor::new(encoded))
+            .map_err(|_| DecodeError(()))?;
+
+        self.files.extend(set.file);
+        Ok(self)
+    }
+
+    /// Build the reflection service.
+    pub fn build(&self) -> ServerReflection {
+        let mut index = Index::default();
+
+        for file in &self.files {
+            index.insert(file);
+        }
+
+        let index = Arc::new(index);
+        let info = Grpc::new(Info { index: index.clone() });
+
+        ServerReflection {
+            info,
+            index,
+        }
+    }
+}
+
+// ===== impl Info =====
+
+impl Service for Info {
+    type Request = Request<Decode<ServerReflectionRequest>>;
+    type Response = Response<InfoStream>;
+    type Error = ::Error;
+    type Future = FutureResult<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self) -> Poll<(), Self::Error> {
+        Ok(().into())
+    }
+
+    fn call(&mut self, request: Self::Request) -> Self::Future {
+        let (_, requests) = request.into_http().into_parts();
+
+        let stream = InfoStream {
+            requests,
+            index: self.index.clone(),
+        };
+
+        future::ok(Response::new(stream))
+    }
+}
+
+impl Stream for InfoStream {
+    type Item = ServerReflectionResponse;
+    type Error = ::Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        let request = try_ready!(self.requests.poll());
+        Ok(request.map(|r| self.index.respond(r)).into())
+    }
+}
+
+// ===== impl Index =====
+
+impl Index {
+    fn insert(&mut self, file: &FileDescriptorProto) {
+        let name = name(&file.name).to_string();
+
+        if self.files.contains_key(&name) {
+            return;
+        }
+
+        let prefix = match file.package {
+            Some(ref package) if !package.is_empty() => format!("{}.", package),
+            _ => String::new(),
+        };
+
+        for message in &file.message_type {
+            self.insert_message(&prefix, message, &name);
+        }
+
+        for enumeration in &file.enum_type {
+            let symbol = format!("{}{}", prefix, self::name(&enumeration.name));
+            self.symbols.insert(symbol, name.clone());
+        }
+
+        for service in &file.service {
+            let symbol = format!("{}{}", prefix, self::name(&service.name));
+
+            for method in &service.method {
+                let method = format!("{}.{}", symbol, self::name(&method.name));
+                self.symbols.insert(method, name.clone());
+            }
+
+            self.symbols.insert(symbol.clone(), name.clone());
+            self.services.push(symbol);
+        }
+
+        let mut encoded = Vec::with_capacity(file.encoded_len());
+        file.encode(&mut encoded).expect("Vec<u8> grows to fit");
+
+        self.dependencies.insert(name.clone(), file.dependency.clone());
+        self.files.insert(name, encoded);
+    }
+
+    fn insert_message(&mut self, prefix: &str, message: &DescriptorProto, file: &str) {
+        let symbol = format!("{}{}", prefix, name(&message.name));
+
+        let nested = format!("{}.", symbol);
+        for child in &message.nested_type {
+            self.insert_message(&nested, child, file);
+        }
+
+        for enumeration in &message.enum_type {
+            let symbol = format!("{}{}", nested, name(&enumeration.name));
+            self.symbols.insert(symbol, file.to_string());
+        }
+
+        self.symbols.insert(symbol, file.to_string());
+    }
+
+    fn respond(&self, request: ServerReflectionRequest) -> ServerReflectionResponse {
+        let message_response = match request.message_request {
+            Some(MessageRequest::FileByFilename(ref file)) => {
+                self.file_response(file)
+            }
+            Some(MessageRequest::FileContainingSymbol(ref symbol)) => {
+                match self.symbols.get(symbol) {
+                    Some(file) => self.file_response(file),
+                    None => error_response(&Status::NOT_FOUND, "symbol not found"),
+                }
+            }
+            Some(MessageRequest::ListServices(_)) => {
+                let service = self.services.iter()
+                    .map(|name| pb::ServiceResponse { name: name.clone() })
+                    .collect();
+
+                MessageResponse::ListServicesResponse(pb::ListServiceResponse {
+                    service,
+                })
+            }
+            Some(MessageRequest::FileContainingExtension(_)) |
+            Some(MessageRequest::AllExtensionNumbersOfType(_)) => {
+                error_response(&Status::UNIMPLEMENTED, "extensions are not supported")
+            }
+            None => {
+                error_response(&Status::INVALID_ARGUMENT, "empty request")
+            }
+        };
+
+        ServerReflectionResponse {
+            valid_host: request.host.clone(),
+            original_request: Some(request),
+            message_response: Some(message_response),
+        }
+    }
+
+    /// Responds with `file` and all of its transitive dependencies.
+    fn file_response(&self, file: &str) -> MessageResponse {
+        if !self.files.contains_key(file) {
+            return error_response(&Status::NOT_FOUND, "file not found");
+        }
+
+        let mut seen = HashSet::new();
+        let mut pending = vec![file.to_string()];
+        let mut file_descriptor_proto = vec![];
+
+        while let Some(file) = pending.pop() {
+            if !seen.insert(file.clone()) {
+                continue;
+            }
+
+            if let Some(encoded) = self.files.get(&file) {
+                file_descriptor_proto.push(encoded.clone());
+            }
+
+            if let Some(deps) = self.dependencies.get(&file) {
+                pending.extend(deps.iter().cloned());
+            }
+        }
+
+        MessageResponse::FileDescriptorResponse(pb::FileDescriptorResponse {
+            file_descriptor_proto,
+        })
+    }
+}
+
+fn name(name: &Option<String>) -> &str {
+    name.as_ref().map(|s| s.as_str()).unwrap_or("")
+}
+
+fn error_response(status: &Status, message: &str) -> MessageResponse {
+    MessageResponse::ErrorResponse(pb::ErrorResponse {
+        error_code: status.code().as_i32(),
+        error_message: message.to_string(),
+    })
+}
+
+// ===== impl ResponseFuture =====
+
+impl Future for ResponseFuture {
+    type Item = http::Response<ResponseBody>;
+    type Error = h2::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        match self.kind {
+            Ok(ref mut fut) => {
+                let (head, body) = try_ready!(fut.poll()).into_parts();
+                let body = ResponseBody { kind: Ok(body) };
+                Ok(http::Response::from_parts(head, body).into())
+            }
+            Err(ref status) => {
+                let body = ResponseBody { kind: Err(status.clone()) };
+                Ok(Response::new(body).into_http().into())
+            }
+        }
+    }
+}
+
+impl fmt::Debug for ResponseFuture {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "ResponseFuture")
+    }
+}
+
+// ===== impl ResponseBody =====
+
+impl Body for ResponseBody {
+    type Data = Bytes;
+
+    fn is_end_stream(&self) -> bool {
+        match self.kind {
+            Ok(ref body) => body.is_end_stream(),
+            Err(_) => false,
+        }
+    }
+
+    fn poll_data(&mut self) -> Poll<Option<Self::Data>, h2::Error> {
+        match self.kind {
+            Ok(ref mut body) => body.poll_data(),
+            Err(_) => Ok(None.into()),
+        }
+    }
+
+    fn poll_trailers(&mut self) -> Poll<Option<HeaderMap>, h2::Error> {
+        match self.kind {
+            Ok(ref mut body) => body.poll_trailers(),
+            Err(ref status) => {
+                let mut map = HeaderMap::new();
+                map.insert("grpc-status", status.to_header_value());
+                Ok(Some(map).into())
+            }
+        }
+    }
+}
+
+impl fmt::Debug for ResponseBody {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "ResponseBody")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost_types::{EnumDescriptorProto, MethodDescriptorProto, ServiceDescriptorProto};
+
+    fn index() -> Index {
+        let dep = FileDescriptorProto {
+            name: Some("dep.proto".into()),
+            package: Some("dep".into()),
+            message_type: vec![DescriptorProto {
+                name: Some("Dep".into()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let outer = DescriptorProto {
+            name: Some("Outer".into()),
+            nested_type: vec![DescriptorProto {
+                name: Some("Inner".into()),
+                ..Default::default()
+            }],
+            enum_type: vec![EnumDescriptorProto {
+                name: Some("Kind".into()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let svc = ServiceDescriptorProto {
+            name: Some("Svc".into()),
+            method: vec![MethodDescriptorProto {
+                name: Some("Get".into()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let test = FileDescriptorProto {
+            name: Some("test.proto".into()),
+            package: Some("test".into()),
+            dependency: vec!["dep.proto".into()],
+            message_type: vec![outer],
+            service: vec![svc],
+            ..Default::default()
+        };
+
+        let mut index = Index::default();
+        index.insert(&test);
+        index.insert(&dep);
+        index
+    }
+
+    fn respond(request: MessageRequest) -> MessageResponse {
+        let request = ServerReflectionRequest {
+            host: String::new(),
+            message_request: Some(request),
+        };
+        index().respond(request).message_response.unwrap()
+    }
+
+    /// Returns the names of the files in a `FileDescriptorResponse`.
+    fn files(response: MessageResponse) -> Vec<String> {
+        let files = match response {
+            MessageResponse::FileDescriptorResponse(files) => files.file_descriptor_proto,
+            response => panic!("unexpected response: {:?}", response),
+        };
+
+        let mut names = files.iter()
+            .map(|f| FileDescriptorProto::decode(Cursor::new(f)).unwrap().name.unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    fn error_code(response: MessageResponse) -> i32 {
+        match response {
+            MessageResponse::ErrorResponse(e) => e.error_code,
+            response => panic!("unexpected response: {:?}", response),
+        }
+    }
+
+    #[test]
+    fn file_by_filename_includes_dependencies() {
+        let response = respond(MessageRequest::FileByFilename("test.proto".into()));
+        assert_eq!(files(response), vec!["dep.proto", "test.proto"]);
+
+        let response = respond(MessageRequest::FileByFilename("dep.proto".into()));
+        assert_eq!(files(response), vec!["dep.proto"]);
+
+        let response = respond(MessageRequest::FileByFilename("missing.proto".into()));
+        assert_eq!(error_code(response), Status::NOT_FOUND.code().as_i32());
+    }
+
+    #[test]
+    fn file_containing_symbol() {
+        let symbols = ["test.Outer", "test.Outer.Inner", "test.Outer.Kind", "test.Svc", "test.Svc.Get"];
+        for symbol in &symbols {
+            let response = respond(MessageRequest::FileContainingSymbol(symbol.to_string()));
+            assert_eq!(files(response), vec!["dep.proto", "test.proto"], "{}", symbol);
+        }
+
+        let response = respond(MessageRequest::FileContainingSymbol("dep.Dep".into()));
+        assert_eq!(files(response), vec!["dep.proto"]);
+
+        let response = respond(MessageRequest::FileContainingSymbol("test.Missing".into()));
+        assert_eq!(error_code(response), Status::NOT_FOUND.code().as_i32());
+    }
+
+    #[test]
+    fn list_services() {
+        match respond(MessageRequest::ListServices(String::new())) {
+            MessageResponse::ListServicesResponse(list) => {
+                let names = list.service.into_iter().map(|s| s.name).collect::<Vec<_>>();
+                assert_eq!(names, vec!["test.Svc"]);
+            }
+            response => panic!("unexpected response: {:?}", response),
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use Status;
 // TODO: These types will most likely be moved back to the top level.
-use client::codec::{DecodeBuf, EncodeBuf, BytesList};
+use client::codec::{DecodeBuf, EncodeBuf, BytesList, exceeds_limit};
 
 use bytes::{Buf, BufMut, BytesMut, Bytes, BigEndian};
 use futures::{Stream, Poll, Async};
@@ -66,6 +66,9 @@ pub struct Encode<T, E> {
 
     /// Destination buffer
     buf: BytesMut,
+
+    /// Largest message that may be sent
+    max_message_size: Option<usize>,
 }
 
 #[derive(Debug)]
@@ -95,6 +98,9 @@ pub struct Decode<D> {
 
     /// Decoding state
     state: State,
+
+    /// Largest message that may be received
+    max_message_size: Option<usize>,
 }
 
 #[derive(Debug)]
@@ -113,10 +119,11 @@ impl<T, E> Encode<T, E>
 where T: Stream,
       E: Encoder<Item = T::Item>,
 {
-    pub(crate) fn new(inner: T, encoder: E) -> Self {
+    pub(crate) fn new(inner: T, encoder: E, max_message_size: Option<usize>) -> Self {
         Encode {
             inner: EncodeInner::Ok { inner, encoder },
             buf: BytesMut::new(),
+            max_message_size,
         }
     }
 
@@ -124,6 +131,7 @@ where T: Stream,
         Encode {
             inner: EncodeInner::Err(status),
             buf: BytesMut::new(),
+            max_message_size: None,
         }
     }
 }
@@ -139,7 +147,7 @@ where T: Stream,
     }
 
     fn poll_data(&mut self) -> Poll<Option<Self::Data>, h2::Error> {
-        match self.inner {
+        let len = match self.inner {
             EncodeInner::Ok { ref mut inner, ref mut encoder } => {
                 let item = try_ready!(inner.poll().map_err(|_| h2_err()));
 
@@ -153,19 +161,29 @@ where T: Stream,
                     // now that we know length, we can write the header
                     let len = self.buf.len() - 5;
                     assert!(len <= ::std::u32::MAX as usize);
-                    {
-                        let mut cursor = ::std::io::Cursor::new(&mut self.buf[..5]);
-                        cursor.put_u8(0); // byte must be 0, reserve doesn't auto-zero
-                        cursor.put_u32::<BigEndian>(len as u32);
-                    }
-
-                    Ok(Async::Ready(Some(self.buf.split_to(len + 5).freeze())))
+                    len
                 } else {
-                    Ok(Async::Ready(None))
+                    return Ok(Async::Ready(None));
                 }
             }
-            _ => Ok(Async::Ready(None)),
+            _ => return Ok(Async::Ready(None)),
+        };
+
+        if exceeds_limit(len, self.max_message_size) {
+            // End the body here; the trailers carry the error status.
+            debug!("encoded message of {} bytes exceeds limit", len);
+            self.buf.clear();
+            self.inner = EncodeInner::Err(Status::RESOURCE_EXHAUSTED);
+            return Ok(Async::Ready(None));
         }
+
+        {
+            let mut cursor = ::std::io::Cursor::new(&mut self.buf[..5]);
+            cursor.put_u8(0); // byte must be 0, reserve doesn't auto-zero
+            cursor.put_u32::<BigEndian>(len as u32);
+        }
+
+        Ok(Async::Ready(Some(self.buf.split_to(len + 5).freeze())))
     }
 
     fn poll_trailers(&mut self) -> Poll<Option<HeaderMap>, h2::Error> {
@@ -188,7 +206,10 @@ where T: Stream,
 impl<D> Decode<D>
 where D: Decoder,
 {
-    pub(crate) fn new(inner: tower_h2::RecvBody, decoder: D) -> Self {
+    pub(crate) fn new(inner: tower_h2::RecvBody,
+                      decoder: D,
+                      max_message_size: Option<usize>) -> Self
+    {
         Decode {
             inner,
             decoder,
@@ -196,6 +217,7 @@ where D: Decoder,
                 bufs: VecDeque::new(),
             },
             state: State::ReadHeader,
+            max_message_size,
         }
     }
 
@@ -218,6 +240,12 @@ where D: Decoder,
             };
             let len = self.bufs.get_u32::<BigEndian>() as usize;
 
+            // Reject oversized messages before buffering any of their bytes.
+            if exceeds_limit(len, self.max_message_size) {
+                debug!("message of {} bytes exceeds limit", len);
+                return Err(Status::RESOURCE_EXHAUSTED);
+            }
+
             self.state = State::ReadBody {
                 compression: is_compressed,
                 len,
@@ -311,3 +339,95 @@ fn grpc_status(trailers: &HeaderMap) -> Result<(), Status> {
         Err(Status::UNKNOWN)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::stream;
+
+    const LIMIT: usize = 16;
+
+    /// Passes message bytes through unchanged.
+    struct Raw;
+
+    impl Encoder for Raw {
+        type Item = Vec<u8>;
+
+        fn encode(&mut self, item: Vec<u8>, buf: &mut EncodeBuf) -> Result<(), ::Error> {
+            buf.reserve(item.len());
+            buf.put_slice(&item);
+            Ok(())
+        }
+    }
+
+    impl Decoder for Raw {
+        type Item = Vec<u8>;
+
+        fn decode(&mut self, buf: &mut DecodeBuf) -> Result<Vec<u8>, ::Error> {
+            let mut item = vec![0; buf.remaining()];
+            buf.copy_to_slice(&mut item);
+            Ok(item)
+        }
+    }
+
+    fn frame(len: usize) -> Bytes {
+        let mut buf = BytesMut::with_capacity(5 + len);
+        buf.put_u8(0);
+        buf.put_u32::<BigEndian>(len as u32);
+        buf.put_slice(&vec![0; len]);
+        buf.freeze()
+    }
+
+    fn encode(len: usize) -> (Option<Bytes>, HeaderMap) {
+        let items = stream::iter_ok::<_, ()>(vec![vec![0; len]]);
+        let mut encode = Encode::new(items, Raw, Some(LIMIT));
+        let data = match encode.poll_data() {
+            Ok(Async::Ready(data)) => data,
+            res => panic!("unexpected poll_data result: {:?}", res),
+        };
+        let trailers = match encode.poll_trailers() {
+            Ok(Async::Ready(Some(trailers))) => trailers,
+            res => panic!("unexpected poll_trailers result: {:?}", res),
+        };
+        (data, trailers)
+    }
+
+    fn decode(len: usize) -> Result<Vec<u8>, Status> {
+        let body = tower_h2::RecvBody::from_stream(
+            stream::iter_ok::<_, h2::Error>(vec![frame(len)]));
+        match Decode::new(body, Raw, Some(LIMIT)).poll() {
+            Ok(Async::Ready(Some(item))) => Ok(item),
+            Err(::Error::Grpc(status)) => Err(status),
+            res => panic!("unexpected poll result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn encode_within_limit() {
+        for &len in &[LIMIT - 1, LIMIT] {
+            let (data, trailers) = encode(len);
+            assert_eq!(data, Some(frame(len)));
+            assert_eq!(trailers["grpc-status"], "0");
+        }
+    }
+
+    #[test]
+    fn encode_over_limit_ends_with_resource_exhausted() {
+        let (data, trailers) = encode(LIMIT + 1);
+        assert_eq!(data, None);
+        assert_eq!(trailers["grpc-status"], "8");
+    }
+
+    #[test]
+    fn decode_within_limit() {
+        for &len in &[LIMIT - 1, LIMIT] {
+            assert_eq!(decode(len).unwrap().len(), len);
+        }
+    }
+
+    #[test]
+    fn decode_over_limit_is_resource_exhausted() {
+        let status = decode(LIMIT + 1).unwrap_err();
+        assert_eq!(status.code(), Status::RESOURCE_EXHAUSTED.code());
+    }
+}
@@ -0,0 +1,487 @@
+use Status;
+
+use bytes::Bytes;
+use futures::{Future, Poll, Async};
+use h2;
+use http::{self, request, response, HeaderMap};
+use tower::{Service, NewService};
+use tower_h2::{Body, RecvBody};
+
+/// Hooks invoked for every call made to an intercepted gRPC service.
+///
+/// An interceptor sees the method path and metadata of each call before it
+/// is dispatched and may reject it with a `Status`. Every call, including
+/// rejected ones, is observed until its final status is known.
+pub trait Interceptor {
+    /// Observes the response of a single call.
+    type Call: ObserveCall;
+
+    /// Returns an observer for a call, before the call is intercepted.
+    fn observe(&mut self, request: &request::Parts) -> Self::Call;
+
+    /// Called before a request is dispatched to the inner service.
+    ///
+    /// The method name is available as `request.uri.path()` and the
+    /// metadata as `request.headers`; both may be modified. Returning an
+    /// error responds to the call with that status without calling the
+    /// inner service.
+    fn intercept(&mut self, request: &mut request::Parts) -> Result<(), Status>;
+}
+
+/// Observes the response of an intercepted call.
+pub trait ObserveCall {
+    /// Called with the head of the response, before any messages.
+    ///
+    /// The response metadata is available as `response.headers` and may be
+    /// modified. Returning an error responds to the call with that status
+    /// instead.
+    fn on_response(&mut self, response: &mut response::Parts) -> Result<(), Status> {
+        let _ = response;
+        Ok(())
+    }
+
+    /// Called with each frame of encoded response messages.
+    ///
+    /// Returning an error ends the response stream with that status.
+    fn on_data(&mut self, data: &Bytes) -> Result<(), Status> {
+        let _ = data;
+        Ok(())
+    }
+
+    /// Called once with the final status of the call.
+    fn on_status(&mut self, status: &Status) {
+        let _ = status;
+    }
+}
+
+/// Wraps a gRPC service or `NewService` with an `Interceptor`.
+#[derive(Debug, Clone)]
+pub struct Intercept<T, I> {
+    inner: T,
+    interceptor: I,
+}
+
+/// Completes with an intercepted service.
+#[derive(Debug)]
+pub struct NewServiceFuture<T, I> {
+    inner: T,
+    interceptor: Option<I>,
+}
+
+#[derive(Debug)]
+pub struct ResponseFuture<T, C> {
+    kind: Result<(T, Option<C>), Status>,
+}
+
+#[derive(Debug)]
+pub struct ResponseBody<B, C> {
+    kind: Result<Observed<B, C>, Status>,
+}
+
+#[derive(Debug)]
+struct Observed<B, C> {
+    body: B,
+    call: C,
+
+    /// Set when the observer ends the stream early.
+    aborted: Option<Status>,
+}
+
+// ===== impl Interceptor =====
+
+impl<F> Interceptor for F
+where F: FnMut(&mut request::Parts) -> Result<(), Status>,
+{
+    type Call = ();
+
+    fn observe(&mut self, _: &request::Parts) {}
+
+    fn intercept(&mut self, request: &mut request::Parts) -> Result<(), Status> {
+        self(request)
+    }
+}
+
+impl ObserveCall for () {}
+
+// ===== impl Intercept =====
+
+impl<T, I> Intercept<T, I> {
+    /// Intercept all calls made to `inner`.
+    pub fn new(inner: T, interceptor: I) -> Self {
+        Intercept {
+            inner,
+            interceptor,
+        }
+    }
+}
+
+impl<T, I, B> NewService for Intercept<T, I>
+where T: NewService<Request = http::Request<RecvBody>,
+                   Response = http::Response<B>,
+                      Error = h2::Error>,
+      I: Interceptor + Clone,
+      B: Body<Data = Bytes>,
+{
+    type Request = http::Request<RecvBody>;
+    type Response = http::Response<ResponseBody<B, I::Call>>;
+    type Error = h2::Error;
+    type Service = Intercept<T::Service, I>;
+    type InitError = T::InitError;
+    type Future = NewServiceFuture<T::Future, I>;
+
+    fn new_service(&self) -> Self::Future {
+        NewServiceFuture {
+            inner: self.inner.new_service(),
+            interceptor: Some(self.interceptor.clone()),
+        }
+    }
+}
+
+impl<T, I, B> Service for Intercept<T, I>
+where T: Service<Request = http::Request<RecvBody>,
+                Response = http::Response<B>,
+                   Error = h2::Error>,
+      I: Interceptor,
+      B: Body<Data = Bytes>,
+{
+    type Request = http::Request<RecvBody>;
+    type Response = http::Response<ResponseBody<B, I::Call>>;
+    type Error = h2::Error;
+    type Future = ResponseFuture<T::Future, I::Call>;
+
+    fn poll_ready(&mut self) -> Poll<(), Self::Error> {
+        self.inner.poll_ready()
+    }
+
+    fn call(&mut self, request: Self::Request) -> Self::Future {
+        let (mut head, body) = request.into_parts();
+
+        let mut call = self.interceptor.observe(&head);
+        let kind = match self.interceptor.intercept(&mut head) {
+            Ok(()) => {
+                let request = http::Request::from_parts(head, body);
+                Ok((self.inner.call(request), Some(call)))
+            }
+            Err(status) => {
+                debug!("call to {} rejected by interceptor", head.uri.path());
+                call.on_status(&status);
+                Err(status)
+            }
+        };
+
+        ResponseFuture { kind }
+    }
+}
+
+// ===== impl NewServiceFuture =====
+
+impl<T, I> Future for NewServiceFuture<T, I>
+where T: Future,
+{
+    type Item = Intercept<T::Item, I>;
+    type Error = T::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let inner = try_ready!(self.inner.poll());
+        let interceptor = self.interceptor.take().expect("polled after complete");
+        Ok(Intercept::new(inner, interceptor).into())
+    }
+}
+
+// ===== impl ResponseFuture =====
+
+impl<T, C, B> Future for ResponseFuture<T, C>
+where T: Future<Item = http::Response<B>, Error = h2::Error>,
+      C: ObserveCall,
+      B: Body<Data = Bytes>,
+{
+    type Item = http::Response<ResponseBody<B, C>>;
+    type Error = h2::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let (fut, call) = match self.kind {
+            Ok((ref mut fut, ref mut call)) => (fut, call),
+            Err(ref status) => {
+                let body = ResponseBody { kind: Err(status.clone()) };
+                return Ok(::Response::new(body).into_http().into());
+            }
+        };
+
+        let response = match fut.poll() {
+            Ok(Async::Ready(response)) => response,
+            Ok(Async::NotReady) => return Ok(Async::NotReady),
+            Err(e) => {
+                if let Some(mut call) = call.take() {
+                    call.on_status(&Status::INTERNAL);
+                }
+                return Err(e);
+            }
+        };
+
+        let mut call = call.take().expect("polled after complete");
+        let (mut head, body) = response.into_parts();
+        if let Err(status) = call.on_response(&mut head) {
+            debug!("response rejected by interceptor");
+            call.on_status(&status);
+            let body = ResponseBody { kind: Err(status) };
+            return Ok(::Response::new(body).into_http().into());
+        }
+
+        let body = ResponseBody {
+            kind: Ok(Observed {
+                body,
+                call,
+                aborted: None,
+            }),
+        };
+
+        Ok(http::Response::from_parts(head, body).into())
+    }
+}
+
+// ===== impl ResponseBody =====
+
+impl<B, C> Body for ResponseBody<B, C>
+where B: Body<Data = Bytes>,
+      C: ObserveCall,
+{
+    type Data = Bytes;
+
+    fn is_end_stream(&self) -> bool {
+        match self.kind {
+            Ok(ref observed) => observed.aborted.is_none() && observed.body.is_end_stream(),
+            Err(_) => false,
+        }
+    }
+
+    fn poll_data(&mut self) -> Poll<Option<Self::Data>, h2::Error> {
+        let observed = match self.kind {
+            Ok(ref mut observed) => observed,
+            Err(_) => return Ok(None.into()),
+        };
+
+        if observed.aborted.is_some() {
+            return Ok(None.into());
+        }
+
+        let data = match observed.body.poll_data() {
+            Ok(Async::Ready(Some(data))) => data,
+            Ok(Async::Ready(None)) => return Ok(None.into()),
+            Ok(Async::NotReady) => return Ok(Async::NotReady),
+            Err(e) => {
+                observed.call.on_status(&Status::INTERNAL);
+                return Err(e);
+            }
+        };
+
+        if let Err(status) = observed.call.on_data(&data) {
+            debug!("response stream ended by interceptor");
+            observed.aborted = Some(status);
+            return Ok(None.into());
+        }
+
+        Ok(Some(data).into())
+    }
+
+    fn poll_trailers(&mut self) -> Poll<Option<HeaderMap>, h2::Error> {
+        let observed = match self.kind {
+            Ok(ref mut observed) => observed,
+            Err(ref status) => return Ok(Some(status_trailers(status)).into()),
+        };
+
+        if let Some(ref status) = observed.aborted {
+            observed.call.on_status(status);
+            return Ok(Some(status_trailers(status)).into());
+        }
+
+        let trailers = match observed.body.poll_trailers() {
+            Ok(Async::Ready(trailers)) => trailers,
+            Ok(Async::NotReady) => return Ok(Async::NotReady),
+            Err(e) => {
+                observed.call.on_status(&Status::INTERNAL);
+                return Err(e);
+            }
+        };
+
+        let status = trailers.as_ref()
+            .and_then(|t| t.get("grpc-status"))
+            .map(|s| Status::from_bytes(s.as_ref()))
+            .unwrap_or(Status::UNKNOWN);
+        observed.call.on_status(&status);
+
+        Ok(trailers.into())
+    }
+}
+
+fn status_trailers(status: &Status) -> HeaderMap {
+    let mut map = HeaderMap::new();
+    map.insert("grpc-status", status.to_header_value());
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future::{self, FutureResult};
+    use http::header::HeaderValue;
+
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+
+    type Events = Arc<Mutex<Vec<String>>>;
+
+    /// Responds to every call with `messages`, then a status.
+    #[derive(Clone)]
+    struct Respond {
+        messages: Vec<&'static str>,
+        status: Status,
+    }
+
+    struct Messages {
+        messages: VecDeque<Bytes>,
+        status: Status,
+    }
+
+    /// Records calls to `/test.Svc/` methods, and rejects all others.
+    #[derive(Clone)]
+    struct Record(Events);
+
+    struct RecordCall(Events);
+
+    impl Service for Respond {
+        type Request = http::Request<RecvBody>;
+        type Response = http::Response<Messages>;
+        type Error = h2::Error;
+        type Future = FutureResult<Self::Response, h2::Error>;
+
+        fn poll_ready(&mut self) -> Poll<(), h2::Error> {
+            Ok(().into())
+        }
+
+        fn call(&mut self, _: Self::Request) -> Self::Future {
+            let body = Messages {
+                messages: self.messages.iter().map(|m| Bytes::from(*m)).collect(),
+                status: self.status.clone(),
+            };
+            future::ok(http::Response::new(body))
+        }
+    }
+
+    impl Body for Messages {
+        type Data = Bytes;
+
+        fn poll_data(&mut self) -> Poll<Option<Bytes>, h2::Error> {
+            Ok(self.messages.pop_front().into())
+        }
+
+        fn poll_trailers(&mut self) -> Poll<Option<HeaderMap>, h2::Error> {
+            Ok(Some(status_trailers(&self.status)).into())
+        }
+    }
+
+    impl Interceptor for Record {
+        type Call = RecordCall;
+
+        fn observe(&mut self, request: &request::Parts) -> RecordCall {
+            self.0.lock().unwrap().push(format!("call {}", request.uri.path()));
+            RecordCall(self.0.clone())
+        }
+
+        fn intercept(&mut self, request: &mut request::Parts) -> Result<(), Status> {
+            if request.uri.path().starts_with("/test.Svc/") {
+                Ok(())
+            } else {
+                Err(Status::PERMISSION_DENIED)
+            }
+        }
+    }
+
+    impl ObserveCall for RecordCall {
+        fn on_response(&mut self, response: &mut response::Parts) -> Result<(), Status> {
+            response.headers.insert("x-observed", HeaderValue::from_static("1"));
+            self.0.lock().unwrap().push("response".to_owned());
+            Ok(())
+        }
+
+        fn on_data(&mut self, data: &Bytes) -> Result<(), Status> {
+            self.0.lock().unwrap().push(format!("data {:?}", data));
+            Ok(())
+        }
+
+        fn on_status(&mut self, status: &Status) {
+            self.0.lock().unwrap().push(format!("status {:?}", status.code()));
+        }
+    }
+
+    /// Makes a call to `path`, returning the response headers, messages and
+    /// trailers.
+    fn call(svc: Respond, path: &str, events: &Events)
+        -> (HeaderMap, Vec<Bytes>, Option<HeaderMap>)
+    {
+        let mut svc = Intercept::new(svc, Record(events.clone()));
+        let request = http::Request::builder()
+            .uri(path)
+            .body(RecvBody::default())
+            .unwrap();
+        let (head, mut body) = svc.call(request).wait().unwrap().into_parts();
+
+        let mut messages = vec![];
+        while let Async::Ready(Some(data)) = body.poll_data().unwrap() {
+            messages.push(data);
+        }
+        let trailers = match body.poll_trailers().unwrap() {
+            Async::Ready(trailers) => trailers,
+            Async::NotReady => panic!("trailers not ready"),
+        };
+
+        (head.headers, messages, trailers)
+    }
+
+    #[test]
+    fn observes_unary_call() {
+        let events = Events::default();
+        let svc = Respond { messages: vec!["hello"], status: Status::OK };
+
+        let (headers, messages, trailers) = call(svc, "/test.Svc/Unary", &events);
+        assert_eq!(headers["x-observed"], "1");
+        assert_eq!(messages, vec![Bytes::from("hello")]);
+        assert_eq!(trailers.unwrap()["grpc-status"], "0");
+        assert_eq!(*events.lock().unwrap(), vec![
+            "call /test.Svc/Unary",
+            "response",
+            "data b\"hello\"",
+            "status Ok",
+        ]);
+    }
+
+    #[test]
+    fn observes_streaming_call() {
+        let events = Events::default();
+        let svc = Respond { messages: vec!["one", "two"], status: Status::UNAVAILABLE };
+
+        let (_, messages, trailers) = call(svc, "/test.Svc/Streaming", &events);
+        assert_eq!(messages, vec![Bytes::from("one"), Bytes::from("two")]);
+        assert_eq!(trailers.unwrap()["grpc-status"], "14");
+        assert_eq!(*events.lock().unwrap(), vec![
+            "call /test.Svc/Streaming",
+            "response",
+            "data b\"one\"",
+            "data b\"two\"",
+            "status Unavailable",
+        ]);
+    }
+
+    #[test]
+    fn observes_rejected_call() {
+        let events = Events::default();
+        let svc = Respond { messages: vec!["hello"], status: Status::OK };
+
+        let (_, messages, trailers) = call(svc, "/other.Svc/Unary", &events);
+        assert!(messages.is_empty());
+        assert_eq!(trailers.unwrap()["grpc-status"], "7");
+        assert_eq!(*events.lock().unwrap(), vec![
+            "call /other.Svc/Unary",
+            "status PermissionDenied",
+        ]);
+    }
+}
@@ -1,11 +1,15 @@
 mod codec;
 pub mod client_streaming;
+pub mod intercept;
+pub mod router;
 pub mod server_streaming;
 pub mod streaming;
 pub mod unary;
 
 pub use self::codec::{Codec, Encoder, Decoder, Decode, Encode};
-pub use self::streaming::Grpc;
+pub use self::intercept::{Intercept, Interceptor, ObserveCall};
+pub use self::router::Router;
+pub use self::streaming::{Grpc, Streaming};
 pub use self::client_streaming::ClientStreaming;
 pub use self::server_streaming::ServerStreaming;
 pub use self::unary::Unary;
@@ -0,0 +1,475 @@
+use Status;
+
+use bytes::Bytes;
+use futures::{Future, Poll, Async};
+use futures::future::{self, JoinAll};
+use h2;
+use http::{self, HeaderMap};
+use tower::{Service, NewService};
+use tower_h2::{Body, RecvBody};
+use tower_router::{self, Recognize};
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Serves several gRPC services on a single connection.
+///
+/// Requests are dispatched on the `/package.Service/` prefix of their path.
+/// Requests for services that have not been added are answered with
+/// `UNIMPLEMENTED`.
+#[derive(Clone)]
+pub struct Router {
+    services: Arc<Vec<(String, Box<NewBoxService>)>>,
+}
+
+/// Builds a `Router`.
+pub struct Builder {
+    services: Vec<(String, Box<NewBoxService>)>,
+}
+
+/// Dispatches requests to the services of a single connection.
+pub type RouterService = tower_router::Router<Routes>;
+
+/// Completes with a `RouterService` once all services are built.
+pub struct NewServiceFuture {
+    names: Vec<String>,
+    inner: JoinAll<Vec<Box<Future<Item = BoxService, Error = h2::Error>>>>,
+}
+
+/// Recognizes the service a request is for.
+pub struct Routes {
+    /// Services by name. Binding a route clones the service, so a route that
+    /// the router evicts may be bound again later.
+    services: HashMap<String, BoxService>,
+}
+
+/// A type-erased gRPC service.
+///
+/// Clones share the same underlying service.
+#[derive(Clone)]
+pub struct BoxService {
+    inner: Rc<RefCell<Box<Service<Request = http::Request<RecvBody>,
+                                 Response = http::Response<BoxBody>,
+                                    Error = h2::Error,
+                                   Future = BoxFuture>>>>,
+}
+
+/// A type-erased gRPC response body.
+pub struct BoxBody {
+    inner: Box<Body<Data = Bytes>>,
+}
+
+type BoxFuture = Box<Future<Item = http::Response<BoxBody>, Error = h2::Error>>;
+
+/// Builds a `BoxService` from any supported `NewService`.
+trait NewBoxService {
+    fn new_service(&self) -> Box<Future<Item = BoxService, Error = h2::Error>>;
+}
+
+/// Adapts a service to `BoxService`.
+struct Boxed<T>(T);
+
+/// Answers all requests with `UNIMPLEMENTED`.
+struct Unimplemented;
+
+/// A response body that only carries a status in its trailers.
+struct StatusBody(Status);
+
+// ===== impl Router =====
+
+impl Router {
+    /// Returns a `Builder` to add services to.
+    pub fn builder() -> Builder {
+        Builder {
+            services: vec![],
+        }
+    }
+}
+
+impl NewService for Router {
+    type Request = http::Request<RecvBody>;
+    type Response = http::Response<BoxBody>;
+    type Error = tower_router::Error<h2::Error, ()>;
+    type Service = RouterService;
+    type InitError = h2::Error;
+    type Future = NewServiceFuture;
+
+    fn new_service(&self) -> Self::Future {
+        let names = self.services.iter()
+            .map(|&(ref name, _)| name.clone())
+            .collect();
+
+        let futures = self.services.iter()
+            .map(|&(_, ref new_service)| new_service.new_service())
+            .collect::<Vec<_>>();
+
+        NewServiceFuture {
+            names,
+            inner: future::join_all(futures),
+        }
+    }
+}
+
+impl fmt::Debug for Router {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let names = self.services.iter()
+            .map(|&(ref name, _)| name)
+            .collect::<Vec<_>>();
+
+        fmt.debug_struct("Router")
+            .field("services", &names)
+            .finish()
+    }
+}
+
+// ===== impl Builder =====
+
+impl Builder {
+    /// Serve `new_service` for requests to the service named `name`.
+    ///
+    /// `name` is the fully qualified proto service name, e.g.
+    /// `helloworld.Greeter`. A service added earlier with the same name is
+    /// replaced.
+    pub fn add_service<T, B>(&mut self, name: &str, new_service: T) -> &mut Self
+    where T: NewService<Request = http::Request<RecvBody>,
+                       Response = http::Response<B>,
+                          Error = h2::Error,
+                      InitError = h2::Error> + 'static,
+          T::Service: 'static,
+          <T::Service as Service>::Future: 'static,
+          B: Body<Data = Bytes> + 'static,
+    {
+        self.services.retain(|&(ref n, _)| n != name);
+        self.services.push((name.to_string(), Box::new(new_service)));
+        self
+    }
+
+    /// Build the router.
+    pub fn build(&mut self) -> Router {
+        let services = ::std::mem::replace(&mut self.services, vec![]);
+
+        Router {
+            services: Arc::new(services),
+        }
+    }
+}
+
+// ===== impl NewServiceFuture =====
+
+impl Future for NewServiceFuture {
+    type Item = RouterService;
+    type Error = h2::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let services = try_ready!(self.inner.poll());
+
+        let services = self.names.drain(..)
+            .zip(services)
+            .collect();
+
+        Ok(tower_router::Router::new(Routes { services }).into())
+    }
+}
+
+// ===== impl Routes =====
+
+impl Recognize for Routes {
+    type Request = http::Request<RecvBody>;
+    type Response = http::Response<BoxBody>;
+    type Error = h2::Error;
+    type Key = Option<String>;
+    type RouteError = ();
+    type Service = BoxService;
+
+    /// Returns the name of the requested service if it is served, or `None`
+    /// to route to the `UNIMPLEMENTED` fallback.
+    fn recognize(&self, req: &Self::Request) -> Option<Self::Key> {
+        match service_name(req.uri().path()) {
+            Some(name) if self.services.contains_key(name) => {
+                Some(Some(name.to_string()))
+            }
+            _ => Some(None),
+        }
+    }
+
+    fn bind_service(&self, key: &Self::Key) -> Result<Self::Service, Self::RouteError> {
+        match *key {
+            Some(ref name) => {
+                self.services.get(name).cloned().ok_or_else(|| {
+                    debug!("no service named {}", name);
+                })
+            }
+            None => Ok(BoxService::new(Unimplemented)),
+        }
+    }
+}
+
+/// Extracts `package.Service` from a `/package.Service/Method` path.
+fn service_name(path: &str) -> Option<&str> {
+    let mut parts = path.splitn(3, '/');
+
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(""), Some(name), Some(_)) if !name.is_empty() => Some(name),
+        _ => None,
+    }
+}
+
+// ===== impl BoxService =====
+
+impl BoxService {
+    fn new<T, B>(service: T) -> Self
+    where T: Service<Request = http::Request<RecvBody>,
+                    Response = http::Response<B>,
+                       Error = h2::Error> + 'static,
+          T::Future: 'static,
+          B: Body<Data = Bytes> + 'static,
+    {
+        BoxService { inner: Rc::new(RefCell::new(Box::new(Boxed(service)))) }
+    }
+}
+
+impl Service for BoxService {
+    type Request = http::Request<RecvBody>;
+    type Response = http::Response<BoxBody>;
+    type Error = h2::Error;
+    type Future = BoxFuture;
+
+    fn poll_ready(&mut self) -> Poll<(), Self::Error> {
+        self.inner.borrow_mut().poll_ready()
+    }
+
+    fn call(&mut self, request: Self::Request) -> Self::Future {
+        self.inner.borrow_mut().call(request)
+    }
+}
+
+impl fmt::Debug for BoxService {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "BoxService")
+    }
+}
+
+// ===== impl NewBoxService =====
+
+impl<T, B> NewBoxService for T
+where T: NewService<Request = http::Request<RecvBody>,
+                   Response = http::Response<B>,
+                      Error = h2::Error,
+                  InitError = h2::Error>,
+      T::Future: 'static,
+      T::Service: 'static,
+      <T::Service as Service>::Future: 'static,
+      B: Body<Data = Bytes> + 'static,
+{
+    fn new_service(&self) -> Box<Future<Item = BoxService, Error = h2::Error>> {
+        Box::new(NewService::new_service(self).map(BoxService::new))
+    }
+}
+
+// ===== impl Boxed =====
+
+impl<T, B> Service for Boxed<T>
+where T: Service<Request = http::Request<RecvBody>,
+                Response = http::Response<B>,
+                   Error = h2::Error>,
+      T::Future: 'static,
+      B: Body<Data = Bytes> + 'static,
+{
+    type Request = http::Request<RecvBody>;
+    type Response = http::Response<BoxBody>;
+    type Error = h2::Error;
+    type Future = BoxFuture;
+
+    fn poll_ready(&mut self) -> Poll<(), Self::Error> {
+        self.0.poll_ready()
+    }
+
+    fn call(&mut self, request: Self::Request) -> Self::Future {
+        let response = self.0.call(request).map(|response| {
+            let (head, body) = response.into_parts();
+            http::Response::from_parts(head, BoxBody::new(body))
+        });
+
+        Box::new(response)
+    }
+}
+
+// ===== impl Unimplemented =====
+
+impl Service for Unimplemented {
+    type Request = http::Request<RecvBody>;
+    type Response = http::Response<StatusBody>;
+    type Error = h2::Error;
+    type Future = future::FutureResult<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self) -> Poll<(), Self::Error> {
+        Ok(().into())
+    }
+
+    fn call(&mut self, request: Self::Request) -> Self::Future {
+        debug!("no service for {}", request.uri().path());
+
+        let body = StatusBody(Status::UNIMPLEMENTED);
+        future::ok(::Response::new(body).into_http())
+    }
+}
+
+// ===== impl BoxBody =====
+
+impl BoxBody {
+    fn new<B>(body: B) -> Self
+    where B: Body<Data = Bytes> + 'static,
+    {
+        BoxBody { inner: Box::new(body) }
+    }
+}
+
+impl Body for BoxBody {
+    type Data = Bytes;
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn poll_data(&mut self) -> Poll<Option<Self::Data>, h2::Error> {
+        self.inner.poll_data()
+    }
+
+    fn poll_trailers(&mut self) -> Poll<Option<HeaderMap>, h2::Error> {
+        self.inner.poll_trailers()
+    }
+}
+
+impl fmt::Debug for BoxBody {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "BoxBody")
+    }
+}
+
+// ===== impl StatusBody =====
+
+impl Body for StatusBody {
+    type Data = Bytes;
+
+    fn poll_data(&mut self) -> Poll<Option<Self::Data>, h2::Error> {
+        Ok(Async::Ready(None))
+    }
+
+    fn poll_trailers(&mut self) -> Poll<Option<HeaderMap>, h2::Error> {
+        let mut map = HeaderMap::new();
+        map.insert("grpc-status", self.0.to_header_value());
+        Ok(Async::Ready(Some(map)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::header::HeaderValue;
+
+    /// Responds with its name in the `x-service` header.
+    #[derive(Clone)]
+    struct Named(&'static str);
+
+    impl NewService for Named {
+        type Request = http::Request<RecvBody>;
+        type Response = http::Response<StatusBody>;
+        type Error = h2::Error;
+        type Service = Self;
+        type InitError = h2::Error;
+        type Future = future::FutureResult<Self, h2::Error>;
+
+        fn new_service(&self) -> Self::Future {
+            future::ok(self.clone())
+        }
+    }
+
+    impl Service for Named {
+        type Request = http::Request<RecvBody>;
+        type Response = http::Response<StatusBody>;
+        type Error = h2::Error;
+        type Future = future::FutureResult<Self::Response, h2::Error>;
+
+        fn poll_ready(&mut self) -> Poll<(), h2::Error> {
+            Ok(().into())
+        }
+
+        fn call(&mut self, _: Self::Request) -> Self::Future {
+            let mut response = ::Response::new(StatusBody(Status::OK)).into_http();
+            response.headers_mut().insert("x-service", HeaderValue::from_static(self.0));
+            future::ok(response)
+        }
+    }
+
+    fn router() -> RouterService {
+        Router::builder()
+            .add_service("test.A", Named("a"))
+            .add_service("test.B", Named("old b"))
+            .add_service("test.B", Named("b"))
+            .build()
+            .new_service()
+            .wait()
+            .unwrap()
+    }
+
+    /// Returns the `x-service` header and the status of a call to `path`.
+    fn call(router: &mut RouterService, path: &str) -> (Option<HeaderValue>, HeaderValue) {
+        let request = http::Request::builder()
+            .uri(path)
+            .body(RecvBody::default())
+            .unwrap();
+        let (head, mut body) = router.call(request).wait().unwrap().into_parts();
+
+        let trailers = match body.poll_trailers().unwrap() {
+            Async::Ready(Some(trailers)) => trailers,
+            _ => panic!("no trailers"),
+        };
+
+        (head.headers.get("x-service").cloned(), trailers["grpc-status"].clone())
+    }
+
+    #[test]
+    fn dispatches_by_service_name() {
+        let mut router = router();
+
+        let (service, status) = call(&mut router, "/test.A/Method");
+        assert_eq!(service.unwrap(), "a");
+        assert_eq!(status, "0");
+
+        let (service, status) = call(&mut router, "/test.B/Method");
+        assert_eq!(service.unwrap(), "b");
+        assert_eq!(status, "0");
+
+        // Routes are reused.
+        let (service, _) = call(&mut router, "/test.A/Other");
+        assert_eq!(service.unwrap(), "a");
+    }
+
+    #[test]
+    fn unknown_services_are_unimplemented() {
+        let mut router = router();
+
+        for path in &["/test.C/Method", "/test.A", "/", "/test.AB/Method"] {
+            let (service, status) = call(&mut router, path);
+            assert!(service.is_none(), "{}", path);
+            assert_eq!(status, "12", "{}", path);
+        }
+    }
+
+    #[test]
+    fn routes_may_be_bound_again() {
+        let routes = Routes {
+            services: vec![("test.A".to_string(), BoxService::new(Named("a")))]
+                .into_iter()
+                .collect(),
+        };
+
+        let key = Some("test.A".to_string());
+        assert!(routes.bind_service(&key).is_ok());
+        assert!(routes.bind_service(&key).is_ok());
+        assert!(routes.bind_service(&Some("test.C".to_string())).is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use {Request, Response};
 use super::codec::{Codec, Encoder, Decode, Encode};
+use protobuf::server::GrpcService;
 
 use {http, h2};
 use futures::{Future, Stream, Poll, Async};
@@ -11,12 +12,25 @@ use tower_h2::RecvBody;
 pub struct Grpc<T, C> {
     inner: T,
     codec: C,
+
+    /// Largest request message accepted, if limited.
+    max_decoding_message_size: Option<usize>,
+
+    /// Largest response message sent, if limited.
+    max_encoding_message_size: Option<usize>,
+}
+
+/// Maps to a bidirectional streaming gRPC service.
+#[derive(Debug, Clone)]
+pub struct Streaming<T> {
+    inner: T,
 }
 
 #[derive(Debug)]
 pub struct ResponseFuture<T, E> {
     inner: T,
     encoder: Option<E>,
+    max_encoding_message_size: Option<usize>,
 }
 
 // ===== impl Grpc =====
@@ -32,10 +46,30 @@ where T: Service<Request = Request<Decode<C::Decoder>>,
         Grpc {
             inner,
             codec,
+            max_decoding_message_size: None,
+            max_encoding_message_size: None,
         }
     }
 }
 
+impl<T, C> Grpc<T, C> {
+    /// Limit the size of request messages.
+    ///
+    /// A request message whose length prefix exceeds the limit fails the
+    /// call with `RESOURCE_EXHAUSTED` before any of it is buffered.
+    pub fn set_max_decoding_message_size(&mut self, limit: Option<usize>) {
+        self.max_decoding_message_size = limit;
+    }
+
+    /// Limit the size of response messages.
+    ///
+    /// A response message that encodes to more than the limit ends the
+    /// response with `RESOURCE_EXHAUSTED` instead of being sent.
+    pub fn set_max_encoding_message_size(&mut self, limit: Option<usize>) {
+        self.max_encoding_message_size = limit;
+    }
+}
+
 impl<T, C, S> Service for Grpc<T, C>
 where T: Service<Request = Request<Decode<C::Decoder>>,
                 Response = Response<S>,
@@ -49,7 +83,7 @@ where T: Service<Request = Request<Decode<C::Decoder>>,
     type Future = ResponseFuture<T::Future, C::Encoder>;
 
     fn poll_ready(&mut self) -> Poll<(), Self::Error> {
-        self.inner.poll_ready().map_err(|_| unimplemented!())
+        self.inner.poll_ready().map_err(Into::into)
     }
 
     fn call(&mut self, request: Self::Request) -> Self::Future {
@@ -57,7 +91,10 @@ where T: Service<Request = Request<Decode<C::Decoder>>,
         let (head, body) = request.into_parts();
 
         // Wrap the body stream with a decoder
-        let body = Decode::new(body, self.codec.decoder());
+        let body = Decode::new(
+            body,
+            self.codec.decoder(),
+            self.max_decoding_message_size);
 
         // Reconstruct the HTTP request
         let request = http::Request::from_parts(head, body);
@@ -72,10 +109,39 @@ where T: Service<Request = Request<Decode<C::Decoder>>,
         ResponseFuture {
             inner,
             encoder: Some(self.codec.encoder()),
+            max_encoding_message_size: self.max_encoding_message_size,
         }
     }
 }
 
+// ===== impl Streaming =====
+
+impl<T> Streaming<T>
+where T: GrpcService,
+{
+    /// Return a new `Streaming` gRPC service handler
+    pub fn new(inner: T) -> Self {
+        Streaming { inner }
+    }
+}
+
+impl<T> Service for Streaming<T>
+where T: GrpcService,
+{
+    type Request = Request<T::RequestStream>;
+    type Response = Response<T::ResponseStream>;
+    type Error = ::Error;
+    type Future = T::Future;
+
+    fn poll_ready(&mut self) -> Poll<(), Self::Error> {
+        self.inner.poll_ready()
+    }
+
+    fn call(&mut self, request: Self::Request) -> Self::Future {
+        self.inner.call(request)
+    }
+}
+
 // ===== impl ResponseFuture =====
 
 impl<T, E, S> Future for ResponseFuture<T, E>
@@ -114,7 +180,7 @@ where T: Future<Item = Response<S>,
         let encoder = self.encoder.take().expect("encoder consumed");
 
         // Encode the body
-        let body = Encode::new(body, encoder);
+        let body = Encode::new(body, encoder, self.max_encoding_message_size);
 
         // Success
         Ok(http::Response::from_parts(head, body).into())
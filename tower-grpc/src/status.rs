@@ -174,6 +174,11 @@ impl From<Status> for h2::Error {
 impl Code {
     pub const OK: Code = Code(Code_::Ok);
     //TODO: the rest...
+
+    /// Returns the numeric value of the code, as sent in `grpc-status`.
+    pub fn as_i32(&self) -> i32 {
+        self.0 as i32
+    }
 }
 
 impl fmt::Debug for Code {
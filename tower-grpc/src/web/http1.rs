@@ -0,0 +1,230 @@
+use bytes::Bytes;
+use futures::{Future, Stream, Poll, Async};
+use h2;
+use http;
+use hyper;
+use tokio_io::{AsyncRead, AsyncWrite};
+use tower::{Service, NewService};
+use tower_h2::{Body, RecvBody};
+
+use std::cell::RefCell;
+use std::fmt;
+use std::io;
+use std::rc::Rc;
+
+/// Serves a `NewService` over HTTP/1.1 connections.
+///
+/// HTTP/1.1 cannot carry the trailers gRPC depends on, so the service should
+/// be wrapped in `GrpcWeb`; any trailers it produces are dropped. Each
+/// request waits for the service to be ready before it is dispatched.
+#[derive(Debug)]
+pub struct Http1<S> {
+    new_service: S,
+}
+
+/// Serves a single HTTP/1.1 connection.
+pub struct Connection<T, S>
+where T: AsyncRead + AsyncWrite + 'static,
+      S: NewService,
+{
+    state: State<T, S>,
+}
+
+/// Error produced by a `Connection`.
+#[derive(Debug)]
+pub enum Error<E> {
+    /// Error produced when obtaining the service
+    NewService(E),
+
+    /// Error produced by the HTTP/1.1 connection
+    Http(hyper::Error),
+}
+
+enum State<T, S>
+where T: AsyncRead + AsyncWrite + 'static,
+      S: NewService,
+{
+    Init(S::Future, Option<T>),
+    Serving(Box<Future<Item = (), Error = hyper::Error>>),
+}
+
+/// Adapts a service to hyper's `Service` trait.
+struct Adapter<S> {
+    service: Rc<RefCell<S>>,
+}
+
+/// Completes with the response to an HTTP/1.1 request.
+struct ResponseFuture<S: Service> {
+    state: Call<S>,
+}
+
+enum Call<S: Service> {
+    /// Waiting for the service to be ready for the request.
+    Pending(Rc<RefCell<S>>, Option<S::Request>),
+    Dispatched(S::Future),
+}
+
+/// Streams a response body to hyper.
+struct ResponseBody<B> {
+    inner: B,
+}
+
+// ===== impl Http1 =====
+
+impl<S, B> Http1<S>
+where S: NewService<Request = http::Request<RecvBody>,
+                   Response = http::Response<B>>,
+      S::Service: 'static,
+      S::Error: fmt::Debug,
+      B: Body<Data = Bytes> + 'static,
+{
+    pub fn new(new_service: S) -> Self {
+        Http1 { new_service }
+    }
+
+    /// Produces a future that serves `io` until the connection is closed.
+    pub fn serve<T>(&self, io: T) -> Connection<T, S>
+    where T: AsyncRead + AsyncWrite + 'static,
+    {
+        Connection {
+            state: State::Init(self.new_service.new_service(), Some(io)),
+        }
+    }
+}
+
+// ===== impl Connection =====
+
+impl<T, S, B> Future for Connection<T, S>
+where T: AsyncRead + AsyncWrite + 'static,
+      S: NewService<Request = http::Request<RecvBody>,
+                   Response = http::Response<B>>,
+      S::Service: 'static,
+      S::Error: fmt::Debug,
+      B: Body<Data = Bytes> + 'static,
+{
+    type Item = ();
+    type Error = Error<S::InitError>;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        loop {
+            let service = match self.state {
+                State::Init(ref mut fut, ref mut io) => {
+                    let service = try_ready!(fut.poll().map_err(Error::NewService));
+                    let io = io.take().expect("polled after complete");
+                    (service, io)
+                }
+                State::Serving(ref mut conn) => {
+                    return conn.poll().map_err(Error::Http);
+                }
+            };
+
+            let (service, io) = service;
+            let adapter = Adapter {
+                service: Rc::new(RefCell::new(service)),
+            };
+
+            let conn = hyper::server::Http::<Bytes>::new().serve_connection(io, adapter);
+            self.state = State::Serving(Box::new(conn));
+        }
+    }
+}
+
+impl<T, S> fmt::Debug for Connection<T, S>
+where T: AsyncRead + AsyncWrite + 'static,
+      S: NewService,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "Connection")
+    }
+}
+
+// ===== impl Adapter =====
+
+impl<S, B> hyper::server::Service for Adapter<S>
+where S: Service<Request = http::Request<RecvBody>,
+                Response = http::Response<B>>,
+      S::Error: fmt::Debug,
+      B: Body<Data = Bytes>,
+{
+    type Request = hyper::Request;
+    type Response = hyper::Response<ResponseBody<B>>;
+    type Error = hyper::Error;
+    type Future = ResponseFuture<S>;
+
+    fn call(&self, request: Self::Request) -> Self::Future {
+        let request: http::Request<hyper::Body> = request.into();
+        let (head, body) = request.into_parts();
+
+        let body = body
+            .map(|chunk| {
+                let chunk: &[u8] = chunk.as_ref();
+                Bytes::from(chunk)
+            })
+            .map_err(|e| {
+                debug!("HTTP/1.1 request body error: {}", e);
+                h2::Error::from(h2::Reason::INTERNAL_ERROR)
+            });
+
+        let request = http::Request::from_parts(head, RecvBody::from_stream(body));
+
+        ResponseFuture {
+            state: Call::Pending(self.service.clone(), Some(request)),
+        }
+    }
+}
+
+// ===== impl ResponseFuture =====
+
+impl<S, B> Future for ResponseFuture<S>
+where S: Service<Request = http::Request<RecvBody>,
+                Response = http::Response<B>>,
+      S::Error: fmt::Debug,
+      B: Body<Data = Bytes>,
+{
+    type Item = hyper::Response<ResponseBody<B>>;
+    type Error = hyper::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        loop {
+            let future = match self.state {
+                Call::Pending(ref service, ref mut request) => {
+                    let mut service = service.borrow_mut();
+                    try_ready!(service.poll_ready().map_err(service_error));
+
+                    let request = request.take().expect("polled after complete");
+                    service.call(request)
+                }
+                Call::Dispatched(ref mut future) => {
+                    let response = try_ready!(future.poll().map_err(service_error));
+
+                    let (head, inner) = response.into_parts();
+                    let response = http::Response::from_parts(head, ResponseBody { inner });
+
+                    return Ok(Async::Ready(response.into()));
+                }
+            };
+
+            self.state = Call::Dispatched(future);
+        }
+    }
+}
+
+/// Converts a service error to a hyper error, closing the connection.
+fn service_error<E: fmt::Debug>(e: E) -> hyper::Error {
+    io::Error::new(io::ErrorKind::Other, format!("{:?}", e)).into()
+}
+
+// ===== impl ResponseBody =====
+
+impl<B> Stream for ResponseBody<B>
+where B: Body<Data = Bytes>,
+{
+    type Item = Bytes;
+    type Error = hyper::Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        self.inner.poll_data().map_err(|e| {
+            io::Error::new(io::ErrorKind::Other, format!("{:?}", e)).into()
+        })
+    }
+}
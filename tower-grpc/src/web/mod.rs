@@ -0,0 +1,621 @@
+//! gRPC-Web support for gRPC servers.
+//!
+//! Browsers cannot read HTTP/2 trailers, so gRPC-Web sends the trailers of a
+//! call as a final length-prefixed frame of the response body, flagged by
+//! the high bit of its first byte. In the `application/grpc-web-text` mode,
+//! request and response bodies are additionally base64 encoded.
+//!
+//! `GrpcWeb` translates gRPC-Web calls to regular gRPC calls for the service
+//! it wraps. `Http1` serves such a service over HTTP/1.1 connections.
+//!
+//! Browsers send gRPC-Web calls to other origins only after a CORS preflight
+//! request. `GrpcWeb` answers preflight requests itself and allows calls
+//! from any origin.
+
+use base64;
+use bytes::{BufMut, Bytes, BytesMut, BigEndian};
+use futures::{Future, Stream, Poll, Async};
+use h2;
+use http::{self, HeaderMap};
+use http::header::{CONTENT_LENGTH, CONTENT_TYPE, HeaderValue};
+use http::header::{
+    ACCESS_CONTROL_ALLOW_HEADERS,
+    ACCESS_CONTROL_ALLOW_METHODS,
+    ACCESS_CONTROL_ALLOW_ORIGIN,
+    ACCESS_CONTROL_EXPOSE_HEADERS,
+    ACCESS_CONTROL_MAX_AGE,
+    ACCESS_CONTROL_REQUEST_HEADERS,
+    ACCESS_CONTROL_REQUEST_METHOD,
+    ORIGIN,
+};
+use tower::{Service, NewService};
+use tower_h2::{Body, RecvBody};
+
+pub mod http1;
+
+pub use self::http1::Http1;
+
+/// Flags a frame that carries trailers rather than a message.
+const TRAILERS_FLAG: u8 = 0x80;
+
+/// Request headers allowed by preflight requests that don't list any.
+const DEFAULT_ALLOW_HEADERS: &'static str =
+    "content-type,x-grpc-web,x-user-agent,grpc-timeout";
+
+/// Response headers that browsers let gRPC-Web clients read.
+const EXPOSE_HEADERS: &'static str = "grpc-status,grpc-message";
+
+/// How long browsers may cache a preflight response, in seconds.
+const PREFLIGHT_MAX_AGE: &'static str = "86400";
+
+/// Serves gRPC-Web calls with a gRPC service.
+///
+/// Calls that are not gRPC-Web are passed through unchanged, so the same
+/// service may be served to gRPC and gRPC-Web clients.
+#[derive(Debug, Clone)]
+pub struct GrpcWeb<T> {
+    inner: T,
+}
+
+/// Completes with a `GrpcWeb` service.
+#[derive(Debug)]
+pub struct NewServiceFuture<T> {
+    inner: T,
+}
+
+#[derive(Debug)]
+pub struct ResponseFuture<T> {
+    kind: Kind<T>,
+}
+
+#[derive(Debug)]
+pub struct ResponseBody<B> {
+    /// `None` for preflight responses, which have no body.
+    inner: Option<B>,
+    mode: Option<Mode>,
+
+    /// Set once the trailers frame has been sent.
+    done: bool,
+}
+
+#[derive(Debug)]
+enum Kind<T> {
+    Call {
+        inner: T,
+        mode: Option<Mode>,
+
+        /// The origin of a cross-origin gRPC-Web call.
+        origin: Option<HeaderValue>,
+    },
+    Preflight(Option<http::response::Parts>),
+}
+
+/// The wire format of a gRPC-Web call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// `application/grpc-web`
+    Binary,
+
+    /// `application/grpc-web-text`
+    Text,
+}
+
+/// Decodes a base64 encoded request body.
+#[derive(Debug)]
+struct DecodeText {
+    inner: RecvBody,
+
+    /// Encoded bytes that do not yet form a complete base64 quantum
+    buf: BytesMut,
+}
+
+// ===== impl GrpcWeb =====
+
+impl<T> GrpcWeb<T> {
+    /// Serve gRPC-Web calls with `inner`.
+    pub fn new(inner: T) -> Self {
+        GrpcWeb { inner }
+    }
+}
+
+impl<T, B> NewService for GrpcWeb<T>
+where T: NewService<Request = http::Request<RecvBody>,
+                   Response = http::Response<B>>,
+      B: Body<Data = Bytes>,
+{
+    type Request = http::Request<RecvBody>;
+    type Response = http::Response<ResponseBody<B>>;
+    type Error = T::Error;
+    type Service = GrpcWeb<T::Service>;
+    type InitError = T::InitError;
+    type Future = NewServiceFuture<T::Future>;
+
+    fn new_service(&self) -> Self::Future {
+        NewServiceFuture {
+            inner: self.inner.new_service(),
+        }
+    }
+}
+
+impl<T, B> Service for GrpcWeb<T>
+where T: Service<Request = http::Request<RecvBody>,
+                Response = http::Response<B>>,
+      B: Body<Data = Bytes>,
+{
+    type Request = http::Request<RecvBody>;
+    type Response = http::Response<ResponseBody<B>>;
+    type Error = T::Error;
+    type Future = ResponseFuture<T::Future>;
+
+    fn poll_ready(&mut self) -> Poll<(), Self::Error> {
+        self.inner.poll_ready()
+    }
+
+    fn call(&mut self, request: Self::Request) -> Self::Future {
+        if is_preflight(&request) {
+            trace!("gRPC-Web preflight request");
+            return ResponseFuture {
+                kind: Kind::Preflight(Some(preflight(request.headers()))),
+            };
+        }
+
+        let mode = Mode::of(request.headers());
+        let origin = match mode {
+            Some(_) => request.headers().get(ORIGIN).cloned(),
+            None => None,
+        };
+
+        let request = match mode {
+            Some(mode) => {
+                trace!("gRPC-Web call; mode={:?}", mode);
+
+                let (mut head, body) = request.into_parts();
+                head.headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/grpc"));
+
+                let body = match mode {
+                    Mode::Binary => body,
+                    Mode::Text => {
+                        // The length of the decoded body differs.
+                        head.headers.remove(CONTENT_LENGTH);
+                        RecvBody::from_stream(DecodeText::new(body))
+                    }
+                };
+
+                http::Request::from_parts(head, body)
+            }
+            None => request,
+        };
+
+        ResponseFuture {
+            kind: Kind::Call {
+                inner: self.inner.call(request),
+                mode,
+                origin,
+            },
+        }
+    }
+}
+
+// ===== impl NewServiceFuture =====
+
+impl<T> Future for NewServiceFuture<T>
+where T: Future,
+{
+    type Item = GrpcWeb<T::Item>;
+    type Error = T::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let inner = try_ready!(self.inner.poll());
+        Ok(GrpcWeb::new(inner).into())
+    }
+}
+
+// ===== impl ResponseFuture =====
+
+impl<T, B> Future for ResponseFuture<T>
+where T: Future<Item = http::Response<B>>,
+      B: Body<Data = Bytes>,
+{
+    type Item = http::Response<ResponseBody<B>>;
+    type Error = T::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let (inner, mode, origin) = match self.kind {
+            Kind::Call { ref mut inner, mode, ref mut origin } => (inner, mode, origin),
+            Kind::Preflight(ref mut head) => {
+                let head = head.take().expect("polled after complete");
+                let body = ResponseBody {
+                    inner: None,
+                    mode: None,
+                    done: true,
+                };
+                return Ok(http::Response::from_parts(head, body).into());
+            }
+        };
+
+        let (mut head, inner) = try_ready!(inner.poll()).into_parts();
+
+        if let Some(mode) = mode {
+            head.headers.insert(CONTENT_TYPE, mode.content_type());
+        }
+
+        if let Some(origin) = origin.take() {
+            head.headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+            head.headers.insert(ACCESS_CONTROL_EXPOSE_HEADERS,
+                                HeaderValue::from_static(EXPOSE_HEADERS));
+        }
+
+        let body = ResponseBody {
+            inner: Some(inner),
+            mode,
+            done: false,
+        };
+
+        Ok(http::Response::from_parts(head, body).into())
+    }
+}
+
+/// Returns true if `request` is a CORS preflight request.
+fn is_preflight<B>(request: &http::Request<B>) -> bool {
+    *request.method() == http::Method::OPTIONS &&
+        request.headers().contains_key(ORIGIN) &&
+        request.headers().contains_key(ACCESS_CONTROL_REQUEST_METHOD)
+}
+
+/// Returns the head of the response to a preflight request with `headers`.
+fn preflight(headers: &HeaderMap) -> http::response::Parts {
+    let (mut head, ()) = http::Response::new(()).into_parts();
+
+    if let Some(origin) = headers.get(ORIGIN) {
+        head.headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin.clone());
+    }
+
+    let allow_headers = headers.get(ACCESS_CONTROL_REQUEST_HEADERS)
+        .cloned()
+        .unwrap_or_else(|| HeaderValue::from_static(DEFAULT_ALLOW_HEADERS));
+
+    head.headers.insert(ACCESS_CONTROL_ALLOW_METHODS, HeaderValue::from_static("POST"));
+    head.headers.insert(ACCESS_CONTROL_ALLOW_HEADERS, allow_headers);
+    head.headers.insert(ACCESS_CONTROL_MAX_AGE, HeaderValue::from_static(PREFLIGHT_MAX_AGE));
+    head.headers.insert(CONTENT_LENGTH, HeaderValue::from_static("0"));
+
+    head
+}
+
+// ===== impl ResponseBody =====
+
+impl<B> Body for ResponseBody<B>
+where B: Body<Data = Bytes>,
+{
+    type Data = Bytes;
+
+    fn is_end_stream(&self) -> bool {
+        match (self.mode, self.inner.as_ref()) {
+            (None, Some(inner)) => inner.is_end_stream(),
+            _ => self.done,
+        }
+    }
+
+    fn poll_data(&mut self) -> Poll<Option<Self::Data>, h2::Error> {
+        if self.done {
+            return Ok(None.into());
+        }
+
+        let inner = self.inner.as_mut().expect("preflight bodies are done");
+
+        if self.mode.is_none() {
+            return inner.poll_data();
+        }
+
+        if let Some(data) = try_ready!(inner.poll_data()) {
+            return Ok(Some(encode(self.mode, data)).into());
+        }
+
+        // The messages have all been sent, so send the trailers in their
+        // place.
+        let trailers = try_ready!(inner.poll_trailers())
+            .unwrap_or_else(HeaderMap::new);
+
+        self.done = true;
+
+        let frame = trailers_frame(&trailers);
+        Ok(Some(encode(self.mode, frame)).into())
+    }
+
+    fn poll_trailers(&mut self) -> Poll<Option<HeaderMap>, h2::Error> {
+        match (self.mode, self.inner.as_mut()) {
+            (None, Some(inner)) => inner.poll_trailers(),
+            _ => Ok(None.into()),
+        }
+    }
+}
+
+/// Encodes response body `data` for a call in `mode`.
+fn encode(mode: Option<Mode>, data: Bytes) -> Bytes {
+    match mode {
+        Some(Mode::Text) => {
+            // Each chunk is padded separately; clients decode the
+            // concatenation of padded chunks.
+            let mut encoded = String::new();
+            base64::encode_config_buf(&data, base64::STANDARD, &mut encoded);
+            Bytes::from(encoded)
+        }
+        _ => data,
+    }
+}
+
+/// Encodes `trailers` as a gRPC-Web trailers frame.
+fn trailers_frame(trailers: &HeaderMap) -> Bytes {
+    let mut block = Vec::new();
+
+    for (name, value) in trailers.iter() {
+        block.extend_from_slice(name.as_str().as_bytes());
+        block.extend_from_slice(b":");
+        block.extend_from_slice(value.as_bytes());
+        block.extend_from_slice(b"\r\n");
+    }
+
+    let mut frame = BytesMut::with_capacity(5 + block.len());
+    frame.put_u8(TRAILERS_FLAG);
+    frame.put_u32::<BigEndian>(block.len() as u32);
+    frame.put_slice(&block);
+    frame.freeze()
+}
+
+// ===== impl Mode =====
+
+impl Mode {
+    /// Returns the mode of a gRPC-Web request, or `None` for other
+    /// requests.
+    fn of(headers: &HeaderMap) -> Option<Mode> {
+        let content_type = match headers.get(CONTENT_TYPE).and_then(|v| v.to_str().ok()) {
+            Some(content_type) => content_type,
+            None => return None,
+        };
+
+        if content_type.starts_with("application/grpc-web-text") {
+            Some(Mode::Text)
+        } else if content_type.starts_with("application/grpc-web") {
+            Some(Mode::Binary)
+        } else {
+            None
+        }
+    }
+
+    fn content_type(&self) -> HeaderValue {
+        match *self {
+            Mode::Binary => HeaderValue::from_static("application/grpc-web+proto"),
+            Mode::Text => HeaderValue::from_static("application/grpc-web-text+proto"),
+        }
+    }
+}
+
+// ===== impl DecodeText =====
+
+impl DecodeText {
+    fn new(inner: RecvBody) -> Self {
+        DecodeText {
+            inner,
+            buf: BytesMut::new(),
+        }
+    }
+}
+
+impl Stream for DecodeText {
+    type Item = Bytes;
+    type Error = h2::Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        loop {
+            match try_ready!(self.inner.poll_data()) {
+                Some(data) => {
+                    self.buf.extend_from_slice(&Bytes::from(data));
+
+                    // Only complete quanta can be decoded.
+                    let len = self.buf.len() / 4 * 4;
+                    if len == 0 {
+                        continue;
+                    }
+
+                    let encoded = self.buf.split_to(len);
+                    let decoded = decode_text(&encoded)?;
+                    return Ok(Async::Ready(Some(decoded)));
+                }
+                None => {
+                    if !self.buf.is_empty() {
+                        debug!("gRPC-Web request ended with a partial base64 quantum");
+                        return Err(h2::Reason::INTERNAL_ERROR.into());
+                    }
+
+                    return Ok(Async::Ready(None));
+                }
+            }
+        }
+    }
+}
+
+/// Decodes base64 text, which may be a concatenation of padded chunks.
+fn decode_text(encoded: &[u8]) -> Result<Bytes, h2::Error> {
+    let mut decoded = Vec::with_capacity(encoded.len() / 4 * 3);
+    let mut start = 0;
+
+    for (i, quantum) in encoded.chunks(4).enumerate() {
+        let end = (i + 1) * 4;
+
+        if quantum.contains(&b'=') || end == encoded.len() {
+            base64::decode_config_buf(&encoded[start..end], base64::STANDARD, &mut decoded)
+                .map_err(|_| {
+                    debug!("invalid base64 in gRPC-Web request");
+                    h2::Error::from(h2::Reason::INTERNAL_ERROR)
+                })?;
+            start = end;
+        }
+    }
+
+    Ok(decoded.into())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{future, stream};
+
+    /// Responds with a single message and an OK status.
+    struct Reply;
+
+    struct ReplyBody {
+        data: Option<Bytes>,
+    }
+
+    impl Service for Reply {
+        type Request = http::Request<RecvBody>;
+        type Response = http::Response<ReplyBody>;
+        type Error = h2::Error;
+        type Future = future::FutureResult<Self::Response, h2::Error>;
+
+        fn poll_ready(&mut self) -> Poll<(), h2::Error> {
+            Ok(().into())
+        }
+
+        fn call(&mut self, _: Self::Request) -> Self::Future {
+            let body = ReplyBody { data: Some(Bytes::from_static(b"msg")) };
+            future::ok(http::Response::new(body))
+        }
+    }
+
+    impl Body for ReplyBody {
+        type Data = Bytes;
+
+        fn poll_data(&mut self) -> Poll<Option<Bytes>, h2::Error> {
+            Ok(self.data.take().into())
+        }
+
+        fn poll_trailers(&mut self) -> Poll<Option<HeaderMap>, h2::Error> {
+            let mut trailers = HeaderMap::new();
+            trailers.insert("grpc-status", HeaderValue::from_static("0"));
+            Ok(Some(trailers).into())
+        }
+    }
+
+    fn request(method: http::Method, headers: &[(&'static str, &'static str)])
+        -> http::Request<RecvBody>
+    {
+        let mut request = http::Request::new(RecvBody::default());
+        *request.method_mut() = method;
+        for &(name, value) in headers {
+            request.headers_mut().insert(name, HeaderValue::from_static(value));
+        }
+        request
+    }
+
+    fn body_bytes<B: Body<Data = Bytes>>(mut body: B) -> Vec<u8> {
+        let mut bytes = vec![];
+        while let Async::Ready(Some(data)) = body.poll_data().unwrap() {
+            bytes.extend_from_slice(&data);
+        }
+        bytes
+    }
+
+    fn decode_chunks(chunks: Vec<&'static str>) -> Result<Vec<u8>, h2::Error> {
+        let chunks = chunks.into_iter().map(|chunk| Bytes::from_static(chunk.as_bytes()));
+        let body = RecvBody::from_stream(stream::iter_ok::<_, h2::Error>(chunks));
+
+        DecodeText::new(body)
+            .collect()
+            .wait()
+            .map(|decoded| decoded.iter().flat_map(|d| d.iter().cloned()).collect())
+    }
+
+    #[test]
+    fn decode_text_handles_padded_chunks() {
+        assert_eq!(&decode_text(b"aGVsbG8h").unwrap()[..], b"hello!");
+        assert_eq!(&decode_text(b"aGVsbG8=").unwrap()[..], b"hello");
+        assert_eq!(&decode_text(b"aGk=aGk=").unwrap()[..], b"hihi");
+        assert_eq!(&decode_text(b"aA==aGVsbG8h").unwrap()[..], b"hhello!");
+        assert!(decode_text(b"").unwrap().is_empty());
+    }
+
+    #[test]
+    fn decode_text_rejects_invalid_base64() {
+        assert!(decode_text(b"a$==").is_err());
+        assert!(decode_text(b"aGk=a!k=").is_err());
+    }
+
+    #[test]
+    fn trailers_frame_is_flagged_and_length_prefixed() {
+        let mut trailers = HeaderMap::new();
+        trailers.insert("grpc-status", HeaderValue::from_static("0"));
+
+        let frame = trailers_frame(&trailers);
+        assert_eq!(&frame[..5], &[TRAILERS_FLAG, 0, 0, 0, 15][..]);
+        assert_eq!(&frame[5..], &b"grpc-status:0\r\n"[..]);
+
+        let frame = trailers_frame(&HeaderMap::new());
+        assert_eq!(&frame[..], &[TRAILERS_FLAG, 0, 0, 0, 0][..]);
+    }
+
+    #[test]
+    fn decode_text_buffers_partial_quanta() {
+        let decoded = decode_chunks(vec!["aGV", "sbG8", "=aGk="]).unwrap();
+        assert_eq!(decoded, b"hellohi");
+    }
+
+    #[test]
+    fn decode_text_rejects_trailing_partial_quantum() {
+        assert!(decode_chunks(vec!["aGVs", "bG"]).is_err());
+    }
+
+    #[test]
+    fn answers_preflight_requests() {
+        let mut service = GrpcWeb::new(Reply);
+
+        let request = request(http::Method::OPTIONS, &[
+            ("origin", "https://example.com"),
+            ("access-control-request-method", "POST"),
+            ("access-control-request-headers", "content-type,x-grpc-web"),
+        ]);
+        let (head, body) = service.call(request).wait().unwrap().into_parts();
+
+        assert_eq!(head.status, http::StatusCode::OK);
+        assert_eq!(head.headers["access-control-allow-origin"], "https://example.com");
+        assert_eq!(head.headers["access-control-allow-methods"], "POST");
+        assert_eq!(head.headers["access-control-allow-headers"], "content-type,x-grpc-web");
+        assert!(body.is_end_stream());
+        assert!(body_bytes(body).is_empty());
+    }
+
+    #[test]
+    fn cross_origin_calls_expose_status() {
+        let mut service = GrpcWeb::new(Reply);
+
+        let request = request(http::Method::POST, &[
+            ("origin", "https://example.com"),
+            ("content-type", "application/grpc-web+proto"),
+        ]);
+        let (head, body) = service.call(request).wait().unwrap().into_parts();
+
+        assert_eq!(head.headers["content-type"], "application/grpc-web+proto");
+        assert_eq!(head.headers["access-control-allow-origin"], "https://example.com");
+        assert_eq!(head.headers["access-control-expose-headers"], EXPOSE_HEADERS);
+
+        let mut expected = b"msg".to_vec();
+        expected.extend_from_slice(&[TRAILERS_FLAG, 0, 0, 0, 15]);
+        expected.extend_from_slice(b"grpc-status:0\r\n");
+        assert_eq!(body_bytes(body), expected);
+    }
+
+    #[test]
+    fn grpc_calls_pass_through() {
+        let mut service = GrpcWeb::new(Reply);
+
+        let request = request(http::Method::POST, &[
+            ("origin", "https://example.com"),
+            ("content-type", "application/grpc"),
+        ]);
+        let (head, body) = service.call(request).wait().unwrap().into_parts();
+
+        assert!(!head.headers.contains_key("access-control-allow-origin"));
+        assert_eq!(body_bytes(body), b"msg");
+    }
+}
@@ -1,7 +1,9 @@
 mod background;
 mod new_service;
+pub mod pool;
 mod service;
 
 pub use self::background::Background;
 pub use self::new_service::{Client, ConnectFuture, ConnectError};
+pub use self::pool::Pool;
 pub use self::service::{Service, ResponseFuture, Error};
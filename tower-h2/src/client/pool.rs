@@ -0,0 +1,678 @@
+use futures::{Async, Future, Poll};
+use h2;
+use http::{self, HeaderMap};
+use tower::{NewService, Service};
+
+use Body;
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Balances requests over a pool of connections to a single endpoint.
+///
+/// The pool starts with a single connection, obtained from `N`. Another
+/// connection is opened, up to `Config::max_connections`, when every
+/// connection is out of streams or when responses on the least loaded
+/// connection are slower than `Config::max_latency`. Each request is sent on
+/// the ready connection with the fewest requests in flight.
+///
+/// A request counts as in flight until its response body ends or is
+/// dropped. Extra connections that have no requests in flight for
+/// `Config::idle_timeout` are closed. If every connection fails, the pool
+/// reconnects when it is next polled.
+pub struct Pool<N>
+where N: NewService,
+{
+    new_service: N,
+    config: Config,
+    connections: Vec<Connection<N::Service>>,
+    connecting: Option<N::Future>,
+    /// The connection the next request is sent on, as chosen by `poll_ready`.
+    ready: Option<usize>,
+}
+
+/// Configures the size of a `Pool`.
+#[derive(Clone, Copy, Debug)]
+pub struct Config {
+    max_connections: usize,
+    idle_timeout: Duration,
+    max_latency: Option<Duration>,
+}
+
+pub struct ResponseFuture<N>
+where N: NewService,
+{
+    inner: Option<<N::Service as Service>::Future>,
+    in_flight: Option<InFlight>,
+}
+
+/// Keeps a request counted as in flight until the response body ends.
+#[derive(Debug)]
+pub struct ResponseBody<B> {
+    inner: B,
+    in_flight: Option<InFlight>,
+}
+
+/// Errors produced by a `Pool`.
+#[derive(Debug)]
+pub enum Error<T, U> {
+    /// A connection's service failed.
+    Inner(T),
+
+    /// No connection could be established.
+    Connect(U),
+
+    /// `call` was invoked before `poll_ready` was ready.
+    NotReady,
+}
+
+struct Connection<S> {
+    service: S,
+    load: Arc<Mutex<Load>>,
+}
+
+#[derive(Debug)]
+struct Load {
+    in_flight: usize,
+    /// A moving average of the time taken to receive a response.
+    latency: Option<Duration>,
+    /// When the last request in flight completed.
+    idle_since: Instant,
+}
+
+/// Counts a request as in flight until it is dropped.
+#[derive(Debug)]
+struct InFlight {
+    load: Arc<Mutex<Load>>,
+    start: Instant,
+}
+
+const DEFAULT_MAX_CONNECTIONS: usize = 4;
+const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 10;
+
+// ===== impl Pool =====
+
+impl<N> Pool<N>
+where N: NewService,
+{
+    /// Returns a new `Pool` that opens connections with `new_service`.
+    ///
+    /// No connection is opened until the pool is polled.
+    pub fn new(new_service: N, config: Config) -> Self {
+        Pool {
+            new_service,
+            config,
+            connections: Vec::new(),
+            connecting: None,
+            ready: None,
+        }
+    }
+
+    /// Returns the number of open connections.
+    pub fn connections(&self) -> usize {
+        self.connections.len()
+    }
+
+    /// Polls the connection being opened, if any, adding it to the pool once
+    /// it is established.
+    fn poll_connecting(&mut self) -> Result<(), Error<N::Error, N::InitError>> {
+        let poll = match self.connecting {
+            Some(ref mut connecting) => connecting.poll(),
+            None => return Ok(()),
+        };
+
+        match poll {
+            Ok(Async::Ready(service)) => {
+                self.connecting = None;
+                self.connections.push(Connection::new(service));
+                trace!("opened connection; connections={}", self.connections.len());
+                Ok(())
+            }
+            Ok(Async::NotReady) => Ok(()),
+            Err(e) => {
+                self.connecting = None;
+
+                if self.connections.is_empty() {
+                    return Err(Error::Connect(e));
+                }
+
+                debug!("failed to open an additional connection");
+                Ok(())
+            }
+        }
+    }
+
+    /// Closes connections that have been idle for longer than the idle
+    /// timeout, always keeping at least one.
+    fn retire_idle(&mut self) {
+        let now = Instant::now();
+        let idle_timeout = self.config.idle_timeout;
+
+        while self.connections.len() > 1 {
+            let idle = self.connections.iter()
+                .position(|c| c.is_idle(now, idle_timeout));
+
+            match idle {
+                Some(i) => {
+                    self.connections.remove(i);
+                    trace!("closed idle connection; connections={}", self.connections.len());
+                }
+                None => return,
+            }
+        }
+    }
+
+    /// Whether responses on the ready connection are too slow.
+    fn is_slow(&self) -> bool {
+        match (self.ready, self.config.max_latency) {
+            (Some(i), Some(max)) => {
+                self.connections[i].latency().map(|l| l > max).unwrap_or(false)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<N, B> Service for Pool<N>
+where N: NewService<Response = http::Response<B>>,
+      B: Body,
+{
+    type Request = N::Request;
+    type Response = http::Response<ResponseBody<B>>;
+    type Error = Error<N::Error, N::InitError>;
+    type Future = ResponseFuture<N>;
+
+    fn poll_ready(&mut self) -> Poll<(), Self::Error> {
+        self.retire_idle();
+
+        let mut failed = None;
+
+        loop {
+            self.poll_connecting()?;
+
+            // Pick the ready connection with the fewest requests in flight,
+            // dropping any that have failed.
+            self.ready = None;
+            let mut least = 0;
+            let mut i = 0;
+
+            while i < self.connections.len() {
+                match self.connections[i].service.poll_ready() {
+                    Ok(Async::Ready(())) => {
+                        let in_flight = self.connections[i].in_flight();
+
+                        if self.ready.is_none() || in_flight < least {
+                            self.ready = Some(i);
+                            least = in_flight;
+                        }
+
+                        i += 1;
+                    }
+                    Ok(Async::NotReady) => {
+                        i += 1;
+                    }
+                    Err(e) => {
+                        debug!("connection failed; connections={}", self.connections.len() - 1);
+                        self.connections.remove(i);
+                        failed = Some(e);
+                    }
+                }
+            }
+
+            let satisfied = self.ready.is_some() && !self.is_slow();
+            let at_capacity = self.connections.len() >= self.config.max_connections;
+
+            if satisfied || at_capacity || self.connecting.is_some() {
+                break;
+            }
+
+            trace!("opening connection; connections={}", self.connections.len());
+            self.connecting = Some(self.new_service.new_service());
+        }
+
+        if self.ready.is_some() {
+            return Ok(Async::Ready(()));
+        }
+
+        // Only surface a failure once there is nothing left to wait on.
+        if self.connections.is_empty() {
+            if let Some(e) = failed {
+                return Err(Error::Inner(e));
+            }
+        }
+
+        Ok(Async::NotReady)
+    }
+
+    fn call(&mut self, request: Self::Request) -> Self::Future {
+        let connection = match self.ready.take() {
+            Some(i) => &mut self.connections[i],
+            None => {
+                return ResponseFuture {
+                    inner: None,
+                    in_flight: None,
+                };
+            }
+        };
+
+        let in_flight = InFlight::new(&connection.load);
+        let inner = connection.service.call(request);
+
+        ResponseFuture {
+            inner: Some(inner),
+            in_flight: Some(in_flight),
+        }
+    }
+}
+
+impl<N> fmt::Debug for Pool<N>
+where N: NewService,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Pool")
+            .field("config", &self.config)
+            .field("connections", &self.connections.len())
+            .field("connecting", &self.connecting.is_some())
+            .finish()
+    }
+}
+
+// ===== impl Config =====
+
+impl Config {
+    /// Returns a `Config` with the default settings.
+    pub fn new() -> Self {
+        Config {
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            idle_timeout: Duration::from_secs(DEFAULT_IDLE_TIMEOUT_SECS),
+            max_latency: None,
+        }
+    }
+
+    /// Limit the number of connections that are opened.
+    ///
+    /// A limit of 1 disables pooling.
+    pub fn max_connections(self, max_connections: usize) -> Self {
+        assert!(max_connections > 0, "a pool needs at least one connection");
+        Config {
+            max_connections,
+            ..self
+        }
+    }
+
+    /// Close extra connections that have been idle for `idle_timeout`.
+    pub fn idle_timeout(self, idle_timeout: Duration) -> Self {
+        Config {
+            idle_timeout,
+            ..self
+        }
+    }
+
+    /// Open another connection when responses take longer than
+    /// `max_latency` on average.
+    ///
+    /// By default, connections are only opened when the others are out of
+    /// streams.
+    pub fn max_latency(self, max_latency: Duration) -> Self {
+        Config {
+            max_latency: Some(max_latency),
+            ..self
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config::new()
+    }
+}
+
+// ===== impl ResponseFuture =====
+
+impl<N, B> Future for ResponseFuture<N>
+where N: NewService<Response = http::Response<B>>,
+      B: Body,
+{
+    type Item = http::Response<ResponseBody<B>>;
+    type Error = Error<N::Error, N::InitError>;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let response = match self.inner {
+            Some(ref mut inner) => try_ready!(inner.poll().map_err(Error::Inner)),
+            None => return Err(Error::NotReady),
+        };
+
+        let mut in_flight = self.in_flight.take();
+        if let Some(ref mut in_flight) = in_flight {
+            in_flight.record_latency();
+        }
+
+        let (head, inner) = response.into_parts();
+        let body = ResponseBody::new(inner, in_flight);
+
+        Ok(Async::Ready(http::Response::from_parts(head, body)))
+    }
+}
+
+// ===== impl ResponseBody =====
+
+impl<B: Body> ResponseBody<B> {
+    fn new(inner: B, in_flight: Option<InFlight>) -> Self {
+        let mut body = ResponseBody {
+            inner,
+            in_flight,
+        };
+        body.release_if_ended();
+        body
+    }
+
+    /// Stops counting the request as in flight once the stream has ended.
+    fn release_if_ended(&mut self) {
+        if self.inner.is_end_stream() {
+            self.in_flight = None;
+        }
+    }
+}
+
+impl<B: Body> Body for ResponseBody<B> {
+    type Data = B::Data;
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn poll_data(&mut self) -> Poll<Option<Self::Data>, h2::Error> {
+        let poll = self.inner.poll_data();
+
+        match poll {
+            Ok(Async::NotReady) => {}
+            Ok(Async::Ready(Some(_))) => self.release_if_ended(),
+            // The stream has ended, or failed.
+            _ => self.in_flight = None,
+        }
+
+        poll
+    }
+
+    fn poll_trailers(&mut self) -> Poll<Option<HeaderMap>, h2::Error> {
+        let poll = self.inner.poll_trailers();
+
+        if let Ok(Async::NotReady) = poll {
+            return poll;
+        }
+
+        self.in_flight = None;
+        poll
+    }
+}
+
+impl<B: Default> Default for ResponseBody<B> {
+    fn default() -> Self {
+        ResponseBody {
+            inner: B::default(),
+            in_flight: None,
+        }
+    }
+}
+
+// ===== impl Connection =====
+
+impl<S> Connection<S> {
+    fn new(service: S) -> Self {
+        let load = Load {
+            in_flight: 0,
+            latency: None,
+            idle_since: Instant::now(),
+        };
+
+        Connection {
+            service,
+            load: Arc::new(Mutex::new(load)),
+        }
+    }
+
+    fn in_flight(&self) -> usize {
+        self.load.lock().expect("pool load lock").in_flight
+    }
+
+    fn latency(&self) -> Option<Duration> {
+        self.load.lock().expect("pool load lock").latency
+    }
+
+    fn is_idle(&self, now: Instant, idle_timeout: Duration) -> bool {
+        let load = self.load.lock().expect("pool load lock");
+        load.in_flight == 0 && now.duration_since(load.idle_since) >= idle_timeout
+    }
+}
+
+// ===== impl InFlight =====
+
+impl InFlight {
+    fn new(load: &Arc<Mutex<Load>>) -> Self {
+        load.lock().expect("pool load lock").in_flight += 1;
+
+        InFlight {
+            load: load.clone(),
+            start: Instant::now(),
+        }
+    }
+
+    /// Records how long the request took to be answered.
+    ///
+    /// The request still counts as in flight until `self` is dropped.
+    fn record_latency(&mut self) {
+        let elapsed = self.start.elapsed();
+        let mut load = self.load.lock().expect("pool load lock");
+
+        load.latency = Some(match load.latency {
+            // Weigh the newest response by a quarter.
+            Some(latency) => (latency * 3 + elapsed) / 4,
+            None => elapsed,
+        });
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        let mut load = self.load.lock().expect("pool load lock");
+        load.in_flight -= 1;
+
+        if load.in_flight == 0 {
+            load.idle_since = Instant::now();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use futures::future::{self, FutureResult};
+    use std::cell::{Cell, RefCell};
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+
+    /// Opens the given connections in order, failing once none are left.
+    struct NewMock(RefCell<VecDeque<Mock>>);
+
+    #[derive(Clone)]
+    struct Mock {
+        ready: Rc<Cell<bool>>,
+        calls: Rc<Cell<usize>>,
+    }
+
+    /// A response body with a single chunk.
+    #[derive(Default)]
+    struct Chunk(Option<Bytes>);
+
+    fn request() -> http::Request<()> {
+        http::Request::new(())
+    }
+
+    #[test]
+    fn opens_connections_until_one_is_ready() {
+        let busy = Mock::new(false);
+        let ready = Mock::new(true);
+        let mut pool = Pool::new(NewMock::new(&[&busy, &ready]), Config::new());
+
+        future::lazy(|| {
+            assert!(pool.poll_ready().unwrap().is_ready());
+            assert_eq!(pool.connections(), 2);
+
+            pool.call(request()).wait().unwrap();
+            Ok::<(), ()>(())
+        }).wait().unwrap();
+
+        assert_eq!(busy.calls.get(), 0);
+        assert_eq!(ready.calls.get(), 1);
+    }
+
+    #[test]
+    fn opens_at_most_max_connections() {
+        let busy = Mock::new(false);
+        let config = Config::new().max_connections(1);
+        let mut pool = Pool::new(NewMock::new(&[&busy, &Mock::new(true)]), config);
+
+        future::lazy(|| {
+            assert!(pool.poll_ready().unwrap().is_not_ready());
+            assert_eq!(pool.connections(), 1);
+
+            busy.ready.set(true);
+            assert!(pool.poll_ready().unwrap().is_ready());
+            assert_eq!(pool.connections(), 1);
+            Ok::<(), ()>(())
+        }).wait().unwrap();
+    }
+
+    #[test]
+    fn sends_requests_on_the_least_loaded_connection() {
+        let a = Mock::new(false);
+        let b = Mock::new(true);
+        let mut pool = Pool::new(NewMock::new(&[&a, &b]), Config::new());
+
+        future::lazy(|| {
+            assert!(pool.poll_ready().unwrap().is_ready());
+            a.ready.set(true);
+
+            // Both connections are idle, so the first is used.
+            assert!(pool.poll_ready().unwrap().is_ready());
+            let mut rsp_a = pool.call(request()).wait().unwrap();
+            assert_eq!(a.calls.get(), 1);
+
+            // The request on `a` is in flight until its body ends.
+            assert!(pool.poll_ready().unwrap().is_ready());
+            let _rsp_b = pool.call(request()).wait().unwrap();
+            assert_eq!(b.calls.get(), 1);
+
+            assert!(rsp_a.body_mut().poll_data().unwrap().is_ready());
+            assert!(rsp_a.body().is_end_stream());
+
+            // `b` still has a request in flight.
+            assert!(pool.poll_ready().unwrap().is_ready());
+            pool.call(request()).wait().unwrap();
+            assert_eq!(a.calls.get(), 2);
+            assert_eq!(b.calls.get(), 1);
+            Ok::<(), ()>(())
+        }).wait().unwrap();
+    }
+
+    #[test]
+    fn closes_idle_connections() {
+        let a = Mock::new(false);
+        let b = Mock::new(true);
+        let config = Config::new().idle_timeout(Duration::from_secs(0));
+        let mut pool = Pool::new(NewMock::new(&[&a, &b]), config);
+
+        future::lazy(|| {
+            assert!(pool.poll_ready().unwrap().is_ready());
+            assert_eq!(pool.connections(), 2);
+
+            // Only `a` is idle, as a request is in flight on `b`.
+            let rsp = pool.call(request()).wait().unwrap();
+            assert!(pool.poll_ready().unwrap().is_ready());
+            assert_eq!(pool.connections(), 1);
+
+            // The last connection is kept, even when idle.
+            drop(rsp);
+            assert!(pool.poll_ready().unwrap().is_ready());
+            assert_eq!(pool.connections(), 1);
+
+            pool.call(request()).wait().unwrap();
+            Ok::<(), ()>(())
+        }).wait().unwrap();
+
+        assert_eq!(a.calls.get(), 0);
+        assert_eq!(b.calls.get(), 2);
+    }
+
+    // ===== impl NewMock =====
+
+    impl NewMock {
+        fn new(connections: &[&Mock]) -> Self {
+            let connections = connections.iter().map(|&m| m.clone()).collect();
+            NewMock(RefCell::new(connections))
+        }
+    }
+
+    impl NewService for NewMock {
+        type Request = http::Request<()>;
+        type Response = http::Response<Chunk>;
+        type Error = ();
+        type InitError = ();
+        type Service = Mock;
+        type Future = FutureResult<Mock, ()>;
+
+        fn new_service(&self) -> Self::Future {
+            match self.0.borrow_mut().pop_front() {
+                Some(mock) => future::ok(mock),
+                None => future::err(()),
+            }
+        }
+    }
+
+    // ===== impl Mock =====
+
+    impl Mock {
+        fn new(ready: bool) -> Self {
+            Mock {
+                ready: Rc::new(Cell::new(ready)),
+                calls: Rc::new(Cell::new(0)),
+            }
+        }
+    }
+
+    impl Service for Mock {
+        type Request = http::Request<()>;
+        type Response = http::Response<Chunk>;
+        type Error = ();
+        type Future = FutureResult<Self::Response, ()>;
+
+        fn poll_ready(&mut self) -> Poll<(), ()> {
+            if self.ready.get() {
+                Ok(Async::Ready(()))
+            } else {
+                Ok(Async::NotReady)
+            }
+        }
+
+        fn call(&mut self, _: Self::Request) -> Self::Future {
+            self.calls.set(self.calls.get() + 1);
+            future::ok(http::Response::new(Chunk(Some(Bytes::from_static(b"hello")))))
+        }
+    }
+
+    // ===== impl Chunk =====
+
+    impl Body for Chunk {
+        type Data = Bytes;
+
+        fn is_end_stream(&self) -> bool {
+            self.0.is_none()
+        }
+
+        fn poll_data(&mut self) -> Poll<Option<Bytes>, h2::Error> {
+            Ok(Async::Ready(self.0.take()))
+        }
+    }
+}
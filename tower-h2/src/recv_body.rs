@@ -4,15 +4,20 @@ use futures::{Poll, Stream};
 use h2;
 use http;
 
+use std::fmt;
+
 /// Allows a stream to be read from the remote.
-#[derive(Debug, Default)]
+#[derive(Default)]
 pub struct RecvBody {
     inner: Option<h2::RecvStream>,
+
+    /// A body that is not received on an h2 stream.
+    stream: Option<Box<Stream<Item = Bytes, Error = h2::Error> + Send>>,
 }
 
 #[derive(Debug)]
 pub struct Data {
-    release_capacity: h2::ReleaseCapacity,
+    release_capacity: Option<h2::ReleaseCapacity>,
     bytes: Bytes,
 }
 
@@ -21,7 +26,24 @@ pub struct Data {
 impl RecvBody {
     /// Return a new `RecvBody`.
     pub(crate) fn new(inner: h2::RecvStream) -> Self {
-        RecvBody { inner: Some(inner) }
+        RecvBody {
+            inner: Some(inner),
+            stream: None,
+        }
+    }
+
+    /// Return a `RecvBody` that yields the data of `stream`.
+    ///
+    /// This allows services to be called with bodies that are received by
+    /// other means, e.g. over HTTP/1.1, or that are transformed before being
+    /// read. Such bodies never have trailers.
+    pub fn from_stream<S>(stream: S) -> Self
+    where S: Stream<Item = Bytes, Error = h2::Error> + Send + 'static,
+    {
+        RecvBody {
+            inner: None,
+            stream: Some(Box::new(stream)),
+        }
     }
 }
 
@@ -32,7 +54,7 @@ impl Body for RecvBody {
     fn is_end_stream(&self) -> bool {
         match self.inner {
             Some(ref inner) => inner.is_end_stream(),
-            None => true,
+            None => self.stream.is_none(),
         }
     }
 
@@ -42,14 +64,32 @@ impl Body for RecvBody {
                 let data = try_ready!(inner.poll())
                     .map(|bytes| {
                         Data {
-                            release_capacity: inner.release_capacity().clone(),
+                            release_capacity: Some(inner.release_capacity().clone()),
                             bytes,
                         }
                     });
 
                 Ok(data.into())
             }
-            None => Ok(None.into()),
+            None => {
+                let data = match self.stream {
+                    Some(ref mut stream) => try_ready!(stream.poll()),
+                    None => return Ok(None.into()),
+                };
+
+                if data.is_none() {
+                    self.stream = None;
+                }
+
+                let data = data.map(|bytes| {
+                    Data {
+                        release_capacity: None,
+                        bytes,
+                    }
+                });
+
+                Ok(data.into())
+            }
         }
     }
 
@@ -61,8 +101,26 @@ impl Body for RecvBody {
     }
 }
 
+impl fmt::Debug for RecvBody {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("RecvBody")
+            .field("inner", &self.inner)
+            .field("stream", &self.stream.as_ref().map(|_| "Stream"))
+            .finish()
+    }
+}
+
 // ===== impl Data =====
 
+impl Data {
+    fn release_capacity(&mut self, sz: usize) {
+        if let Some(ref mut release_capacity) = self.release_capacity {
+            release_capacity.release_capacity(sz)
+                .expect("flow control error");
+        }
+    }
+}
+
 impl Buf for Data {
     fn remaining(&self) -> usize {
         self.bytes.len()
@@ -80,8 +138,7 @@ impl Buf for Data {
         trace!("releasing capacity: {} of {}", cnt, self.remaining());
         let _ = self.bytes.split_to(cnt);
 
-        self.release_capacity.release_capacity(cnt)
-            .expect("flow control error")
+        self.release_capacity(cnt)
     }
 }
 
@@ -89,9 +146,7 @@ impl Drop for Data {
     fn drop(&mut self) {
         let sz = self.remaining();
         trace!("Data::drop: releasing capacity: {}", sz);
-        self.release_capacity
-            .release_capacity(sz)
-            .expect("flow control error");
+        self.release_capacity(sz);
     }
 }
 
@@ -99,8 +154,7 @@ impl From<Data> for Bytes {
     fn from(mut src: Data) -> Self {
         let bytes = ::std::mem::replace(&mut src.bytes, Bytes::new());
 
-        src.release_capacity.release_capacity(bytes.len())
-            .expect("flow control error");
+        src.release_capacity(bytes.len());
 
         bytes
     }
@@ -110,8 +164,7 @@ impl From<Data> for BytesMut {
     fn from(mut src: Data) -> Self {
         let bytes = ::std::mem::replace(&mut src.bytes, Bytes::new());
 
-        src.release_capacity.release_capacity(bytes.len())
-            .expect("flow control error");
+        src.release_capacity(bytes.len());
 
         bytes.into()
     }
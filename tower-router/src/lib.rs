@@ -1,20 +1,52 @@
+#[macro_use]
 extern crate futures;
+extern crate http;
 extern crate ordermap;
 extern crate tower;
 
-use futures::{Future, Poll};
+use futures::{Async, Future, Poll};
 use ordermap::OrderMap;
 use tower::Service;
 
 use std::hash::Hash;
 use std::mem;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, Weak};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+pub mod recognize;
+
+pub use recognize::{Header, MapKey, Method, Or, PathPrefix};
 
 /// Route requests based on the request authority
+///
+/// Services are bound once per key and cached. A bounded router evicts
+/// routes that have not been used within a maximum idle age and, when full,
+/// the least recently used route, or a route whose service fails.
+///
+/// Each clone of a router remembers the routes it has used, so requests to
+/// known routes are dispatched without locking the routes shared by all
+/// clones. These are weak handles: an evicted service is dropped as soon as
+/// no request is using it. Routes apply backpressure independently: a
+/// request to a route that is not ready waits in its `ResponseFuture`
+/// without holding up requests to other routes. Once too many requests are
+/// waiting for a route, further requests to it fail with
+/// `Error::Overloaded`.
 pub struct Router<T>
 where T: Recognize,
 {
-    inner: Arc<Mutex<Inner<T>>>,
+    shared: Arc<Shared<T>>,
+
+    /// Routes used through this handle. Each is only used while it is still
+    /// cached in `shared`.
+    local: OrderMap<T::Key, Weak<Mutex<Route<T::Service>>>>,
+
+    /// The earliest time at which a route cached in `shared` may become idle
+    next_purge: Option<Instant>,
+
+    /// The maximum number of requests that may wait for a route to become
+    /// ready
+    max_pending: usize,
 }
 
 /// Route a request based on an authority
@@ -44,18 +76,49 @@ pub trait Recognize {
 
     /// Return a `Service` to handle requests from the provided authority.
     ///
-    /// The returned service must always be in the ready state (i.e.
-    /// `poll_ready` must always return `Ready` or `Err`).
-    fn bind_service(&mut self, key: &Self::Key) -> Result<Self::Service, Self::RouteError>;
+    /// The returned service is shared by all clones of the `Router`.
+    fn bind_service(&self, key: &Self::Key) -> Result<Self::Service, Self::RouteError>;
+
+    /// Routes requests that `self` does not recognize with `other`.
+    fn or<B>(self, other: B) -> Or<Self, B>
+    where Self: Sized,
+          B: Recognize<Request = Self::Request,
+                      Response = Self::Response,
+                         Error = Self::Error,
+                    RouteError = Self::RouteError>,
+    {
+        Or::new(self, other)
+    }
+
+    /// Routes requests by `f` applied to the keys recognized by `self`.
+    ///
+    /// Requests whose keys map to the same value share a route.
+    fn map_key<F, K>(self, f: F) -> MapKey<Self, F>
+    where Self: Sized,
+          F: Fn(&Self::Key) -> K,
+          K: Clone + Eq + Hash,
+    {
+        MapKey::new(self, f)
+    }
 }
 
-pub struct Single<S>(Option<S>);
+pub struct Single<S>(Mutex<Option<S>>);
+
+/// Counts the routes cached by one or more routers.
+///
+/// Clones share the same count, so a single `RouteCount` may be given to
+/// several routers to count their routes together.
+#[derive(Clone, Debug, Default)]
+pub struct RouteCount(Arc<AtomicUsize>);
 
 #[derive(Debug)]
 pub enum Error<T, U> {
     Inner(T),
     Route(U),
     NotRecognized,
+    /// The route was not ready, and too many requests were already waiting
+    /// for it.
+    Overloaded,
 }
 
 pub struct ResponseFuture<T>
@@ -64,34 +127,175 @@ where T: Recognize,
     state: State<T>,
 }
 
-struct Inner<T>
+/// State shared by all clones of a `Router`.
+struct Shared<T>
 where T: Recognize,
 {
-    routes: OrderMap<T::Key, T::Service>,
     recognize: T,
+
+    routes: Mutex<Cache<T::Key, T::Service>>,
+}
+
+/// Services by key, with eviction of idle and least recently used routes.
+struct Cache<K, S>
+where K: Clone + Eq + Hash,
+{
+    routes: OrderMap<K, Arc<Mutex<Route<S>>>>,
+
+    /// The maximum number of cached routes
+    capacity: usize,
+
+    /// Routes that have not been used for this long are evicted
+    max_idle_age: Option<Duration>,
+
+    /// The earliest time at which a cached route may become idle
+    next_purge: Option<Instant>,
+
+    count: RouteCount,
+}
+
+struct Route<S> {
+    service: S,
+    last_used: Instant,
+
+    /// Set once the route is removed from the cache, so that router handles
+    /// stop using it.
+    evicted: bool,
+
+    /// The number of requests waiting for the service to become ready
+    pending: usize,
+}
+
+/// A request waiting for its route to become ready.
+struct Pending<T>
+where T: Recognize,
+{
+    shared: Arc<Shared<T>>,
+    key: T::Key,
+    route: Arc<Mutex<Route<T::Service>>>,
+    request: Option<T::Request>,
+
+    /// Whether the request is counted as waiting for the route
+    parked: bool,
 }
 
 enum State<T>
 where T: Recognize,
 {
     Inner(<T::Service as Service>::Future),
+    NotReady(Pending<T>),
+    InnerError(T::Error),
     RouteError(T::RouteError),
     NotRecognized,
+    Overloaded,
     Invalid,
 }
 
+/// The number of requests that may wait for a route to become ready, unless
+/// the router is configured otherwise.
+const DEFAULT_MAX_PENDING: usize = 1_000;
+
 // ===== impl Router =====
 
 impl<T> Router<T>
 where T: Recognize
 {
+    /// Returns a router that caches every bound route indefinitely.
     pub fn new(recognize: T) -> Self {
+        Router::with_limits(recognize, None, None, RouteCount::default())
+    }
+
+    /// Returns a router that caches at most `capacity` routes, each for no
+    /// longer than `max_idle_age` after it was last used. Either limit may be
+    /// `None`, in which case routes are not evicted for that reason.
+    ///
+    /// Evicted services are dropped, and bound again by `recognize` when
+    /// they are next needed. The number of cached routes is added to `count`.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is zero.
+    pub fn with_limits(
+        recognize: T,
+        capacity: Option<usize>,
+        max_idle_age: Option<Duration>,
+        count: RouteCount,
+    ) -> Self {
+        let capacity = capacity.unwrap_or(::std::usize::MAX);
+        assert!(capacity > 0, "router capacity must be positive");
+
         Router {
-            inner: Arc::new(Mutex::new(Inner {
-                routes: Default::default(),
+            shared: Arc::new(Shared {
                 recognize,
-            })),
+                routes: Mutex::new(Cache::new(capacity, max_idle_age, count)),
+            }),
+            local: OrderMap::new(),
+            next_purge: None,
+            max_pending: DEFAULT_MAX_PENDING,
+        }
+    }
+
+    /// Allows at most `max_pending` requests to wait for each route to
+    /// become ready. Further requests to the route fail with
+    /// `Error::Overloaded` until it is ready again.
+    ///
+    /// The limit applies to clones of the router made afterwards, too.
+    ///
+    /// # Panics
+    ///
+    /// If `max_pending` is zero.
+    pub fn with_max_pending(mut self, max_pending: usize) -> Self {
+        assert!(max_pending > 0, "router max_pending must be positive");
+        self.max_pending = max_pending;
+        self
+    }
+
+    /// Returns the number of cached routes.
+    pub fn len(&self) -> usize {
+        self.shared.routes.lock().unwrap().len()
+    }
+
+    /// Returns true if no routes are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns this handle's route for `key`, if it is still cached.
+    fn local_route(&self, key: &T::Key, now: Instant) -> Option<Arc<Mutex<Route<T::Service>>>> {
+        let route = match self.local.get(key).and_then(|route| route.upgrade()) {
+            Some(route) => route,
+            None => return None,
+        };
+
+        {
+            let mut r = route.lock().unwrap();
+            if r.evicted {
+                return None;
+            }
+            r.last_used = now;
+        }
+
+        Some(route)
+    }
+
+    /// Evicts idle routes from the shared cache, if any may be idle.
+    fn purge_idle(&mut self, now: Instant) {
+        match self.next_purge {
+            Some(t) if t <= now => {}
+            _ => return,
         }
+
+        self.next_purge = self.shared.purge_idle(now);
+        self.forget_evicted();
+    }
+
+    /// Forgets the routes that are no longer cached.
+    fn forget_evicted(&mut self) {
+        self.local.retain(|_, route| {
+            route.upgrade()
+                .map(|route| !route.lock().unwrap().evicted)
+                .unwrap_or(false)
+        });
     }
 }
 
@@ -103,72 +307,320 @@ where T: Recognize,
     type Error = Error<T::Error, T::RouteError>;
     type Future = ResponseFuture<T>;
 
+    /// The router is always ready; the readiness of each route is checked as
+    /// requests are dispatched to it.
     fn poll_ready(&mut self) -> Poll<(), Self::Error> {
         Ok(().into())
     }
 
     fn call(&mut self, request: Self::Request) -> Self::Future {
-        let mut inner = self.inner.lock().unwrap();
-        let inner = &mut *inner;
-
-        // This insanity is to make the borrow checker happy...
-
-        // These vars will be used to insert a new service in the cache.
-        let new_key;
-        let mut new_service;
-
-        // This loop is used to create a borrow checker scope as well as being
-        // able to call `break` to jump out of it.
-        loop {
-            let service;
-
-            if let Some(key) = inner.recognize.recognize(&request) {
-                if let Some(s) = inner.routes.get_mut(&key) {
-                    // The service for the authority is already cached
-                    service = s;
-                } else {
-                    // The authority does not match an existing route, try to
-                    // recognize it.
-                    match inner.recognize.bind_service(&key) {
-                        Ok(s) => {
-                            // A new service has been matched. Set the outer
-                            // variables and jump out o the loop
-                            new_key = key.clone();
-                            new_service = s;
-                            break;
-                        }
-                        Err(e) => {
-                            // Route recognition failed
-                            return ResponseFuture { state: State::RouteError(e) };
-                        }
-                    }
+        let key = match self.shared.recognize.recognize(&request) {
+            Some(key) => key,
+            // The request has no authority
+            None => return ResponseFuture { state: State::NotRecognized },
+        };
+
+        let now = Instant::now();
+        self.purge_idle(now);
+
+        let route = match self.local_route(&key, now) {
+            Some(route) => route,
+            None => {
+                // This handle has not used the route yet, or it was evicted,
+                // so obtain it from the shared routes.
+                let (route, next_purge) = match self.shared.bind(&key, now) {
+                    Ok(bound) => bound,
+                    Err(e) => return ResponseFuture { state: State::RouteError(e) },
+                };
+
+                if self.local.len() >= self.shared.capacity() {
+                    self.forget_evicted();
                 }
-            } else {
-                // The request has no authority
-                return ResponseFuture { state: State::NotRecognized };
+                self.local.insert(key.clone(), Arc::downgrade(&route));
+                self.next_purge = next_purge;
+
+                route
             }
+        };
+
+        let mut pending = Pending {
+            shared: self.shared.clone(),
+            key,
+            route,
+            request: Some(request),
+            parked: false,
+        };
+
+        let state = match pending.poll_route() {
+            Ok(Async::Ready(fut)) => State::Inner(fut),
+            Ok(Async::NotReady) if pending.park(self.max_pending) => State::NotReady(pending),
+            Ok(Async::NotReady) => State::Overloaded,
+            Err(e) => State::InnerError(e),
+        };
+
+        ResponseFuture { state }
+    }
+}
+
+impl<T> Clone for Router<T>
+where T: Recognize,
+{
+    fn clone(&self) -> Self {
+        Router {
+            shared: self.shared.clone(),
+            local: OrderMap::new(),
+            next_purge: None,
+            max_pending: self.max_pending,
+        }
+    }
+}
 
-            // Route to the cached service.
-            let response = service.call(request);
-            return ResponseFuture { state: State::Inner(response) };
+// ===== impl Shared =====
+
+impl<T> Shared<T>
+where T: Recognize,
+{
+    /// Returns the route for `key`, binding it if it is not cached, and the
+    /// time at which a cached route may next become idle.
+    fn bind(&self, key: &T::Key, now: Instant)
+        -> Result<(Arc<Mutex<Route<T::Service>>>, Option<Instant>), T::RouteError>
+    {
+        {
+            let mut routes = self.routes.lock().unwrap();
+            routes.purge_idle(now);
+
+            if let Some(route) = routes.get(key, now) {
+                return Ok((route, routes.next_purge));
+            }
         }
 
-        // First, route the request to the new service
-        let response = new_service.call(request);
+        // Bind the service without holding the lock, so that binding doesn't
+        // hold up requests to other routes.
+        let service = self.recognize.bind_service(key)?;
+
+        let mut routes = self.routes.lock().unwrap();
+        let route = match routes.get(key, now) {
+            // Another handle bound the route in the meantime, and its service
+            // is used instead.
+            Some(route) => route,
+            None => routes.insert(key.clone(), service, now),
+        };
+
+        Ok((route, routes.next_purge))
+    }
 
-        // Now, cache the new service
-        inner.routes.insert(new_key, new_service);
+    /// Evicts idle routes, returning the time at which a cached route may
+    /// next become idle.
+    fn purge_idle(&self, now: Instant) -> Option<Instant> {
+        let mut routes = self.routes.lock().unwrap();
+        routes.purge_idle(now);
+        routes.next_purge
+    }
+
+    /// Evicts `route` if it is still cached for `key`.
+    fn evict(&self, key: &T::Key, route: &Arc<Mutex<Route<T::Service>>>) {
+        self.routes.lock().unwrap().remove(key, route);
+    }
 
-        // And finally, return the response
-        ResponseFuture { state: State::Inner(response) }
+    fn capacity(&self) -> usize {
+        self.routes.lock().unwrap().capacity
     }
 }
 
-impl<T> Clone for Router<T>
+// ===== impl Cache =====
+
+impl<K, S> Cache<K, S>
+where K: Clone + Eq + Hash,
+{
+    fn new(capacity: usize, max_idle_age: Option<Duration>, count: RouteCount) -> Self {
+        Cache {
+            routes: OrderMap::new(),
+            capacity,
+            max_idle_age,
+            next_purge: None,
+            count,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.routes.contains_key(key)
+    }
+
+    /// Returns the route for `key`, marking it as used at `now`.
+    fn get(&mut self, key: &K, now: Instant) -> Option<Arc<Mutex<Route<S>>>> {
+        self.routes.get(key).map(|route| {
+            route.lock().unwrap().last_used = now;
+            route.clone()
+        })
+    }
+
+    fn insert(&mut self, key: K, service: S, now: Instant) -> Arc<Mutex<Route<S>>> {
+        // Make room by evicting the least recently used routes.
+        while !self.routes.is_empty() && self.routes.len() >= self.capacity {
+            let lru = self.routes.iter()
+                .min_by_key(|&(_, route)| route.lock().unwrap().last_used)
+                .map(|(key, _)| key.clone())
+                .expect("routes must not be empty");
+            if let Some(route) = self.routes.remove(&lru) {
+                self.evicted(&route);
+            }
+        }
+
+        let route = Arc::new(Mutex::new(Route {
+            service,
+            last_used: now,
+            evicted: false,
+            pending: 0,
+        }));
+
+        match self.routes.insert(key, route.clone()) {
+            // The replaced route is no longer cached, but the count is unchanged.
+            Some(replaced) => replaced.lock().unwrap().evicted = true,
+            None => self.count.add(1),
+        }
+
+        if self.next_purge.is_none() {
+            self.next_purge = self.max_idle_age.map(|age| now + age);
+        }
+
+        route
+    }
+
+    /// Removes `route` if it is cached for `key`.
+    fn remove(&mut self, key: &K, route: &Arc<Mutex<Route<S>>>) {
+        let cached = self.routes.get(key).map_or(false, |r| Arc::ptr_eq(r, route));
+        if cached {
+            self.routes.remove(key);
+            self.evicted(route);
+        }
+    }
+
+    /// Evicts all routes that have been idle for longer than `max_idle_age`.
+    fn purge_idle(&mut self, now: Instant) {
+        let max_idle_age = match self.max_idle_age {
+            Some(age) => age,
+            None => return,
+        };
+
+        match self.next_purge {
+            Some(t) if t <= now => {}
+            _ => return,
+        }
+
+        let count = &self.count;
+        self.routes.retain(|_, route| {
+            let mut route = route.lock().unwrap();
+            if route.last_used + max_idle_age <= now {
+                route.evicted = true;
+                count.sub(1);
+                return false;
+            }
+            true
+        });
+
+        // Routes that are used later only become idle later, so there is
+        // nothing to purge until the oldest remaining route becomes idle.
+        self.next_purge = self.routes.iter()
+            .map(|(_, route)| route.lock().unwrap().last_used)
+            .min()
+            .map(|t| t + max_idle_age);
+    }
+
+    /// Marks a route that was removed from the cache.
+    fn evicted(&self, route: &Arc<Mutex<Route<S>>>) {
+        route.lock().unwrap().evicted = true;
+        self.count.sub(1);
+    }
+}
+
+impl<K, S> Drop for Cache<K, S>
+where K: Clone + Eq + Hash,
+{
+    fn drop(&mut self) {
+        self.count.sub(self.routes.len());
+    }
+}
+
+// ===== impl RouteCount =====
+
+impl RouteCount {
+    pub fn new() -> Self {
+        RouteCount::default()
+    }
+
+    /// Returns the number of routes currently cached.
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn add(&self, n: usize) {
+        self.0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    fn sub(&self, n: usize) {
+        self.0.fetch_sub(n, Ordering::Relaxed);
+    }
+}
+
+// ===== impl Pending =====
+
+impl<T> Pending<T>
 where T: Recognize,
 {
-    fn clone(&self) -> Self {
-        Router { inner: self.inner.clone() }
+    /// Calls the route once it is ready.
+    ///
+    /// A route that fails is evicted, so that it is bound again for later
+    /// requests.
+    fn poll_route(&mut self) -> Poll<<T::Service as Service>::Future, T::Error> {
+        let ready = self.route.lock().unwrap().service.poll_ready();
+
+        match ready {
+            Ok(Async::Ready(())) => {
+                self.unpark();
+                let request = self.request.take().expect("request dispatched twice");
+                let fut = self.route.lock().unwrap().service.call(request);
+                Ok(Async::Ready(fut))
+            }
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Err(e) => {
+                self.unpark();
+                self.shared.evict(&self.key, &self.route);
+                Err(e)
+            }
+        }
+    }
+
+    /// Counts the request as waiting for the route, unless `max_pending`
+    /// requests already are. Returns whether the request may wait.
+    fn park(&mut self, max_pending: usize) -> bool {
+        let mut route = self.route.lock().unwrap();
+        if route.pending >= max_pending {
+            return false;
+        }
+
+        route.pending += 1;
+        self.parked = true;
+        true
+    }
+
+    /// Stops counting the request as waiting for the route.
+    fn unpark(&mut self) {
+        if self.parked {
+            self.parked = false;
+            self.route.lock().unwrap().pending -= 1;
+        }
+    }
+}
+
+impl<T> Drop for Pending<T>
+where T: Recognize,
+{
+    fn drop(&mut self) {
+        self.unpark();
     }
 }
 
@@ -178,7 +630,7 @@ where T: Recognize,
 
 impl<S: Service> Single<S> {
     pub fn new(svc: S) -> Self {
-        Single(Some(svc))
+        Single(Mutex::new(Some(svc)))
     }
 }
 
@@ -194,8 +646,8 @@ impl<S: Service> Recognize for Single<S> {
         Some(())
     }
 
-    fn bind_service(&mut self, _: &Self::Key) -> Result<S, Self::RouteError> {
-        Ok(self.0.take().expect("static route bound twice"))
+    fn bind_service(&self, _: &Self::Key) -> Result<S, Self::RouteError> {
+        Ok(self.0.lock().unwrap().take().expect("static route bound twice"))
     }
 }
 
@@ -210,16 +662,324 @@ where T: Recognize,
     fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
         use self::State::*;
 
-        match self.state {
-            Inner(ref mut fut) => fut.poll().map_err(Error::Inner),
-            RouteError(..) => {
+        let fut = match self.state {
+            Inner(ref mut fut) => return fut.poll().map_err(Error::Inner),
+            NotReady(ref mut pending) => {
+                // Wait for the route to become ready.
+                try_ready!(pending.poll_route().map_err(Error::Inner))
+            }
+            InnerError(..) | RouteError(..) => {
                 match mem::replace(&mut self.state, Invalid) {
-                    RouteError(e) => Err(Error::Route(e)),
+                    InnerError(e) => return Err(Error::Inner(e)),
+                    RouteError(e) => return Err(Error::Route(e)),
                     _ => unreachable!(),
                 }
             }
-            NotRecognized => Err(Error::NotRecognized),
+            NotRecognized => return Err(Error::NotRecognized),
+            Overloaded => return Err(Error::Overloaded),
             Invalid => panic!(),
+        };
+
+        self.state = Inner(fut);
+        self.poll()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future;
+
+    use std::cell::{Cell, RefCell};
+    use std::collections::HashSet;
+    use std::rc::Rc;
+
+    fn cache(capacity: usize, max_idle_age: Option<Duration>) -> Cache<&'static str, ()> {
+        Cache::new(capacity, max_idle_age, RouteCount::default())
+    }
+
+    #[test]
+    fn evicts_least_recently_used_routes() {
+        let t0 = Instant::now();
+        let mut cache = cache(2, None);
+
+        cache.insert("a", (), t0);
+        let b = cache.insert("b", (), t0 + Duration::from_secs(1));
+        assert!(cache.get(&"a", t0 + Duration::from_secs(2)).is_some());
+
+        // `b` was used least recently.
+        cache.insert("c", (), t0 + Duration::from_secs(3));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.contains(&"a"));
+        assert!(!cache.contains(&"b"));
+        assert!(b.lock().unwrap().evicted);
+        assert!(cache.contains(&"c"));
+        assert_eq!(cache.count.get(), 2);
+    }
+
+    #[test]
+    fn evicts_idle_routes() {
+        let t0 = Instant::now();
+        let secs = Duration::from_secs;
+        let mut cache = cache(10, Some(secs(10)));
+
+        cache.insert("a", (), t0);
+        cache.insert("b", (), t0 + secs(5));
+
+        cache.purge_idle(t0 + secs(9));
+        assert_eq!(cache.len(), 2);
+
+        cache.purge_idle(t0 + secs(10));
+        assert!(!cache.contains(&"a"));
+        assert!(cache.contains(&"b"));
+        assert_eq!(cache.next_purge, Some(t0 + secs(15)));
+
+        // Using a route postpones its eviction.
+        assert!(cache.get(&"b", t0 + secs(12)).is_some());
+        cache.purge_idle(t0 + secs(15));
+        assert!(cache.contains(&"b"));
+
+        cache.purge_idle(t0 + secs(22));
+        assert_eq!(cache.len(), 0);
+        assert_eq!(cache.next_purge, None);
+        assert_eq!(cache.count.get(), 0);
+    }
+
+    #[test]
+    fn counts_routes_of_all_caches() {
+        let t0 = Instant::now();
+        let count = RouteCount::new();
+        let mut a = Cache::new(10, None, count.clone());
+        let mut b = Cache::new(10, None, count.clone());
+
+        a.insert("x", (), t0);
+        a.insert("x", (), t0);
+        b.insert("x", (), t0);
+        b.insert("y", (), t0);
+        assert_eq!(count.get(), 3);
+
+        drop(b);
+        assert_eq!(count.get(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_zero_capacity() {
+        Router::with_limits(Single::new(Never), Some(0), None, RouteCount::new());
+    }
+
+    #[test]
+    fn parks_requests_to_unready_routes() {
+        let state = TestState::new();
+        let mut router = Router::new(TestRecognize(state.clone()));
+
+        future::lazy(|| {
+            state.unready.borrow_mut().insert("a");
+            let mut a = router.call("a");
+            assert!(a.poll().unwrap().is_not_ready());
+
+            // Other routes are not held up.
+            assert_eq!(router.call("b").wait().unwrap(), "b");
+
+            state.unready.borrow_mut().remove("a");
+            assert_eq!(a.poll().unwrap(), Async::Ready("a"));
+
+            Ok::<(), ()>(())
+        }).wait().unwrap();
+    }
+
+    #[test]
+    fn rejects_requests_once_too_many_are_parked() {
+        let state = TestState::new();
+        let mut router = Router::new(TestRecognize(state.clone())).with_max_pending(1);
+
+        future::lazy(|| {
+            state.unready.borrow_mut().insert("a");
+            let mut a = router.call("a");
+            assert!(a.poll().unwrap().is_not_ready());
+
+            match router.call("a").poll() {
+                Err(Error::Overloaded) => {}
+                r => panic!("unexpected result: {:?}", r),
+            }
+            // Other routes are not held up.
+            assert_eq!(router.call("b").wait().unwrap(), "b");
+
+            // Dropping a parked request makes room for another.
+            drop(a);
+            let mut a = router.call("a");
+            assert!(a.poll().unwrap().is_not_ready());
+
+            state.unready.borrow_mut().remove("a");
+            assert_eq!(a.poll().unwrap(), Async::Ready("a"));
+            assert_eq!(router.call("a").wait().unwrap(), "a");
+
+            Ok::<(), ()>(())
+        }).wait().unwrap();
+    }
+
+    #[test]
+    fn drops_evicted_services() {
+        let state = TestState::new();
+        let mut router = Router::with_limits(
+            TestRecognize(state.clone()),
+            Some(1),
+            None,
+            RouteCount::new(),
+        );
+        let mut other = router.clone();
+
+        assert_eq!(router.call("a").wait().unwrap(), "a");
+        assert_eq!(other.call("a").wait().unwrap(), "a");
+        assert_eq!(*state.bound.borrow(), vec!["a"]);
+
+        // Evicts `a`, which neither handle keeps alive.
+        assert_eq!(router.call("b").wait().unwrap(), "b");
+        assert_eq!(router.len(), 1);
+        assert_eq!(state.live.get(), 1);
+
+        // `a` is bound again.
+        assert_eq!(other.call("a").wait().unwrap(), "a");
+        assert_eq!(*state.bound.borrow(), vec!["a", "b", "a"]);
+        assert_eq!(state.live.get(), 1);
+    }
+
+    #[test]
+    fn evicts_failed_routes() {
+        let state = TestState::new();
+        let count = RouteCount::new();
+        let mut router = Router::with_limits(
+            TestRecognize(state.clone()),
+            None,
+            None,
+            count.clone(),
+        );
+
+        assert_eq!(router.call("a").wait().unwrap(), "a");
+        assert_eq!(count.get(), 1);
+
+        state.failing.borrow_mut().insert("a");
+        match router.call("a").wait() {
+            Err(Error::Inner(())) => {}
+            r => panic!("unexpected result: {:?}", r),
+        }
+        assert!(router.is_empty());
+        assert_eq!(count.get(), 0);
+        assert_eq!(state.live.get(), 0);
+
+        state.failing.borrow_mut().remove("a");
+        assert_eq!(router.call("a").wait().unwrap(), "a");
+        assert_eq!(*state.bound.borrow(), vec!["a", "a"]);
+    }
+
+    #[test]
+    fn rejects_unrecognized_requests() {
+        let mut router = Router::new(TestRecognize(TestState::new()));
+
+        match router.call("").wait() {
+            Err(Error::NotRecognized) => {}
+            r => panic!("unexpected result: {:?}", r),
+        }
+        assert!(router.is_empty());
+    }
+
+    /// Recognizes requests by their content, which services respond with.
+    struct TestRecognize(Rc<TestState>);
+
+    struct TestService {
+        key: &'static str,
+        state: Rc<TestState>,
+    }
+
+    #[derive(Default)]
+    struct TestState {
+        /// Routes that are not ready
+        unready: RefCell<HashSet<&'static str>>,
+
+        /// Routes that fail
+        failing: RefCell<HashSet<&'static str>>,
+
+        /// Routes in the order they were bound
+        bound: RefCell<Vec<&'static str>>,
+
+        /// The number of services that have not been dropped
+        live: Cell<usize>,
+    }
+
+    impl TestState {
+        fn new() -> Rc<Self> {
+            Rc::new(TestState::default())
+        }
+    }
+
+    impl Recognize for TestRecognize {
+        type Request = &'static str;
+        type Response = &'static str;
+        type Error = ();
+        type Key = &'static str;
+        type RouteError = ();
+        type Service = TestService;
+
+        fn recognize(&self, req: &Self::Request) -> Option<Self::Key> {
+            if req.is_empty() {
+                None
+            } else {
+                Some(*req)
+            }
+        }
+
+        fn bind_service(&self, key: &Self::Key) -> Result<TestService, ()> {
+            self.0.bound.borrow_mut().push(*key);
+            self.0.live.set(self.0.live.get() + 1);
+            Ok(TestService {
+                key: *key,
+                state: self.0.clone(),
+            })
+        }
+    }
+
+    impl Service for TestService {
+        type Request = &'static str;
+        type Response = &'static str;
+        type Error = ();
+        type Future = future::FutureResult<&'static str, ()>;
+
+        fn poll_ready(&mut self) -> Poll<(), ()> {
+            if self.state.failing.borrow().contains(self.key) {
+                return Err(());
+            }
+            if self.state.unready.borrow().contains(self.key) {
+                return Ok(Async::NotReady);
+            }
+            Ok(().into())
+        }
+
+        fn call(&mut self, request: &'static str) -> Self::Future {
+            future::ok(request)
+        }
+    }
+
+    impl Drop for TestService {
+        fn drop(&mut self) {
+            self.state.live.set(self.state.live.get() - 1);
+        }
+    }
+
+    /// A service that is never called.
+    struct Never;
+
+    impl Service for Never {
+        type Request = ();
+        type Response = ();
+        type Error = ();
+        type Future = ::futures::future::Empty<(), ()>;
+
+        fn poll_ready(&mut self) -> Poll<(), ()> {
+            Ok(().into())
+        }
+
+        fn call(&mut self, _: ()) -> Self::Future {
+            unreachable!()
         }
     }
 }
@@ -0,0 +1,305 @@
+//! Reusable `Recognize` implementations and combinators.
+//!
+//! `PathPrefix`, `Header` and `Method` recognize HTTP requests and bind a
+//! service for each distinct key with a closure. Recognizers may be composed
+//! with `Recognize::or` and `Recognize::map_key`.
+
+use futures::{Future, Poll};
+use http;
+use tower::Service;
+
+use std::hash::{Hash, Hasher};
+
+use Recognize;
+
+/// Routes requests by the longest of a set of path prefixes they match.
+#[derive(Debug)]
+pub struct PathPrefix<F> {
+    prefixes: Vec<String>,
+    bind: F,
+}
+
+/// Routes requests by the value of a header.
+#[derive(Debug)]
+pub struct Header<F> {
+    name: http::header::HeaderName,
+    bind: F,
+}
+
+/// Routes requests by their method.
+#[derive(Debug)]
+pub struct Method<F> {
+    bind: F,
+}
+
+/// Routes requests with `A`, or with `B` if `A` does not recognize them.
+///
+/// Returned by `Recognize::or`.
+#[derive(Debug)]
+pub struct Or<A, B> {
+    a: A,
+    b: B,
+}
+
+/// Routes requests by a function of the keys of another recognizer.
+///
+/// Returned by `Recognize::map_key`.
+#[derive(Debug)]
+pub struct MapKey<R, F> {
+    inner: R,
+    f: F,
+}
+
+/// A key produced by `MapKey`.
+///
+/// Keys compare by their mapped value only, so requests whose keys map to
+/// the same value share a route. That route is bound with the original key
+/// of the first such request.
+#[derive(Clone, Debug)]
+pub struct Mapped<K, I> {
+    key: K,
+    original: I,
+}
+
+/// Either of two keys, services or futures.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Either<A, B> {
+    A(A),
+    B(B),
+}
+
+// ===== impl PathPrefix =====
+
+impl<F> PathPrefix<F> {
+    /// Routes requests by the longest of `prefixes` that their path starts
+    /// with, binding services with `bind`.
+    pub fn new<I>(prefixes: I, bind: F) -> Self
+    where I: IntoIterator,
+          I::Item: Into<String>,
+    {
+        PathPrefix {
+            prefixes: prefixes.into_iter().map(Into::into).collect(),
+            bind,
+        }
+    }
+}
+
+impl<F, S, E, B> Recognize for PathPrefix<F>
+where F: Fn(&String) -> Result<S, E>,
+      S: Service<Request = http::Request<B>>,
+{
+    type Request = S::Request;
+    type Response = S::Response;
+    type Error = S::Error;
+    type Key = String;
+    type RouteError = E;
+    type Service = S;
+
+    fn recognize(&self, req: &Self::Request) -> Option<Self::Key> {
+        let path = req.uri().path();
+
+        self.prefixes.iter()
+            .filter(|prefix| path.starts_with(prefix.as_str()))
+            .max_by_key(|prefix| prefix.len())
+            .cloned()
+    }
+
+    fn bind_service(&self, key: &Self::Key) -> Result<Self::Service, Self::RouteError> {
+        (self.bind)(key)
+    }
+}
+
+// ===== impl Header =====
+
+impl<F> Header<F> {
+    /// Routes requests by the value of the `name` header, binding services
+    /// with `bind`. Requests without the header are not recognized.
+    pub fn new(name: http::header::HeaderName, bind: F) -> Self {
+        Header {
+            name,
+            bind,
+        }
+    }
+}
+
+impl<F, S, E, B> Recognize for Header<F>
+where F: Fn(&http::header::HeaderValue) -> Result<S, E>,
+      S: Service<Request = http::Request<B>>,
+{
+    type Request = S::Request;
+    type Response = S::Response;
+    type Error = S::Error;
+    type Key = http::header::HeaderValue;
+    type RouteError = E;
+    type Service = S;
+
+    fn recognize(&self, req: &Self::Request) -> Option<Self::Key> {
+        req.headers().get(&self.name).cloned()
+    }
+
+    fn bind_service(&self, key: &Self::Key) -> Result<Self::Service, Self::RouteError> {
+        (self.bind)(key)
+    }
+}
+
+// ===== impl Method =====
+
+impl<F> Method<F> {
+    /// Routes requests by their method, binding services with `bind`.
+    pub fn new(bind: F) -> Self {
+        Method { bind }
+    }
+}
+
+impl<F, S, E, B> Recognize for Method<F>
+where F: Fn(&http::Method) -> Result<S, E>,
+      S: Service<Request = http::Request<B>>,
+{
+    type Request = S::Request;
+    type Response = S::Response;
+    type Error = S::Error;
+    type Key = http::Method;
+    type RouteError = E;
+    type Service = S;
+
+    fn recognize(&self, req: &Self::Request) -> Option<Self::Key> {
+        Some(req.method().clone())
+    }
+
+    fn bind_service(&self, key: &Self::Key) -> Result<Self::Service, Self::RouteError> {
+        (self.bind)(key)
+    }
+}
+
+// ===== impl Or =====
+
+impl<A, B> Or<A, B> {
+    pub(crate) fn new(a: A, b: B) -> Self {
+        Or { a, b }
+    }
+}
+
+impl<A, B> Recognize for Or<A, B>
+where A: Recognize,
+      B: Recognize<Request = A::Request,
+                  Response = A::Response,
+                     Error = A::Error,
+                RouteError = A::RouteError>,
+{
+    type Request = A::Request;
+    type Response = A::Response;
+    type Error = A::Error;
+    type Key = Either<A::Key, B::Key>;
+    type RouteError = A::RouteError;
+    type Service = Either<A::Service, B::Service>;
+
+    fn recognize(&self, req: &Self::Request) -> Option<Self::Key> {
+        self.a.recognize(req).map(Either::A)
+            .or_else(|| self.b.recognize(req).map(Either::B))
+    }
+
+    fn bind_service(&self, key: &Self::Key) -> Result<Self::Service, Self::RouteError> {
+        match *key {
+            Either::A(ref key) => self.a.bind_service(key).map(Either::A),
+            Either::B(ref key) => self.b.bind_service(key).map(Either::B),
+        }
+    }
+}
+
+// ===== impl MapKey =====
+
+impl<R, F> MapKey<R, F> {
+    pub(crate) fn new(inner: R, f: F) -> Self {
+        MapKey { inner, f }
+    }
+}
+
+impl<R, F, K> Recognize for MapKey<R, F>
+where R: Recognize,
+      F: Fn(&R::Key) -> K,
+      K: Clone + Eq + Hash,
+{
+    type Request = R::Request;
+    type Response = R::Response;
+    type Error = R::Error;
+    type Key = Mapped<K, R::Key>;
+    type RouteError = R::RouteError;
+    type Service = R::Service;
+
+    fn recognize(&self, req: &Self::Request) -> Option<Self::Key> {
+        self.inner.recognize(req).map(|original| {
+            Mapped {
+                key: (self.f)(&original),
+                original,
+            }
+        })
+    }
+
+    fn bind_service(&self, key: &Self::Key) -> Result<Self::Service, Self::RouteError> {
+        self.inner.bind_service(&key.original)
+    }
+}
+
+// ===== impl Mapped =====
+
+impl<K, I> Mapped<K, I> {
+    /// Returns the mapped key.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+}
+
+impl<K: PartialEq, I> PartialEq for Mapped<K, I> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<K: Eq, I> Eq for Mapped<K, I> {}
+
+impl<K: Hash, I> Hash for Mapped<K, I> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state)
+    }
+}
+
+// ===== impl Either =====
+
+impl<A, B> Service for Either<A, B>
+where A: Service,
+      B: Service<Request = A::Request, Response = A::Response, Error = A::Error>,
+{
+    type Request = A::Request;
+    type Response = A::Response;
+    type Error = A::Error;
+    type Future = Either<A::Future, B::Future>;
+
+    fn poll_ready(&mut self) -> Poll<(), Self::Error> {
+        match *self {
+            Either::A(ref mut svc) => svc.poll_ready(),
+            Either::B(ref mut svc) => svc.poll_ready(),
+        }
+    }
+
+    fn call(&mut self, request: Self::Request) -> Self::Future {
+        match *self {
+            Either::A(ref mut svc) => Either::A(svc.call(request)),
+            Either::B(ref mut svc) => Either::B(svc.call(request)),
+        }
+    }
+}
+
+impl<A, B> Future for Either<A, B>
+where A: Future,
+      B: Future<Item = A::Item, Error = A::Error>,
+{
+    type Item = A::Item;
+    type Error = A::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        match *self {
+            Either::A(ref mut fut) => fut.poll(),
+            Either::B(ref mut fut) => fut.poll(),
+        }
+    }
+}